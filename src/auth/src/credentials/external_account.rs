@@ -113,16 +113,17 @@ use super::external_account_sources::file_sourced::FileSourcedCredentials;
 use super::external_account_sources::url_sourced::UrlSourcedCredentials;
 use super::impersonated;
 use super::internal::sts_exchange::{ClientAuthentication, ExchangeTokenRequest, STSHandler};
-use super::{CacheableResource, Credentials};
+use super::{CacheableResource, Credentials, DEFAULT_UNIVERSE_DOMAIN};
 use crate::build_errors::Error as BuilderError;
 use crate::constants::{DEFAULT_SCOPE, STS_TOKEN_URL};
 use crate::credentials::external_account_sources::programmatic_sourced::ProgrammaticSourcedCredentials;
 use crate::credentials::subject_token::dynamic;
+use crate::credentials::token_observer::TokenObserver;
 use crate::errors::non_retryable;
 use crate::headers_util::build_cacheable_headers;
 use crate::retry::Builder as RetryTokenProviderBuilder;
 use crate::token::{CachedTokenProvider, Token, TokenProvider};
-use crate::token_cache::TokenCache;
+use crate::token_cache::{self, TokenCache};
 use crate::{BuildResult, Result};
 use gax::backoff_policy::BackoffPolicyArg;
 use gax::retry_policy::RetryPolicyArg;
@@ -181,6 +182,7 @@ struct ExternalAccountFile {
     client_secret: Option<String>,
     scopes: Option<Vec<String>>,
     credential_source: CredentialSourceFile,
+    universe_domain: Option<String>,
 }
 
 impl From<ExternalAccountFile> for ExternalAccountConfig {
@@ -198,6 +200,7 @@ impl From<ExternalAccountFile> for ExternalAccountConfig {
             service_account_impersonation_url: config.service_account_impersonation_url,
             credential_source: config.credential_source.into(),
             scopes: scope,
+            universe_domain: config.universe_domain,
         }
     }
 }
@@ -233,6 +236,7 @@ struct ExternalAccountConfig {
     client_secret: Option<String>,
     scopes: Vec<String>,
     credential_source: CredentialSource,
+    universe_domain: Option<String>,
 }
 
 #[derive(Debug, Default)]
@@ -245,6 +249,7 @@ struct ExternalAccountConfigBuilder {
     client_secret: Option<String>,
     scopes: Option<Vec<String>>,
     credential_source: Option<CredentialSource>,
+    universe_domain: Option<String>,
 }
 
 impl ExternalAccountConfigBuilder {
@@ -306,6 +311,7 @@ impl ExternalAccountConfigBuilder {
             service_account_impersonation_url: self.service_account_impersonation_url,
             client_id: self.client_id,
             client_secret: self.client_secret,
+            universe_domain: self.universe_domain,
         })
     }
 }
@@ -325,21 +331,38 @@ impl ExternalAccountConfig {
         self,
         quota_project_id: Option<String>,
         retry_builder: RetryTokenProviderBuilder,
+        cache_options: token_cache::Options,
     ) -> Credentials {
         let config = self.clone();
         match self.credential_source {
-            CredentialSource::Url(source) => {
-                Self::make_credentials_from_source(source, config, quota_project_id, retry_builder)
-            }
-            CredentialSource::Executable(source) => {
-                Self::make_credentials_from_source(source, config, quota_project_id, retry_builder)
-            }
-            CredentialSource::Programmatic(source) => {
-                Self::make_credentials_from_source(source, config, quota_project_id, retry_builder)
-            }
-            CredentialSource::File(source) => {
-                Self::make_credentials_from_source(source, config, quota_project_id, retry_builder)
-            }
+            CredentialSource::Url(source) => Self::make_credentials_from_source(
+                source,
+                config,
+                quota_project_id,
+                retry_builder,
+                cache_options,
+            ),
+            CredentialSource::Executable(source) => Self::make_credentials_from_source(
+                source,
+                config,
+                quota_project_id,
+                retry_builder,
+                cache_options,
+            ),
+            CredentialSource::Programmatic(source) => Self::make_credentials_from_source(
+                source,
+                config,
+                quota_project_id,
+                retry_builder,
+                cache_options,
+            ),
+            CredentialSource::File(source) => Self::make_credentials_from_source(
+                source,
+                config,
+                quota_project_id,
+                retry_builder,
+                cache_options,
+            ),
             CredentialSource::Aws => {
                 unimplemented!("AWS sourced credential not supported yet")
             }
@@ -351,20 +374,23 @@ impl ExternalAccountConfig {
         config: ExternalAccountConfig,
         quota_project_id: Option<String>,
         retry_builder: RetryTokenProviderBuilder,
+        cache_options: token_cache::Options,
     ) -> Credentials
     where
         T: dynamic::SubjectTokenProvider + 'static,
     {
+        let universe_domain = config.universe_domain.clone();
         let token_provider = ExternalAccountTokenProvider {
             subject_token_provider,
             config,
         };
         let token_provider_with_retry = retry_builder.build(token_provider);
-        let cache = TokenCache::new(token_provider_with_retry);
+        let cache = TokenCache::with_options(token_provider_with_retry, cache_options);
         Credentials {
             inner: Arc::new(ExternalAccountCredentials {
                 token_provider: cache,
                 quota_project_id,
+                universe_domain,
             }),
         }
     }
@@ -453,6 +479,7 @@ where
 {
     token_provider: T,
     quota_project_id: Option<String>,
+    universe_domain: Option<String>,
 }
 
 /// A builder for external account [Credentials] instances.
@@ -503,6 +530,7 @@ pub struct Builder {
     quota_project_id: Option<String>,
     scopes: Option<Vec<String>>,
     retry_builder: RetryTokenProviderBuilder,
+    cache_options: token_cache::Options,
 }
 
 impl Builder {
@@ -515,6 +543,7 @@ impl Builder {
             quota_project_id: None,
             scopes: None,
             retry_builder: RetryTokenProviderBuilder::default(),
+            cache_options: token_cache::Options::default(),
         }
     }
 
@@ -628,6 +657,51 @@ impl Builder {
         self
     }
 
+    /// Sets how long before the token expires the credentials refresh it.
+    ///
+    /// The credentials refresh access tokens in the background, before they
+    /// expire. By default they start refreshing 4 minutes before the token
+    /// expires. Applications that make requests with long-lived tokens, such
+    /// as streaming RPCs, may want to increase this value.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::external_account::Builder;
+    /// # tokio_test::block_on(async {
+    /// use std::time::Duration;
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_refresh_slack(Duration::from_secs(600))
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_refresh_slack(mut self, v: Duration) -> Self {
+        self.cache_options = self.cache_options.with_refresh_slack(v);
+        self
+    }
+
+    /// Sets an observer for the token lifecycle events.
+    ///
+    /// See the [token_observer][crate::credentials::token_observer] module
+    /// for more information.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::external_account::Builder;
+    /// # use google_cloud_auth::credentials::token_observer::TokenObserver;
+    /// # tokio_test::block_on(async {
+    /// #[derive(Debug)]
+    /// struct MyObserver;
+    /// impl TokenObserver for MyObserver {}
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_token_observer(MyObserver)
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_token_observer<O: TokenObserver + 'static>(mut self, v: O) -> Self {
+        self.cache_options = self.cache_options.with_observer(Arc::new(v));
+        self
+    }
+
     /// Returns a [Credentials] instance with the configured settings.
     ///
     /// # Errors
@@ -650,7 +724,11 @@ impl Builder {
 
         let config: ExternalAccountConfig = file.into();
 
-        Ok(config.make_credentials(self.quota_project_id, self.retry_builder))
+        Ok(config.make_credentials(
+            self.quota_project_id,
+            self.retry_builder,
+            self.cache_options,
+        ))
     }
 }
 
@@ -705,6 +783,7 @@ pub struct ProgrammaticBuilder {
     quota_project_id: Option<String>,
     config: ExternalAccountConfigBuilder,
     retry_builder: RetryTokenProviderBuilder,
+    cache_options: token_cache::Options,
 }
 
 impl ProgrammaticBuilder {
@@ -750,6 +829,7 @@ impl ProgrammaticBuilder {
             quota_project_id: None,
             config,
             retry_builder: RetryTokenProviderBuilder::default(),
+            cache_options: token_cache::Options::default(),
         }
     }
 
@@ -1210,6 +1290,22 @@ impl ProgrammaticBuilder {
         self
     }
 
+    /// Sets how long before the token expires the credentials refresh it.
+    ///
+    /// See [Builder::with_refresh_slack] for more information.
+    pub fn with_refresh_slack(mut self, v: Duration) -> Self {
+        self.cache_options = self.cache_options.with_refresh_slack(v);
+        self
+    }
+
+    /// Sets an observer for the token lifecycle events.
+    ///
+    /// See [Builder::with_token_observer] for more information.
+    pub fn with_token_observer<O: TokenObserver + 'static>(mut self, v: O) -> Self {
+        self.cache_options = self.cache_options.with_observer(Arc::new(v));
+        self
+    }
+
     /// Returns a [Credentials] instance with the configured settings.
     ///
     /// # Errors
@@ -1217,8 +1313,9 @@ impl ProgrammaticBuilder {
     /// Returns a [BuilderError] if any of the required fields (such as
     /// `audience` or `subject_token_type`) have not been set.
     pub fn build(self) -> BuildResult<Credentials> {
+        let cache_options = self.cache_options.clone();
         let (config, quota_project_id, retry_builder) = self.build_components()?;
-        Ok(config.make_credentials(quota_project_id, retry_builder))
+        Ok(config.make_credentials(quota_project_id, retry_builder, cache_options))
     }
 
     /// Consumes the builder and returns its configured components.
@@ -1233,6 +1330,7 @@ impl ProgrammaticBuilder {
             quota_project_id,
             config,
             retry_builder,
+            ..
         } = self;
 
         let mut config_builder = config;
@@ -1257,6 +1355,18 @@ where
         let token = self.token_provider.token(extensions).await?;
         build_cacheable_headers(&token, &self.quota_project_id)
     }
+
+    async fn universe_domain(&self) -> Option<String> {
+        Some(
+            self.universe_domain
+                .clone()
+                .unwrap_or_else(|| DEFAULT_UNIVERSE_DOMAIN.to_string()),
+        )
+    }
+
+    async fn force_refresh(&self) {
+        self.token_provider.force_refresh();
+    }
 }
 
 #[cfg(test)]
@@ -1335,6 +1445,40 @@ mod tests {
         assert!(fmt.contains("ExternalAccountCredentials"));
     }
 
+    #[tokio::test]
+    async fn universe_domain() {
+        let mut contents = json!({
+            "type": "external_account",
+            "audience": "audience",
+            "subject_token_type": "urn:ietf:params:oauth:token-type:jwt",
+            "token_url": "https://sts.my-universe.example.com/v1/token",
+            "credential_source": { "file": "/path/to/token.jwt" },
+            "universe_domain": "my-universe.example.com"
+        });
+        let creds = Builder::new(contents.clone()).build().unwrap();
+        assert_eq!(
+            creds.universe_domain().await.as_deref(),
+            Some("my-universe.example.com")
+        );
+
+        contents.as_object_mut().unwrap().remove("universe_domain");
+        let creds = Builder::new(contents).build().unwrap();
+        assert_eq!(
+            creds.universe_domain().await.as_deref(),
+            Some(DEFAULT_UNIVERSE_DOMAIN)
+        );
+
+        let creds = ProgrammaticBuilder::new(Arc::new(TestSubjectTokenProvider))
+            .with_audience("audience")
+            .with_subject_token_type("urn:ietf:params:oauth:token-type:jwt")
+            .build()
+            .unwrap();
+        assert_eq!(
+            creds.universe_domain().await.as_deref(),
+            Some(DEFAULT_UNIVERSE_DOMAIN)
+        );
+    }
+
     #[tokio::test]
     async fn create_external_account_detect_url_sourced() {
         let contents = json!({
@@ -94,6 +94,7 @@
 use crate::build_errors::Error as BuilderError;
 use crate::constants::DEFAULT_SCOPE;
 use crate::credentials::dynamic::CredentialsProvider;
+use crate::credentials::token_observer::TokenObserver;
 use crate::credentials::{
     CacheableResource, Credentials, build_credentials, extract_credential_type,
 };
@@ -103,7 +104,7 @@ use crate::headers_util::{
 };
 use crate::retry::{Builder as RetryTokenProviderBuilder, TokenProviderWithRetry};
 use crate::token::{CachedTokenProvider, Token, TokenProvider};
-use crate::token_cache::TokenCache;
+use crate::token_cache::{self, TokenCache};
 use crate::{BuildResult, Result};
 use async_trait::async_trait;
 use gax::backoff_policy::BackoffPolicyArg;
@@ -154,6 +155,7 @@ pub struct Builder {
     quota_project_id: Option<String>,
     lifetime: Option<Duration>,
     retry_builder: RetryTokenProviderBuilder,
+    cache_options: token_cache::Options,
 }
 
 impl Builder {
@@ -173,6 +175,7 @@ impl Builder {
             quota_project_id: None,
             lifetime: None,
             retry_builder: RetryTokenProviderBuilder::default(),
+            cache_options: token_cache::Options::default(),
         }
     }
 
@@ -202,6 +205,7 @@ impl Builder {
             quota_project_id: None,
             lifetime: None,
             retry_builder: RetryTokenProviderBuilder::default(),
+            cache_options: token_cache::Options::default(),
         }
     }
 
@@ -400,6 +404,51 @@ impl Builder {
         self
     }
 
+    /// Sets how long before the token expires the credentials refresh it.
+    ///
+    /// The credentials refresh access tokens in the background, before they
+    /// expire. By default they start refreshing 4 minutes before the token
+    /// expires. Applications that make requests with long-lived tokens, such
+    /// as streaming RPCs, may want to increase this value.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::impersonated::Builder;
+    /// # tokio_test::block_on(async {
+    /// use std::time::Duration;
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_refresh_slack(Duration::from_secs(600))
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_refresh_slack(mut self, v: Duration) -> Self {
+        self.cache_options = self.cache_options.with_refresh_slack(v);
+        self
+    }
+
+    /// Sets an observer for the token lifecycle events.
+    ///
+    /// See the [token_observer][crate::credentials::token_observer] module
+    /// for more information.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::impersonated::Builder;
+    /// # use google_cloud_auth::credentials::token_observer::TokenObserver;
+    /// # tokio_test::block_on(async {
+    /// #[derive(Debug)]
+    /// struct MyObserver;
+    /// impl TokenObserver for MyObserver {}
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_token_observer(MyObserver)
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_token_observer<O: TokenObserver + 'static>(mut self, v: O) -> Self {
+        self.cache_options = self.cache_options.with_observer(Arc::new(v));
+        self
+    }
+
     /// Returns a [Credentials] instance with the configured settings.
     ///
     /// # Errors
@@ -417,11 +466,14 @@ impl Builder {
     ///
     /// [application-default credentials]: https://cloud.google.com/docs/authentication/application-default-credentials
     pub fn build(self) -> BuildResult<Credentials> {
+        let cache_options = self.cache_options.clone();
         let (token_provider, quota_project_id) = self.build_components()?;
+        let source_credentials = token_provider.inner.source_credentials.clone();
         Ok(Credentials {
             inner: Arc::new(ImpersonatedServiceAccount {
-                token_provider: TokenCache::new(token_provider),
+                token_provider: TokenCache::with_options(token_provider, cache_options),
                 quota_project_id,
+                source_credentials,
             }),
         })
     }
@@ -453,7 +505,7 @@ impl Builder {
                     // If user does want some specific scopes or quota, they can build using the
                     // from_source_credentials method.
                     let source_credentials =
-                        build_credentials(Some(config.source_credentials), None, None)?;
+                        build_credentials(Some(config.source_credentials), None, None, None)?;
 
                     (
                         source_credentials,
@@ -504,6 +556,7 @@ where
 {
     token_provider: T,
     quota_project_id: Option<String>,
+    source_credentials: Credentials,
 }
 
 #[async_trait::async_trait]
@@ -515,6 +568,16 @@ where
         let token = self.token_provider.token(extensions).await?;
         build_cacheable_headers(&token, &self.quota_project_id)
     }
+
+    // The access tokens are issued by the IAM service in the universe of the
+    // source credentials.
+    async fn universe_domain(&self) -> Option<String> {
+        self.source_credentials.universe_domain().await
+    }
+
+    async fn force_refresh(&self) {
+        self.token_provider.force_refresh();
+    }
 }
 
 struct ImpersonatedTokenProvider {
@@ -638,6 +701,7 @@ struct GenerateAccessTokenResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::credentials::DEFAULT_UNIVERSE_DOMAIN;
     use crate::credentials::tests::{
         find_source_error, get_mock_auth_retry_policy, get_mock_backoff_policy,
         get_mock_retry_throttler,
@@ -1735,4 +1799,48 @@ mod tests {
         server.verify_and_clear();
         Ok(())
     }
+
+    #[tokio::test]
+    async fn universe_domain_from_source_credentials() -> TestResult {
+        let impersonated_credential = json!({
+            "type": "impersonated_service_account",
+            "service_account_impersonation_url": "https://iamcredentials.my-universe.example.com/v1/projects/-/serviceAccounts/test-principal:generateAccessToken",
+            "source_credentials": {
+                "type": "service_account",
+                "client_email": "test-client-email",
+                "private_key_id": "test-private-key-id",
+                "private_key": "",
+                "project_id": "test-project-id",
+                "universe_domain": "my-universe.example.com"
+            }
+        });
+        let creds = Builder::new(impersonated_credential).build()?;
+        assert_eq!(
+            creds.universe_domain().await.as_deref(),
+            Some("my-universe.example.com")
+        );
+
+        let impersonated_credential = json!({
+            "type": "impersonated_service_account",
+            "service_account_impersonation_url": "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/test-principal:generateAccessToken",
+            "source_credentials": {
+                "type": "authorized_user",
+                "client_id": "test-client-id",
+                "client_secret": "test-client-secret",
+                "refresh_token": "test-refresh-token"
+            }
+        });
+        let creds = Builder::new(impersonated_credential).build()?;
+        assert_eq!(
+            creds.universe_domain().await.as_deref(),
+            Some(DEFAULT_UNIVERSE_DOMAIN)
+        );
+
+        let source_credentials = crate::credentials::testing::error_credentials(false);
+        let creds = Builder::from_source_credentials(source_credentials)
+            .with_target_principal("test-principal")
+            .build()?;
+        assert_eq!(creds.universe_domain().await, None);
+        Ok(())
+    }
 }
@@ -75,12 +75,13 @@
 //! [Metadata Service]: https://cloud.google.com/compute/docs/metadata/overview
 
 use crate::credentials::dynamic::CredentialsProvider;
+use crate::credentials::token_observer::TokenObserver;
 use crate::credentials::{CacheableResource, Credentials, DEFAULT_UNIVERSE_DOMAIN};
 use crate::errors::CredentialsError;
 use crate::headers_util::build_cacheable_headers;
 use crate::retry::{Builder as RetryTokenProviderBuilder, TokenProviderWithRetry};
 use crate::token::{CachedTokenProvider, Token, TokenProvider};
-use crate::token_cache::TokenCache;
+use crate::token_cache::{self, TokenCache};
 use crate::{BuildResult, Result};
 use async_trait::async_trait;
 use bon::Builder;
@@ -137,6 +138,7 @@ pub struct Builder {
     universe_domain: Option<String>,
     created_by_adc: bool,
     retry_builder: RetryTokenProviderBuilder,
+    cache_options: token_cache::Options,
 }
 
 impl Builder {
@@ -264,6 +266,51 @@ impl Builder {
         self
     }
 
+    /// Sets how long before the token expires the credentials refresh it.
+    ///
+    /// The credentials refresh access tokens in the background, before they
+    /// expire. By default they start refreshing 4 minutes before the token
+    /// expires. Applications that make requests with long-lived tokens, such
+    /// as streaming RPCs, may want to increase this value.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::mds::Builder;
+    /// # tokio_test::block_on(async {
+    /// use std::time::Duration;
+    /// let credentials = Builder::default()
+    ///     .with_refresh_slack(Duration::from_secs(600))
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_refresh_slack(mut self, v: Duration) -> Self {
+        self.cache_options = self.cache_options.with_refresh_slack(v);
+        self
+    }
+
+    /// Sets an observer for the token lifecycle events.
+    ///
+    /// See the [token_observer][crate::credentials::token_observer] module
+    /// for more information.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::mds::Builder;
+    /// # use google_cloud_auth::credentials::token_observer::TokenObserver;
+    /// # tokio_test::block_on(async {
+    /// #[derive(Debug)]
+    /// struct MyObserver;
+    /// impl TokenObserver for MyObserver {}
+    /// let credentials = Builder::default()
+    ///     .with_token_observer(MyObserver)
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_token_observer<O: TokenObserver + 'static>(mut self, v: O) -> Self {
+        self.cache_options = self.cache_options.with_observer(Arc::new(v));
+        self
+    }
+
     // This method is used to build mds credentials from ADC
     pub(crate) fn from_adc() -> Self {
         Self {
@@ -302,10 +349,11 @@ impl Builder {
 
     /// Returns a [Credentials] instance with the configured settings.
     pub fn build(self) -> BuildResult<Credentials> {
+        let cache_options = self.cache_options.clone();
         let mdsc = MDSCredentials {
             quota_project_id: self.quota_project_id.clone(),
             universe_domain: self.universe_domain.clone(),
-            token_provider: TokenCache::new(self.build_token_provider()),
+            token_provider: TokenCache::with_options(self.build_token_provider(), cache_options),
         };
         Ok(Credentials {
             inner: Arc::new(mdsc),
@@ -323,6 +371,10 @@ where
         build_cacheable_headers(&cached_token, &self.quota_project_id)
     }
 
+    async fn force_refresh(&self) {
+        self.token_provider.force_refresh();
+    }
+
     async fn universe_domain(&self) -> Option<String> {
         if self.universe_domain.is_some() {
             return self.universe_domain.clone();
@@ -70,27 +70,26 @@
 //! [Service Account]: https://cloud.google.com/iam/docs/service-account-overview
 //! [service account key]: https://cloud.google.com/iam/docs/keys-create-delete#creating
 
-mod jws;
+pub(crate) mod jws;
 
 use crate::build_errors::Error as BuilderError;
 use crate::constants::DEFAULT_SCOPE;
 use crate::credentials::dynamic::CredentialsProvider;
-use crate::credentials::{CacheableResource, Credentials};
-use crate::errors::{self, CredentialsError};
+use crate::credentials::token_observer::TokenObserver;
+use crate::credentials::token_store::{self, StoredTokenProvider, TokenStore};
+use crate::credentials::{CacheableResource, Credentials, DEFAULT_UNIVERSE_DOMAIN};
 use crate::headers_util::build_cacheable_headers;
 use crate::token::{CachedTokenProvider, Token, TokenProvider};
-use crate::token_cache::TokenCache;
+use crate::token_cache::{self, TokenCache};
 use crate::{BuildResult, Result};
 use async_trait::async_trait;
 use http::{Extensions, HeaderMap};
-use jws::{CLOCK_SKEW_FUDGE, DEFAULT_TOKEN_TIMEOUT, JwsClaims, JwsHeader};
-use rustls::crypto::CryptoProvider;
+use jws::{Algorithm, CLOCK_SKEW_FUDGE, DEFAULT_TOKEN_TIMEOUT, JwsClaims, JwsHeader};
 use rustls::sign::Signer;
-use rustls_pemfile::Item;
 use serde_json::Value;
 use std::sync::Arc;
 use time::OffsetDateTime;
-use tokio::time::Instant;
+use tokio::time::{Duration, Instant};
 
 /// Represents the access specifier for a service account based token,
 /// specifying either OAuth 2.0 [scopes] or a [JWT] audience.
@@ -146,6 +145,17 @@ impl AccessSpecifier {
         }
     }
 
+    // The persisted token store keys must not change between releases, so
+    // they cannot depend on the `Debug` format.
+    fn store_key_parts(&self) -> Vec<&str> {
+        match self {
+            AccessSpecifier::Audience(aud) => vec!["audience", aud],
+            AccessSpecifier::Scopes(scopes) => std::iter::once("scopes")
+                .chain(scopes.iter().map(String::as_str))
+                .collect(),
+        }
+    }
+
     /// Creates [AccessSpecifier] with [scopes].
     ///
     /// # Example
@@ -207,6 +217,8 @@ pub struct Builder {
     service_account_key: Value,
     access_specifier: AccessSpecifier,
     quota_project_id: Option<String>,
+    token_store: Option<Arc<dyn token_store::dynamic::TokenStore>>,
+    cache_options: token_cache::Options,
 }
 
 impl Builder {
@@ -221,6 +233,8 @@ impl Builder {
             service_account_key,
             access_specifier: AccessSpecifier::Scopes([DEFAULT_SCOPE].map(str::to_string).to_vec()),
             quota_project_id: None,
+            token_store: None,
+            cache_options: token_cache::Options::default(),
         }
     }
 
@@ -263,6 +277,71 @@ impl Builder {
         self
     }
 
+    /// Configure a persistent store for the access tokens.
+    ///
+    /// With a [TokenStore], access tokens are reused across processes until
+    /// they are close to their expiration. Tokens are stored per service
+    /// account key and access specifier.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::service_account::Builder;
+    /// # use google_cloud_auth::credentials::token_store::FileTokenStore;
+    /// let service_account_key = serde_json::json!({ /* add details here */ });
+    /// let credentials = Builder::new(service_account_key)
+    ///     .with_token_store(FileTokenStore::new("/var/cache/my-application/tokens"))
+    ///     .build();
+    /// ```
+    pub fn with_token_store<S: TokenStore + 'static>(mut self, store: S) -> Self {
+        self.token_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets how long before the token expires the credentials refresh it.
+    ///
+    /// The credentials refresh access tokens in the background, before they
+    /// expire. By default they start refreshing 4 minutes before the token
+    /// expires. Applications that make requests with long-lived tokens, such
+    /// as streaming RPCs, may want to increase this value.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::service_account::Builder;
+    /// # tokio_test::block_on(async {
+    /// use std::time::Duration;
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_refresh_slack(Duration::from_secs(600))
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_refresh_slack(mut self, v: Duration) -> Self {
+        self.cache_options = self.cache_options.with_refresh_slack(v);
+        self
+    }
+
+    /// Sets an observer for the token lifecycle events.
+    ///
+    /// See the [token_observer][crate::credentials::token_observer] module
+    /// for more information.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::service_account::Builder;
+    /// # use google_cloud_auth::credentials::token_observer::TokenObserver;
+    /// # tokio_test::block_on(async {
+    /// #[derive(Debug)]
+    /// struct MyObserver;
+    /// impl TokenObserver for MyObserver {}
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_token_observer(MyObserver)
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_token_observer<O: TokenObserver + 'static>(mut self, v: O) -> Self {
+        self.cache_options = self.cache_options.with_observer(Arc::new(v));
+        self
+    }
+
     fn build_token_provider(self) -> BuildResult<ServiceAccountTokenProvider> {
         let service_account_key =
             serde_json::from_value::<ServiceAccountKey>(self.service_account_key)
@@ -287,15 +366,34 @@ impl Builder {
     ///
     /// [creating service account keys]: https://cloud.google.com/iam/docs/keys-create-delete#creating
     pub fn build(self) -> BuildResult<Credentials> {
+        let quota_project_id = self.quota_project_id.clone();
+        let store = self.token_store.clone();
+        let cache_options = self.cache_options.clone();
+        let token_provider = self.build_token_provider()?;
+        let key = store_key(&token_provider);
+        let universe_domain = token_provider.service_account_key.universe_domain.clone();
+        let token_provider =
+            StoredTokenProvider::new(token_provider, store, key, cache_options.refresh_slack());
         Ok(Credentials {
             inner: Arc::new(ServiceAccountCredentials {
-                quota_project_id: self.quota_project_id.clone(),
-                token_provider: TokenCache::new(self.build_token_provider()?),
+                quota_project_id,
+                universe_domain,
+                token_provider: TokenCache::with_options(token_provider, cache_options),
             }),
         })
     }
 }
 
+fn store_key(token_provider: &ServiceAccountTokenProvider) -> String {
+    let mut parts = vec![
+        "service_account",
+        token_provider.service_account_key.client_email.as_str(),
+        token_provider.service_account_key.private_key_id.as_str(),
+    ];
+    parts.extend(token_provider.access_specifier.store_key_parts());
+    token_store::store_key(&parts)
+}
+
 /// A representation of a [service account key].
 ///
 /// [Service Account Key]: https://cloud.google.com/iam/docs/keys-create-delete#creating
@@ -334,6 +432,7 @@ where
 {
     token_provider: T,
     quota_project_id: Option<String>,
+    universe_domain: Option<String>,
 }
 
 #[derive(Debug)]
@@ -375,20 +474,11 @@ impl TokenProvider for ServiceAccountTokenProvider {
         };
 
         let header = JwsHeader {
-            alg: "RS256",
+            alg: Algorithm::RS256.name(),
             typ: "JWT",
             kid: &self.service_account_key.private_key_id,
         };
-        let encoded_header_claims = format!("{}.{}", header.encode()?, claims.encode()?);
-        let sig = signer
-            .sign(encoded_header_claims.as_bytes())
-            .map_err(errors::non_retryable)?;
-        use base64::prelude::{BASE64_URL_SAFE_NO_PAD, Engine as _};
-        let token = format!(
-            "{}.{}",
-            encoded_header_claims,
-            &BASE64_URL_SAFE_NO_PAD.encode(sig)
-        );
+        let token = jws::sign(signer.as_ref(), Algorithm::RS256, &header, &claims)?;
 
         let token = Token {
             token,
@@ -402,32 +492,8 @@ impl TokenProvider for ServiceAccountTokenProvider {
 
 impl ServiceAccountTokenProvider {
     // Creates a signer using the private key stored in the service account file.
-    fn signer(&self, private_key: &String) -> Result<Box<dyn Signer>> {
-        let key_provider = CryptoProvider::get_default().map_or_else(
-            || rustls::crypto::ring::default_provider().key_provider,
-            |p| p.key_provider,
-        );
-
-        let private_key = rustls_pemfile::read_one(&mut private_key.as_bytes())
-            .map_err(errors::non_retryable)?
-            .ok_or_else(|| {
-                errors::non_retryable_from_str("missing PEM section in service account key")
-            })?;
-        let pk = match private_key {
-            Item::Pkcs8Key(item) => key_provider.load_private_key(item.into()),
-            other => {
-                return Err(Self::unexpected_private_key_error(other));
-            }
-        };
-        let sk = pk.map_err(errors::non_retryable)?;
-        sk.choose_scheme(&[rustls::SignatureScheme::RSA_PKCS1_SHA256])
-            .ok_or_else(|| errors::non_retryable_from_str("Unable to choose RSA_PKCS1_SHA256 signing scheme as it is not supported by current signer"))
-    }
-
-    fn unexpected_private_key_error(private_key_format: Item) -> CredentialsError {
-        errors::non_retryable_from_str(format!(
-            "expected key to be in form of PKCS8, found {private_key_format:?}",
-        ))
+    fn signer(&self, private_key: &str) -> Result<Box<dyn Signer>> {
+        jws::signer(private_key, Algorithm::RS256)
     }
 }
 
@@ -440,6 +506,18 @@ where
         let token = self.token_provider.token(extensions).await?;
         build_cacheable_headers(&token, &self.quota_project_id)
     }
+
+    async fn universe_domain(&self) -> Option<String> {
+        Some(
+            self.universe_domain
+                .clone()
+                .unwrap_or_else(|| DEFAULT_UNIVERSE_DOMAIN.to_string()),
+        )
+    }
+
+    async fn force_refresh(&self) {
+        self.token_provider.force_refresh();
+    }
 }
 
 #[cfg(test)]
@@ -449,12 +527,12 @@ mod tests {
     use crate::credentials::tests::{
         PKCS8_PK, b64_decode_to_json, get_headers_from_cache, get_token_from_headers,
     };
+    use crate::errors;
     use crate::token::tests::MockTokenProvider;
     use http::HeaderValue;
     use http::header::AUTHORIZATION;
     use rsa::pkcs1::EncodeRsaPrivateKey;
     use rsa::pkcs8::LineEnding;
-    use rustls_pemfile::Item;
     use serde_json::json;
     use std::error::Error as _;
     use std::time::Duration;
@@ -509,6 +587,7 @@ mod tests {
         let sac = ServiceAccountCredentials {
             token_provider: TokenCache::new(mock),
             quota_project_id: None,
+            universe_domain: None,
         };
 
         let mut extensions = Extensions::new();
@@ -551,6 +630,7 @@ mod tests {
         let sac = ServiceAccountCredentials {
             token_provider: TokenCache::new(mock),
             quota_project_id: Some(quota_project.to_string()),
+            universe_domain: None,
         };
 
         let headers = get_headers_from_cache(sac.headers(Extensions::new()).await.unwrap())?;
@@ -578,10 +658,44 @@ mod tests {
         let sac = ServiceAccountCredentials {
             token_provider: TokenCache::new(mock),
             quota_project_id: None,
+            universe_domain: None,
         };
         assert!(sac.headers(Extensions::new()).await.is_err());
     }
 
+    #[test]
+    fn stable_store_key() -> TestResult {
+        let scopes = Builder::new(get_mock_service_key())
+            .with_access_specifier(AccessSpecifier::from_scopes(["scope1", "scope2"]))
+            .build_token_provider()?;
+        assert_eq!(
+            store_key(&scopes),
+            token_store::store_key(&[
+                "service_account",
+                "test-client-email",
+                "test-private-key-id",
+                "scopes",
+                "scope1",
+                "scope2",
+            ])
+        );
+
+        let audience = Builder::new(get_mock_service_key())
+            .with_access_specifier(AccessSpecifier::from_audience("test-audience"))
+            .build_token_provider()?;
+        assert_eq!(
+            store_key(&audience),
+            token_store::store_key(&[
+                "service_account",
+                "test-client-email",
+                "test-private-key-id",
+                "audience",
+                "test-audience",
+            ])
+        );
+        Ok(())
+    }
+
     fn get_mock_service_key() -> Value {
         json!({
             "client_email": "test-client-email",
@@ -602,7 +716,8 @@ mod tests {
 
         service_account_key["private_key"] = Value::from(key);
         let cred = Builder::new(service_account_key).build()?;
-        let expected_error_message = "expected key to be in form of PKCS8, found Pkcs1Key";
+        let expected_error_message =
+            "expected key to be in form of PKCS8 (or SEC1 for ES256), found Pkcs1Key";
         assert!(
             cred.headers(Extensions::new())
                 .await
@@ -718,16 +833,26 @@ mod tests {
         Ok(())
     }
 
-    #[test]
-    fn unexpected_private_key_error_message() -> TestResult {
-        let expected_message = format!(
-            "expected key to be in form of PKCS8, found {:?}",
-            Item::Crl(Vec::new().into()) // Example unsupported key type
+    #[tokio::test]
+    async fn universe_domain() -> TestResult {
+        let mut service_account_key = get_mock_service_key();
+        service_account_key["universe_domain"] = Value::from("my-universe.example.com");
+        let credentials = Builder::new(service_account_key).build()?;
+        assert_eq!(
+            credentials.universe_domain().await.as_deref(),
+            Some("my-universe.example.com")
         );
 
-        let error =
-            ServiceAccountTokenProvider::unexpected_private_key_error(Item::Crl(Vec::new().into()));
-        assert!(error.to_string().contains(&expected_message));
+        let mut service_account_key = get_mock_service_key();
+        service_account_key
+            .as_object_mut()
+            .unwrap()
+            .remove("universe_domain");
+        let credentials = Builder::new(service_account_key).build()?;
+        assert_eq!(
+            credentials.universe_domain().await.as_deref(),
+            Some(DEFAULT_UNIVERSE_DOMAIN)
+        );
         Ok(())
     }
 
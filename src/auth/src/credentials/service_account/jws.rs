@@ -13,7 +13,11 @@
 // limitations under the License.
 
 use crate::credentials::Result;
-use crate::errors;
+use crate::errors::{self, CredentialsError};
+use rustls::SignatureScheme;
+use rustls::crypto::CryptoProvider;
+use rustls::sign::Signer;
+use rustls_pemfile::Item;
 use serde::Serialize;
 use std::time::Duration;
 use time::OffsetDateTime;
@@ -83,6 +87,141 @@ impl JwsHeader<'_> {
     }
 }
 
+/// The algorithms used to sign a JWS.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    /// RSASSA-PKCS1-v1_5 using SHA-256, used by service account keys.
+    RS256,
+    /// ECDSA using P-256 and SHA-256, used by GDCH service account keys.
+    ES256,
+}
+
+impl Algorithm {
+    /// The value for the `alg` field in the [JwsHeader].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::RS256 => "RS256",
+            Self::ES256 => "ES256",
+        }
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        match self {
+            Self::RS256 => SignatureScheme::RSA_PKCS1_SHA256,
+            Self::ES256 => SignatureScheme::ECDSA_NISTP256_SHA256,
+        }
+    }
+}
+
+/// Creates a signer for `algorithm` from a PEM-encoded private key.
+pub fn signer(private_key: &str, algorithm: Algorithm) -> Result<Box<dyn Signer>> {
+    let key_provider = CryptoProvider::get_default().map_or_else(
+        || rustls::crypto::ring::default_provider().key_provider,
+        |p| p.key_provider,
+    );
+
+    let private_key = rustls_pemfile::read_one(&mut private_key.as_bytes())
+        .map_err(errors::non_retryable)?
+        .ok_or_else(|| {
+            errors::non_retryable_from_str("missing PEM section in service account key")
+        })?;
+    let pk = match (private_key, algorithm) {
+        (Item::Pkcs8Key(item), _) => key_provider.load_private_key(item.into()),
+        // GDCH keys are often in the SEC1 (`BEGIN EC PRIVATE KEY`) format.
+        (Item::Sec1Key(item), Algorithm::ES256) => key_provider.load_private_key(item.into()),
+        (other, _) => {
+            return Err(unexpected_private_key_error(other));
+        }
+    };
+    let sk = pk.map_err(errors::non_retryable)?;
+    let scheme = algorithm.scheme();
+    sk.choose_scheme(&[scheme]).ok_or_else(|| {
+        errors::non_retryable_from_str(format!(
+            "Unable to choose {scheme:?} signing scheme as it is not supported by current signer"
+        ))
+    })
+}
+
+pub fn unexpected_private_key_error(private_key_format: Item) -> CredentialsError {
+    errors::non_retryable_from_str(format!(
+        "expected key to be in form of PKCS8 (or SEC1 for ES256), found {private_key_format:?}",
+    ))
+}
+
+/// Encodes the header and claims, and signs them, returning a complete JWT.
+pub fn sign(
+    signer: &dyn Signer,
+    algorithm: Algorithm,
+    header: &JwsHeader,
+    claims: &JwsClaims,
+) -> Result<String> {
+    use base64::prelude::{BASE64_URL_SAFE_NO_PAD, Engine as _};
+    let encoded_header_claims = format!("{}.{}", header.encode()?, claims.encode()?);
+    let sig = signer
+        .sign(encoded_header_claims.as_bytes())
+        .map_err(errors::non_retryable)?;
+    let sig = match algorithm {
+        Algorithm::RS256 => sig,
+        // The signers produce ASN.1 DER encoded ECDSA signatures, JWS uses
+        // the fixed-width `R || S` encoding.
+        Algorithm::ES256 => der_to_fixed(&sig, 32)?,
+    };
+    Ok(format!(
+        "{}.{}",
+        encoded_header_claims,
+        &BASE64_URL_SAFE_NO_PAD.encode(sig)
+    ))
+}
+
+/// Converts an ASN.1 DER `SEQUENCE { r INTEGER, s INTEGER }` to `R || S`.
+///
+/// Each integer is left-padded with zeros to `width` bytes.
+fn der_to_fixed(der: &[u8], width: usize) -> Result<Vec<u8>> {
+    fn invalid() -> CredentialsError {
+        errors::non_retryable_from_str("invalid ECDSA signature encoding")
+    }
+    // Returns the contents of the element with `tag`, and the remaining bytes.
+    fn element(tag: u8, input: &[u8]) -> Result<(&[u8], &[u8])> {
+        match input {
+            [t, len, rest @ ..] if *t == tag && *len < 0x80 => {
+                let len = *len as usize;
+                if rest.len() < len {
+                    return Err(invalid());
+                }
+                Ok(rest.split_at(len))
+            }
+            [t, 0x81, len, rest @ ..] if *t == tag => {
+                let len = *len as usize;
+                if rest.len() < len {
+                    return Err(invalid());
+                }
+                Ok(rest.split_at(len))
+            }
+            _ => Err(invalid()),
+        }
+    }
+    const SEQUENCE: u8 = 0x30;
+    const INTEGER: u8 = 0x02;
+
+    let (sequence, _) = element(SEQUENCE, der)?;
+    let (r, rest) = element(INTEGER, sequence)?;
+    let (s, _) = element(INTEGER, rest)?;
+    let mut fixed = Vec::with_capacity(2 * width);
+    for n in [r, s] {
+        // DER integers are signed, positive values may have a leading zero.
+        let n = match n {
+            [0, tail @ ..] => tail,
+            _ => n,
+        };
+        if n.len() > width {
+            return Err(invalid());
+        }
+        fixed.extend(std::iter::repeat_n(0_u8, width - n.len()));
+        fixed.extend_from_slice(n);
+    }
+    Ok(fixed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,4 +379,48 @@ mod tests {
         assert_eq!(v["typ"], "JWT");
         assert_eq!(v.get("kid"), None);
     }
+
+    #[test]
+    fn algorithm_names() {
+        assert_eq!(Algorithm::RS256.name(), "RS256");
+        assert_eq!(Algorithm::ES256.name(), "ES256");
+    }
+
+    #[test]
+    fn unexpected_private_key_error_message() {
+        let expected_message = format!(
+            "expected key to be in form of PKCS8 (or SEC1 for ES256), found {:?}",
+            Item::Crl(Vec::new().into()) // Example unsupported key type
+        );
+
+        let error = unexpected_private_key_error(Item::Crl(Vec::new().into()));
+        assert!(error.to_string().contains(&expected_message));
+    }
+
+    #[test]
+    fn der_to_fixed_pads() {
+        // r = 0x01, s = 0x80 0x02 (needs a leading zero in DER).
+        let der = [0x30, 0x08, 0x02, 0x01, 0x01, 0x02, 0x03, 0x00, 0x80, 0x02];
+        let fixed = der_to_fixed(&der, 4).unwrap();
+        assert_eq!(fixed, vec![0, 0, 0, 1, 0, 0, 0x80, 2]);
+    }
+
+    #[test]
+    fn der_to_fixed_errors() {
+        // Not a sequence.
+        assert!(der_to_fixed(&[0x02, 0x01, 0x01], 32).is_err());
+        // Truncated.
+        assert!(der_to_fixed(&[0x30, 0x08, 0x02, 0x01], 32).is_err());
+        // Integer too large for the width.
+        let der = [0x30, 0x08, 0x02, 0x03, 0x01, 0x02, 0x03, 0x02, 0x01, 0x01];
+        assert!(der_to_fixed(&der, 2).is_err());
+    }
+
+    #[test]
+    fn signer_rejects_wrong_key_type() {
+        // An RSA key cannot produce ES256 signatures.
+        let pem = crate::credentials::tests::PKCS8_PK.clone();
+        let e = signer(&pem, Algorithm::ES256).err().unwrap();
+        assert!(e.to_string().contains("ECDSA_NISTP256_SHA256"), "{e:?}");
+    }
 }
@@ -0,0 +1,92 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Observe the lifecycle of access tokens.
+//!
+//! The credentials refresh access tokens in the background, before they
+//! expire. Applications may want to record metrics about these refreshes, or
+//! log failures. Implement [TokenObserver] and configure it in the credentials
+//! builder to receive these events.
+//!
+//! The credentials also emit [tracing] spans and events for each refresh,
+//! with the `google_cloud_auth` target.
+//!
+//! # Example
+//! ```
+//! # use google_cloud_auth::credentials::token_observer::TokenObserver;
+//! # use google_cloud_auth::credentials::mds::Builder;
+//! # use google_cloud_auth::errors::CredentialsError;
+//! # use std::sync::atomic::{AtomicU64, Ordering};
+//! # use std::time::Duration;
+//! # tokio_test::block_on(async {
+//! #[derive(Debug, Default)]
+//! struct RefreshCounter {
+//!     success: AtomicU64,
+//!     failure: AtomicU64,
+//! }
+//!
+//! impl TokenObserver for RefreshCounter {
+//!     fn on_refresh_success(&self, _latency: Duration, _expires_in: Option<Duration>) {
+//!         self.success.fetch_add(1, Ordering::Relaxed);
+//!     }
+//!     fn on_refresh_failure(&self, _latency: Duration, _error: &CredentialsError) {
+//!         self.failure.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! let credentials = Builder::default()
+//!     .with_token_observer(RefreshCounter::default())
+//!     .build()?;
+//! # Ok::<(), anyhow::Error>(())
+//! # });
+//! ```
+//!
+//! [tracing]: https://docs.rs/tracing
+
+use crate::errors::CredentialsError;
+use std::time::Duration;
+
+/// Receives notifications about the lifecycle of access tokens.
+///
+/// All the functions have default implementations that do nothing, so
+/// applications only need to implement the notifications they care about.
+///
+/// The functions are called from the background task refreshing tokens, or
+/// from the task requesting the authentication headers. They should return
+/// quickly, and must not block.
+pub trait TokenObserver: std::fmt::Debug + Send + Sync {
+    /// Called after a new token is successfully fetched.
+    ///
+    /// `latency` is the time spent fetching the token, including any retry
+    /// attempts. `expires_in` is the remaining lifetime of the new token, it
+    /// is `None` if the token does not expire.
+    fn on_refresh_success(&self, latency: Duration, expires_in: Option<Duration>) {
+        let _ = (latency, expires_in);
+    }
+
+    /// Called after a token refresh fails, after any retry attempts.
+    fn on_refresh_failure(&self, latency: Duration, error: &CredentialsError) {
+        let _ = (latency, error);
+    }
+
+    /// Called when a request finds the cached token expired.
+    ///
+    /// The request waits until a new token is available. Frequent calls may
+    /// indicate the refresh slack is too small.
+    fn on_token_expired(&self) {}
+
+    /// Called when the application forces a refresh, typically after a
+    /// service rejects the current token.
+    fn on_forced_refresh(&self) {}
+}
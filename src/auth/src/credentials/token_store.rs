@@ -0,0 +1,763 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Persistent token storage shared across processes.
+//!
+//! By default, the credentials cache access tokens in memory. Short-lived
+//! processes, such as command-line tools, or applications that start many
+//! subprocesses, fetch a new token on every run. A [TokenStore] persists
+//! tokens outside the process, so they can be reused until they are close to
+//! their expiration.
+//!
+//! The store is consulted before fetching a new token. If it contains a token
+//! for the same credentials and access specifier (e.g. scopes), and the token
+//! is not about to expire, the credentials use it. Otherwise the credentials
+//! fetch a new token and save it in the store. If a service rejects a token,
+//! the credentials fetch a new token without consulting the store, and replace
+//! the rejected token in the store. Errors reading or writing the store are not
+//! fatal, the credentials simply fetch a new token.
+//!
+//! This module provides [FileTokenStore], which saves each token in a file
+//! only readable by the current user. Applications can implement [TokenStore]
+//! to use other backends, such as the operating system keychain.
+//!
+//! # Example
+//! ```
+//! # use google_cloud_auth::credentials::token_store::FileTokenStore;
+//! # use google_cloud_auth::credentials::user_account::Builder;
+//! # tokio_test::block_on(async {
+//! let authorized_user = serde_json::json!({
+//!     "client_id": "YOUR_CLIENT_ID.apps.googleusercontent.com",
+//!     "client_secret": "YOUR_CLIENT_SECRET",
+//!     "refresh_token": "YOUR_REFRESH_TOKEN",
+//!     "type": "authorized_user",
+//! });
+//! let store = FileTokenStore::new("/var/cache/my-application/tokens");
+//! let credentials = Builder::new(authorized_user)
+//!     .with_token_store(store)
+//!     .build()?;
+//! # Ok::<(), anyhow::Error>(())
+//! # });
+//! ```
+
+use crate::Result;
+use crate::errors;
+use crate::token::{Token, TokenProvider};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tokio::time::{Duration, Instant};
+
+/// An access token saved in a [TokenStore].
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct StoredToken {
+    /// The token value, used in the `Authorization:` header.
+    pub token: String,
+
+    /// The type of the token, typically `"Bearer"`.
+    pub token_type: String,
+
+    /// The time at which the token expires.
+    ///
+    /// If `None`, the token does not expire.
+    #[serde(with = "time::serde::timestamp::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl std::fmt::Debug for StoredToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoredToken")
+            .field("token", &"[censored]")
+            .field("token_type", &self.token_type)
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+impl From<&Token> for StoredToken {
+    fn from(value: &Token) -> Self {
+        let expires_at = value.expires_at.map(|e| {
+            let remaining = e.saturating_duration_since(Instant::now());
+            OffsetDateTime::now_utc() + remaining
+        });
+        Self {
+            token: value.token.clone(),
+            token_type: value.token_type.clone(),
+            expires_at,
+        }
+    }
+}
+
+impl StoredToken {
+    // Returns the token if it is valid beyond the refresh slack.
+    fn into_fresh_token(self, refresh_slack: Duration) -> Option<Token> {
+        let expires_at = match self.expires_at {
+            None => None,
+            Some(e) => {
+                let remaining = e - OffsetDateTime::now_utc();
+                let remaining = Duration::try_from(remaining).ok()?;
+                if remaining <= refresh_slack {
+                    return None;
+                }
+                Some(Instant::now() + remaining)
+            }
+        };
+        Some(Token {
+            token: self.token,
+            token_type: self.token_type,
+            expires_at,
+            metadata: None,
+        })
+    }
+}
+
+/// A persistent store for access tokens.
+///
+/// Implementations must be safe to use from multiple processes, as the main
+/// use-case for this trait is sharing tokens across processes.
+///
+/// The credentials compute the `key` from the identity of the credentials
+/// (e.g. the OAuth client and refresh token) and the access specifier (e.g.
+/// the scopes). Keys only contain ASCII letters, digits, `-` and `_`.
+/// Implementations can use them as file names or database keys.
+///
+/// # Example
+/// ```
+/// # use google_cloud_auth::credentials::token_store::{StoredToken, TokenStore};
+/// # use google_cloud_auth::errors::CredentialsError;
+/// # use std::collections::HashMap;
+/// # use std::sync::Mutex;
+/// #[derive(Debug, Default)]
+/// struct MyStore(Mutex<HashMap<String, StoredToken>>);
+///
+/// impl TokenStore for MyStore {
+///     async fn load(&self, key: &str) -> Result<Option<StoredToken>, CredentialsError> {
+///         Ok(self.0.lock().unwrap().get(key).cloned())
+///     }
+///     async fn store(&self, key: &str, token: StoredToken) -> Result<(), CredentialsError> {
+///         self.0.lock().unwrap().insert(key.to_string(), token);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait TokenStore: std::fmt::Debug + Send + Sync {
+    /// Loads the token saved under `key`, if any.
+    fn load(&self, key: &str) -> impl Future<Output = Result<Option<StoredToken>>> + Send;
+
+    /// Saves `token` under `key`, replacing any previous value.
+    fn store(&self, key: &str, token: StoredToken) -> impl Future<Output = Result<()>> + Send;
+}
+
+pub(crate) mod dynamic {
+    use super::{Result, StoredToken};
+
+    /// A dyn-compatible, crate-private version of `TokenStore`.
+    #[async_trait::async_trait]
+    pub trait TokenStore: std::fmt::Debug + Send + Sync {
+        async fn load(&self, key: &str) -> Result<Option<StoredToken>>;
+        async fn store(&self, key: &str, token: StoredToken) -> Result<()>;
+    }
+
+    /// The public TokenStore implements the dyn-compatible TokenStore.
+    #[async_trait::async_trait]
+    impl<T> TokenStore for T
+    where
+        T: super::TokenStore,
+    {
+        async fn load(&self, key: &str) -> Result<Option<StoredToken>> {
+            T::load(self, key).await
+        }
+        async fn store(&self, key: &str, token: StoredToken) -> Result<()> {
+            T::store(self, key, token).await
+        }
+    }
+}
+
+/// A [TokenStore] saving each token in a file.
+///
+/// Each token is saved in a separate file in the configured directory. On
+/// Unix-like systems the directory is created with `0700` permissions and the
+/// files with `0600` permissions, so only the current user can read them.
+///
+/// Writers take a lock file, and replace the token file atomically. Readers
+/// never observe partially written tokens.
+#[derive(Clone, Debug)]
+pub struct FileTokenStore {
+    dir: PathBuf,
+}
+
+// A lock file older than this is assumed to be left behind by a crashed
+// process.
+const STALE_LOCK: std::time::Duration = std::time::Duration::from_secs(30);
+const LOCK_ATTEMPTS: u32 = 100;
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(20);
+
+impl FileTokenStore {
+    /// Creates a store saving tokens in `dir`.
+    ///
+    /// The directory is created, if needed, the first time a token is saved.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str, extension: &str) -> Result<PathBuf> {
+        let valid = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(errors::non_retryable_from_str(format!(
+                "invalid token store key <{key}>"
+            )));
+        }
+        Ok(self.dir.join(format!("{key}.{extension}")))
+    }
+
+    async fn create_dir(&self) -> Result<()> {
+        let mut builder = tokio::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        builder.mode(0o700);
+        builder
+            .create(&self.dir)
+            .await
+            .map_err(errors::non_retryable)
+    }
+
+    async fn lock(&self, key: &str) -> Result<LockFile> {
+        let path = self.path(key, "lock")?;
+        for _ in 0..LOCK_ATTEMPTS {
+            match open_new(&path).await {
+                Ok(_) => return Ok(LockFile(path)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale(&path).await {
+                        let _ = tokio::fs::remove_file(&path).await;
+                        continue;
+                    }
+                    tokio::time::sleep(LOCK_RETRY_DELAY).await;
+                }
+                Err(e) => return Err(errors::non_retryable(e)),
+            }
+        }
+        Err(errors::non_retryable_from_str(format!(
+            "timeout acquiring lock file {}",
+            path.display()
+        )))
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    async fn load(&self, key: &str) -> Result<Option<StoredToken>> {
+        let path = self.path(key, "json")?;
+        let contents = match tokio::fs::read(&path).await {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(errors::non_retryable(e)),
+        };
+        // A corrupted file is treated as a cache miss, the next `store()`
+        // call replaces it.
+        Ok(serde_json::from_slice(&contents).ok())
+    }
+
+    async fn store(&self, key: &str, token: StoredToken) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let path = self.path(key, "json")?;
+        let contents = serde_json::to_vec(&token).map_err(errors::non_retryable)?;
+        self.create_dir().await?;
+        let lock = self.lock(key).await?;
+        let tmp = self.path(key, "tmp")?;
+        let mut file = open_new(&tmp).await.map_err(errors::non_retryable)?;
+        let result = async {
+            file.write_all(&contents).await?;
+            file.sync_all().await?;
+            drop(file);
+            tokio::fs::rename(&tmp, &path).await
+        }
+        .await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+        }
+        lock.release().await;
+        result.map_err(errors::non_retryable)
+    }
+}
+
+async fn open_new(path: &Path) -> std::io::Result<tokio::fs::File> {
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(path).await
+}
+
+async fn is_stale(path: &Path) -> bool {
+    let modified = match tokio::fs::metadata(path).await {
+        Ok(m) => m.modified(),
+        // If the lock disappeared, retrying is the right thing to do.
+        Err(_) => return false,
+    };
+    modified
+        .ok()
+        .and_then(|m| m.elapsed().ok())
+        .is_some_and(|elapsed| elapsed > STALE_LOCK)
+}
+
+struct LockFile(PathBuf);
+
+impl LockFile {
+    async fn release(self) {
+        let _ = tokio::fs::remove_file(&self.0).await;
+    }
+}
+
+/// Computes a [TokenStore] key from the identity of some credentials.
+///
+/// The key is a SHA-256 digest, so secrets included in `parts` (such as
+/// refresh tokens) are not exposed through the key.
+pub(crate) fn store_key(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for p in parts {
+        hasher.update(p.as_bytes());
+        // Separate the parts, so ["ab", "c"] and ["a", "bc"] differ.
+        hasher.update([0_u8]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// A [TokenProvider] consulting a [TokenStore] before fetching new tokens.
+///
+/// If no store is configured, this simply forwards to the inner provider.
+#[derive(Debug)]
+pub(crate) struct StoredTokenProvider<T> {
+    inner: T,
+    store: Option<Arc<dyn dynamic::TokenStore>>,
+    key: String,
+    refresh_slack: Duration,
+}
+
+impl<T> StoredTokenProvider<T> {
+    /// Creates a new provider.
+    ///
+    /// Stored tokens expiring within `refresh_slack` are not used, this should
+    /// match the refresh slack of the token cache wrapping this provider.
+    pub(crate) fn new(
+        inner: T,
+        store: Option<Arc<dyn dynamic::TokenStore>>,
+        key: String,
+        refresh_slack: Duration,
+    ) -> Self {
+        Self {
+            inner,
+            store,
+            key,
+            refresh_slack,
+        }
+    }
+}
+
+impl<T> StoredTokenProvider<T>
+where
+    T: TokenProvider,
+{
+    async fn fetch_and_store(&self, store: &Arc<dyn dynamic::TokenStore>) -> Result<Token> {
+        let token = self.inner.token().await?;
+        // Saving the token is best effort, the next process fetches a new
+        // token if this fails.
+        let _ = store.store(&self.key, StoredToken::from(&token)).await;
+        Ok(token)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> TokenProvider for StoredTokenProvider<T>
+where
+    T: TokenProvider,
+{
+    async fn token(&self) -> Result<Token> {
+        let Some(store) = &self.store else {
+            return self.inner.token().await;
+        };
+        if let Ok(Some(stored)) = store.load(&self.key).await {
+            if let Some(token) = stored.into_fresh_token(self.refresh_slack) {
+                return Ok(token);
+            }
+        }
+        self.fetch_and_store(store).await
+    }
+
+    async fn forced_token(&self) -> Result<Token> {
+        // The stored token is likely the token the service rejected. Fetch a
+        // new one, and replace the stored token so other processes stop using
+        // the rejected token too.
+        let Some(store) = &self.store else {
+            return self.inner.forced_token().await;
+        };
+        self.fetch_and_store(store).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::tests::MockTokenProvider;
+    use crate::token_cache::NORMAL_REFRESH_SLACK;
+    use std::sync::Mutex;
+
+    type TestResult = anyhow::Result<()>;
+
+    fn token(name: &str, expires_in: Option<Duration>) -> Token {
+        Token {
+            token: name.to_string(),
+            token_type: "Bearer".to_string(),
+            expires_at: expires_in.map(|d| Instant::now() + d),
+            metadata: None,
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct MemoryStore(Mutex<std::collections::HashMap<String, StoredToken>>);
+
+    impl TokenStore for MemoryStore {
+        async fn load(&self, key: &str) -> Result<Option<StoredToken>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+        async fn store(&self, key: &str, token: StoredToken) -> Result<()> {
+            self.0.lock().unwrap().insert(key.to_string(), token);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct ErrorStore;
+
+    impl TokenStore for ErrorStore {
+        async fn load(&self, _key: &str) -> Result<Option<StoredToken>> {
+            Err(errors::non_retryable_from_str("load failed"))
+        }
+        async fn store(&self, _key: &str, _token: StoredToken) -> Result<()> {
+            Err(errors::non_retryable_from_str("store failed"))
+        }
+    }
+
+    #[test]
+    fn key() {
+        let a = store_key(&["ab", "c"]);
+        let b = store_key(&["a", "bc"]);
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 64, "{a}");
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()), "{a}");
+        assert_eq!(a, store_key(&["ab", "c"]));
+    }
+
+    #[test]
+    fn stored_token_debug() {
+        let stored = StoredToken::from(&token("secret-token", None));
+        let fmt = format!("{stored:?}");
+        assert!(!fmt.contains("secret-token"), "{fmt}");
+    }
+
+    #[test]
+    fn freshness() {
+        let stored = StoredToken::from(&token("t", Some(Duration::from_secs(3600))));
+        let fresh = stored
+            .into_fresh_token(NORMAL_REFRESH_SLACK)
+            .expect("token should be fresh");
+        assert_eq!(fresh.token, "t");
+        let remaining = fresh.expires_at.unwrap() - Instant::now();
+        assert!(remaining > Duration::from_secs(3590), "{remaining:?}");
+
+        let stored = StoredToken::from(&token("t", Some(NORMAL_REFRESH_SLACK / 2)));
+        assert!(stored.into_fresh_token(NORMAL_REFRESH_SLACK).is_none());
+
+        let mut stored = StoredToken::from(&token("t", None));
+        stored.expires_at = Some(OffsetDateTime::now_utc() - Duration::from_secs(60));
+        assert!(stored.into_fresh_token(NORMAL_REFRESH_SLACK).is_none());
+
+        let stored = StoredToken::from(&token("t", None));
+        let fresh = stored
+            .into_fresh_token(NORMAL_REFRESH_SLACK)
+            .expect("token should be fresh");
+        assert!(fresh.expires_at.is_none(), "{fresh:?}");
+    }
+
+    #[tokio::test]
+    async fn file_store_roundtrip() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let store = FileTokenStore::new(dir.path().join("tokens"));
+        assert!(store.load("test-key").await?.is_none());
+
+        let stored = StoredToken::from(&token("t1", Some(Duration::from_secs(3600))));
+        store.store("test-key", stored.clone()).await?;
+        let got = store.load("test-key").await?;
+        assert_eq!(got.as_ref().map(|t| t.token.as_str()), Some("t1"));
+        assert_eq!(
+            got.and_then(|t| t.expires_at).map(|e| e.unix_timestamp()),
+            stored.expires_at.map(|e| e.unix_timestamp())
+        );
+
+        let stored = StoredToken::from(&token("t2", None));
+        store.store("test-key", stored).await?;
+        let got = store.load("test-key").await?;
+        assert_eq!(got.map(|t| t.token), Some("t2".to_string()));
+
+        // Another store for the same directory sees the same tokens.
+        let other = FileTokenStore::new(dir.path().join("tokens"));
+        let got = other.load("test-key").await?;
+        assert_eq!(got.map(|t| t.token), Some("t2".to_string()));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_store_permissions() -> TestResult {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir()?;
+        let store = FileTokenStore::new(dir.path().join("tokens"));
+        store
+            .store("test-key", StoredToken::from(&token("t", None)))
+            .await?;
+        let mode = std::fs::metadata(dir.path().join("tokens"))?
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700, "{mode:o}");
+        let mode = std::fs::metadata(dir.path().join("tokens/test-key.json"))?
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600, "{mode:o}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_store_corrupted() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("test-key.json"), "not json")?;
+        let store = FileTokenStore::new(dir.path());
+        assert!(store.load("test-key").await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_store_invalid_key() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let store = FileTokenStore::new(dir.path());
+        let err = store.load("../escape").await.unwrap_err();
+        assert!(err.to_string().contains("invalid token store key"), "{err}");
+        let err = store
+            .store("", StoredToken::from(&token("t", None)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid token store key"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_store_stale_lock() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let lock = dir.path().join("test-key.lock");
+        let file = std::fs::File::create(&lock)?;
+        file.set_modified(std::time::SystemTime::now() - 2 * STALE_LOCK)?;
+        drop(file);
+
+        let store = FileTokenStore::new(dir.path());
+        store
+            .store("test-key", StoredToken::from(&token("t", None)))
+            .await?;
+        assert!(!lock.exists());
+        assert!(store.load("test-key").await?.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_store_concurrent_writers() -> TestResult {
+        let dir = tempfile::tempdir()?;
+        let store = FileTokenStore::new(dir.path());
+        let tasks = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    let t = StoredToken::from(&token(&format!("t{i}"), None));
+                    store.store("test-key", t).await
+                })
+            })
+            .collect::<Vec<_>>();
+        for t in tasks {
+            t.await??;
+        }
+        let got = store.load("test-key").await?.map(|t| t.token);
+        assert!(got.is_some_and(|t| t.starts_with('t')));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn provider_cache_miss_then_hit() -> TestResult {
+        let store: Arc<dyn dynamic::TokenStore> = Arc::new(MemoryStore::default());
+
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .returning(|| Ok(token("minted", Some(Duration::from_secs(3600)))));
+        let provider =
+            StoredTokenProvider::new(mock, Some(store.clone()), "k".into(), NORMAL_REFRESH_SLACK);
+        assert_eq!(provider.token().await?.token, "minted");
+
+        // A second provider, e.g. in another process, uses the stored token.
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token().never();
+        let provider =
+            StoredTokenProvider::new(mock, Some(store.clone()), "k".into(), NORMAL_REFRESH_SLACK);
+        assert_eq!(provider.token().await?.token, "minted");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn provider_refreshes_near_expiry() -> TestResult {
+        let store: Arc<dyn dynamic::TokenStore> = Arc::new(MemoryStore::default());
+        store
+            .store(
+                "k",
+                StoredToken::from(&token("old", Some(NORMAL_REFRESH_SLACK / 2))),
+            )
+            .await?;
+
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .returning(|| Ok(token("new", Some(Duration::from_secs(3600)))));
+        let provider =
+            StoredTokenProvider::new(mock, Some(store.clone()), "k".into(), NORMAL_REFRESH_SLACK);
+        assert_eq!(provider.token().await?.token, "new");
+        let stored = store.load("k").await?.map(|t| t.token);
+        assert_eq!(stored.as_deref(), Some("new"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn provider_without_store() -> TestResult {
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(2)
+            .returning(|| Ok(token("minted", Some(Duration::from_secs(3600)))));
+        let provider = StoredTokenProvider::new(mock, None, "k".into(), NORMAL_REFRESH_SLACK);
+        assert_eq!(provider.token().await?.token, "minted");
+        assert_eq!(provider.token().await?.token, "minted");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn provider_store_errors_are_ignored() -> TestResult {
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .returning(|| Ok(token("minted", Some(Duration::from_secs(3600)))));
+        let provider = StoredTokenProvider::new(
+            mock,
+            Some(Arc::new(ErrorStore)),
+            "k".into(),
+            NORMAL_REFRESH_SLACK,
+        );
+        assert_eq!(provider.token().await?.token, "minted");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn provider_errors_are_not_stored() -> TestResult {
+        let store: Arc<dyn dynamic::TokenStore> = Arc::new(MemoryStore::default());
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .returning(|| Err(errors::non_retryable_from_str("fail")));
+        let provider =
+            StoredTokenProvider::new(mock, Some(store.clone()), "k".into(), NORMAL_REFRESH_SLACK);
+        assert!(provider.token().await.is_err());
+        assert!(store.load("k").await?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn freshness_custom_slack() {
+        let slack = Duration::from_secs(900);
+        let stored = StoredToken::from(&token("t", Some(Duration::from_secs(600))));
+        assert!(stored.clone().into_fresh_token(slack).is_none());
+        assert!(stored.into_fresh_token(NORMAL_REFRESH_SLACK).is_some());
+    }
+
+    #[tokio::test]
+    async fn provider_forced_token_skips_store() -> TestResult {
+        let store: Arc<dyn dynamic::TokenStore> = Arc::new(MemoryStore::default());
+        store
+            .store(
+                "k",
+                StoredToken::from(&token("rejected", Some(Duration::from_secs(3600)))),
+            )
+            .await?;
+
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .returning(|| Ok(token("new", Some(Duration::from_secs(3600)))));
+        let provider =
+            StoredTokenProvider::new(mock, Some(store.clone()), "k".into(), NORMAL_REFRESH_SLACK);
+        assert_eq!(provider.forced_token().await?.token, "new");
+        let stored = store.load("k").await?.map(|t| t.token);
+        assert_eq!(stored.as_deref(), Some("new"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cache_force_refresh_with_store() -> TestResult {
+        use crate::credentials::CacheableResource;
+        use crate::token::CachedTokenProvider;
+        use crate::token_cache::TokenCache;
+        use http::Extensions;
+
+        let store: Arc<dyn dynamic::TokenStore> = Arc::new(MemoryStore::default());
+        store
+            .store(
+                "k",
+                StoredToken::from(&token("revoked", Some(Duration::from_secs(3600)))),
+            )
+            .await?;
+
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .returning(|| Ok(token("new", Some(Duration::from_secs(3600)))));
+        let provider =
+            StoredTokenProvider::new(mock, Some(store.clone()), "k".into(), NORMAL_REFRESH_SLACK);
+        let cache = TokenCache::new(provider);
+        let got = cache.token(Extensions::new()).await?;
+        assert!(
+            matches!(&got, CacheableResource::New { data, .. } if data.token == "revoked"),
+            "{got:?}"
+        );
+
+        // The service rejected the stored token, the cache must not reuse it.
+        cache.force_refresh();
+        let got = cache.token(Extensions::new()).await?;
+        assert!(
+            matches!(&got, CacheableResource::New { data, .. } if data.token == "new"),
+            "{got:?}"
+        );
+        let stored = store.load("k").await?.map(|t| t.token);
+        assert_eq!(stored.as_deref(), Some("new"));
+        Ok(())
+    }
+}
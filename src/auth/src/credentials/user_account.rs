@@ -22,9 +22,9 @@
 //! information, specifically utilizing an OAuth 2.0 refresh token.
 //!
 //! This module is designed for refresh tokens obtained via the standard
-//! [Authorization Code grant]. Acquiring the initial refresh token (e.g., through
-//! user consent) is outside the scope of this library.
-//! See [RFC 6749 Section 4.1] for flow details.
+//! [Authorization Code grant]. See [RFC 6749 Section 4.1] for flow details.
+//! Applications that need to acquire the initial refresh token (e.g., through
+//! user consent) can use the flows in the [flow] module.
 //!
 //! The Google Cloud client libraries for Rust will typically find and use these
 //! credentials automatically if a credentials file exists in the
@@ -94,14 +94,18 @@
 //! [User Account]: https://cloud.google.com/docs/authentication#user-accounts
 //! [Workforce Identity Federation]: https://cloud.google.com/iam/docs/workforce-identity-federation
 
+pub mod flow;
+
 use crate::build_errors::Error as BuilderError;
 use crate::credentials::dynamic::CredentialsProvider;
-use crate::credentials::{CacheableResource, Credentials};
+use crate::credentials::token_observer::TokenObserver;
+use crate::credentials::token_store::{self, StoredTokenProvider, TokenStore};
+use crate::credentials::{CacheableResource, Credentials, DEFAULT_UNIVERSE_DOMAIN};
 use crate::errors::{self, CredentialsError};
 use crate::headers_util::build_cacheable_headers;
 use crate::retry::Builder as RetryTokenProviderBuilder;
 use crate::token::{CachedTokenProvider, Token, TokenProvider};
-use crate::token_cache::TokenCache;
+use crate::token_cache::{self, TokenCache};
 use crate::{BuildResult, Result};
 use gax::backoff_policy::BackoffPolicyArg;
 use gax::retry_policy::RetryPolicyArg;
@@ -131,6 +135,8 @@ pub struct Builder {
     quota_project_id: Option<String>,
     token_uri: Option<String>,
     retry_builder: RetryTokenProviderBuilder,
+    token_store: Option<Arc<dyn token_store::dynamic::TokenStore>>,
+    cache_options: token_cache::Options,
 }
 
 impl Builder {
@@ -147,6 +153,8 @@ impl Builder {
             quota_project_id: None,
             token_uri: None,
             retry_builder: RetryTokenProviderBuilder::default(),
+            token_store: None,
+            cache_options: token_cache::Options::default(),
         }
     }
 
@@ -303,6 +311,77 @@ impl Builder {
         self
     }
 
+    /// Sets how long before the token expires the credentials refresh it.
+    ///
+    /// The credentials refresh access tokens in the background, before they
+    /// expire. By default they start refreshing 4 minutes before the token
+    /// expires. Applications that make requests with long-lived tokens, such
+    /// as streaming RPCs, may want to increase this value.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::user_account::Builder;
+    /// # tokio_test::block_on(async {
+    /// use std::time::Duration;
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_refresh_slack(Duration::from_secs(600))
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_refresh_slack(mut self, v: Duration) -> Self {
+        self.cache_options = self.cache_options.with_refresh_slack(v);
+        self
+    }
+
+    /// Sets an observer for the token lifecycle events.
+    ///
+    /// See the [token_observer][crate::credentials::token_observer] module
+    /// for more information.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_auth::credentials::user_account::Builder;
+    /// # use google_cloud_auth::credentials::token_observer::TokenObserver;
+    /// # tokio_test::block_on(async {
+    /// #[derive(Debug)]
+    /// struct MyObserver;
+    /// impl TokenObserver for MyObserver {}
+    /// let credentials = Builder::new(serde_json::json!({ /* add details here */ }))
+    ///     .with_token_observer(MyObserver)
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_token_observer<O: TokenObserver + 'static>(mut self, v: O) -> Self {
+        self.cache_options = self.cache_options.with_observer(Arc::new(v));
+        self
+    }
+
+    /// Configure a persistent store for the access tokens.
+    ///
+    /// With a [TokenStore], access tokens are reused across processes until
+    /// they are close to their expiration. Tokens are stored per OAuth
+    /// client, refresh token, token URI and scopes.
+    ///
+    /// ```
+    /// # use google_cloud_auth::credentials::user_account::Builder;
+    /// # use google_cloud_auth::credentials::token_store::FileTokenStore;
+    /// # tokio_test::block_on(async {
+    /// let authorized_user = serde_json::json!({
+    ///     "client_id": "YOUR_CLIENT_ID.apps.googleusercontent.com",
+    ///     "client_secret": "YOUR_CLIENT_SECRET",
+    ///     "refresh_token": "YOUR_REFRESH_TOKEN",
+    ///     "type": "authorized_user",
+    /// });
+    /// let credentials = Builder::new(authorized_user)
+    ///     .with_token_store(FileTokenStore::new("/var/cache/my-application/tokens"))
+    ///     .build();
+    /// # });
+    /// ```
+    pub fn with_token_store<S: TokenStore + 'static>(mut self, store: S) -> Self {
+        self.token_store = Some(Arc::new(store));
+        self
+    }
+
     /// Returns a [Credentials] instance with the configured settings.
     ///
     /// # Errors
@@ -332,7 +411,20 @@ impl Builder {
             scopes: self.scopes.map(|scopes| scopes.join(" ")),
         };
 
-        let token_provider = TokenCache::new(self.retry_builder.build(token_provider));
+        let key = token_store::store_key(&[
+            "authorized_user",
+            &token_provider.client_id,
+            &token_provider.refresh_token,
+            &token_provider.endpoint,
+            token_provider.scopes.as_deref().unwrap_or_default(),
+        ]);
+        let token_provider = StoredTokenProvider::new(
+            self.retry_builder.build(token_provider),
+            self.token_store,
+            key,
+            self.cache_options.refresh_slack(),
+        );
+        let token_provider = TokenCache::with_options(token_provider, self.cache_options);
 
         Ok(Credentials {
             inner: Arc::new(UserCredentials {
@@ -431,6 +523,15 @@ where
         let token = self.token_provider.token(extensions).await?;
         build_cacheable_headers(&token, &self.quota_project_id)
     }
+
+    // User credentials are only issued in the default universe.
+    async fn universe_domain(&self) -> Option<String> {
+        Some(DEFAULT_UNIVERSE_DOMAIN.to_string())
+    }
+
+    async fn force_refresh(&self) {
+        self.token_provider.force_refresh();
+    }
 }
 
 #[derive(Debug, PartialEq, serde::Deserialize)]
@@ -476,12 +577,12 @@ struct Oauth2RefreshResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::credentials::QUOTA_PROJECT_KEY;
     use crate::credentials::tests::{
         find_source_error, get_headers_from_cache, get_mock_auth_retry_policy,
         get_mock_backoff_policy, get_mock_retry_throttler, get_token_from_headers,
         get_token_type_from_headers,
     };
-    use crate::credentials::{DEFAULT_UNIVERSE_DOMAIN, QUOTA_PROJECT_KEY};
     use crate::errors::CredentialsError;
     use crate::token::tests::MockTokenProvider;
     use http::StatusCode;
@@ -545,6 +646,42 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn token_store_shared_across_credentials() -> TestResult {
+        let mut server = Server::run();
+        let response = Oauth2RefreshResponse {
+            access_token: "test-access-token".to_string(),
+            expires_in: Some(3600),
+            refresh_token: None,
+            scope: None,
+            token_type: "Bearer".to_string(),
+        };
+        server.expect(
+            Expectation::matching(request::path("/token"))
+                .times(1)
+                .respond_with(json_encoded(response)),
+        );
+
+        let dir = tempfile::tempdir()?;
+        let store = crate::credentials::token_store::FileTokenStore::new(dir.path());
+        let credentials = Builder::new(authorized_user_json(server.url("/token").to_string()))
+            .with_token_store(store.clone())
+            .build()?;
+        let token = get_token_from_headers(credentials.headers(Extensions::new()).await?);
+        assert_eq!(token.as_deref(), Some("test-access-token"));
+
+        // New credentials, as if created in a different process, reuse the
+        // stored token.
+        let credentials = Builder::new(authorized_user_json(server.url("/token").to_string()))
+            .with_token_store(store)
+            .build()?;
+        let token = get_token_from_headers(credentials.headers(Extensions::new()).await?);
+        assert_eq!(token.as_deref(), Some("test-access-token"));
+
+        server.verify_and_clear();
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_user_account_retries_for_success() -> TestResult {
         let mut server = Server::run();
@@ -678,6 +815,22 @@ mod tests {
         assert_eq!(uc.universe_domain().await.unwrap(), DEFAULT_UNIVERSE_DOMAIN);
     }
 
+    #[tokio::test]
+    async fn universe_domain() -> TestResult {
+        let json = serde_json::json!({
+            "client_id": "test-client-id",
+            "client_secret": "test-client-secret",
+            "refresh_token": "test-refresh-token",
+            "type": "authorized_user",
+        });
+        let credentials = Builder::new(json).build()?;
+        assert_eq!(
+            credentials.universe_domain().await.as_deref(),
+            Some(DEFAULT_UNIVERSE_DOMAIN)
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn headers_success() -> TestResult {
         let token = Token {
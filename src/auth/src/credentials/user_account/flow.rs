@@ -0,0 +1,1142 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OAuth 2.0 flows to obtain user account refresh tokens.
+//!
+//! Command-line tools and other installed applications need to obtain a
+//! refresh token before they can create [user account] credentials. This
+//! module implements the two flows recommended for these applications:
+//!
+//! * The [authorization code] flow with [PKCE] and a [loopback redirect]. The
+//!   application opens (or asks the user to open) a browser to the
+//!   authorization URL, and receives the authorization code on a local port.
+//! * The [device authorization grant]. The application displays a short code
+//!   and a verification URL, the user completes the authorization on a
+//!   different device.
+//!
+//! Both flows produce an [Authorization]. Its [to_json][Authorization::to_json]
+//! function returns a JSON object in the same format used by
+//! `gcloud auth application-default login`. Applications can persist this
+//! object and use it, then or in later runs, with [Builder].
+//!
+//! # Example: installed application flow
+//! ```no_run
+//! # use google_cloud_auth::credentials::user_account::flow::InstalledFlow;
+//! # tokio_test::block_on(async {
+//! let pending = InstalledFlow::new("my-client-id", "my-client-secret")
+//!     .with_scopes(["https://www.googleapis.com/auth/cloud-platform"])
+//!     .start()
+//!     .await?;
+//! println!("Open this URL in your browser: {}", pending.authorization_url());
+//! let authorization = pending.finish().await?;
+//! let credentials = authorization.builder().build()?;
+//! # Ok::<(), anyhow::Error>(())
+//! # });
+//! ```
+//!
+//! # Example: device authorization flow
+//! ```no_run
+//! # use google_cloud_auth::credentials::user_account::flow::DeviceFlow;
+//! # tokio_test::block_on(async {
+//! let pending = DeviceFlow::new("my-client-id", "my-client-secret")
+//!     .with_scopes(["https://www.googleapis.com/auth/cloud-platform"])
+//!     .start()
+//!     .await?;
+//! println!(
+//!     "Visit {} and enter the code {}",
+//!     pending.verification_url(),
+//!     pending.user_code()
+//! );
+//! let authorization = pending.finish().await?;
+//! std::fs::write("credentials.json", authorization.to_json().to_string())?;
+//! # Ok::<(), anyhow::Error>(())
+//! # });
+//! ```
+//!
+//! [authorization code]: https://datatracker.ietf.org/doc/html/rfc6749#section-4.1
+//! [device authorization grant]: https://datatracker.ietf.org/doc/html/rfc8628
+//! [loopback redirect]: https://datatracker.ietf.org/doc/html/rfc8252#section-7.3
+//! [PKCE]: https://datatracker.ietf.org/doc/html/rfc7636
+//! [user account]: https://cloud.google.com/docs/authentication#user-accounts
+
+use super::{Builder, OAUTH2_ENDPOINT};
+use crate::Result;
+use crate::errors::{self, CredentialsError};
+use base64::prelude::{BASE64_URL_SAFE_NO_PAD, Engine as _};
+use rand::RngCore;
+use reqwest::{Client, Url};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::time::{Duration, Instant};
+
+const AUTH_ENDPOINT: &str = "https://accounts.google.com/o/oauth2/auth";
+const DEVICE_AUTH_ENDPOINT: &str = "https://oauth2.googleapis.com/device/code";
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+// RFC 8628 says clients should wait 5 seconds between polls if the server
+// does not specify an interval, and increase the interval by 5 seconds on a
+// `slow_down` response.
+const DEFAULT_POLLING_INTERVAL: Duration = Duration::from_secs(5);
+const SLOW_DOWN_INCREMENT: Duration = Duration::from_secs(5);
+
+// The largest request we accept on the loopback redirect. Browsers send
+// small requests, anything larger is not a redirect from the authorization
+// server.
+const MAX_REDIRECT_REQUEST_SIZE: usize = 16 * 1024;
+
+// How long to wait for each request on the loopback listener. Browsers may
+// open connections speculatively and never send a request on them.
+const REDIRECT_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// How long to wait for the user to complete the authorization.
+const DEFAULT_REDIRECT_TIMEOUT: Duration = Duration::from_secs(300);
+
+const SUCCESS_PAGE: &str =
+    "<html><body>The authorization completed. You may close this window.</body></html>";
+const FAILURE_PAGE: &str =
+    "<html><body>The authorization failed. Check the application for details.</body></html>";
+
+/// The result of a successful user authorization flow.
+///
+/// Contains the information needed to create [user account] credentials.
+/// Applications typically persist the [JSON representation][Self::to_json]
+/// and use it with [Builder] in future runs.
+///
+/// [user account]: https://cloud.google.com/docs/authentication#user-accounts
+#[derive(Clone, PartialEq)]
+pub struct Authorization {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    token_uri: String,
+}
+
+impl Authorization {
+    /// The refresh token granted by the user.
+    pub fn refresh_token(&self) -> &str {
+        &self.refresh_token
+    }
+
+    /// Returns the authorization in the `authorized_user` JSON format.
+    ///
+    /// This is the format used by `gcloud auth application-default login`, and
+    /// it is accepted by [Builder::new].
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "type": "authorized_user",
+            "client_id": self.client_id,
+            "client_secret": self.client_secret,
+            "refresh_token": self.refresh_token,
+            "token_uri": self.token_uri,
+        })
+    }
+
+    /// Returns a [Builder] initialized from this authorization.
+    pub fn builder(&self) -> Builder {
+        Builder::new(self.to_json())
+    }
+}
+
+impl std::fmt::Debug for Authorization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Authorization")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"[censored]")
+            .field("refresh_token", &"[censored]")
+            .field("token_uri", &self.token_uri)
+            .finish()
+    }
+}
+
+/// The authorization code flow for installed applications.
+///
+/// This flow uses [PKCE] to protect the authorization code, and receives the
+/// code on a [loopback redirect], that is, an HTTP server listening on
+/// `127.0.0.1` on an ephemeral port.
+///
+/// The OAuth client must be of the "Desktop app" type.
+///
+/// [loopback redirect]: https://datatracker.ietf.org/doc/html/rfc8252#section-7.3
+/// [PKCE]: https://datatracker.ietf.org/doc/html/rfc7636
+#[derive(Clone, Debug)]
+pub struct InstalledFlow {
+    config: ClientConfig,
+    auth_uri: String,
+    timeout: Duration,
+}
+
+impl InstalledFlow {
+    /// Creates a new flow for the given OAuth client.
+    pub fn new<I: Into<String>, S: Into<String>>(client_id: I, client_secret: S) -> Self {
+        Self {
+            config: ClientConfig::new(client_id.into(), client_secret.into()),
+            auth_uri: AUTH_ENDPOINT.to_string(),
+            timeout: DEFAULT_REDIRECT_TIMEOUT,
+        }
+    }
+
+    /// Sets the [scopes] requested from the user.
+    ///
+    /// [scopes]: https://developers.google.com/identity/protocols/oauth2/scopes
+    pub fn with_scopes<I, S>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.scopes = scopes.into_iter().map(|s| s.into()).collect();
+        self
+    }
+
+    /// Sets the URI of the authorization endpoint.
+    ///
+    /// Defaults to `https://accounts.google.com/o/oauth2/auth`.
+    pub fn with_auth_uri<S: Into<String>>(mut self, auth_uri: S) -> Self {
+        self.auth_uri = auth_uri.into();
+        self
+    }
+
+    /// Sets how long [PendingAuthorization::finish] waits for the redirect.
+    ///
+    /// Defaults to 5 minutes.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the URI of the token endpoint.
+    ///
+    /// Defaults to `https://oauth2.googleapis.com/token`.
+    pub fn with_token_uri<S: Into<String>>(mut self, token_uri: S) -> Self {
+        self.config.token_uri = token_uri.into();
+        self
+    }
+
+    /// Starts the flow.
+    ///
+    /// Binds the loopback listener and returns the pending authorization. The
+    /// application must direct the user to the
+    /// [authorization URL][PendingAuthorization::authorization_url] and then
+    /// call [PendingAuthorization::finish].
+    pub async fn start(self) -> Result<PendingAuthorization> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(errors::non_retryable)?;
+        let port = listener.local_addr().map_err(errors::non_retryable)?.port();
+        let redirect_uri = format!("http://127.0.0.1:{port}");
+        let code_verifier = random_string();
+        let state = random_string();
+        let scopes = self.config.scopes.join(" ");
+        let authorization_url = Url::parse_with_params(
+            &self.auth_uri,
+            [
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("scope", scopes.as_str()),
+                ("state", state.as_str()),
+                ("code_challenge", code_challenge(&code_verifier).as_str()),
+                ("code_challenge_method", "S256"),
+                ("access_type", "offline"),
+                ("prompt", "consent"),
+            ],
+        )
+        .map_err(errors::non_retryable)?;
+
+        Ok(PendingAuthorization {
+            config: self.config,
+            listener,
+            redirect_uri,
+            code_verifier,
+            state,
+            authorization_url: authorization_url.to_string(),
+            timeout: self.timeout,
+        })
+    }
+}
+
+/// An authorization code flow waiting for the user consent.
+///
+/// Created by [InstalledFlow::start].
+pub struct PendingAuthorization {
+    config: ClientConfig,
+    listener: TcpListener,
+    redirect_uri: String,
+    code_verifier: String,
+    state: String,
+    authorization_url: String,
+    timeout: Duration,
+}
+
+impl PendingAuthorization {
+    /// The URL where the user grants (or denies) access to the application.
+    pub fn authorization_url(&self) -> &str {
+        &self.authorization_url
+    }
+
+    /// The loopback URI receiving the authorization code.
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// Waits for the redirect and exchanges the authorization code.
+    ///
+    /// This function waits until the browser is redirected to the loopback
+    /// listener, or until the [timeout][InstalledFlow::with_timeout] expires.
+    /// Requests that are not a redirect for this flow, including redirects
+    /// with a different `state` parameter, are rejected and the function
+    /// keeps waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user denies the request, if no redirect for
+    /// this flow arrives before the timeout, or if the code exchange fails.
+    pub async fn finish(self) -> Result<Authorization> {
+        let deadline = Instant::now() + self.timeout;
+        let code = loop {
+            let (mut stream, _) = tokio::time::timeout_at(deadline, self.listener.accept())
+                .await
+                .map_err(|_| {
+                    errors::non_retryable_from_str(
+                        "timed out waiting for the authorization redirect",
+                    )
+                })?
+                .map_err(errors::non_retryable)?;
+            let read_deadline = deadline.min(Instant::now() + REDIRECT_READ_TIMEOUT);
+            let read = tokio::time::timeout_at(read_deadline, read_redirect(&mut stream)).await;
+            let Some(params) = read.ok().flatten() else {
+                // Ignore unrelated or idle requests, e.g. `/favicon.ico`.
+                let _ = respond(&mut stream, "404 Not Found", "").await;
+                continue;
+            };
+            if !matches_state(&params, &self.state) {
+                // Ignore redirects for other flows, they cannot complete
+                // this one.
+                let _ = respond(&mut stream, "400 Bad Request", FAILURE_PAGE).await;
+                continue;
+            }
+            let result = validate_redirect(&params);
+            let page = match &result {
+                Ok(_) => ("200 OK", SUCCESS_PAGE),
+                Err(_) => ("400 Bad Request", FAILURE_PAGE),
+            };
+            let _ = respond(&mut stream, page.0, page.1).await;
+            break result?;
+        };
+
+        let response = self
+            .config
+            .exchange(&[
+                ("grant_type", "authorization_code"),
+                ("code", code.as_str()),
+                ("code_verifier", self.code_verifier.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+            ])
+            .await?;
+        match response {
+            TokenResponse::Success(r) => self.config.authorization(r),
+            TokenResponse::Error(e) => Err(e.into_error()),
+        }
+    }
+}
+
+impl std::fmt::Debug for PendingAuthorization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingAuthorization")
+            .field("config", &self.config)
+            .field("redirect_uri", &self.redirect_uri)
+            .field("code_verifier", &"[censored]")
+            .field("state", &self.state)
+            .field("authorization_url", &self.authorization_url)
+            .finish()
+    }
+}
+
+/// The [device authorization grant] flow.
+///
+/// Use this flow when the application runs on a device without a browser,
+/// or where the browser cannot reach a loopback address, for example, on a
+/// remote workstation.
+///
+/// The OAuth client must be of the "TVs and Limited Input devices" type.
+///
+/// [device authorization grant]: https://datatracker.ietf.org/doc/html/rfc8628
+#[derive(Clone, Debug)]
+pub struct DeviceFlow {
+    config: ClientConfig,
+    device_authorization_uri: String,
+}
+
+impl DeviceFlow {
+    /// Creates a new flow for the given OAuth client.
+    pub fn new<I: Into<String>, S: Into<String>>(client_id: I, client_secret: S) -> Self {
+        Self {
+            config: ClientConfig::new(client_id.into(), client_secret.into()),
+            device_authorization_uri: DEVICE_AUTH_ENDPOINT.to_string(),
+        }
+    }
+
+    /// Sets the [scopes] requested from the user.
+    ///
+    /// [scopes]: https://developers.google.com/identity/protocols/oauth2/scopes
+    pub fn with_scopes<I, S>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.scopes = scopes.into_iter().map(|s| s.into()).collect();
+        self
+    }
+
+    /// Sets the URI of the device authorization endpoint.
+    ///
+    /// Defaults to `https://oauth2.googleapis.com/device/code`.
+    pub fn with_device_authorization_uri<S: Into<String>>(mut self, uri: S) -> Self {
+        self.device_authorization_uri = uri.into();
+        self
+    }
+
+    /// Sets the URI of the token endpoint.
+    ///
+    /// Defaults to `https://oauth2.googleapis.com/token`.
+    pub fn with_token_uri<S: Into<String>>(mut self, token_uri: S) -> Self {
+        self.config.token_uri = token_uri.into();
+        self
+    }
+
+    /// Starts the flow.
+    ///
+    /// Requests a device code and returns the pending authorization. The
+    /// application must display the [user code][DeviceAuthorization::user_code]
+    /// and [verification URL][DeviceAuthorization::verification_url], and then
+    /// call [DeviceAuthorization::finish].
+    pub async fn start(self) -> Result<DeviceAuthorization> {
+        let scopes = self.config.scopes.join(" ");
+        let response = Client::new()
+            .post(&self.device_authorization_uri)
+            .form(&[
+                ("client_id", self.config.client_id.as_str()),
+                ("scope", scopes.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| errors::from_http_error(e, DEVICE_MSG))?;
+        if !response.status().is_success() {
+            return Err(errors::from_http_response(response, DEVICE_MSG).await);
+        }
+        let response = response
+            .json::<DeviceCodeResponse>()
+            .await
+            .map_err(|e| CredentialsError::new(!e.is_decode(), DEVICE_MSG, e))?;
+        let verification_url = response
+            .verification_url
+            .or(response.verification_uri)
+            .ok_or_else(|| {
+                errors::non_retryable_from_str(format!(
+                    "{DEVICE_MSG}, missing verification URL in response"
+                ))
+            })?;
+        let expires_at = Instant::now()
+            .checked_add(Duration::from_secs(response.expires_in))
+            .ok_or_else(|| {
+                errors::non_retryable_from_str(format!(
+                    "{DEVICE_MSG}, the code expiration is out of range, expires_in={}",
+                    response.expires_in
+                ))
+            })?;
+
+        Ok(DeviceAuthorization {
+            config: self.config,
+            device_code: response.device_code,
+            user_code: response.user_code,
+            verification_url,
+            expires_at,
+            interval: response
+                .interval
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_POLLING_INTERVAL),
+        })
+    }
+}
+
+/// A device authorization flow waiting for the user consent.
+///
+/// Created by [DeviceFlow::start].
+pub struct DeviceAuthorization {
+    config: ClientConfig,
+    device_code: String,
+    user_code: String,
+    verification_url: String,
+    expires_at: Instant,
+    interval: Duration,
+}
+
+impl DeviceAuthorization {
+    /// The code the user enters at the verification URL.
+    pub fn user_code(&self) -> &str {
+        &self.user_code
+    }
+
+    /// The URL where the user enters the [user code][Self::user_code].
+    pub fn verification_url(&self) -> &str {
+        &self.verification_url
+    }
+
+    /// The instant at which the device and user codes expire.
+    pub fn expires_at(&self) -> Instant {
+        self.expires_at
+    }
+
+    /// Polls the token endpoint until the user completes the authorization.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user denies the request, if the codes expire,
+    /// or if the token endpoint returns an unexpected error.
+    pub async fn finish(self) -> Result<Authorization> {
+        let mut interval = self.interval;
+        loop {
+            tokio::time::sleep(interval).await;
+            if Instant::now() >= self.expires_at {
+                return Err(errors::non_retryable_from_str(
+                    "the device code expired before the user completed the authorization",
+                ));
+            }
+            let response = self
+                .config
+                .exchange(&[
+                    ("grant_type", DEVICE_CODE_GRANT_TYPE),
+                    ("device_code", self.device_code.as_str()),
+                ])
+                .await?;
+            match response {
+                TokenResponse::Success(r) => return self.config.authorization(r),
+                TokenResponse::Error(e) if e.error == "authorization_pending" => {}
+                TokenResponse::Error(e) if e.error == "slow_down" => {
+                    interval += SLOW_DOWN_INCREMENT;
+                }
+                TokenResponse::Error(e) => return Err(e.into_error()),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for DeviceAuthorization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceAuthorization")
+            .field("config", &self.config)
+            .field("device_code", &"[censored]")
+            .field("user_code", &self.user_code)
+            .field("verification_url", &self.verification_url)
+            .field("expires_at", &self.expires_at)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+const DEVICE_MSG: &str = "failed to request a device code";
+const EXCHANGE_MSG: &str = "failed to exchange the authorization grant";
+
+#[derive(Clone)]
+struct ClientConfig {
+    client_id: String,
+    client_secret: String,
+    scopes: Vec<String>,
+    token_uri: String,
+}
+
+impl ClientConfig {
+    fn new(client_id: String, client_secret: String) -> Self {
+        Self {
+            client_id,
+            client_secret,
+            scopes: Vec::new(),
+            token_uri: OAUTH2_ENDPOINT.to_string(),
+        }
+    }
+
+    async fn exchange(&self, params: &[(&str, &str)]) -> Result<TokenResponse> {
+        let mut form = vec![
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+        ];
+        form.extend_from_slice(params);
+        let response = Client::new()
+            .post(&self.token_uri)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| errors::from_http_error(e, EXCHANGE_MSG))?;
+        let status = response.status();
+        if status.is_success() {
+            let response = response
+                .json::<GrantResponse>()
+                .await
+                .map_err(|e| CredentialsError::new(!e.is_decode(), EXCHANGE_MSG, e))?;
+            return Ok(TokenResponse::Success(response));
+        }
+        if status.is_client_error() {
+            // OAuth 2.0 servers report grant errors as a JSON object in a
+            // `400 Bad Request` (or `401 Unauthorized`) response.
+            let body = response.text().await.unwrap_or_default();
+            return match serde_json::from_str::<ErrorResponse>(&body) {
+                Ok(e) => Ok(TokenResponse::Error(e)),
+                Err(_) => Err(errors::non_retryable_from_str(format!(
+                    "{EXCHANGE_MSG}, status={status}, body=<{body}>"
+                ))),
+            };
+        }
+        Err(errors::from_http_response(response, EXCHANGE_MSG).await)
+    }
+
+    fn authorization(self, response: GrantResponse) -> Result<Authorization> {
+        let refresh_token = response.refresh_token.ok_or_else(|| {
+            errors::non_retryable_from_str(format!(
+                "{EXCHANGE_MSG}, the response does not include a refresh token"
+            ))
+        })?;
+        Ok(Authorization {
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+            refresh_token,
+            token_uri: self.token_uri,
+        })
+    }
+}
+
+impl std::fmt::Debug for ClientConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientConfig")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"[censored]")
+            .field("scopes", &self.scopes)
+            .field("token_uri", &self.token_uri)
+            .finish()
+    }
+}
+
+enum TokenResponse {
+    Success(GrantResponse),
+    Error(ErrorResponse),
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GrantResponse {
+    refresh_token: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+impl ErrorResponse {
+    fn into_error(self) -> CredentialsError {
+        let message = match self.error_description {
+            Some(d) => format!("{EXCHANGE_MSG}, error={}, description={d}", self.error),
+            None => format!("{EXCHANGE_MSG}, error={}", self.error),
+        };
+        errors::non_retryable_from_str(message)
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    // Google returns `verification_url`, RFC 8628 uses `verification_uri`.
+    verification_url: Option<String>,
+    verification_uri: Option<String>,
+    expires_in: u64,
+    interval: Option<u64>,
+}
+
+/// Returns a high-entropy string suitable for the PKCE code verifier and the
+/// `state` parameter.
+fn random_string() -> String {
+    let mut bytes = [0_u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    BASE64_URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Computes the `S256` code challenge, as defined in [RFC 7636].
+///
+/// [RFC 7636]: https://datatracker.ietf.org/doc/html/rfc7636#section-4.2
+fn code_challenge(code_verifier: &str) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Reads a request from the loopback listener and returns its query
+/// parameters.
+///
+/// Returns `None` if the request is not a redirect from the authorization
+/// server.
+async fn read_redirect<S>(stream: &mut S) -> Option<Vec<(String, String)>>
+where
+    S: tokio::io::AsyncRead + Unpin,
+{
+    let mut buffer = Vec::new();
+    let mut chunk = [0_u8; 1024];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+        let count = stream.read(&mut chunk).await.ok()?;
+        if count == 0 || buffer.len() + count > MAX_REDIRECT_REQUEST_SIZE {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..count]);
+    }
+    let request = String::from_utf8_lossy(&buffer);
+    let mut request_line = request.lines().next()?.split(' ');
+    let (method, target) = (request_line.next()?, request_line.next()?);
+    if method != "GET" {
+        return None;
+    }
+    let url = Url::parse("http://127.0.0.1").ok()?.join(target).ok()?;
+    let params = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect::<Vec<_>>();
+    let is_redirect = params.iter().any(|(k, _)| k == "code" || k == "error");
+    is_redirect.then_some(params)
+}
+
+fn get_param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find_map(|(k, v)| (k == name).then_some(v.as_str()))
+}
+
+/// Returns true if the redirect `state` parameter matches the authorization
+/// request.
+fn matches_state(params: &[(String, String)], expected_state: &str) -> bool {
+    get_param(params, "state") == Some(expected_state)
+}
+
+fn validate_redirect(params: &[(String, String)]) -> Result<String> {
+    let get = |name: &str| get_param(params, name);
+    if let Some(error) = get("error") {
+        return Err(errors::non_retryable_from_str(format!(
+            "the authorization request failed, error={error}"
+        )));
+    }
+    get("code")
+        .map(str::to_string)
+        .ok_or_else(|| errors::non_retryable_from_str("the redirect is missing the `code`"))
+}
+
+async fn respond<S>(stream: &mut S, status: &str, body: &str) -> std::io::Result<()>
+where
+    S: tokio::io::AsyncWrite + Unpin,
+{
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::matchers::{all_of, contains, request, url_decoded};
+    use httptest::responders::{json_encoded, status_code};
+    use httptest::{Expectation, Server, cycle};
+    use std::collections::HashMap;
+
+    type TestResult = anyhow::Result<()>;
+
+    fn query(url: &str) -> anyhow::Result<HashMap<String, String>> {
+        let url = Url::parse(url)?;
+        Ok(url.query_pairs().into_owned().collect())
+    }
+
+    #[test]
+    fn pkce_challenge() {
+        // The expected value is BASE64URL(SHA256(verifier)), without padding.
+        let got = code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r7wW1gFWFOEjXk");
+        assert_eq!(got, "bwWFMyPfdG9qreDhH2lmftFx_dFeLDalzcT1gb_j68g");
+    }
+
+    #[test]
+    fn random_strings_are_unique() {
+        let a = random_string();
+        let b = random_string();
+        assert_ne!(a, b);
+        // RFC 7636 requires between 43 and 128 characters.
+        assert_eq!(a.len(), 43, "{a}");
+    }
+
+    #[test]
+    fn validate() {
+        let params = |v: &[(&str, &str)]| {
+            v.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert!(matches_state(
+            &params(&[("code", "c"), ("state", "s")]),
+            "s"
+        ));
+        assert!(!matches_state(
+            &params(&[("code", "c"), ("state", "bad")]),
+            "s"
+        ));
+        assert!(!matches_state(&params(&[("code", "c")]), "s"));
+
+        let got = validate_redirect(&params(&[("code", "c"), ("state", "s")]));
+        assert_eq!(got.ok().as_deref(), Some("c"));
+
+        let got = validate_redirect(&params(&[("error", "access_denied"), ("state", "s")]));
+        let err = got.unwrap_err();
+        assert!(err.to_string().contains("access_denied"), "{err}");
+
+        let got = validate_redirect(&params(&[("state", "s")]));
+        assert!(got.is_err(), "{got:?}");
+    }
+
+    #[test]
+    fn authorization_json_roundtrip() -> TestResult {
+        let authorization = Authorization {
+            client_id: "test-client-id".into(),
+            client_secret: "test-client-secret".into(),
+            refresh_token: "test-refresh-token".into(),
+            token_uri: "test-token-uri".into(),
+        };
+        let json = authorization.to_json();
+        let parsed = serde_json::from_value::<super::super::AuthorizedUser>(json)?;
+        assert_eq!(parsed.client_id, "test-client-id");
+        assert_eq!(parsed.refresh_token, "test-refresh-token");
+        assert_eq!(parsed.token_uri.as_deref(), Some("test-token-uri"));
+
+        let fmt = format!("{authorization:?}");
+        assert!(!fmt.contains("test-client-secret"), "{fmt}");
+        assert!(!fmt.contains("test-refresh-token"), "{fmt}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn installed_flow_success() -> TestResult {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/token"),
+                request::body(url_decoded(contains(("grant_type", "authorization_code")))),
+                request::body(url_decoded(contains(("code", "test-code")))),
+                request::body(url_decoded(contains(("client_id", "test-client-id")))),
+                request::body(url_decoded(contains(("client_secret", "test-secret")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "access_token": "test-access-token",
+                "refresh_token": "test-refresh-token",
+                "expires_in": 3600,
+                "token_type": "Bearer",
+            }))),
+        );
+
+        let pending = InstalledFlow::new("test-client-id", "test-secret")
+            .with_scopes(["scope1", "scope2"])
+            .with_auth_uri("https://auth.example.com/o/oauth2/auth")
+            .with_token_uri(server.url("/token").to_string())
+            .start()
+            .await?;
+        let params = query(pending.authorization_url())?;
+        assert_eq!(
+            params.get("scope").map(String::as_str),
+            Some("scope1 scope2")
+        );
+        assert_eq!(
+            params.get("code_challenge_method").map(String::as_str),
+            Some("S256")
+        );
+        assert_eq!(
+            params.get("redirect_uri").map(String::as_str),
+            Some(pending.redirect_uri())
+        );
+        let challenge = params.get("code_challenge").cloned().unwrap_or_default();
+        assert_eq!(challenge, code_challenge(&pending.code_verifier));
+
+        // Simulate the browser, first with an unrelated request.
+        let redirect = format!(
+            "{}/?code=test-code&state={}",
+            pending.redirect_uri(),
+            params.get("state").cloned().unwrap_or_default()
+        );
+        let favicon = format!("{}/favicon.ico", pending.redirect_uri());
+        let browser = tokio::spawn(async move {
+            let client = Client::new();
+            let favicon = client.get(favicon).send().await?;
+            let redirect = client.get(redirect).send().await?;
+            anyhow::Ok((favicon.status(), redirect.status()))
+        });
+
+        let authorization = pending.finish().await?;
+        assert_eq!(authorization.refresh_token(), "test-refresh-token");
+        let json = authorization.to_json();
+        assert_eq!(json["client_id"], "test-client-id");
+        assert_eq!(json["token_uri"], server.url("/token").to_string());
+
+        let (favicon, redirect) = browser.await??;
+        assert_eq!(favicon, reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(redirect, reqwest::StatusCode::OK);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn installed_flow_access_denied() -> TestResult {
+        let pending = InstalledFlow::new("test-client-id", "test-secret")
+            .start()
+            .await?;
+        let state = query(pending.authorization_url())?
+            .get("state")
+            .cloned()
+            .unwrap_or_default();
+        let redirect = format!(
+            "{}/?error=access_denied&state={state}",
+            pending.redirect_uri()
+        );
+        let browser = tokio::spawn(async move { Client::new().get(redirect).send().await });
+
+        let err = pending.finish().await.unwrap_err();
+        assert!(!err.is_transient(), "{err:?}");
+        assert!(err.to_string().contains("access_denied"), "{err}");
+        let response = browser.await??;
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn installed_flow_ignores_mismatched_state() -> TestResult {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/token"),
+                request::body(url_decoded(contains(("code", "test-code")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "access_token": "test-access-token",
+                "refresh_token": "test-refresh-token",
+                "expires_in": 3600,
+                "token_type": "Bearer",
+            }))),
+        );
+        let pending = InstalledFlow::new("test-client-id", "test-secret")
+            .with_token_uri(server.url("/token").to_string())
+            .start()
+            .await?;
+        let state = query(pending.authorization_url())?
+            .get("state")
+            .cloned()
+            .unwrap_or_default();
+        let forged = format!("{}/?code=bad-code&state=bad-state", pending.redirect_uri());
+        let redirect = format!("{}/?code=test-code&state={state}", pending.redirect_uri());
+        let browser = tokio::spawn(async move {
+            let client = Client::new();
+            let forged = client.get(forged).send().await?;
+            let redirect = client.get(redirect).send().await?;
+            anyhow::Ok((forged.status(), redirect.status()))
+        });
+
+        let authorization = pending.finish().await?;
+        assert_eq!(authorization.refresh_token(), "test-refresh-token");
+        let (forged, redirect) = browser.await??;
+        assert_eq!(forged, reqwest::StatusCode::BAD_REQUEST);
+        assert_eq!(redirect, reqwest::StatusCode::OK);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn installed_flow_timeout() -> TestResult {
+        let pending = InstalledFlow::new("test-client-id", "test-secret")
+            .with_timeout(Duration::from_millis(200))
+            .start()
+            .await?;
+        // A connection that never sends a request does not block the flow
+        // past its deadline.
+        let addr = pending
+            .redirect_uri()
+            .trim_start_matches("http://")
+            .to_string();
+        let _idle = tokio::net::TcpStream::connect(addr).await?;
+
+        let err = pending.finish().await.unwrap_err();
+        assert!(!err.is_transient(), "{err:?}");
+        assert!(err.to_string().contains("timed out"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn device_flow_success() -> TestResult {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/device/code"),
+                request::body(url_decoded(contains(("client_id", "test-client-id")))),
+                request::body(url_decoded(contains(("scope", "scope1")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "device_code": "test-device-code",
+                "user_code": "ABCD-EFGH",
+                "verification_url": "https://www.google.com/device",
+                "expires_in": 1800,
+                "interval": 5,
+            }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/token"),
+                request::body(url_decoded(contains((
+                    "grant_type",
+                    DEVICE_CODE_GRANT_TYPE
+                )))),
+                request::body(url_decoded(contains(("device_code", "test-device-code")))),
+            ])
+            .times(3)
+            .respond_with(cycle![
+                status_code(428)
+                    .append_header("Content-Type", "application/json")
+                    .body(r#"{"error": "authorization_pending"}"#),
+                status_code(403)
+                    .append_header("Content-Type", "application/json")
+                    .body(r#"{"error": "slow_down"}"#),
+                json_encoded(serde_json::json!({
+                    "access_token": "test-access-token",
+                    "refresh_token": "test-refresh-token",
+                    "expires_in": 3600,
+                    "token_type": "Bearer",
+                })),
+            ]),
+        );
+
+        let pending = DeviceFlow::new("test-client-id", "test-secret")
+            .with_scopes(["scope1"])
+            .with_device_authorization_uri(server.url("/device/code").to_string())
+            .with_token_uri(server.url("/token").to_string())
+            .start()
+            .await?;
+        assert_eq!(pending.user_code(), "ABCD-EFGH");
+        assert_eq!(pending.verification_url(), "https://www.google.com/device");
+
+        let authorization = pending.finish().await?;
+        assert_eq!(authorization.refresh_token(), "test-refresh-token");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn device_flow_denied() -> TestResult {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/device/code")).respond_with(
+                json_encoded(serde_json::json!({
+                    "device_code": "test-device-code",
+                    "user_code": "ABCD-EFGH",
+                    "verification_uri": "https://example.com/device",
+                    "expires_in": 1800,
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/token")).respond_with(
+                status_code(403)
+                    .append_header("Content-Type", "application/json")
+                    .body(r#"{"error": "access_denied", "error_description": "Forbidden"}"#),
+            ),
+        );
+
+        let pending = DeviceFlow::new("test-client-id", "test-secret")
+            .with_device_authorization_uri(server.url("/device/code").to_string())
+            .with_token_uri(server.url("/token").to_string())
+            .start()
+            .await?;
+        assert_eq!(pending.verification_url(), "https://example.com/device");
+        let err = pending.finish().await.unwrap_err();
+        assert!(!err.is_transient(), "{err:?}");
+        assert!(err.to_string().contains("access_denied"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn device_flow_expired() -> TestResult {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/device/code")).respond_with(
+                json_encoded(serde_json::json!({
+                    "device_code": "test-device-code",
+                    "user_code": "ABCD-EFGH",
+                    "verification_url": "https://www.google.com/device",
+                    "expires_in": 12,
+                    "interval": 5,
+                })),
+            ),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/token"))
+                .times(2)
+                .respond_with(
+                    status_code(428)
+                        .append_header("Content-Type", "application/json")
+                        .body(r#"{"error": "authorization_pending"}"#),
+                ),
+        );
+
+        let pending = DeviceFlow::new("test-client-id", "test-secret")
+            .with_device_authorization_uri(server.url("/device/code").to_string())
+            .with_token_uri(server.url("/token").to_string())
+            .start()
+            .await?;
+        let err = pending.finish().await.unwrap_err();
+        assert!(err.to_string().contains("expired"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn device_flow_expires_in_out_of_range() -> TestResult {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/device/code")).respond_with(
+                json_encoded(serde_json::json!({
+                    "device_code": "test-device-code",
+                    "user_code": "ABCD-EFGH",
+                    "verification_url": "https://www.google.com/device",
+                    "expires_in": u64::MAX,
+                })),
+            ),
+        );
+        let err = DeviceFlow::new("test-client-id", "test-secret")
+            .with_device_authorization_uri(server.url("/device/code").to_string())
+            .start()
+            .await
+            .unwrap_err();
+        assert!(!err.is_transient(), "{err:?}");
+        assert!(err.to_string().contains("out of range"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn device_flow_start_error() -> TestResult {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/device/code"))
+                .respond_with(status_code(503)),
+        );
+        let err = DeviceFlow::new("test-client-id", "test-secret")
+            .with_device_authorization_uri(server.url("/device/code").to_string())
+            .start()
+            .await
+            .unwrap_err();
+        assert!(err.is_transient(), "{err:?}");
+        Ok(())
+    }
+}
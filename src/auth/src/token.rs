@@ -72,11 +72,22 @@ impl std::fmt::Debug for Token {
 #[async_trait::async_trait]
 pub(crate) trait TokenProvider: std::fmt::Debug + Send + Sync {
     async fn token(&self) -> Result<Token>;
+
+    /// Fetches a token after the service rejected the current token.
+    ///
+    /// Providers that reuse tokens from a persistent store must skip the store,
+    /// as it likely contains the rejected token.
+    async fn forced_token(&self) -> Result<Token> {
+        self.token().await
+    }
 }
 
 #[async_trait::async_trait]
 pub(crate) trait CachedTokenProvider: std::fmt::Debug + Send + Sync {
     async fn token(&self, extensions: Extensions) -> Result<CacheableResource<Token>>;
+
+    /// Discards the cached token and starts a refresh immediately.
+    fn force_refresh(&self) {}
 }
 
 #[cfg(test)]
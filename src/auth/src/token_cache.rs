@@ -13,24 +13,188 @@
 // limitations under the License.
 
 use crate::Result;
+use crate::credentials::token_observer::TokenObserver;
 use crate::credentials::{CacheableResource, EntityTag};
+use crate::errors;
 use crate::token::{CachedTokenProvider, Token, TokenProvider};
 use http::Extensions;
-use std::sync::Arc;
-use tokio::sync::watch;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, watch};
 use tokio::time::{Duration, Instant, sleep};
+use tracing::Instrument;
 
 // Different MDS(Metadata Service) backends have different policies to
 // determine when to refresh a token. Most MDS' refresh token 5 mins before
 // expiry, except for Serverless which refresh tokens 4 mins before
 // expiry. So we are using 4 mins as the staleness limit for our refresh logic.
-const NORMAL_REFRESH_SLACK: Duration = Duration::from_secs(240);
+pub(crate) const NORMAL_REFRESH_SLACK: Duration = Duration::from_secs(240);
 const SHORT_REFRESH_SLACK: Duration = Duration::from_secs(10);
 
+type TokenResult = Option<Result<(Token, EntityTag)>>;
+
+/// Configures the [TokenCache] behavior.
+///
+/// The credential builders hold one of these, and forward the relevant
+/// builder methods to it.
+#[derive(Clone, Debug)]
+pub(crate) struct Options {
+    refresh_slack: Duration,
+    observer: Option<Arc<dyn TokenObserver>>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            refresh_slack: NORMAL_REFRESH_SLACK,
+            observer: None,
+        }
+    }
+}
+
+impl Options {
+    pub(crate) fn with_refresh_slack(mut self, v: Duration) -> Self {
+        self.refresh_slack = v;
+        self
+    }
+
+    pub(crate) fn refresh_slack(&self) -> Duration {
+        self.refresh_slack
+    }
+
+    pub(crate) fn with_observer(mut self, v: Arc<dyn TokenObserver>) -> Self {
+        self.observer = Some(v);
+        self
+    }
+
+    fn on_result(&self, result: &Result<Token>, latency: Duration) {
+        match result {
+            Ok(token) => {
+                let expires_in = token
+                    .expires_at
+                    .map(|e| e.saturating_duration_since(Instant::now()));
+                tracing::debug!(?latency, ?expires_in, "access token refreshed");
+                if let Some(o) = &self.observer {
+                    o.on_refresh_success(latency, expires_in);
+                }
+            }
+            Err(error) => {
+                tracing::warn!(?latency, %error, "access token refresh failed");
+                if let Some(o) = &self.observer {
+                    o.on_refresh_failure(latency, error);
+                }
+            }
+        }
+    }
+
+    fn on_token_expired(&self) {
+        tracing::debug!("cached access token expired, waiting for refresh");
+        if let Some(o) = &self.observer {
+            o.on_token_expired();
+        }
+    }
+
+    fn on_forced_refresh(&self) {
+        tracing::debug!("forced access token refresh");
+        if let Some(o) = &self.observer {
+            o.on_forced_refresh();
+        }
+    }
+}
+
+/// The state shared between a [TokenCache] and its refresh task.
+#[derive(Debug)]
+struct Shared {
+    tx_token: watch::Sender<TokenResult>,
+    refresh_now: Notify,
+    options: Options,
+    generations: Mutex<Generations>,
+}
+
+/// Tracks which token result (if any) was rejected by a forced refresh.
+///
+/// The token result in the channel is never discarded, so callers waiting for
+/// a token always receive one. Instead, a forced refresh marks the current
+/// result as stale, and callers wait until the refresh task publishes a newer
+/// result.
+#[derive(Debug, Default)]
+struct Generations {
+    /// Incremented each time the refresh task publishes a result.
+    current: u64,
+    /// The generation rejected by the last forced refresh.
+    stale: Option<u64>,
+}
+
+impl Shared {
+    fn new(tx_token: watch::Sender<TokenResult>, options: Options) -> Self {
+        Self {
+            tx_token,
+            refresh_now: Notify::new(),
+            options,
+            generations: Mutex::new(Generations::default()),
+        }
+    }
+
+    fn generations(&self) -> std::sync::MutexGuard<'_, Generations> {
+        self.generations
+            .lock()
+            .expect("token cache generations lock is poisoned")
+    }
+
+    /// Returns the latest token result, or `None` if there is no result yet or
+    /// the result is stale.
+    fn current(&self, rx_token: &mut watch::Receiver<TokenResult>) -> TokenResult {
+        // The refresh task publishes results while holding this lock, so the
+        // generation matches the value in the channel.
+        let generations = self.generations();
+        let result = rx_token.borrow_and_update().clone();
+        if generations.stale == Some(generations.current) {
+            return None;
+        }
+        result
+    }
+
+    fn publish(&self, result: Result<(Token, EntityTag)>) {
+        let mut generations = self.generations();
+        generations.current += 1;
+        let _ = self.tx_token.send(Some(result));
+    }
+
+    fn force_refresh(&self) {
+        {
+            let mut generations = self.generations();
+            // Coalesce forced refreshes: many requests may fail with the same
+            // token, only the first one needs to start a refresh. If the
+            // initial refresh has not completed there is nothing to discard.
+            if generations.current == 0 || generations.stale == Some(generations.current) {
+                tracing::debug!("access token refresh already in progress");
+                return;
+            }
+            generations.stale = Some(generations.current);
+        }
+        self.options.on_forced_refresh();
+        self.refresh_now.notify_one();
+    }
+
+    async fn wait_for_next_token(
+        &self,
+        mut rx_token: watch::Receiver<TokenResult>,
+    ) -> Result<(Token, EntityTag)> {
+        loop {
+            rx_token.changed().await.map_err(|_| {
+                errors::non_retryable_from_str("the access token refresh task has stopped")
+            })?;
+            if let Some(result) = self.current(&mut rx_token) {
+                return result;
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct TokenCache<T: TokenProvider> {
-    rx_token: watch::Receiver<Option<Result<(Token, EntityTag)>>>,
+    rx_token: watch::Receiver<TokenResult>,
     token_provider: Arc<T>,
+    shared: Arc<Shared>,
 }
 
 // The default implementation requires `T` to implement `Clone`, which is not always the case.
@@ -39,6 +203,7 @@ impl<T: TokenProvider> Clone for TokenCache<T> {
         Self {
             rx_token: self.rx_token.clone(),
             token_provider: self.token_provider.clone(),
+            shared: self.shared.clone(),
         }
     }
 }
@@ -48,20 +213,26 @@ where
     T: TokenProvider + Send + Sync + 'static,
 {
     pub(crate) fn new(inner: T) -> Self {
-        let (tx_token, rx_token) = watch::channel::<Option<Result<(Token, EntityTag)>>>(None);
+        Self::with_options(inner, Options::default())
+    }
+
+    pub(crate) fn with_options(inner: T, options: Options) -> Self {
+        let (tx_token, rx_token) = watch::channel::<TokenResult>(None);
         let token_provider = Arc::new(inner);
+        let shared = Arc::new(Shared::new(tx_token, options));
 
-        tokio::spawn(refresh_task(token_provider.clone(), tx_token));
+        tokio::spawn(refresh_task(token_provider.clone(), shared.clone()));
 
         Self {
             rx_token,
             token_provider,
+            shared,
         }
     }
 
     async fn latest_token_and_entity_tag(&self) -> Result<(Token, EntityTag)> {
         let mut rx = self.rx_token.clone();
-        let token_result = rx.borrow_and_update().clone();
+        let token_result = self.shared.current(&mut rx);
         if let Some(token_result) = token_result {
             match token_result {
                 Ok((token, tag)) => match token.expires_at {
@@ -69,7 +240,8 @@ where
                     Some(e) => {
                         if e < Instant::now() {
                             // Expired token, wait for refresh
-                            wait_for_next_token(rx).await
+                            self.shared.options.on_token_expired();
+                            self.shared.wait_for_next_token(rx).await
                         } else {
                             // valid token
                             Ok((token, tag))
@@ -80,7 +252,7 @@ where
                 Err(e) => Err(e),
             }
         } else {
-            wait_for_next_token(rx).await
+            self.shared.wait_for_next_token(rx).await
         }
     }
 }
@@ -97,33 +269,39 @@ where
             _ => Ok(CacheableResource::New { entity_tag, data }),
         }
     }
-}
-
-async fn wait_for_next_token(
-    mut rx_token: watch::Receiver<Option<Result<(Token, EntityTag)>>>,
-) -> Result<(Token, EntityTag)> {
-    rx_token.changed().await.unwrap();
-    let token_result = rx_token.borrow().clone();
 
-    token_result.expect("There should always be a token or error in the channel after changed()")
+    fn force_refresh(&self) {
+        // Callers wait for the new token instead of using a token the service
+        // rejected.
+        self.shared.force_refresh();
+    }
 }
 
-async fn refresh_task<T>(
-    token_provider: Arc<T>,
-    tx_token: watch::Sender<Option<Result<(Token, EntityTag)>>>,
-) where
+async fn refresh_task<T>(token_provider: Arc<T>, shared: Arc<Shared>)
+where
     T: TokenProvider + Send + Sync + 'static,
 {
+    // Set when a forced refresh wakes up the task, the service rejected the
+    // previous token.
+    let mut forced = false;
     loop {
-        let token_result = token_provider.token().await;
+        let start = Instant::now();
+        let token_result = if forced {
+            token_provider.forced_token()
+        } else {
+            token_provider.token()
+        }
+        .instrument(tracing::debug_span!("refresh_token"))
+        .await;
+        shared.options.on_result(&token_result, start.elapsed());
         let result = token_result.clone().map(|token| {
             let entity_tag = EntityTag::new();
             (token, entity_tag)
         });
 
-        let _ = tx_token.send(Some(result));
+        shared.publish(result);
 
-        match token_result {
+        let wait = match token_result {
             Ok(new_token) => {
                 if let Some(expiry) = new_token.expires_at {
                     let time_until_expiry = expiry.checked_duration_since(Instant::now());
@@ -132,29 +310,46 @@ async fn refresh_task<T>(
                         None => {
                             // We were given a token that is expired, or expires in less than 10 seconds.
                             // We will immediately restart the loop, and fetch a new token.
+                            continue;
                         }
                         Some(time_until_expiry) => {
-                            if time_until_expiry > NORMAL_REFRESH_SLACK {
-                                sleep(time_until_expiry - NORMAL_REFRESH_SLACK).await;
+                            let slack = shared.options.refresh_slack;
+                            if time_until_expiry > slack {
+                                Some(time_until_expiry - slack)
                             } else if time_until_expiry > SHORT_REFRESH_SLACK {
-                                // If expiry is less than 4 mins, try to refresh every 10 seconds
+                                // If expiry is less than the slack, try to refresh every 10 seconds
                                 // This is to handle cases where MDS **repeatedly** returns about to expire tokens.
-                                sleep(SHORT_REFRESH_SLACK).await;
+                                Some(SHORT_REFRESH_SLACK)
+                            } else {
+                                continue;
                             }
                         }
                     }
                 } else {
                     // If there is no expiry, the token is valid forever, so no need to refresh
                     // TODO(#1553): Validate that all auth backends provide expiry and make expiry not optional.
-                    break;
+                    None
                 }
             }
             Err(_) => {
                 // The retry policy has been used already by the inner token provider.
-                // If it ended in an error, just quit the background task.
-                break;
+                // If it ended in an error, only refresh again if forced to.
+                None
             }
-        }
+        };
+
+        let sleep_or_wait = async move {
+            match wait {
+                Some(d) => sleep(d).await,
+                None => std::future::pending::<()>().await,
+            }
+        };
+        forced = tokio::select! {
+            // All the caches are gone, stop refreshing.
+            _ = shared.tx_token.closed() => break,
+            _ = shared.refresh_now.notified() => true,
+            _ = sleep_or_wait => false,
+        };
     }
 }
 
@@ -171,6 +366,254 @@ mod tests {
     static TOKEN_VALID_DURATION: Duration = Duration::from_secs(3600);
     type TestResult = std::result::Result<(), Box<dyn std::error::Error>>;
 
+    fn test_shared(tx_token: watch::Sender<TokenResult>) -> Arc<Shared> {
+        Arc::new(Shared::new(tx_token, Options::default()))
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl RecordingObserver {
+        fn events(&self) -> Vec<String> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl TokenObserver for RecordingObserver {
+        fn on_refresh_success(&self, _latency: Duration, expires_in: Option<Duration>) {
+            let e = format!("success {:?}", expires_in.map(|d| d.as_secs()));
+            self.events.lock().unwrap().push(e);
+        }
+        fn on_refresh_failure(&self, _latency: Duration, error: &CredentialsError) {
+            self.events.lock().unwrap().push(format!("failure {error}"));
+        }
+        fn on_token_expired(&self) {
+            self.events.lock().unwrap().push("expired".to_string());
+        }
+        fn on_forced_refresh(&self) {
+            self.events.lock().unwrap().push("forced".to_string());
+        }
+    }
+
+    fn test_token(name: &str, expires_at: Option<Instant>) -> Token {
+        Token {
+            token: name.to_string(),
+            token_type: "Bearer".to_string(),
+            expires_at,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn force_refresh_replaces_token() -> TestResult {
+        let now = Instant::now();
+        let initial = test_token("initial", Some(now + TOKEN_VALID_DURATION));
+        let refreshed = test_token("refreshed", Some(now + TOKEN_VALID_DURATION));
+
+        let mut seq = mockall::Sequence::new();
+        let mut mock = MockTokenProvider::new();
+        let initial_clone = initial.clone();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .return_once(|| Ok(initial_clone));
+        let refreshed_clone = refreshed.clone();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .return_once(|| Ok(refreshed_clone));
+
+        let cache = TokenCache::new(mock);
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual, initial);
+
+        // The token is still valid, but the service rejected it.
+        cache.force_refresh();
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual, refreshed);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn force_refresh_after_error() -> TestResult {
+        let now = Instant::now();
+        let recovered = test_token("recovered", Some(now + TOKEN_VALID_DURATION));
+
+        let mut seq = mockall::Sequence::new();
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|| Err(errors::non_retryable_from_str("fail")));
+        let recovered_clone = recovered.clone();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .return_once(|| Ok(recovered_clone));
+
+        let cache = TokenCache::new(mock);
+        assert!(cache.token(Extensions::new()).await.is_err());
+        // Without a forced refresh the error is sticky.
+        assert!(cache.token(Extensions::new()).await.is_err());
+
+        cache.force_refresh();
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual, recovered);
+        Ok(())
+    }
+
+    #[derive(Debug, Default)]
+    struct SlowProvider {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenProvider for SlowProvider {
+        async fn token(&self) -> Result<Token> {
+            let n = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            sleep(Duration::from_secs(5)).await;
+            Ok(test_token(
+                &format!("token-{n}"),
+                Some(Instant::now() + TOKEN_VALID_DURATION),
+            ))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn force_refresh_concurrent_waiters() -> TestResult {
+        let cache = Arc::new(TokenCache::new(SlowProvider::default()));
+        // Forcing a refresh before the initial token is available is ignored.
+        cache.force_refresh();
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual.token, "token-0");
+
+        // Many requests fail with the same token, only one refresh starts.
+        cache.force_refresh();
+        let waiters = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                tokio::spawn(async move { cache.token(Extensions::new()).await })
+            })
+            .collect::<Vec<_>>();
+        cache.force_refresh();
+        cache.force_refresh();
+
+        for waiter in waiters {
+            let actual = get_cached_token(waiter.await??)?;
+            assert_eq!(actual.token, "token-1");
+        }
+        assert_eq!(
+            cache
+                .token_provider
+                .calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+
+        // Once the new token arrives, forced refreshes are honored again.
+        cache.force_refresh();
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual.token, "token-2");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn custom_refresh_slack() -> TestResult {
+        let now = Instant::now();
+        let token1 = test_token("token1", Some(now + TOKEN_VALID_DURATION));
+        let token2 = test_token("token2", Some(now + 2 * TOKEN_VALID_DURATION));
+
+        let mut seq = mockall::Sequence::new();
+        let mut mock = MockTokenProvider::new();
+        let token1_clone = token1.clone();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .return_once(|| Ok(token1_clone));
+        let token2_clone = token2.clone();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .return_once(|| Ok(token2_clone));
+
+        let slack = Duration::from_secs(900);
+        let cache = TokenCache::with_options(mock, Options::default().with_refresh_slack(slack));
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual, token1);
+
+        // Just before the custom slack the token is not refreshed.
+        tokio::time::advance(TOKEN_VALID_DURATION - slack - Duration::from_secs(10)).await;
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual, token1);
+
+        // After the custom slack, but well before the default slack.
+        tokio::time::advance(Duration::from_secs(20)).await;
+        tokio::task::yield_now().await;
+        let actual = get_cached_token(cache.token(Extensions::new()).await?)?;
+        assert_eq!(actual, token2);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn observer_events() -> TestResult {
+        let now = Instant::now();
+        let mut seq = mockall::Sequence::new();
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .return_once(move || Ok(test_token("t1", Some(now + TOKEN_VALID_DURATION))));
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|| Err(errors::non_retryable_from_str("test-only-fail")));
+
+        let observer = Arc::new(RecordingObserver::default());
+        let cache =
+            TokenCache::with_options(mock, Options::default().with_observer(observer.clone()));
+        let _ = cache.token(Extensions::new()).await?;
+        assert_eq!(observer.events(), vec!["success Some(3600)"]);
+
+        cache.force_refresh();
+        assert!(cache.token(Extensions::new()).await.is_err());
+        let events = observer.events();
+        assert_eq!(events.len(), 3, "{events:?}");
+        assert_eq!(events[1], "forced");
+        assert!(events[2].contains("test-only-fail"), "{events:?}");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn observer_token_expired() -> TestResult {
+        let now = Instant::now();
+        let mut seq = mockall::Sequence::new();
+        let mut mock = MockTokenProvider::new();
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .return_once(move || Ok(test_token("t1", Some(now + TOKEN_VALID_DURATION))));
+        mock.expect_token()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|| Err(errors::non_retryable_from_str("fail")));
+
+        let observer = Arc::new(RecordingObserver::default());
+        let cache =
+            TokenCache::with_options(mock, Options::default().with_observer(observer.clone()));
+        let _ = cache.token(Extensions::new()).await?;
+        // The refresh fails, so the cached token eventually expires.
+        tokio::time::advance(TOKEN_VALID_DURATION + Duration::from_secs(10)).await;
+        assert!(cache.token(Extensions::new()).await.is_err());
+        assert!(
+            observer.events().iter().any(|e| e == "expired"),
+            "{:?}",
+            observer.events()
+        );
+        Ok(())
+    }
+
     fn get_cached_token(cache: CacheableResource<Token>) -> Result<Token> {
         match cache {
             CacheableResource::New { data, .. } => Ok(data),
@@ -386,9 +829,10 @@ mod tests {
             .return_once(|| Ok(token2_clone));
 
         let (tx, mut rx) = watch::channel::<Option<Result<(Token, EntityTag)>>>(None);
+        let shared = test_shared(tx);
 
         tokio::spawn(async move {
-            refresh_task(Arc::new(mock), tx).await;
+            refresh_task(Arc::new(mock), shared).await;
         });
 
         // Give the refresh task a chance to run
@@ -445,13 +889,14 @@ mod tests {
             .return_once(|| Ok(token3_clone));
 
         let (tx, mut rx) = watch::channel::<Option<Result<(Token, EntityTag)>>>(None);
+        let shared = test_shared(tx);
 
         // check that channel has None before refresh task starts
         let actual = rx.borrow().clone();
         assert!(actual.is_none());
 
         tokio::spawn(async move {
-            refresh_task(Arc::new(mock), tx).await;
+            refresh_task(Arc::new(mock), shared).await;
         });
 
         rx.changed().await.unwrap();
@@ -519,13 +964,14 @@ mod tests {
             .return_once(|| Ok(token2_clone));
 
         let (tx, mut rx) = watch::channel::<Option<Result<(Token, EntityTag)>>>(None);
+        let shared = test_shared(tx);
 
         // check that channel has None before refresh task starts
         let actual = rx.borrow().clone();
         assert!(actual.is_none());
 
         tokio::spawn(async move {
-            refresh_task(Arc::new(mock), tx).await;
+            refresh_task(Arc::new(mock), shared).await;
         });
 
         rx.changed().await.unwrap();
@@ -584,13 +1030,14 @@ mod tests {
             .return_once(|| Ok(token2_clone));
 
         let (tx, mut rx) = watch::channel::<Option<Result<(Token, EntityTag)>>>(None);
+        let shared = test_shared(tx);
 
         // check that channel has None before refresh task starts
         let actual = rx.borrow().clone();
         assert!(actual.is_none());
 
         tokio::spawn(async move {
-            refresh_task(Arc::new(mock), tx).await;
+            refresh_task(Arc::new(mock), shared).await;
         });
 
         rx.changed().await.unwrap();
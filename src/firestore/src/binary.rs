@@ -0,0 +1,159 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implements the binary protobuf encoding for the Firestore model types.
+//!
+//! The encoding is only available for the messages in `google.firestore.v1`.
+//! It reuses the conversions between the model types and the `prost` types
+//! used by the gRPC transport.
+
+/// Implements [BinaryMessage][wkt::message::BinaryMessage] for model types.
+///
+/// Each model type is encoded via the `prost` type with the same relative path
+/// in the `$proto` module.
+macro_rules! binary_message {
+    ($proto:ident, $model:ident; $($($name:ident)::+),* $(,)?) => {
+        $(
+            impl wkt::message::BinaryMessage for $model::$($name)::+ {
+                fn encode_to_vec(&self) -> std::result::Result<std::vec::Vec<u8>, wkt::AnyError> {
+                    gaxi::prost::encode_to_vec::<$proto::$($name)::+, _>(self)
+                }
+
+                fn decode(buf: &[u8]) -> std::result::Result<Self, wkt::AnyError> {
+                    gaxi::prost::decode::<$proto::$($name)::+, _>(buf)
+                }
+            }
+        )*
+
+        /// The relative paths of the model types listed above.
+        #[cfg(test)]
+        pub(super) const NAMES: &[&str] = &[$(stringify!($($name)::+)),*];
+    };
+}
+
+mod firestore {
+    use crate::generated::gapic::model;
+    use crate::google::firestore::v1 as proto;
+
+    binary_message!(
+        proto, model;
+        AggregationResult,
+        BitSequence,
+        BloomFilter,
+        DocumentMask,
+        Precondition,
+        transaction_options::ReadWrite,
+        transaction_options::ReadOnly,
+        TransactionOptions,
+        Document,
+        Value,
+        ArrayValue,
+        MapValue,
+        GetDocumentRequest,
+        ListDocumentsRequest,
+        ListDocumentsResponse,
+        CreateDocumentRequest,
+        UpdateDocumentRequest,
+        DeleteDocumentRequest,
+        BatchGetDocumentsRequest,
+        BatchGetDocumentsResponse,
+        BeginTransactionRequest,
+        BeginTransactionResponse,
+        CommitRequest,
+        CommitResponse,
+        RollbackRequest,
+        RunQueryRequest,
+        RunQueryResponse,
+        RunAggregationQueryRequest,
+        RunAggregationQueryResponse,
+        PartitionQueryRequest,
+        PartitionQueryResponse,
+        WriteRequest,
+        WriteResponse,
+        ListenRequest,
+        ListenResponse,
+        target::DocumentsTarget,
+        target::QueryTarget,
+        Target,
+        TargetChange,
+        ListCollectionIdsRequest,
+        ListCollectionIdsResponse,
+        BatchWriteRequest,
+        BatchWriteResponse,
+        structured_query::CollectionSelector,
+        structured_query::Filter,
+        structured_query::CompositeFilter,
+        structured_query::FieldFilter,
+        structured_query::UnaryFilter,
+        structured_query::Order,
+        structured_query::FieldReference,
+        structured_query::Projection,
+        structured_query::FindNearest,
+        StructuredQuery,
+        structured_aggregation_query::aggregation::Count,
+        structured_aggregation_query::aggregation::Sum,
+        structured_aggregation_query::aggregation::Avg,
+        structured_aggregation_query::Aggregation,
+        StructuredAggregationQuery,
+        Cursor,
+        ExplainOptions,
+        ExplainMetrics,
+        PlanSummary,
+        ExecutionStats,
+        Write,
+        document_transform::FieldTransform,
+        DocumentTransform,
+        WriteResult,
+        DocumentChange,
+        DocumentDelete,
+        DocumentRemove,
+        ExistenceFilter,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    // Detects drift between the list above and the generated conversions.
+    #[test]
+    fn list_matches_generated_conversions() {
+        let want = converted_messages(include_str!("generated/convert/firestore/convert.rs"));
+        assert!(!want.is_empty());
+        assert_eq!(listed(super::firestore::NAMES), want);
+    }
+
+    // Returns the model types with conversions to and from a `prost` message
+    // in a generated `convert.rs` file. Messages are built field by field,
+    // enums (converted to `i32`) and oneofs (converted via `match`) are
+    // skipped.
+    fn converted_messages(source: &str) -> BTreeSet<String> {
+        let lines = source.lines().collect::<Vec<_>>();
+        lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let rest = line.strip_prefix("impl gaxi::prost::ToProto<")?;
+                let (_, model) = rest.split_once("::model::")?;
+                let body = lines.get(i + 3)?.trim();
+                body.starts_with("Ok(Self::Output {")
+                    .then(|| model.trim_end_matches(" {").to_string())
+            })
+            .collect()
+    }
+
+    fn listed(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|n| n.replace(' ', "")).collect()
+    }
+}
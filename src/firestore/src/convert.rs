@@ -232,4 +232,38 @@ mod tests {
         assert_eq!(got, sidekick);
         Ok(())
     }
+
+    #[test]
+    fn test_binary_message() -> anyhow::Result<()> {
+        use prost::Message as _;
+        use wkt::message::BinaryMessage;
+        let sidekick = model::Document::new()
+            .set_name("projects/p/databases/d/documents/c/doc")
+            .set_fields([(
+                "answer",
+                model::Value::new().set_value_type(model::value::ValueType::IntegerValue(42)),
+            )]);
+        let bytes = sidekick.encode_to_vec()?;
+
+        let proto = google::firestore::v1::Document::decode(bytes.as_slice())?;
+        assert_eq!(proto, sidekick.clone().to_proto()?);
+        assert_eq!(bytes, proto.encode_to_vec());
+
+        let got = model::Document::decode(&bytes)?;
+        assert_eq!(got, sidekick);
+
+        let any = wkt::Any::from_encoded::<model::Document>(&bytes)?;
+        assert_eq!(any.to_msg::<model::Document>()?, sidekick);
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_message_decode_error() {
+        use wkt::message::BinaryMessage;
+        let got = model::Document::decode(&[0xff, 0xff]);
+        assert!(
+            matches!(got, Err(wkt::AnyError::Deserialization(_))),
+            "{got:?}"
+        );
+    }
 }
@@ -55,5 +55,6 @@ pub(crate) mod google {
     }
 }
 
+mod binary;
 mod convert;
 pub mod status;
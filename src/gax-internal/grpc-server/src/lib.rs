@@ -31,11 +31,17 @@ pub async fn start_echo_server() -> anyhow::Result<(String, JoinHandle<()>)> {
     let addr = listener.local_addr()?;
 
     let server = tokio::spawn(async {
+        use tonic::codec::CompressionEncoding;
         let echo = Echo::default();
         let stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+        let service = google::test::v1::echo_service_server::EchoServiceServer::new(echo)
+            .accept_compressed(CompressionEncoding::Gzip)
+            .accept_compressed(CompressionEncoding::Zstd)
+            .send_compressed(CompressionEncoding::Gzip)
+            .send_compressed(CompressionEncoding::Zstd);
 
         let _ = tonic::transport::Server::builder()
-            .add_service(google::test::v1::echo_service_server::EchoServiceServer::new(echo))
+            .add_service(service)
             .serve_with_incoming(stream)
             .await;
     });
@@ -0,0 +1,334 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The attributes shared by the client metrics and traces.
+//!
+//! The names follow the OpenTelemetry semantic conventions, and the
+//! conventions for Google Cloud client libraries.
+
+use gax::error::Error;
+
+#[cfg(feature = "_internal-opentelemetry")]
+pub(crate) const SERVICE: &str = "gcp.client.service";
+pub(crate) const LOCATION: &str = "gcp.resource.destination.location";
+#[cfg(feature = "_internal-opentelemetry")]
+pub(crate) const SERVER_ADDRESS: &str = "server.address";
+#[cfg(feature = "_internal-opentelemetry")]
+pub(crate) const RPC_SYSTEM: &str = "rpc.system";
+#[cfg(feature = "_internal-grpc-client")]
+pub(crate) const RPC_SERVICE: &str = "rpc.service";
+#[cfg(feature = "_internal-grpc-client")]
+pub(crate) const RPC_METHOD: &str = "rpc.method";
+#[cfg(feature = "_internal-http-client")]
+pub(crate) const HTTP_METHOD: &str = "http.request.method";
+#[cfg(all(feature = "_internal-http-client", feature = "_internal-opentelemetry"))]
+pub(crate) const URL_TEMPLATE: &str = "url.template";
+pub(crate) const STATUS_CODE: &str = "rpc.response.status_code";
+pub(crate) const HTTP_STATUS_CODE: &str = "http.response.status_code";
+pub(crate) const ERROR_TYPE: &str = "error.type";
+pub(crate) const RESOURCE_NAME: &str = "gcp.resource.name";
+
+// Classifies the errors using low-cardinality values.
+pub(crate) fn error_type(error: &Error) -> String {
+    if let Some(status) = error.status() {
+        return status.code.name().to_string();
+    }
+    let kind = match error {
+        e if e.is_deadline_exceeded() => "DEADLINE_EXCEEDED",
+        e if e.is_timeout() => "CLIENT_TIMEOUT",
+        e if e.is_exhausted() => "CLIENT_RETRY_EXHAUSTED",
+        e if e.is_authentication() => "CLIENT_AUTHENTICATION_ERROR",
+        e if e.is_binding() => "CLIENT_REQUEST_ERROR",
+        e if e.is_serialization() => "CLIENT_REQUEST_ERROR",
+        e if e.is_deserialization() => "CLIENT_RESPONSE_DECODE_ERROR",
+        e if e.http_status_code().is_some() => "HTTP_ERROR",
+        e if e.is_io() || e.is_transport() => "CLIENT_CONNECTION_ERROR",
+        _ => "CLIENT_UNKNOWN_ERROR",
+    };
+    kind.to_string()
+}
+
+// Returns the host in an endpoint, e.g. `secretmanager.googleapis.com` in
+// `https://secretmanager.googleapis.com`.
+pub(crate) fn host(endpoint: &str) -> Option<&str> {
+    let authority = endpoint
+        .split_once("://")
+        .map_or(endpoint, |(_, rest)| rest)
+        .split(['/', '?'])
+        .next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    Some(host).filter(|h| !h.is_empty())
+}
+
+// Returns the service name in a host, e.g. `secretmanager` in
+// `secretmanager.googleapis.com`. IP addresses do not identify a service.
+pub(crate) fn service(host: &str) -> Option<&str> {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return None;
+    }
+    host.split('.').next().filter(|s| !s.is_empty())
+}
+
+// Finds the location in a resource name, request path, or routing header.
+// Routing headers are percent-encoded, so `locations/` is `locations%2F`.
+pub(crate) fn location(input: &str) -> Option<&str> {
+    ["locations/", "locations%2F"]
+        .into_iter()
+        .find_map(|prefix| {
+            let start = input.find(prefix)? + prefix.len();
+            let tail = &input[start..];
+            let end = tail.find(['/', '%', '&', '?', ':']).unwrap_or(tail.len());
+            Some(&tail[..end]).filter(|l| !l.is_empty() && *l != "-")
+        })
+}
+
+// Splits a gRPC path, e.g. `/google.cloud.secretmanager.v1.SecretManagerService/GetSecret`,
+// into the service and method names.
+#[cfg(feature = "_internal-grpc-client")]
+pub(crate) fn grpc_method(path: &str) -> (Option<&str>, Option<&str>) {
+    let mut parts = path.trim_start_matches('/').splitn(2, '/');
+    let service = parts.next().filter(|s| !s.is_empty());
+    let method = parts.next().filter(|s| !s.is_empty());
+    (service, method)
+}
+
+// Returns the resource name in the routing headers of a gRPC request, e.g.
+// `projects/p/secrets/s` in `name=projects%2Fp%2Fsecrets%2Fs`.
+#[cfg(feature = "_internal-grpc-client")]
+pub(crate) fn resource_name_from_params(request_params: &str) -> Option<String> {
+    let (_, value) = request_params.split('&').next()?.split_once('=')?;
+    let value = percent_encoding::percent_decode_str(value)
+        .decode_utf8()
+        .ok()?;
+    Some(value.into_owned()).filter(|v| !v.is_empty())
+}
+
+// Returns the resource name in the path of an HTTP request, e.g.
+// `projects/p/secrets/s` in `/v1/projects/p/secrets/s:addVersion`.
+#[cfg(feature = "_internal-http-client")]
+pub(crate) fn resource_name_from_path(path: &str) -> Option<String> {
+    let path = path.trim_start_matches('/');
+    let path = match path.split_once('/') {
+        Some((version, tail)) if is_version(version) => tail,
+        _ => path,
+    };
+    let path = path.rsplit_once(':').map_or(path, |(name, _)| name);
+    let name = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .ok()?;
+    Some(name.into_owned()).filter(|n| !n.is_empty())
+}
+
+// Returns the template for the path of an HTTP request, e.g.
+// `/v1/projects/*/secrets/*:addVersion` for `/v1/projects/p/secrets/s:addVersion`.
+//
+// Together with the HTTP method, the template identifies the RPC without
+// including the (high-cardinality) resource IDs. The segments after the API
+// version alternate between collections and resource IDs, as described in
+// AIP-122, and the IDs are replaced with `*`.
+#[cfg(all(feature = "_internal-http-client", feature = "_internal-opentelemetry"))]
+pub(crate) fn url_template(path: &str) -> Option<String> {
+    let path = path.trim_start_matches('/');
+    if path.is_empty() {
+        return None;
+    }
+    let (path, verb) = match path.rsplit_once(':') {
+        Some((p, v)) if !v.contains('/') => (p, Some(v)),
+        _ => (path, None),
+    };
+    let segments = path.split('/').collect::<Vec<_>>();
+    // The version is the first segment, or the second for services such as
+    // `/storage/v1/b/bucket`.
+    let start = segments
+        .iter()
+        .take(2)
+        .position(|s| is_version(s))
+        .map_or(0, |p| p + 1);
+    let mut template = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        template.push('/');
+        match i {
+            i if i < start || (i - start) % 2 == 0 => template.push_str(segment),
+            _ => template.push('*'),
+        }
+    }
+    if let Some(verb) = verb {
+        template.push(':');
+        template.push_str(verb);
+    }
+    Some(template)
+}
+
+// Returns true for API versions such as `v1`, `v2beta`, `v1alpha3` or
+// `v1p1beta1`.
+#[cfg(feature = "_internal-http-client")]
+fn is_version(segment: &str) -> bool {
+    let Some(mut tail) = segment
+        .strip_prefix('v')
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+    else {
+        return false;
+    };
+    loop {
+        tail = tail.trim_start_matches(|c: char| c.is_ascii_digit());
+        match ["alpha", "beta", "p"]
+            .iter()
+            .find_map(|p| tail.strip_prefix(p))
+        {
+            Some(t) => tail = t,
+            None => return tail.is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gax::error::rpc::{Code, Status};
+    use test_case::test_case;
+
+    #[test_case(
+        "https://secretmanager.googleapis.com",
+        Some("secretmanager.googleapis.com")
+    )]
+    #[test_case(
+        "https://storage.googleapis.com/storage/v1",
+        Some("storage.googleapis.com")
+    )]
+    #[test_case("http://127.0.0.1:8080", Some("127.0.0.1"))]
+    #[test_case("http://[::1]:8080/", Some("::1"))]
+    #[test_case("https://user@example.com:443", Some("example.com"))]
+    #[test_case("localhost:1234", Some("localhost"))]
+    #[test_case("https://", None)]
+    fn host(input: &str, want: Option<&str>) {
+        assert_eq!(super::host(input), want, "{input}");
+    }
+
+    #[test_case("secretmanager.googleapis.com", Some("secretmanager"))]
+    #[test_case("storage.us-central1.rep.googleapis.com", Some("storage"))]
+    #[test_case("localhost", Some("localhost"))]
+    #[test_case("127.0.0.1", None)]
+    #[test_case("::1", None)]
+    fn service(input: &str, want: Option<&str>) {
+        assert_eq!(super::service(input), want, "{input}");
+    }
+
+    #[test_case("/v1/projects/p/locations/us-central1/secrets/s", Some("us-central1"))]
+    #[test_case("/v1/projects/p/locations/global", Some("global"))]
+    #[test_case("/v1/projects/p/locations/us-east1:list", Some("us-east1"))]
+    #[test_case("/v1/projects/p/locations/-/instances", None)]
+    #[test_case(
+        "name=projects%2Fp%2Flocations%2Feurope-west1%2Fsecrets%2Fs",
+        Some("europe-west1")
+    )]
+    #[test_case(
+        "parent=projects%2Fp%2Flocations%2Fasia-south1&other=x",
+        Some("asia-south1")
+    )]
+    #[test_case("/v1/projects/p/secrets/s", None)]
+    #[test_case("", None)]
+    fn location(input: &str, want: Option<&str>) {
+        assert_eq!(super::location(input), want, "{input}");
+    }
+
+    #[test]
+    fn error_type() {
+        let status = Status::default()
+            .set_code(Code::Unavailable)
+            .set_message("try-again");
+        let input = Error::service(status);
+        assert_eq!(super::error_type(&input), "UNAVAILABLE");
+        let input = Error::timeout("test-only");
+        assert_eq!(super::error_type(&input), "CLIENT_TIMEOUT");
+        let input = Error::deadline_exceeded("test-only");
+        assert_eq!(super::error_type(&input), "DEADLINE_EXCEEDED");
+        let input = Error::io("test-only");
+        assert_eq!(super::error_type(&input), "CLIENT_CONNECTION_ERROR");
+        let input =
+            Error::authentication(auth::errors::CredentialsError::from_msg(false, "test-only"));
+        assert_eq!(super::error_type(&input), "CLIENT_AUTHENTICATION_ERROR");
+        let input = Error::deser("test-only");
+        assert_eq!(super::error_type(&input), "CLIENT_RESPONSE_DECODE_ERROR");
+        let input = Error::http(404, http::HeaderMap::new(), bytes::Bytes::new());
+        assert_eq!(super::error_type(&input), "HTTP_ERROR");
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[test_case(
+        "/google.test.v1.EchoService/Echo",
+        Some("google.test.v1.EchoService"),
+        Some("Echo")
+    )]
+    #[test_case(
+        "/google.test.v1.EchoService",
+        Some("google.test.v1.EchoService"),
+        None
+    )]
+    #[test_case("", None, None)]
+    fn grpc_method(input: &str, service: Option<&str>, method: Option<&str>) {
+        assert_eq!(super::grpc_method(input), (service, method), "{input}");
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[test_case("name=projects%2Fp%2Fsecrets%2Fs", Some("projects/p/secrets/s"))]
+    #[test_case("parent=projects%2Fp&other=x", Some("projects/p"))]
+    #[test_case("name=", None)]
+    #[test_case("", None)]
+    fn resource_name_from_params(input: &str, want: Option<&str>) {
+        assert_eq!(
+            super::resource_name_from_params(input).as_deref(),
+            want,
+            "{input}"
+        );
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    #[test_case("/v1/projects/p/secrets/s", Some("projects/p/secrets/s"))]
+    #[test_case("/v1/projects/p/secrets/s:addVersion", Some("projects/p/secrets/s"))]
+    #[test_case("/v2beta1/projects/p/locations/l", Some("projects/p/locations/l"))]
+    #[test_case("/storage/v1/b/bucket", Some("storage/v1/b/bucket"))]
+    #[test_case("/v1/projects/p/topics/t%2Fx", Some("projects/p/topics/t/x"))]
+    #[test_case("/v1p1beta1/projects/p", Some("projects/p"))]
+    #[test_case("/", None)]
+    fn resource_name_from_path(input: &str, want: Option<&str>) {
+        assert_eq!(
+            super::resource_name_from_path(input).as_deref(),
+            want,
+            "{input}"
+        );
+    }
+
+    #[cfg(all(feature = "_internal-http-client", feature = "_internal-opentelemetry"))]
+    #[test_case("/v1/projects/p/secrets/s", Some("/v1/projects/*/secrets/*"))]
+    #[test_case(
+        "/v1/projects/p/secrets/s:addVersion",
+        Some("/v1/projects/*/secrets/*:addVersion")
+    )]
+    #[test_case(
+        "/v2beta1/projects/p/locations/l/instances",
+        Some("/v2beta1/projects/*/locations/*/instances")
+    )]
+    #[test_case("/v1/operations:cancel", Some("/v1/operations:cancel"))]
+    #[test_case("/storage/v1/b/bucket/o", Some("/storage/v1/b/*/o"))]
+    #[test_case("/b/v1bucket", Some("/b/*"))]
+    #[test_case("/v1/projects/p/topics/t%3Ax", Some("/v1/projects/*/topics/*"))]
+    #[test_case("/", None)]
+    fn url_template(input: &str, want: Option<&str>) {
+        assert_eq!(super::url_template(input).as_deref(), want, "{input}");
+    }
+}
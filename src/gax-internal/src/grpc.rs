@@ -14,14 +14,23 @@
 
 //! Implements the common features of all gRPC-based client.
 
+mod channel_pool;
 mod from_status;
+mod proxy;
 pub mod status;
 
+use crate::limits::Limits;
+use crate::logging::DebugLogging;
+use crate::metrics::Metrics;
+use crate::trace::{self, Tracing};
+use crate::universe_domain::{self, UniverseDomainCheck};
 use auth::credentials::{CacheableResource, Credentials};
+use channel_pool::ChannelPool;
 use from_status::to_gax_error;
 use gax::Result;
 use gax::backoff_policy::BackoffPolicy;
 use gax::client_builder::Error as BuilderError;
+use gax::compression::Compression;
 use gax::error::Error;
 use gax::exponential_backoff::ExponentialBackoff;
 use gax::polling_backoff_policy::PollingBackoffPolicy;
@@ -31,18 +40,26 @@ use gax::retry_throttler::SharedRetryThrottler;
 use http::HeaderMap;
 use std::sync::Arc;
 use std::time::Duration;
+use tracing::Instrument as _;
 
 pub type InnerClient = tonic::client::Grpc<tonic::transport::Channel>;
 
 #[derive(Clone, Debug)]
 pub struct Client {
-    inner: InnerClient,
+    channels: ChannelPool,
     credentials: Credentials,
+    universe_domain_check: UniverseDomainCheck,
     retry_policy: Arc<dyn RetryPolicy>,
     backoff_policy: Arc<dyn BackoffPolicy>,
     retry_throttler: SharedRetryThrottler,
     polling_error_policy: Arc<dyn PollingErrorPolicy>,
     polling_backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    request_compression: Compression,
+    accept_compression: Vec<Compression>,
+    metrics: Metrics,
+    tracing: Tracing,
+    logging: DebugLogging,
+    limits: Limits,
 }
 
 impl Client {
@@ -52,10 +69,21 @@ impl Client {
         default_endpoint: &str,
     ) -> gax::client_builder::Result<Self> {
         let credentials = Self::make_credentials(&config).await?;
-        let inner = Self::make_inner(config.endpoint, default_endpoint).await?;
+        let tracing_enabled = crate::options::tracing_enabled(&config);
+        let logging = DebugLogging::new(crate::options::debug_logging_enabled(&config));
+        let limits = Limits::new(&config);
+        let endpoint = universe_domain::endpoint(
+            config.endpoint,
+            default_endpoint,
+            config.universe_domain.as_deref(),
+        );
+        let metrics = Metrics::new(config.metrics, default_endpoint, &endpoint, "grpc");
+        let tracing = Tracing::new(tracing_enabled, default_endpoint, &endpoint, "grpc");
+        let channels = ChannelPool::new(endpoint, &config.grpc_config, &config.transport_config)?;
         Ok(Self {
-            inner,
+            channels,
             credentials,
+            universe_domain_check: UniverseDomainCheck::new(config.universe_domain),
             retry_policy: config.retry_policy.clone().unwrap_or_else(|| {
                 Arc::new(
                     RetryAip194Strict
@@ -74,6 +102,12 @@ impl Client {
             polling_backoff_policy: config
                 .polling_backoff_policy
                 .unwrap_or_else(|| Arc::new(ExponentialBackoff::default())),
+            request_compression: config.request_compression,
+            accept_compression: config.accept_compression,
+            metrics,
+            tracing,
+            logging,
+            limits,
         })
     }
 
@@ -88,12 +122,19 @@ impl Client {
         request_params: &str,
     ) -> Result<tonic::Response<Response>>
     where
-        Request: prost::Message + 'static + Clone,
-        Response: prost::Message + Default + 'static,
+        Request: prost::Message + std::fmt::Debug + 'static + Clone,
+        Response: prost::Message + std::fmt::Debug + Default + 'static,
     {
         let headers = Self::make_headers(api_client_header, request_params, &options).await?;
-        self.retry_loop::<Request, Response>(extensions, path, request, options, headers)
-            .await
+        self.retry_loop::<Request, Response>(
+            extensions,
+            path,
+            request,
+            options,
+            headers,
+            request_params,
+        )
+        .await
     }
 
     /// Runs the retry loop.
@@ -104,18 +145,30 @@ impl Client {
         request: Request,
         options: gax::options::RequestOptions,
         headers: HeaderMap,
+        request_params: &str,
     ) -> Result<tonic::Response<Response>>
     where
-        Request: prost::Message + 'static + Clone,
-        Response: prost::Message + Default + 'static,
+        Request: prost::Message + std::fmt::Debug + 'static + Clone,
+        Response: prost::Message + std::fmt::Debug + Default + 'static,
     {
+        let operation = self.metrics.grpc_operation(path.path(), request_params);
+        let span = self.tracing.grpc_operation(path.path(), request_params);
         let idempotent = options.idempotent().unwrap_or(false);
-        let retry_throttler = self.get_retry_throttler(&options);
+        let retry_throttler = operation.throttler(self.get_retry_throttler(&options));
         let retry_policy = self.get_retry_policy(&options);
         let backoff_policy = self.get_backoff_policy(&options);
+        let deadline = options.deadline();
         let this = self.clone();
+        let recorder = operation.clone();
+        let spans = span.clone();
         let inner = async move |remaining_time: Option<Duration>| {
-            this.clone()
+            let attempt = recorder.attempt();
+            let attempt_span = spans.attempt();
+            if attempt.enabled() {
+                attempt.request_size(request.encoded_len());
+            }
+            let result = this
+                .clone()
                 .request_attempt::<Request, Response>(
                     extensions.clone(),
                     path.clone(),
@@ -124,18 +177,30 @@ impl Client {
                     remaining_time,
                     headers.clone(),
                 )
-                .await
+                .instrument(attempt_span.clone())
+                .await;
+            trace::record_outcome(&attempt_span, &result);
+            if let (true, Ok(response)) = (attempt.enabled(), &result) {
+                attempt.response_size(response.get_ref().encoded_len());
+            }
+            attempt.finish(&result);
+            result
         };
         let sleep = async |d| tokio::time::sleep(d).await;
-        gax::retry_loop_internal::retry_loop(
+        let result = gax::retry_loop_internal::retry_loop_with_deadline(
             inner,
             sleep,
             idempotent,
             retry_throttler,
             retry_policy,
             backoff_policy,
+            deadline,
         )
-        .await
+        .instrument(span.span().clone())
+        .await;
+        span.finish(&result);
+        operation.finish(&result);
+        result
     }
 
     /// Makes a single request attempt.
@@ -149,10 +214,11 @@ impl Client {
         headers: HeaderMap,
     ) -> Result<tonic::Response<Response>>
     where
-        Request: prost::Message + 'static,
-        Response: prost::Message + std::default::Default + 'static,
+        Request: prost::Message + std::fmt::Debug + 'static,
+        Response: prost::Message + std::fmt::Debug + std::default::Default + 'static,
     {
         let mut headers = headers;
+        self.universe_domain_check.check(&self.credentials).await?;
         let cached_auth_headers = self
             .credentials
             .headers(http::Extensions::new())
@@ -168,32 +234,52 @@ impl Client {
 
         let auth_headers = auth_headers?;
         headers.extend(auth_headers);
+        if self.tracing.enabled() {
+            headers.extend(trace::propagation_headers());
+        }
+        let timeout = gax::retry_loop_internal::effective_timeout(options, remaining_time);
+        let (_permit, timeout) = self
+            .limits
+            .acquire(options, || request.encoded_len(), timeout)
+            .await?;
+        self.logging.grpc_request(path.path(), &headers, &request);
         let metadata = tonic::metadata::MetadataMap::from_headers(headers);
         let mut request = tonic::Request::from_parts(metadata, extensions, request);
-        if let Some(timeout) = gax::retry_loop_internal::effective_timeout(options, remaining_time)
-        {
+        if let Some(timeout) = timeout {
             request.set_timeout(timeout);
         }
         let codec = tonic_prost::ProstCodec::<Request, Response>::default();
-        let mut inner = self.inner.clone();
+        let (mut inner, _in_flight) = self.channels.pick();
+        let compression = options
+            .request_compression()
+            .unwrap_or(self.request_compression);
+        if let Some(encoding) = to_encoding(compression) {
+            inner = inner.send_compressed(encoding);
+        }
+        for encoding in options
+            .accept_compression()
+            .unwrap_or(&self.accept_compression)
+            .iter()
+            .filter_map(|c| to_encoding(*c))
+        {
+            inner = inner.accept_compressed(encoding);
+        }
         inner.ready().await.map_err(Error::io)?;
-        inner
-            .unary(request, path, codec)
-            .await
-            .map_err(to_gax_error)
-    }
-
-    async fn make_inner(
-        endpoint: Option<String>,
-        default_endpoint: &str,
-    ) -> gax::client_builder::Result<InnerClient> {
-        use tonic::transport::{ClientTlsConfig, Endpoint};
-        let endpoint =
-            Endpoint::from_shared(endpoint.unwrap_or_else(|| default_endpoint.to_string()))
-                .map_err(BuilderError::transport)?
-                .tls_config(ClientTlsConfig::new().with_enabled_roots())
-                .map_err(BuilderError::transport)?;
-        Ok(tonic::client::Grpc::new(endpoint.connect_lazy()))
+        let method = path.clone();
+        let result = inner.unary(request, path, codec).await;
+        self.logging.grpc_response(method.path(), &result);
+        match result {
+            Ok(response) => Ok(response),
+            Err(status) => {
+                if status.code() == tonic::Code::Unauthenticated {
+                    // The service rejected the token, it may have been revoked
+                    // before its expiration time. Fetch a new one for any
+                    // retry attempts.
+                    self.credentials.force_refresh().await;
+                }
+                Err(to_gax_error(status))
+            }
+        }
     }
 
     async fn make_credentials(
@@ -269,20 +355,32 @@ impl Client {
         &self,
         options: &gax::options::RequestOptions,
     ) -> Arc<dyn PollingErrorPolicy> {
-        options
+        let policy = options
             .polling_error_policy()
             .clone()
-            .unwrap_or_else(|| self.polling_error_policy.clone())
+            .unwrap_or_else(|| self.polling_error_policy.clone());
+        gax::retry_loop_internal::polling_error_policy_with_deadline(policy, options.deadline())
     }
 
     pub fn get_polling_backoff_policy(
         &self,
         options: &gax::options::RequestOptions,
     ) -> Arc<dyn PollingBackoffPolicy> {
-        options
+        let policy = options
             .polling_backoff_policy()
             .clone()
-            .unwrap_or_else(|| self.polling_backoff_policy.clone())
+            .unwrap_or_else(|| self.polling_backoff_policy.clone());
+        gax::retry_loop_internal::polling_backoff_policy_with_deadline(policy, options.deadline())
+    }
+}
+
+/// Maps the compression algorithm to its gRPC encoding, if any.
+fn to_encoding(compression: Compression) -> Option<tonic::codec::CompressionEncoding> {
+    use tonic::codec::CompressionEncoding;
+    match compression {
+        Compression::Gzip => Some(CompressionEncoding::Gzip),
+        Compression::Zstd => Some(CompressionEncoding::Zstd),
+        _ => None,
     }
 }
 
@@ -0,0 +1,268 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A pool of gRPC channels.
+//!
+//! Each channel is a separate HTTP/2 connection. Services limit the number of
+//! concurrent streams in each connection, using more than one channel allows
+//! clients to send more concurrent requests.
+
+use super::InnerClient;
+use super::proxy::ProxyConnector;
+use gax::client_builder::Error as BuilderError;
+use gax::grpc_config::{ChannelSelection, GrpcConfig};
+use gax::transport_config::TransportConfig;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Clone, Debug)]
+pub(crate) struct ChannelPool {
+    channels: Arc<[PooledChannel]>,
+    selection: ChannelSelection,
+    next: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+struct PooledChannel {
+    client: InnerClient,
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// Tracks a request in progress on a channel.
+///
+/// Dropping this value marks the request as completed.
+#[derive(Debug)]
+pub(crate) struct InFlight(Arc<AtomicUsize>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ChannelPool {
+    pub(crate) fn new(
+        endpoint: String,
+        config: &GrpcConfig,
+        transport: &TransportConfig,
+    ) -> gax::client_builder::Result<Self> {
+        let endpoint = make_endpoint(endpoint, config, transport)?;
+        let proxy = transport
+            .proxy()
+            .map(|p| ProxyConnector::new(p, endpoint.get_tcp_nodelay()))
+            .transpose()?;
+        let channels = (0..config.channel_pool_size().max(1))
+            .map(|_| {
+                let channel = match &proxy {
+                    None => endpoint.connect_lazy(),
+                    Some(p) => endpoint.connect_with_connector_lazy(p.clone()),
+                };
+                PooledChannel {
+                    client: make_client(channel, config),
+                    in_flight: Arc::new(AtomicUsize::new(0)),
+                }
+            })
+            .collect();
+        Ok(Self {
+            channels,
+            selection: config.channel_selection(),
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Returns the channel for the next request.
+    ///
+    /// The request is considered in progress until the returned [InFlight] is
+    /// dropped.
+    pub(crate) fn pick(&self) -> (InnerClient, InFlight) {
+        let channel = match self.selection {
+            ChannelSelection::LeastLoaded => self
+                .channels
+                .iter()
+                .min_by_key(|c| c.in_flight.load(Ordering::Relaxed))
+                .expect("the pool has at least one channel"),
+            _ => {
+                let index = self.next.fetch_add(1, Ordering::Relaxed);
+                &self.channels[index % self.channels.len()]
+            }
+        };
+        channel.in_flight.fetch_add(1, Ordering::Relaxed);
+        (channel.client.clone(), InFlight(channel.in_flight.clone()))
+    }
+
+    #[cfg(test)]
+    fn in_flight(&self) -> Vec<usize> {
+        self.channels
+            .iter()
+            .map(|c| c.in_flight.load(Ordering::Relaxed))
+            .collect()
+    }
+}
+
+fn make_endpoint(
+    endpoint: String,
+    config: &GrpcConfig,
+    transport: &TransportConfig,
+) -> gax::client_builder::Result<tonic::transport::Endpoint> {
+    use tonic::transport::Endpoint;
+    let mut endpoint = Endpoint::from_shared(endpoint)
+        .map_err(BuilderError::transport)?
+        .tls_config(make_tls_config(transport)?)
+        .map_err(BuilderError::transport)?
+        .initial_stream_window_size(config.initial_stream_window_size())
+        .initial_connection_window_size(config.initial_connection_window_size());
+    if let Some(v) = config.connect_timeout() {
+        endpoint = endpoint.connect_timeout(v);
+    }
+    if let Some(v) = config.http2_keep_alive_interval() {
+        endpoint = endpoint.http2_keep_alive_interval(v);
+    }
+    if let Some(v) = config.keep_alive_timeout() {
+        endpoint = endpoint.keep_alive_timeout(v);
+    }
+    if let Some(v) = config.keep_alive_while_idle() {
+        endpoint = endpoint.keep_alive_while_idle(v);
+    }
+    if let Some(v) = config.tcp_nodelay() {
+        endpoint = endpoint.tcp_nodelay(v);
+    }
+    Ok(endpoint)
+}
+
+fn make_tls_config(
+    transport: &TransportConfig,
+) -> gax::client_builder::Result<tonic::transport::ClientTlsConfig> {
+    use tonic::transport::{Certificate, ClientTlsConfig, Identity};
+    let mut tls = ClientTlsConfig::new();
+    if transport.builtin_root_certificates() {
+        tls = tls.with_enabled_roots();
+    }
+    for pem in transport.root_certificates_pem() {
+        // The TLS configuration ignores any invalid PEM blocks, detect
+        // bundles without certificates here.
+        if !String::from_utf8_lossy(pem).contains("-----BEGIN CERTIFICATE-----") {
+            return Err(BuilderError::transport(
+                "no certificates found in root certificates bundle",
+            ));
+        }
+        tls = tls.ca_certificate(Certificate::from_pem(pem));
+    }
+    if let Some(identity) = transport.client_identity() {
+        tls = tls.identity(Identity::from_pem(
+            identity.certificate_pem(),
+            identity.private_key_pem(),
+        ));
+    }
+    Ok(tls)
+}
+
+fn make_client(channel: tonic::transport::Channel, config: &GrpcConfig) -> InnerClient {
+    let mut client = tonic::client::Grpc::new(channel);
+    if let Some(v) = config.max_decoding_message_size() {
+        client = client.max_decoding_message_size(v);
+    }
+    if let Some(v) = config.max_encoding_message_size() {
+        client = client.max_encoding_message_size(v);
+    }
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const ENDPOINT: &str = "https://test.googleapis.com";
+
+    #[tokio::test]
+    async fn default_pool() -> anyhow::Result<()> {
+        let pool = ChannelPool::new(
+            ENDPOINT.to_string(),
+            &GrpcConfig::default(),
+            &TransportConfig::default(),
+        )?;
+        assert_eq!(pool.channels.len(), 1);
+        let (_client, _guard) = pool.pick();
+        assert_eq!(pool.in_flight(), vec![1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn round_robin() -> anyhow::Result<()> {
+        let config = GrpcConfig::default().with_channel_pool_size(3);
+        let pool = ChannelPool::new(ENDPOINT.to_string(), &config, &TransportConfig::default())?;
+        assert_eq!(pool.channels.len(), 3);
+
+        let (_, g0) = pool.pick();
+        assert_eq!(pool.in_flight(), vec![1, 0, 0]);
+        let (_, g1) = pool.pick();
+        assert_eq!(pool.in_flight(), vec![1, 1, 0]);
+        drop(g0);
+        let (_, _g2) = pool.pick();
+        assert_eq!(pool.in_flight(), vec![0, 1, 1]);
+        drop(g1);
+        // Round robin ignores the load.
+        let (_, _g3) = pool.pick();
+        assert_eq!(pool.in_flight(), vec![1, 0, 1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn least_loaded() -> anyhow::Result<()> {
+        let config = GrpcConfig::default()
+            .with_channel_pool_size(3)
+            .with_channel_selection(ChannelSelection::LeastLoaded);
+        let pool = ChannelPool::new(ENDPOINT.to_string(), &config, &TransportConfig::default())?;
+
+        let guards = (0..3).map(|_| pool.pick().1).collect::<Vec<_>>();
+        assert_eq!(pool.in_flight(), vec![1, 1, 1]);
+        let mut guards = guards.into_iter();
+        let _g0 = guards.next();
+        drop(guards.next());
+        assert_eq!(pool.in_flight(), vec![1, 0, 1]);
+        let (_, _g) = pool.pick();
+        assert_eq!(pool.in_flight(), vec![1, 1, 1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn endpoint_settings() -> anyhow::Result<()> {
+        let config = GrpcConfig::default()
+            .with_connect_timeout(Duration::from_secs(5))
+            .with_tcp_nodelay(false);
+        let endpoint = make_endpoint(ENDPOINT.to_string(), &config, &TransportConfig::default())?;
+        assert_eq!(endpoint.get_connect_timeout(), Some(Duration::from_secs(5)));
+        assert!(!endpoint.get_tcp_nodelay());
+
+        let endpoint = make_endpoint(
+            ENDPOINT.to_string(),
+            &GrpcConfig::default(),
+            &TransportConfig::default(),
+        )?;
+        assert_eq!(endpoint.get_connect_timeout(), None);
+        assert!(endpoint.get_tcp_nodelay());
+        Ok(())
+    }
+
+    #[test]
+    fn bad_endpoint() {
+        let err = ChannelPool::new(
+            "not a valid uri".to_string(),
+            &GrpcConfig::default(),
+            &TransportConfig::default(),
+        )
+        .unwrap_err();
+        assert!(err.is_transport(), "{err:?}");
+    }
+}
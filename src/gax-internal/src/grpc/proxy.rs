@@ -0,0 +1,227 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Connects gRPC channels through an HTTP proxy.
+//!
+//! The connector opens a TCP connection to the proxy and sends a `CONNECT`
+//! request to create a tunnel to the service. The channel then uses the
+//! tunnel as if it was a direct connection, including the TLS handshake with
+//! the service.
+
+use base64::Engine as _;
+use gax::client_builder::Error as BuilderError;
+use gax::transport_config::Proxy;
+use http::Uri;
+use hyper_util::rt::TokioIo;
+use std::future::Future;
+use std::io::{Error, ErrorKind, Result};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// The maximum size of the proxy response headers.
+const MAX_RESPONSE_SIZE: usize = 8 * 1024;
+
+#[derive(Clone, Debug)]
+pub(crate) struct ProxyConnector {
+    address: String,
+    authorization: Option<String>,
+    tcp_nodelay: bool,
+}
+
+impl ProxyConnector {
+    pub(crate) fn new(proxy: &Proxy, tcp_nodelay: bool) -> gax::client_builder::Result<Self> {
+        let uri = proxy
+            .url()
+            .parse::<Uri>()
+            .map_err(BuilderError::transport)?;
+        if uri.scheme_str() != Some("http") {
+            return Err(BuilderError::transport(format!(
+                "unsupported proxy scheme in {uri}, gRPC clients only support `http://` proxies"
+            )));
+        }
+        let host = uri
+            .host()
+            .ok_or_else(|| BuilderError::transport(format!("missing host in proxy URL {uri}")))?;
+        let address = format!("{host}:{}", uri.port_u16().unwrap_or(80));
+        let authorization = proxy.basic_auth().map(|(username, password)| {
+            let credentials =
+                base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+            format!("Basic {credentials}")
+        });
+        Ok(Self {
+            address,
+            authorization,
+            tcp_nodelay,
+        })
+    }
+
+    async fn connect(self, target: Uri) -> Result<TokioIo<TcpStream>> {
+        let host = target.host().ok_or_else(|| {
+            Error::new(ErrorKind::InvalidInput, format!("missing host in {target}"))
+        })?;
+        let port = target
+            .port_u16()
+            .unwrap_or(if target.scheme_str() == Some("https") {
+                443
+            } else {
+                80
+            });
+        let authority = format!("{host}:{port}");
+
+        let mut stream = TcpStream::connect(&self.address).await?;
+        stream.set_nodelay(self.tcp_nodelay)?;
+        let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+        if let Some(authorization) = &self.authorization {
+            request.push_str(&format!("Proxy-Authorization: {authorization}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        let response = read_response(&mut stream).await?;
+        let status_line = response.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(TokioIo::new(stream)),
+            _ => Err(Error::other(format!(
+                "the proxy rejected the tunnel to {authority}: {status_line}"
+            ))),
+        }
+    }
+}
+
+/// Reads the proxy response headers.
+///
+/// The service may start sending data as soon as the tunnel is established,
+/// this reads one byte at a time to avoid consuming any of that data.
+async fn read_response(stream: &mut TcpStream) -> Result<String> {
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_RESPONSE_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "the proxy response headers are too large",
+            ));
+        }
+        response.push(stream.read_u8().await?);
+    }
+    String::from_utf8(response).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+impl tower_service::Service<Uri> for ProxyConnector {
+    type Response = TokioIo<TcpStream>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, target: Uri) -> Self::Future {
+        Box::pin(self.clone().connect(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tower_service::Service as _;
+
+    #[test]
+    fn new() -> anyhow::Result<()> {
+        let connector = ProxyConnector::new(&Proxy::new("http://proxy.example.com"), true)?;
+        assert_eq!(connector.address, "proxy.example.com:80");
+        assert_eq!(connector.authorization, None);
+
+        let proxy = Proxy::new("http://127.0.0.1:3128").with_basic_auth("user", "pass");
+        let connector = ProxyConnector::new(&proxy, true)?;
+        assert_eq!(connector.address, "127.0.0.1:3128");
+        assert_eq!(
+            connector.authorization.as_deref(),
+            Some("Basic dXNlcjpwYXNz")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn new_errors() {
+        for url in [
+            "https://proxy.example.com",
+            "socks5://proxy:1080",
+            "not a url",
+        ] {
+            let err = ProxyConnector::new(&Proxy::new(url), true).unwrap_err();
+            assert!(err.is_transport(), "{url}: {err:?}");
+        }
+    }
+
+    // Accepts one connection, returns the request headers and sends `response`.
+    async fn fake_proxy(
+        response: &'static str,
+    ) -> anyhow::Result<(Proxy, tokio::task::JoinHandle<String>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let proxy = Proxy::new(format!("http://{}", listener.local_addr()?));
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let request = read_response(&mut stream).await.unwrap();
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.write_all(b"tunnel data").await.unwrap();
+            request
+        });
+        Ok((proxy, handle))
+    }
+
+    #[tokio::test]
+    async fn connect() -> anyhow::Result<()> {
+        let (proxy, handle) = fake_proxy("HTTP/1.1 200 Connection established\r\n\r\n").await?;
+        let mut connector = ProxyConnector::new(&proxy.with_basic_auth("user", "pass"), true)?;
+        let io = connector
+            .call(Uri::from_static("https://test.googleapis.com"))
+            .await?;
+        let request = handle.await?;
+        assert!(
+            request.starts_with("CONNECT test.googleapis.com:443 HTTP/1.1\r\n"),
+            "{request}"
+        );
+        assert!(
+            request.contains("Host: test.googleapis.com:443\r\n"),
+            "{request}"
+        );
+        assert!(
+            request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"),
+            "{request}"
+        );
+
+        // Any data after the response headers belongs to the tunnel.
+        let mut stream = io.into_inner();
+        let mut data = String::new();
+        stream.read_to_string(&mut data).await?;
+        assert_eq!(data, "tunnel data");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn connect_rejected() -> anyhow::Result<()> {
+        let (proxy, _handle) =
+            fake_proxy("HTTP/1.1 407 Proxy Authentication Required\r\n\r\n").await?;
+        let mut connector = ProxyConnector::new(&proxy, true)?;
+        let err = connector
+            .call(Uri::from_static("http://127.0.0.1:8080"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("407"), "{err}");
+        Ok(())
+    }
+}
@@ -12,10 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::limits::Limits;
+use crate::logging::DebugLogging;
+use crate::metrics::{Attempt, Metrics};
+use crate::trace::{self, Tracing};
+use crate::universe_domain::{self, UniverseDomainCheck};
 use auth::credentials::{CacheableResource, Credentials};
 use gax::Result;
 use gax::backoff_policy::BackoffPolicy;
 use gax::client_builder::Error as BuilderError;
+use gax::compression::Compression;
 use gax::error::Error;
 use gax::exponential_backoff::ExponentialBackoff;
 use gax::polling_backoff_policy::PollingBackoffPolicy;
@@ -23,20 +29,29 @@ use gax::polling_error_policy::{Aip194Strict as PollingAip194Strict, PollingErro
 use gax::response::{Parts, Response};
 use gax::retry_policy::{Aip194Strict as RetryAip194Strict, RetryPolicy, RetryPolicyExt as _};
 use gax::retry_throttler::SharedRetryThrottler;
+use gax::transport_config::TransportConfig;
 use http::{Extensions, Method};
 use std::sync::Arc;
 use std::time::Duration;
+use tracing::Instrument as _;
 
 #[derive(Clone, Debug)]
 pub struct ReqwestClient {
     inner: reqwest::Client,
     cred: Credentials,
     endpoint: String,
+    universe_domain_check: UniverseDomainCheck,
     retry_policy: Arc<dyn RetryPolicy>,
     backoff_policy: Arc<dyn BackoffPolicy>,
     retry_throttler: SharedRetryThrottler,
     polling_error_policy: Arc<dyn PollingErrorPolicy>,
     polling_backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    request_compression: Compression,
+    accept_compression: Vec<Compression>,
+    metrics: Metrics,
+    tracing: Tracing,
+    logging: DebugLogging,
+    limits: Limits,
 }
 
 impl ReqwestClient {
@@ -45,14 +60,22 @@ impl ReqwestClient {
         default_endpoint: &str,
     ) -> gax::client_builder::Result<Self> {
         let cred = Self::make_credentials(&config).await?;
-        let inner = reqwest::Client::new();
-        let endpoint = config
-            .endpoint
-            .unwrap_or_else(|| default_endpoint.to_string());
+        let inner = Self::make_inner(&config.transport_config)?;
+        let tracing_enabled = crate::options::tracing_enabled(&config);
+        let logging = DebugLogging::new(crate::options::debug_logging_enabled(&config));
+        let limits = Limits::new(&config);
+        let endpoint = universe_domain::endpoint(
+            config.endpoint,
+            default_endpoint,
+            config.universe_domain.as_deref(),
+        );
+        let metrics = Metrics::new(config.metrics, default_endpoint, &endpoint, "http");
+        let tracing = Tracing::new(tracing_enabled, default_endpoint, &endpoint, "http");
         Ok(Self {
             inner,
             cred,
             endpoint,
+            universe_domain_check: UniverseDomainCheck::new(config.universe_domain),
             retry_policy: config.retry_policy.unwrap_or_else(|| {
                 Arc::new(
                     RetryAip194Strict
@@ -70,6 +93,12 @@ impl ReqwestClient {
             polling_backoff_policy: config
                 .polling_backoff_policy
                 .unwrap_or_else(|| Arc::new(ExponentialBackoff::default())),
+            request_compression: config.request_compression,
+            accept_compression: config.accept_compression,
+            metrics,
+            tracing,
+            logging,
+            limits,
         })
     }
 
@@ -90,8 +119,26 @@ impl ReqwestClient {
                 reqwest::header::HeaderValue::from_str(user_agent).map_err(Error::ser)?,
             );
         }
+        let accept = options
+            .accept_compression()
+            .unwrap_or(&self.accept_compression);
+        if let Some(accept) = accept_encoding(accept) {
+            builder = builder.header(reqwest::header::ACCEPT_ENCODING, accept);
+        }
         if let Some(body) = body {
-            builder = builder.json(&body);
+            let compression = options
+                .request_compression()
+                .unwrap_or(self.request_compression);
+            builder = match compression {
+                Compression::Identity => builder.json(&body),
+                c => {
+                    let payload = serde_json::to_vec(&body).map_err(Error::ser)?;
+                    builder
+                        .header(reqwest::header::CONTENT_TYPE, "application/json")
+                        .header(reqwest::header::CONTENT_ENCODING, c.name())
+                        .body(compress(c, &payload)?)
+                }
+            };
         }
         self.retry_loop::<O>(builder, options).await
     }
@@ -107,25 +154,88 @@ impl ReqwestClient {
             .map_err(BuilderError::cred)
     }
 
+    fn make_inner(config: &TransportConfig) -> gax::client_builder::Result<reqwest::Client> {
+        // The client decodes compressed responses itself. This prevents
+        // `reqwest` from sending its own `Accept-Encoding` header if another
+        // crate enables its compression features.
+        let mut builder = reqwest::Client::builder()
+            .no_gzip()
+            .no_zstd()
+            .tls_built_in_root_certs(config.builtin_root_certificates());
+        if let Some(proxy) = config.proxy() {
+            let mut p = reqwest::Proxy::all(proxy.url()).map_err(BuilderError::transport)?;
+            if let Some((username, password)) = proxy.basic_auth() {
+                p = p.basic_auth(username, password);
+            }
+            builder = builder.proxy(p);
+        }
+        for pem in config.root_certificates_pem() {
+            let certificates =
+                reqwest::Certificate::from_pem_bundle(pem).map_err(BuilderError::transport)?;
+            if certificates.is_empty() {
+                return Err(BuilderError::transport(
+                    "no certificates found in root certificates bundle",
+                ));
+            }
+            builder = certificates
+                .into_iter()
+                .fold(builder, |b, c| b.add_root_certificate(c));
+        }
+        if let Some(identity) = config.client_identity() {
+            let pem = [identity.certificate_pem(), identity.private_key_pem()].join(&b'\n');
+            let identity = reqwest::Identity::from_pem(&pem).map_err(BuilderError::transport)?;
+            builder = builder.identity(identity);
+        }
+        if let Some(timeout) = config.pool_idle_timeout() {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = config.pool_max_idle_per_host() {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if config.http2_prior_knowledge() {
+            builder = builder.http2_prior_knowledge();
+        }
+        builder.build().map_err(BuilderError::transport)
+    }
+
     async fn retry_loop<O: serde::de::DeserializeOwned + Default>(
         &self,
         builder: reqwest::RequestBuilder,
         options: gax::options::RequestOptions,
     ) -> Result<Response<O>> {
+        let operation = self.metrics.http_operation(&builder);
+        let span = self.tracing.http_operation(&builder);
         let idempotent = options.idempotent().unwrap_or(false);
-        let throttler = self.get_retry_throttler(&options);
+        let throttler = operation.throttler(self.get_retry_throttler(&options));
         let retry = self.get_retry_policy(&options);
         let backoff = self.get_backoff_policy(&options);
+        let deadline = options.deadline();
         let this = self.clone();
+        let recorder = operation.clone();
+        let spans = span.clone();
         let inner = async move |d| {
             let builder = builder
                 .try_clone()
                 .expect("client libraries only create builders where `try_clone()` succeeds");
-            this.request_attempt(builder, &options, d).await
+            let attempt = recorder.attempt();
+            let attempt_span = spans.attempt();
+            let result = this
+                .request_attempt(builder, &options, d, &attempt)
+                .instrument(attempt_span.clone())
+                .await;
+            trace::record_outcome(&attempt_span, &result);
+            attempt.finish(&result);
+            result
         };
         let sleep = async |d| tokio::time::sleep(d).await;
-        gax::retry_loop_internal::retry_loop(inner, sleep, idempotent, throttler, retry, backoff)
-            .await
+        let result = gax::retry_loop_internal::retry_loop_with_deadline(
+            inner, sleep, idempotent, throttler, retry, backoff, deadline,
+        )
+        .instrument(span.span().clone())
+        .await;
+        span.finish(&result);
+        operation.finish(&result);
+        result
     }
 
     async fn request_attempt<O: serde::de::DeserializeOwned + Default>(
@@ -133,16 +243,54 @@ impl ReqwestClient {
         mut builder: reqwest::RequestBuilder,
         options: &gax::options::RequestOptions,
         remaining_time: Option<std::time::Duration>,
+        attempt: &Attempt<'_>,
     ) -> Result<Response<O>> {
-        builder = gax::retry_loop_internal::effective_timeout(options, remaining_time)
-            .into_iter()
-            .fold(builder, |b, t| b.timeout(t));
+        self.universe_domain_check.check(&self.cred).await?;
         builder = match self.cred.headers(Extensions::new()).await {
             Err(e) => return Err(Error::authentication(e)),
             Ok(CacheableResource::New { data, .. }) => builder.headers(data),
             Ok(CacheableResource::NotModified) => unreachable!("headers are not cached"),
         };
-        let response = builder.send().await.map_err(Self::map_send_error)?;
+        if self.tracing.enabled() {
+            builder = builder.headers(trace::propagation_headers());
+        }
+        let (client, request) = builder.build_split();
+        let mut request = request.map_err(Self::map_send_error)?;
+        let size = || {
+            request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .map_or(0, <[u8]>::len)
+        };
+        if attempt.enabled() {
+            attempt.request_size(size());
+        }
+        let timeout = gax::retry_loop_internal::effective_timeout(options, remaining_time);
+        let (_permit, timeout) = self.limits.acquire(options, size, timeout).await?;
+        if let Some(t) = timeout {
+            *request.timeout_mut() = Some(t);
+        }
+        self.logging.http_request(&request);
+        let url = self.logging.enabled().then(|| request.url().clone());
+        let response = client
+            .execute(request)
+            .await
+            .map_err(Self::map_send_error)?;
+        let mut response = decode_response(response).await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            // The service rejected the token, it may have been revoked before
+            // its expiration time. Fetch a new one for any retry attempts.
+            self.cred.force_refresh().await;
+        }
+        if attempt.enabled() || self.logging.enabled() {
+            let (buffered, body) = buffer_response(response).await?;
+            attempt.response_size(body.len());
+            if let Some(url) = &url {
+                self.logging
+                    .http_response(url, buffered.status(), buffered.headers(), &body);
+            }
+            response = buffered;
+        }
         if !response.status().is_success() {
             return self::to_http_error(response).await;
         }
@@ -188,20 +336,22 @@ impl ReqwestClient {
         &self,
         options: &gax::options::RequestOptions,
     ) -> Arc<dyn PollingErrorPolicy> {
-        options
+        let policy = options
             .polling_error_policy()
             .clone()
-            .unwrap_or_else(|| self.polling_error_policy.clone())
+            .unwrap_or_else(|| self.polling_error_policy.clone());
+        gax::retry_loop_internal::polling_error_policy_with_deadline(policy, options.deadline())
     }
 
     pub fn get_polling_backoff_policy(
         &self,
         options: &gax::options::RequestOptions,
     ) -> Arc<dyn PollingBackoffPolicy> {
-        options
+        let policy = options
             .polling_backoff_policy()
             .clone()
-            .unwrap_or_else(|| self.polling_backoff_policy.clone())
+            .unwrap_or_else(|| self.polling_backoff_policy.clone());
+        gax::retry_loop_internal::polling_backoff_policy_with_deadline(policy, options.deadline())
     }
 }
 
@@ -223,6 +373,77 @@ pub fn default_idempotency(m: &Method) -> bool {
     m == Method::GET || m == Method::PUT || m == Method::DELETE
 }
 
+// Returns the `Accept-Encoding` header value for the accepted compression
+// algorithms, if the application accepts any.
+fn accept_encoding(accept: &[Compression]) -> Option<String> {
+    if accept.is_empty() {
+        return None;
+    }
+    let names = accept.iter().map(Compression::name).collect::<Vec<_>>();
+    Some(names.join(", "))
+}
+
+fn compress(compression: Compression, payload: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Write as _;
+    match compression {
+        Compression::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(payload).map_err(Error::ser)?;
+            encoder.finish().map_err(Error::ser)
+        }
+        Compression::Zstd => zstd::stream::encode_all(payload, 0).map_err(Error::ser),
+        _ => Ok(payload.to_vec()),
+    }
+}
+
+fn decompress(compression: Compression, payload: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read as _;
+    match compression {
+        Compression::Gzip => {
+            let mut decoded = Vec::new();
+            flate2::read::GzDecoder::new(payload)
+                .read_to_end(&mut decoded)
+                .map_err(Error::deser)?;
+            Ok(decoded)
+        }
+        Compression::Zstd => zstd::stream::decode_all(payload).map_err(Error::deser),
+        _ => Ok(payload.to_vec()),
+    }
+}
+
+// Decodes compressed response payloads, returning an equivalent response
+// without the `Content-Encoding` header.
+async fn decode_response(response: reqwest::Response) -> Result<reqwest::Response> {
+    let encoding = response.headers().get(reqwest::header::CONTENT_ENCODING);
+    let compression = match encoding.map(|v| v.as_bytes()) {
+        Some(b"gzip") => Compression::Gzip,
+        Some(b"zstd") => Compression::Zstd,
+        _ => return Ok(response),
+    };
+    let response = http::Response::from(response);
+    let (mut parts, body) = response.into_parts();
+    let body = http_body_util::BodyExt::collect(body)
+        .await
+        .map_err(Error::io)?
+        .to_bytes();
+    let decoded = bytes::Bytes::from(decompress(compression, &body)?);
+    parts.headers.remove(reqwest::header::CONTENT_ENCODING);
+    parts.headers.remove(reqwest::header::CONTENT_LENGTH);
+    Ok(http::Response::from_parts(parts, decoded).into())
+}
+
+// Collects the response body, returning an equivalent response and the body.
+async fn buffer_response(response: reqwest::Response) -> Result<(reqwest::Response, bytes::Bytes)> {
+    let response = http::Response::from(response);
+    let (parts, body) = response.into_parts();
+    let body = http_body_util::BodyExt::collect(body)
+        .await
+        .map_err(Error::io)?
+        .to_bytes();
+    Ok((http::Response::from_parts(parts, body.clone()).into(), body))
+}
+
 pub async fn to_http_error<O>(response: reqwest::Response) -> Result<O> {
     let status_code = response.status().as_u16();
     let response = http::Response::from(response);
@@ -267,10 +488,73 @@ async fn to_http_response<O: serde::de::DeserializeOwned + Default>(
 
 #[cfg(test)]
 mod tests {
+    use gax::compression::Compression;
     use http::{HeaderMap, HeaderValue, Method};
     use test_case::test_case;
     type TestResult = std::result::Result<(), Box<dyn std::error::Error>>;
 
+    #[test_case(&[], None)]
+    #[test_case(&[Compression::Gzip], Some("gzip"))]
+    #[test_case(&[Compression::Zstd, Compression::Gzip], Some("zstd, gzip"))]
+    fn accept_encoding(input: &[Compression], want: Option<&str>) {
+        assert_eq!(super::accept_encoding(input).as_deref(), want);
+    }
+
+    #[test]
+    fn compress() -> TestResult {
+        use std::io::Read as _;
+        let payload = br#"{"name": "projects/test-only"}"#;
+
+        let got = super::compress(Compression::Identity, payload)?;
+        assert_eq!(got, payload);
+
+        let got = super::compress(Compression::Gzip, payload)?;
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(got.as_slice()).read_to_end(&mut decoded)?;
+        assert_eq!(decoded, payload);
+
+        let got = super::compress(Compression::Zstd, payload)?;
+        assert_eq!(zstd::stream::decode_all(got.as_slice())?, payload);
+        Ok(())
+    }
+
+    #[test_case(Compression::Identity)]
+    #[test_case(Compression::Gzip)]
+    #[test_case(Compression::Zstd)]
+    fn decompress(compression: Compression) -> TestResult {
+        let payload = br#"{"name": "projects/test-only"}"#;
+        let compressed = super::compress(compression, payload)?;
+        let got = super::decompress(compression, &compressed)?;
+        assert_eq!(got, payload);
+        Ok(())
+    }
+
+    #[test]
+    fn decompress_error() {
+        let got = super::decompress(Compression::Gzip, b"not gzip");
+        assert!(matches!(&got, Err(e) if e.is_deserialization()), "{got:?}");
+    }
+
+    #[tokio::test]
+    async fn decode_response() -> TestResult {
+        let payload = br#"{"name": "projects/test-only"}"#;
+        let compressed = super::compress(Compression::Zstd, payload)?;
+        let response = http::Response::builder()
+            .header("content-encoding", "zstd")
+            .header("content-length", compressed.len())
+            .header("x-test", "preserved")
+            .body(compressed)?;
+        let response = super::decode_response(response.into()).await?;
+        assert_eq!(response.headers().get("content-encoding"), None);
+        assert_eq!(response.headers().get("content-length"), None);
+        assert_eq!(
+            response.headers().get("x-test"),
+            Some(&HeaderValue::from_static("preserved"))
+        );
+        assert_eq!(response.bytes().await?.as_ref(), payload);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn client_http_error_bytes() -> TestResult {
         let http_resp = http::Response::builder()
@@ -51,6 +51,38 @@ pub mod unimplemented;
 #[cfg(feature = "_internal-common")]
 pub mod routing_parameter;
 
+#[cfg(any(feature = "_internal-http-client", feature = "_internal-grpc-client"))]
+pub(crate) mod attributes;
+
+#[cfg(any(feature = "_internal-http-client", feature = "_internal-grpc-client"))]
+pub(crate) mod limits;
+
+#[cfg(any(feature = "_internal-http-client", feature = "_internal-grpc-client"))]
+pub(crate) mod logging;
+
+#[cfg(all(
+    feature = "_internal-opentelemetry",
+    any(feature = "_internal-http-client", feature = "_internal-grpc-client")
+))]
+pub(crate) mod metrics;
+
+#[cfg(all(
+    not(feature = "_internal-opentelemetry"),
+    any(feature = "_internal-http-client", feature = "_internal-grpc-client")
+))]
+mod noop_metrics;
+#[cfg(all(
+    not(feature = "_internal-opentelemetry"),
+    any(feature = "_internal-http-client", feature = "_internal-grpc-client")
+))]
+pub(crate) use noop_metrics as metrics;
+
+#[cfg(any(feature = "_internal-http-client", feature = "_internal-grpc-client"))]
+pub(crate) mod trace;
+
+#[cfg(any(feature = "_internal-http-client", feature = "_internal-grpc-client"))]
+pub(crate) mod universe_domain;
+
 #[cfg(feature = "_internal-grpc-client")]
 pub(crate) mod google {
     pub mod rpc {
@@ -0,0 +1,158 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applies the client-side rate and concurrency limits to each attempt.
+
+use gax::Result;
+use gax::error::Error;
+use gax::options::RequestOptions;
+use gax::rate_limiter::{ConcurrencyLimiter, ConcurrencyPermit, RateLimiter};
+use std::time::Duration;
+
+/// The rate and concurrency limits configured in a client.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Limits {
+    rate_limiter: Option<RateLimiter>,
+    concurrency_limiter: Option<ConcurrencyLimiter>,
+}
+
+impl Limits {
+    pub(crate) fn new(config: &crate::options::ClientConfig) -> Self {
+        Self {
+            rate_limiter: config.rate_limiter.clone(),
+            concurrency_limiter: config.concurrency_limiter.clone(),
+        }
+    }
+
+    /// Waits until an attempt is within the limits.
+    ///
+    /// The request options override the client limits. `bytes` returns the
+    /// size of the request payload, it is only called if there is a rate
+    /// limiter.
+    ///
+    /// Returns the permit to hold while the attempt is in flight, if any, and
+    /// what remains of the attempt `timeout` after waiting. Returns a timeout
+    /// error if the attempt cannot start before its `timeout`.
+    pub(crate) async fn acquire<F>(
+        &self,
+        options: &RequestOptions,
+        bytes: F,
+        timeout: Option<Duration>,
+    ) -> Result<(Option<ConcurrencyPermit>, Option<Duration>)>
+    where
+        F: FnOnce() -> usize,
+    {
+        let rate_limiter = options.rate_limiter().or(self.rate_limiter.as_ref());
+        let concurrency_limiter = options
+            .concurrency_limiter()
+            .or(self.concurrency_limiter.as_ref());
+        if rate_limiter.is_none() && concurrency_limiter.is_none() {
+            return Ok((None, timeout));
+        }
+        let start = tokio::time::Instant::now();
+        let wait = async move {
+            let permit = match concurrency_limiter {
+                Some(l) => Some(l.acquire().await),
+                None => None,
+            };
+            if let Some(l) = rate_limiter {
+                l.acquire(bytes()).await;
+            }
+            permit
+        };
+        let permit = match timeout {
+            None => wait.await,
+            Some(t) => tokio::time::timeout(t, wait).await.map_err(|_| {
+                Error::timeout("timed out waiting for the client-side rate or concurrency limits")
+            })?,
+        };
+        let remaining = timeout.map(|t| t.saturating_sub(start.elapsed()));
+        Ok((permit, remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::ClientConfig;
+
+    #[tokio::test]
+    async fn no_limits() -> anyhow::Result<()> {
+        let limits = Limits::new(&ClientConfig::default());
+        let timeout = Some(Duration::from_secs(5));
+        let (permit, remaining) = limits
+            .acquire(&RequestOptions::default(), || unreachable!(), timeout)
+            .await?;
+        assert!(permit.is_none(), "{permit:?}");
+        assert_eq!(remaining, timeout);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_limits() -> anyhow::Result<()> {
+        let config = ClientConfig {
+            rate_limiter: Some(RateLimiter::new().with_bytes_per_second(100.0)?),
+            concurrency_limiter: Some(ConcurrencyLimiter::new(1)?),
+            ..ClientConfig::default()
+        };
+        let limits = Limits::new(&config);
+        let options = RequestOptions::default();
+        let (permit, remaining) = limits
+            .acquire(&options, || 100, Some(Duration::from_secs(5)))
+            .await?;
+        assert!(permit.is_some(), "{permit:?}");
+        assert_eq!(remaining, Some(Duration::from_secs(5)));
+        drop(permit);
+
+        // Waits for the rate limiter, and returns the remaining time.
+        let (permit, remaining) = limits
+            .acquire(&options, || 50, Some(Duration::from_secs(5)))
+            .await?;
+        assert!(permit.is_some(), "{permit:?}");
+        let remaining = remaining.expect("timeout is set");
+        assert!(
+            remaining <= Duration::from_millis(4505) && remaining >= Duration::from_millis(4495),
+            "{remaining:?}"
+        );
+
+        // Times out waiting for the concurrency limiter.
+        let err = limits
+            .acquire(&options, || 0, Some(Duration::from_secs(1)))
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout(), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_limits() -> anyhow::Result<()> {
+        let config = ClientConfig {
+            concurrency_limiter: Some(ConcurrencyLimiter::new(1)?),
+            ..ClientConfig::default()
+        };
+        let limits = Limits::new(&config);
+        let (_held, _) = limits
+            .acquire(&RequestOptions::default(), || 0, None)
+            .await?;
+
+        // The request options replace the client limits.
+        let mut options = RequestOptions::default();
+        options.set_concurrency_limiter(ConcurrencyLimiter::new(1)?);
+        let (permit, _) = limits
+            .acquire(&options, || 0, Some(Duration::from_millis(10)))
+            .await?;
+        assert!(permit.is_some(), "{permit:?}");
+        Ok(())
+    }
+}
@@ -0,0 +1,603 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Logs each request attempt and its response, for troubleshooting.
+//!
+//! When enabled, the client emits a `DEBUG` level [tracing] event before
+//! sending each request attempt, and another event when the attempt completes.
+//! The events include the method, the URL or gRPC path, the headers, and the
+//! payload.
+//!
+//! Credentials never appear in the logs: the `authorization` and API key
+//! headers, any header marked as sensitive, API keys in the query string, and
+//! payload fields that are known to contain secrets or application data are
+//! redacted. Binary (`bytes`) values in gRPC payloads are always redacted.
+//! Payloads larger than [MAX_BODY_SIZE] are truncated.
+//!
+//! [tracing]: https://docs.rs/tracing
+
+use http::HeaderMap;
+use std::collections::BTreeMap;
+
+/// The maximum number of bytes logged for each payload.
+pub(crate) const MAX_BODY_SIZE: usize = 8 * 1024;
+
+/// Replaces the value of any redacted header, parameter, or field.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+// Headers containing credentials. The credentials mark most of their headers
+// as sensitive, these are redacted even if they are not.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-goog-api-key",
+    "cookie",
+    "set-cookie",
+];
+
+#[cfg(feature = "_internal-http-client")]
+// Query parameters containing credentials.
+const SENSITIVE_PARAMETERS: &[&str] = &["key", "access_token"];
+
+// Payload fields containing credentials, secrets, or application data, in
+// `snake_case`. The JSON payloads use `camelCase`, the comparison ignores case
+// and underscores.
+const SENSITIVE_FIELDS: &[&str] = &[
+    "access_token",
+    "api_key",
+    "client_secret",
+    "data",
+    "id_token",
+    "password",
+    "payload",
+    "private_key",
+    "refresh_token",
+    "secret",
+    "secret_data",
+];
+
+/// Logs the requests and responses for a client.
+///
+/// A default-constructed value logs nothing, this is what clients use unless
+/// the application enables debug logging.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DebugLogging {
+    enabled: bool,
+}
+
+impl DebugLogging {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    /// Returns true if the client logs requests and responses.
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Logs an HTTP request attempt.
+    #[cfg(feature = "_internal-http-client")]
+    pub(crate) fn http_request(&self, request: &reqwest::Request) {
+        if !self.enabled {
+            return;
+        }
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(json_payload);
+        tracing::debug!(
+            rpc.system = "http",
+            http.request.method = %request.method(),
+            url.full = %redact_url(request.url()),
+            headers = ?redact_headers(request.headers()),
+            body = body.as_deref(),
+            "sending request"
+        );
+    }
+
+    /// Logs the response to an HTTP request attempt.
+    #[cfg(feature = "_internal-http-client")]
+    pub(crate) fn http_response(
+        &self,
+        url: &reqwest::Url,
+        status: reqwest::StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) {
+        if !self.enabled {
+            return;
+        }
+        tracing::debug!(
+            rpc.system = "http",
+            url.full = %redact_url(url),
+            http.response.status_code = status.as_u16(),
+            headers = ?redact_headers(headers),
+            body = json_payload(body),
+            "received response"
+        );
+    }
+
+    /// Logs a gRPC request attempt.
+    #[cfg(feature = "_internal-grpc-client")]
+    pub(crate) fn grpc_request<M: std::fmt::Debug>(
+        &self,
+        path: &str,
+        headers: &HeaderMap,
+        request: &M,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        tracing::debug!(
+            rpc.system = "grpc",
+            rpc.method = path,
+            headers = ?redact_headers(headers),
+            body = debug_payload(request),
+            "sending request"
+        );
+    }
+
+    /// Logs the response to a gRPC request attempt.
+    #[cfg(feature = "_internal-grpc-client")]
+    pub(crate) fn grpc_response<M: std::fmt::Debug>(
+        &self,
+        path: &str,
+        result: &std::result::Result<tonic::Response<M>, tonic::Status>,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        match result {
+            Ok(response) => tracing::debug!(
+                rpc.system = "grpc",
+                rpc.method = path,
+                rpc.response.status_code = "OK",
+                headers = ?redact_headers(&response.metadata().clone().into_headers()),
+                body = debug_payload(response.get_ref()),
+                "received response"
+            ),
+            Err(status) => tracing::debug!(
+                rpc.system = "grpc",
+                rpc.method = path,
+                rpc.response.status_code = ?status.code(),
+                headers = ?redact_headers(&status.metadata().clone().into_headers()),
+                message = status.message(),
+                "received error"
+            ),
+        }
+    }
+}
+
+/// Returns the headers as a map, with any credentials redacted.
+pub(crate) fn redact_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if value.is_sensitive() || SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).to_string()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+/// Returns the URL with any credentials in the query string redacted.
+#[cfg(feature = "_internal-http-client")]
+pub(crate) fn redact_url(url: &reqwest::Url) -> String {
+    if !url
+        .query_pairs()
+        .any(|(k, _)| SENSITIVE_PARAMETERS.contains(&k.as_ref()))
+    {
+        return url.to_string();
+    }
+    let mut redacted = url.clone();
+    redacted
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(url.query_pairs().map(|(k, v)| {
+            match SENSITIVE_PARAMETERS.contains(&k.as_ref()) {
+                true => (k, REDACTED.into()),
+                false => (k, v),
+            }
+        }));
+    redacted.to_string()
+}
+
+#[cfg(feature = "_internal-http-client")]
+/// Returns a JSON payload suitable for logging.
+///
+/// Payloads that are not valid JSON, for example compressed payloads, are
+/// replaced by their size.
+pub(crate) fn json_payload(body: &[u8]) -> String {
+    if body.is_empty() {
+        return String::new();
+    }
+    match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            truncate(value.to_string())
+        }
+        Err(_) => format!("[{} bytes]", body.len()),
+    }
+}
+
+#[cfg(feature = "_internal-grpc-client")]
+/// Returns the debug representation of a message, suitable for logging.
+pub(crate) fn debug_payload<M: std::fmt::Debug>(message: &M) -> String {
+    truncate(redact_debug(format!("{message:?}")))
+}
+
+fn is_sensitive_field(name: &str) -> bool {
+    let normalized = name.replace('_', "").to_ascii_lowercase();
+    SENSITIVE_FIELDS
+        .iter()
+        .any(|f| f.replace('_', "") == normalized)
+}
+
+#[cfg(feature = "_internal-http-client")]
+fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if is_sensitive_field(k) {
+                    *v = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(v);
+                }
+            }
+        }
+        serde_json::Value::Array(list) => list.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+#[cfg(feature = "_internal-grpc-client")]
+// Redacts the `Debug` representation of a message.
+//
+// The value of any sensitive field is redacted, these look like
+// `name: "value"`, `name: Some("value")`, `name: b"value"`, or
+// `name: Message { ... }`. Binary values, formatted as `b"..."`, may contain
+// arbitrary application data and are always redacted.
+fn redact_debug(text: String) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut start = 0;
+    while let Some(c) = text[start..].chars().next() {
+        if c == '"' {
+            let end = string_end(&text, start);
+            redacted.push_str(&text[start..end]);
+            start = end;
+            continue;
+        }
+        if !(c.is_alphanumeric() || c == '_') {
+            redacted.push(c);
+            start += c.len_utf8();
+            continue;
+        }
+        let end = text[start..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map_or(text.len(), |e| start + e);
+        let token = &text[start..end];
+        if token == "b" && text[end..].starts_with('"') {
+            redacted.push_str(&format!("b\"{REDACTED}\""));
+            start = string_end(&text, end);
+            continue;
+        }
+        redacted.push_str(token);
+        start = end;
+        if is_sensitive_field(token) && text[end..].starts_with(": ") {
+            let value = end + ": ".len();
+            let field_value = text[value..value_end(&text, value)].trim_end();
+            redacted.push_str(": ");
+            redacted.push_str(&redact_debug_value(field_value));
+            start = value + field_value.len();
+        }
+    }
+    redacted
+}
+
+#[cfg(feature = "_internal-grpc-client")]
+// Returns the redacted `Debug` representation of a sensitive field value.
+fn redact_debug_value(value: &str) -> String {
+    if value == "None" {
+        return value.to_string();
+    }
+    if let Some(inner) = value
+        .strip_prefix("Some(")
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        return format!("Some({})", redact_debug_value(inner));
+    }
+    if value.starts_with("b\"") {
+        return format!("b\"{REDACTED}\"");
+    }
+    format!("\"{REDACTED}\"")
+}
+
+#[cfg(feature = "_internal-grpc-client")]
+// Finds the end of a field value in a `Debug` formatted message, that is, the
+// first `,` or closing bracket outside any nested value or string.
+fn value_end(text: &str, start: usize) -> usize {
+    let mut depth = 0_usize;
+    let mut offset = start;
+    while let Some(c) = text[offset..].chars().next() {
+        match c {
+            '"' => {
+                offset = string_end(text, offset);
+                continue;
+            }
+            '{' | '[' | '(' => depth += 1,
+            '}' | ']' | ')' if depth == 0 => return offset,
+            '}' | ']' | ')' => depth -= 1,
+            ',' if depth == 0 => return offset,
+            _ => {}
+        }
+        offset += c.len_utf8();
+    }
+    text.len()
+}
+
+#[cfg(feature = "_internal-grpc-client")]
+// Returns the offset just past the `Debug` formatted string starting at
+// `start`, skipping escapes.
+fn string_end(text: &str, start: usize) -> usize {
+    let mut escaped = false;
+    for (i, c) in text[start + 1..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return start + 1 + i + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+fn truncate(mut text: String) -> String {
+    if text.len() <= MAX_BODY_SIZE {
+        return text;
+    }
+    let mut end = MAX_BODY_SIZE;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let omitted = text.len() - end;
+    text.truncate(end);
+    text.push_str(&format!("...[{omitted} bytes truncated]"));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[test]
+    fn headers() {
+        let mut sensitive = HeaderValue::from_static("secret");
+        sensitive.set_sensitive(true);
+        let headers = HeaderMap::from_iter([
+            (
+                http::header::AUTHORIZATION,
+                HeaderValue::from_static("Bearer token"),
+            ),
+            (
+                http::HeaderName::from_static("x-goog-api-key"),
+                HeaderValue::from_static("api-key"),
+            ),
+            (http::HeaderName::from_static("x-custom"), sensitive),
+            (
+                http::HeaderName::from_static("x-goog-user-project"),
+                HeaderValue::from_static("my-project"),
+            ),
+        ]);
+        let got = redact_headers(&headers);
+        let want = BTreeMap::from_iter(
+            [
+                ("authorization", REDACTED),
+                ("x-goog-api-key", REDACTED),
+                ("x-custom", REDACTED),
+                ("x-goog-user-project", "my-project"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string())),
+        );
+        assert_eq!(got, want);
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    #[test_case("https://example.com/v1/foo", "https://example.com/v1/foo")]
+    #[test_case("https://example.com/v1/foo?a=b", "https://example.com/v1/foo?a=b")]
+    #[test_case(
+        "https://example.com/v1/foo?a=b&key=k&c=d",
+        "https://example.com/v1/foo?a=b&key=%5BREDACTED%5D&c=d"
+    )]
+    #[test_case(
+        "https://example.com/v1/foo?access_token=t",
+        "https://example.com/v1/foo?access_token=%5BREDACTED%5D"
+    )]
+    fn url(input: &str, want: &str) -> anyhow::Result<()> {
+        let got = redact_url(&reqwest::Url::parse(input)?);
+        assert_eq!(got, want);
+        Ok(())
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    #[test]
+    fn json() {
+        let body = json!({
+            "name": "projects/p/secrets/s",
+            "labels": {"data": "abc", "secretData": "abc", "owner": "o"},
+            "users": [{"name": "u", "password": "p"}],
+            "private_key": "pk",
+            "apiKey": {"nested": "k"},
+        });
+        let got = json_payload(body.to_string().as_bytes());
+        let got = serde_json::from_str::<serde_json::Value>(&got).unwrap();
+        let want = json!({
+            "name": "projects/p/secrets/s",
+            "labels": {"data": REDACTED, "secretData": REDACTED, "owner": "o"},
+            "users": [{"name": "u", "password": REDACTED}],
+            "private_key": REDACTED,
+            "apiKey": REDACTED,
+        });
+        assert_eq!(got, want);
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    #[test]
+    fn json_secret_payload() {
+        // A Secret Manager `AccessSecretVersionResponse`.
+        let body = json!({
+            "name": "projects/p/secrets/s/versions/1",
+            "payload": {"data": "c2VjcmV0", "dataCrc32c": "123"},
+        });
+        let got = json_payload(body.to_string().as_bytes());
+        assert!(!got.contains("c2VjcmV0"), "{got}");
+        let got = serde_json::from_str::<serde_json::Value>(&got).unwrap();
+        let want = json!({
+            "name": "projects/p/secrets/s/versions/1",
+            "payload": REDACTED,
+        });
+        assert_eq!(got, want);
+
+        // A Secret Manager `AddSecretVersionRequest`.
+        let body = json!({"payload": {"data": "c2VjcmV0"}});
+        let got = json_payload(body.to_string().as_bytes());
+        assert!(!got.contains("c2VjcmV0"), "{got}");
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    #[test_case(b"", ""; "empty")]
+    #[test_case(b"\x1f\x8b\x08\x00", "[4 bytes]"; "binary")]
+    fn json_not_json(input: &[u8], want: &str) {
+        assert_eq!(json_payload(input), want);
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Message {
+        name: String,
+        password: String,
+        api_key: Option<String>,
+        my_password: String,
+        count: i32,
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[test]
+    fn debug() {
+        let message = Message {
+            name: "n".into(),
+            password: "p\"with\\escapes".into(),
+            api_key: Some("k".into()),
+            my_password: "not-redacted".into(),
+            count: 42,
+        };
+        let got = debug_payload(&message);
+        assert_eq!(
+            got,
+            r#"Message { name: "n", password: "[REDACTED]", api_key: Some("[REDACTED]"), my_password: "not-redacted", count: 42 }"#
+        );
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct SecretPayload {
+        data: bytes::Bytes,
+        data_crc32c: Option<i64>,
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct AccessSecretVersionResponse {
+        name: String,
+        payload: Option<SecretPayload>,
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[test]
+    fn debug_secret_payload() {
+        let payload = SecretPayload {
+            data: bytes::Bytes::from_static(b"secret"),
+            data_crc32c: Some(123),
+        };
+        let got = debug_payload(&payload);
+        assert_eq!(
+            got,
+            r#"SecretPayload { data: b"[REDACTED]", data_crc32c: Some(123) }"#
+        );
+
+        let response = AccessSecretVersionResponse {
+            name: "projects/p/secrets/s/versions/1".into(),
+            payload: Some(payload),
+        };
+        let got = debug_payload(&response);
+        assert_eq!(
+            got,
+            r#"AccessSecretVersionResponse { name: "projects/p/secrets/s/versions/1", payload: Some("[REDACTED]") }"#
+        );
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct WithBytes {
+        name: String,
+        key: bytes::Bytes,
+        keys: Vec<bytes::Bytes>,
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    #[test]
+    fn debug_bytes() {
+        let message = WithBytes {
+            name: "b\"quoted\"".into(),
+            key: bytes::Bytes::from_static(b"k\"ey"),
+            keys: vec![bytes::Bytes::from_static(b"a"), bytes::Bytes::new()],
+        };
+        let got = debug_payload(&message);
+        assert_eq!(
+            got,
+            r#"WithBytes { name: "b\"quoted\"", key: b"[REDACTED]", keys: [b"[REDACTED]", b"[REDACTED]"] }"#
+        );
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    #[test]
+    fn truncated() {
+        let body = json!({"description": "a".repeat(2 * MAX_BODY_SIZE)});
+        let got = json_payload(body.to_string().as_bytes());
+        assert!(got.len() < MAX_BODY_SIZE + 64, "{}", got.len());
+        assert!(got.ends_with("bytes truncated]"), "{got}");
+
+        let got = truncate("é".repeat(MAX_BODY_SIZE));
+        assert!(got.ends_with("...[8192 bytes truncated]"), "{got}");
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    #[test]
+    fn disabled() {
+        let logging = DebugLogging::default();
+        assert!(!logging.enabled());
+        let logging = DebugLogging::new(true);
+        assert!(logging.enabled());
+    }
+}
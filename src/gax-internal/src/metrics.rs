@@ -0,0 +1,524 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Records OpenTelemetry metrics for each client request.
+//!
+//! The metrics follow the semantic conventions for Google Cloud client
+//! libraries. Each *operation* is a call to a client method, including all its
+//! retry attempts. Each *attempt* is a single RPC sent to the service.
+
+use crate::attributes::*;
+use gax::Result;
+use gax::retry_result::RetryResult;
+use gax::retry_throttler::{RetryThrottler, SharedRetryThrottler};
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::{InstrumentationScope, KeyValue};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+pub(crate) const OPERATION_DURATION: &str = "gcp.client.operation.duration";
+pub(crate) const ATTEMPT_DURATION: &str = "gcp.client.attempt.duration";
+pub(crate) const ATTEMPT_COUNT: &str = "gcp.client.attempt.count";
+pub(crate) const RETRY_COUNT: &str = "gcp.client.retry.count";
+pub(crate) const THROTTLE_COUNT: &str = "gcp.client.throttle.count";
+pub(crate) const REQUEST_SIZE: &str = "gcp.client.request.size";
+pub(crate) const RESPONSE_SIZE: &str = "gcp.client.response.size";
+
+// Most RPCs complete in milliseconds, but some take minutes.
+const DURATION_BOUNDARIES: [f64; 19] = [
+    0.0, 0.005, 0.01, 0.025, 0.05, 0.075, 0.1, 0.25, 0.5, 0.75, 1.0, 2.5, 5.0, 7.5, 10.0, 30.0,
+    60.0, 120.0, 300.0,
+];
+
+// Powers of 4, from 1KiB to 64MiB.
+const SIZE_BOUNDARIES: [f64; 10] = [
+    0.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0, 16777216.0, 67108864.0,
+];
+
+/// The metrics recorded by a client.
+///
+/// A default-constructed value records nothing, this is what clients use when
+/// metrics are disabled.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Metrics {
+    instruments: Option<Arc<Instruments>>,
+}
+
+impl Metrics {
+    /// Creates the instruments using the global meter provider, if `enabled`.
+    ///
+    /// The `default_endpoint` identifies the service, even if the application
+    /// overrides the endpoint. The `endpoint` is the server receiving the
+    /// requests.
+    pub(crate) fn new(
+        enabled: bool,
+        default_endpoint: &str,
+        endpoint: &str,
+        system: &'static str,
+    ) -> Self {
+        if !enabled {
+            return Self::default();
+        }
+        let scope = InstrumentationScope::builder(env!("CARGO_PKG_NAME"))
+            .with_version(env!("CARGO_PKG_VERSION"))
+            .build();
+        let meter = opentelemetry::global::meter_with_scope(scope);
+        Self::with_meter(&meter, default_endpoint, endpoint, system)
+    }
+
+    fn with_meter(
+        meter: &Meter,
+        default_endpoint: &str,
+        endpoint: &str,
+        system: &'static str,
+    ) -> Self {
+        let mut attributes = vec![KeyValue::new(RPC_SYSTEM, system)];
+        if let Some(service) = host(default_endpoint).and_then(service) {
+            attributes.push(KeyValue::new(SERVICE, service.to_string()));
+        }
+        if let Some(address) = host(endpoint) {
+            attributes.push(KeyValue::new(SERVER_ADDRESS, address.to_string()));
+        }
+        let instruments = Instruments {
+            operation_duration: meter
+                .f64_histogram(OPERATION_DURATION)
+                .with_description(
+                    "The total time to complete a client operation, including retries.",
+                )
+                .with_unit("s")
+                .with_boundaries(DURATION_BOUNDARIES.to_vec())
+                .build(),
+            attempt_duration: meter
+                .f64_histogram(ATTEMPT_DURATION)
+                .with_description("The time to complete each attempt of a client operation.")
+                .with_unit("s")
+                .with_boundaries(DURATION_BOUNDARIES.to_vec())
+                .build(),
+            attempt_count: meter
+                .u64_counter(ATTEMPT_COUNT)
+                .with_description("The number of attempts, including the initial attempt.")
+                .with_unit("{attempt}")
+                .build(),
+            retry_count: meter
+                .u64_counter(RETRY_COUNT)
+                .with_description("The number of retry attempts.")
+                .with_unit("{attempt}")
+                .build(),
+            throttle_count: meter
+                .u64_counter(THROTTLE_COUNT)
+                .with_description("The number of retry attempts rejected by the retry throttler.")
+                .with_unit("{attempt}")
+                .build(),
+            request_size: meter
+                .u64_histogram(REQUEST_SIZE)
+                .with_description("The size of each request payload.")
+                .with_unit("By")
+                .with_boundaries(SIZE_BOUNDARIES.to_vec())
+                .build(),
+            response_size: meter
+                .u64_histogram(RESPONSE_SIZE)
+                .with_description("The size of each response payload.")
+                .with_unit("By")
+                .with_boundaries(SIZE_BOUNDARIES.to_vec())
+                .build(),
+            attributes,
+        };
+        Self {
+            instruments: Some(Arc::new(instruments)),
+        }
+    }
+
+    /// Starts recording a gRPC operation.
+    ///
+    /// The `path` is the gRPC method path, e.g.
+    /// `/google.cloud.secretmanager.v1.SecretManagerService/GetSecret`. The
+    /// `request_params` are the (percent-encoded) routing headers.
+    #[cfg(feature = "_internal-grpc-client")]
+    pub(crate) fn grpc_operation(&self, path: &str, request_params: &str) -> Operation {
+        self.operation(|attributes| {
+            let (service, method) = grpc_method(path);
+            if let Some(service) = service {
+                attributes.push(KeyValue::new(RPC_SERVICE, service.to_string()));
+            }
+            if let Some(method) = method {
+                attributes.push(KeyValue::new(RPC_METHOD, method.to_string()));
+            }
+            if let Some(location) = location(request_params) {
+                attributes.push(KeyValue::new(LOCATION, location.to_string()));
+            }
+        })
+    }
+
+    /// Starts recording an HTTP operation.
+    ///
+    /// The HTTP method and the [url_template] of the request path identify the
+    /// RPC, the HTTP transport does not receive the RPC name.
+    #[cfg(feature = "_internal-http-client")]
+    pub(crate) fn http_operation(&self, builder: &reqwest::RequestBuilder) -> Operation {
+        self.operation(|attributes| {
+            let Some(Ok(request)) = builder.try_clone().map(|b| b.build()) else {
+                return;
+            };
+            attributes.push(KeyValue::new(HTTP_METHOD, request.method().to_string()));
+            if let Some(template) = url_template(request.url().path()) {
+                attributes.push(KeyValue::new(URL_TEMPLATE, template));
+            }
+            if let Some(location) = location(request.url().path()) {
+                attributes.push(KeyValue::new(LOCATION, location.to_string()));
+            }
+        })
+    }
+
+    fn operation<F>(&self, f: F) -> Operation
+    where
+        F: FnOnce(&mut Vec<KeyValue>),
+    {
+        let attributes = self.instruments.as_ref().map(|i| {
+            let mut attributes = i.attributes.clone();
+            f(&mut attributes);
+            attributes
+        });
+        Operation {
+            instruments: self.instruments.clone(),
+            attributes: attributes.unwrap_or_default().into(),
+            start: now(),
+            attempts: Arc::new(AtomicU32::new(0)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Instruments {
+    operation_duration: Histogram<f64>,
+    attempt_duration: Histogram<f64>,
+    attempt_count: Counter<u64>,
+    retry_count: Counter<u64>,
+    throttle_count: Counter<u64>,
+    request_size: Histogram<u64>,
+    response_size: Histogram<u64>,
+    // The attributes shared by all the metrics recorded by this client.
+    attributes: Vec<KeyValue>,
+}
+
+/// Records the metrics for a single client operation.
+///
+/// Cloning is cheap, all the clones record the attempts for the same operation.
+#[derive(Clone, Debug)]
+pub(crate) struct Operation {
+    instruments: Option<Arc<Instruments>>,
+    attributes: Arc<[KeyValue]>,
+    start: Instant,
+    attempts: Arc<AtomicU32>,
+}
+
+impl Operation {
+    /// Decorates the retry throttler to count the throttled attempts.
+    pub(crate) fn throttler(&self, throttler: SharedRetryThrottler) -> SharedRetryThrottler {
+        match &self.instruments {
+            None => throttler,
+            Some(i) => Arc::new(Mutex::new(CountingThrottler {
+                inner: throttler,
+                counter: i.throttle_count.clone(),
+                attributes: self.attributes.clone(),
+            })),
+        }
+    }
+
+    /// Starts recording a new attempt.
+    pub(crate) fn attempt(&self) -> Attempt<'_> {
+        if let Some(i) = &self.instruments {
+            if self.attempts.fetch_add(1, Ordering::Relaxed) != 0 {
+                i.retry_count.add(1, &self.attributes);
+            }
+        }
+        Attempt {
+            operation: self,
+            start: now(),
+        }
+    }
+
+    /// Records the operation duration and outcome.
+    pub(crate) fn finish<T>(self, result: &Result<T>) {
+        if let Some(i) = &self.instruments {
+            let attributes = with_outcome(&self.attributes, result);
+            i.operation_duration
+                .record(elapsed(self.start), &attributes);
+        }
+    }
+}
+
+/// Records the metrics for a single attempt.
+#[derive(Debug)]
+pub(crate) struct Attempt<'a> {
+    operation: &'a Operation,
+    start: Instant,
+}
+
+impl Attempt<'_> {
+    /// Returns true if the metrics are recorded.
+    ///
+    /// Use this to skip computing the payload sizes when not needed.
+    pub(crate) fn enabled(&self) -> bool {
+        self.operation.instruments.is_some()
+    }
+
+    pub(crate) fn request_size(&self, size: usize) {
+        if let Some(i) = &self.operation.instruments {
+            i.request_size
+                .record(size as u64, &self.operation.attributes);
+        }
+    }
+
+    pub(crate) fn response_size(&self, size: usize) {
+        if let Some(i) = &self.operation.instruments {
+            i.response_size
+                .record(size as u64, &self.operation.attributes);
+        }
+    }
+
+    /// Records the attempt duration and outcome.
+    pub(crate) fn finish<T>(self, result: &Result<T>) {
+        if let Some(i) = &self.operation.instruments {
+            let attributes = with_outcome(&self.operation.attributes, result);
+            i.attempt_count.add(1, &attributes);
+            i.attempt_duration.record(elapsed(self.start), &attributes);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CountingThrottler {
+    inner: SharedRetryThrottler,
+    counter: Counter<u64>,
+    attributes: Arc<[KeyValue]>,
+}
+
+impl CountingThrottler {
+    fn lock(&self) -> std::sync::MutexGuard<'_, dyn RetryThrottler + 'static> {
+        self.inner.lock().expect("retry throttler lock is poisoned")
+    }
+}
+
+impl RetryThrottler for CountingThrottler {
+    fn throttle_retry_attempt(&self) -> bool {
+        let throttled = self.lock().throttle_retry_attempt();
+        if throttled {
+            self.counter.add(1, &self.attributes);
+        }
+        throttled
+    }
+
+    fn on_retry_failure(&mut self, flow: &RetryResult) {
+        self.lock().on_retry_failure(flow);
+    }
+
+    fn on_success(&mut self) {
+        self.lock().on_success();
+    }
+}
+
+// Use the tokio clock, so tests with paused time see consistent values.
+fn now() -> Instant {
+    tokio::time::Instant::now().into_std()
+}
+
+fn elapsed(start: Instant) -> f64 {
+    now().saturating_duration_since(start).as_secs_f64()
+}
+
+fn with_outcome<T>(attributes: &[KeyValue], result: &Result<T>) -> Vec<KeyValue> {
+    let mut attributes = attributes.to_vec();
+    match result {
+        Ok(_) => attributes.push(KeyValue::new(STATUS_CODE, "OK")),
+        Err(e) => {
+            if let Some(status) = e.status() {
+                attributes.push(KeyValue::new(STATUS_CODE, status.code.name().to_string()));
+            }
+            if let Some(code) = e.http_status_code() {
+                attributes.push(KeyValue::new(HTTP_STATUS_CODE, i64::from(code)));
+            }
+            attributes.push(KeyValue::new(ERROR_TYPE, error_type(e)));
+        }
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gax::error::Error;
+    use gax::retry_throttler::CircuitBreaker;
+    use opentelemetry::metrics::MeterProvider as _;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+    use opentelemetry_sdk::metrics::{InMemoryMetricExporter, SdkMeterProvider};
+    use std::collections::BTreeMap;
+    type TestResult = anyhow::Result<()>;
+
+    #[test]
+    fn disabled() {
+        let metrics = Metrics::default();
+        let operation = metrics.operation(|_| unreachable!("not called when disabled"));
+        assert!(operation.attributes.is_empty());
+        let attempt = operation.attempt();
+        assert!(!attempt.enabled());
+        attempt.finish(&Ok(()));
+        operation.finish(&Ok(()));
+    }
+
+    #[test]
+    fn operation() -> TestResult {
+        let (provider, exporter, metrics) = test_metrics();
+        let operation = metrics.operation(|a| a.push(KeyValue::new("rpc.method", "GetSecret")));
+        let attempt = operation.attempt();
+        assert!(attempt.enabled());
+        attempt.request_size(123);
+        attempt.finish(&Err::<(), _>(Error::io("test-only")));
+        let attempt = operation.attempt();
+        attempt.request_size(123);
+        attempt.response_size(456);
+        attempt.finish(&Ok(()));
+        operation.finish(&Ok(()));
+
+        provider.force_flush()?;
+        let got = summarize(&exporter.get_finished_metrics()?);
+        let common = [
+            (RPC_SYSTEM, "grpc"),
+            (SERVICE, "secretmanager"),
+            (SERVER_ADDRESS, "localhost"),
+            ("rpc.method", "GetSecret"),
+        ];
+        let ok = with(&common, &[(STATUS_CODE, "OK")]);
+        let io = with(&common, &[(ERROR_TYPE, "CLIENT_CONNECTION_ERROR")]);
+        let common = with(&common, &[]);
+        assert_eq!(
+            got.get(&(OPERATION_DURATION.to_string(), ok.clone())),
+            Some(&1)
+        );
+        assert_eq!(
+            got.get(&(ATTEMPT_DURATION.to_string(), ok.clone())),
+            Some(&1)
+        );
+        assert_eq!(
+            got.get(&(ATTEMPT_DURATION.to_string(), io.clone())),
+            Some(&1)
+        );
+        assert_eq!(got.get(&(ATTEMPT_COUNT.to_string(), ok)), Some(&1));
+        assert_eq!(got.get(&(ATTEMPT_COUNT.to_string(), io)), Some(&1));
+        assert_eq!(
+            got.get(&(RETRY_COUNT.to_string(), common.clone())),
+            Some(&1)
+        );
+        assert_eq!(
+            got.get(&(REQUEST_SIZE.to_string(), common.clone())),
+            Some(&2)
+        );
+        assert_eq!(got.get(&(RESPONSE_SIZE.to_string(), common)), Some(&1));
+        Ok(())
+    }
+
+    #[test]
+    fn throttler() -> TestResult {
+        let (provider, exporter, metrics) = test_metrics();
+        let operation = metrics.operation(|_| {});
+        // Throttle all the requests after the first failure.
+        let throttler = operation.throttler(Arc::new(Mutex::new(CircuitBreaker::clamp(10, 5, 10))));
+        let mut guard = throttler.lock().expect("retry throttler lock is poisoned");
+        guard.on_retry_failure(&RetryResult::Continue(Error::io("test-only")));
+        assert!(guard.throttle_retry_attempt());
+        assert!(guard.throttle_retry_attempt());
+        guard.on_success();
+        drop(guard);
+
+        provider.force_flush()?;
+        let got = summarize(&exporter.get_finished_metrics()?);
+        let common = with(
+            &[
+                (RPC_SYSTEM, "grpc"),
+                (SERVICE, "secretmanager"),
+                (SERVER_ADDRESS, "localhost"),
+            ],
+            &[],
+        );
+        assert_eq!(got.get(&(THROTTLE_COUNT.to_string(), common)), Some(&2));
+        Ok(())
+    }
+
+    type Attributes = Vec<(String, String)>;
+
+    fn with(common: &[(&str, &str)], extra: &[(&str, &str)]) -> Attributes {
+        let mut attributes: Attributes = common
+            .iter()
+            .chain(extra.iter())
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        attributes.sort();
+        attributes
+    }
+
+    fn test_metrics() -> (SdkMeterProvider, InMemoryMetricExporter, Metrics) {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(exporter.clone())
+            .build();
+        let meter = provider.meter("test-only");
+        let metrics = Metrics::with_meter(
+            &meter,
+            "https://secretmanager.googleapis.com",
+            "http://localhost:8080",
+            "grpc",
+        );
+        (provider, exporter, metrics)
+    }
+
+    // Returns the number of measurements for each (metric, attributes) pair.
+    fn summarize(exported: &[ResourceMetrics]) -> BTreeMap<(String, Attributes), u64> {
+        let mut summary = BTreeMap::new();
+        let metrics = exported
+            .iter()
+            .flat_map(|r| r.scope_metrics())
+            .flat_map(|s| s.metrics());
+        for metric in metrics {
+            let points: Vec<(Attributes, u64)> = match metric.data() {
+                AggregatedMetrics::F64(MetricData::Histogram(h)) => h
+                    .data_points()
+                    .map(|p| (attributes(p.attributes()), p.count()))
+                    .collect(),
+                AggregatedMetrics::U64(MetricData::Histogram(h)) => h
+                    .data_points()
+                    .map(|p| (attributes(p.attributes()), p.count()))
+                    .collect(),
+                AggregatedMetrics::U64(MetricData::Sum(s)) => s
+                    .data_points()
+                    .map(|p| (attributes(p.attributes()), p.value()))
+                    .collect(),
+                d => panic!("unexpected metric data {d:?}"),
+            };
+            for (attributes, count) in points {
+                *summary
+                    .entry((metric.name().to_string(), attributes))
+                    .or_default() += count;
+            }
+        }
+        summary
+    }
+
+    fn attributes<'a>(input: impl Iterator<Item = &'a KeyValue>) -> Attributes {
+        let mut attributes: Attributes = input
+            .map(|kv| (kv.key.to_string(), kv.value.to_string()))
+            .collect();
+        attributes.sort();
+        attributes
+    }
+}
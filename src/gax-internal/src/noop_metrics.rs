@@ -0,0 +1,78 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replaces the client metrics when the `_internal-opentelemetry` feature is
+//! disabled.
+//!
+//! The types have the same interface as in the `metrics` module, and record
+//! nothing.
+
+use gax::Result;
+use gax::retry_throttler::SharedRetryThrottler;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Metrics;
+
+impl Metrics {
+    pub(crate) fn new(
+        _enabled: bool,
+        _default_endpoint: &str,
+        _endpoint: &str,
+        _system: &'static str,
+    ) -> Self {
+        Self
+    }
+
+    #[cfg(feature = "_internal-grpc-client")]
+    pub(crate) fn grpc_operation(&self, _path: &str, _request_params: &str) -> Operation {
+        Operation
+    }
+
+    #[cfg(feature = "_internal-http-client")]
+    pub(crate) fn http_operation(&self, _builder: &reqwest::RequestBuilder) -> Operation {
+        Operation
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Operation;
+
+impl Operation {
+    pub(crate) fn throttler(&self, throttler: SharedRetryThrottler) -> SharedRetryThrottler {
+        throttler
+    }
+
+    pub(crate) fn attempt(&self) -> Attempt<'_> {
+        Attempt { _operation: self }
+    }
+
+    pub(crate) fn finish<T>(self, _result: &Result<T>) {}
+}
+
+#[derive(Debug)]
+pub(crate) struct Attempt<'a> {
+    _operation: &'a Operation,
+}
+
+impl Attempt<'_> {
+    pub(crate) fn enabled(&self) -> bool {
+        false
+    }
+
+    pub(crate) fn request_size(&self, _size: usize) {}
+
+    pub(crate) fn response_size(&self, _size: usize) {}
+
+    pub(crate) fn finish<T>(self, _result: &Result<T>) {}
+}
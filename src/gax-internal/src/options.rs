@@ -18,6 +18,7 @@ pub use auth::credentials::Credentials;
 pub type ClientConfig = gax::client_builder::internal::ClientConfig<Credentials>;
 
 pub(crate) const LOGGING_VAR: &str = "GOOGLE_CLOUD_RUST_LOGGING";
+pub(crate) const DEBUG_LOGGING_VAR: &str = "GOOGLE_SDK_RUST_LOGGING";
 
 // Returns true if the environment or client configuration enables tracing.
 pub fn tracing_enabled(config: &ClientConfig) -> bool {
@@ -29,6 +30,17 @@ pub fn tracing_enabled(config: &ClientConfig) -> bool {
         .unwrap_or(false)
 }
 
+// Returns true if the environment or client configuration enables debug
+// logging of requests and responses.
+pub fn debug_logging_enabled(config: &ClientConfig) -> bool {
+    if config.debug_logging {
+        return true;
+    }
+    std::env::var(DEBUG_LOGGING_VAR)
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,4 +67,38 @@ mod tests {
         let config = ClientConfig::default();
         assert!(!tracing_enabled(&config), "expected tracing to be disabled");
     }
+
+    // This test must run serially because it manipulates the environment.
+    #[test]
+    #[serial_test::serial]
+    fn config_debug_logging() {
+        let _e = ScopedEnv::remove(DEBUG_LOGGING_VAR);
+        let config = ClientConfig::default();
+        assert!(
+            !debug_logging_enabled(&config),
+            "expected debug logging to be disabled"
+        );
+        let config = ClientConfig {
+            debug_logging: true,
+            ..ClientConfig::default()
+        };
+        assert!(
+            debug_logging_enabled(&config),
+            "expected debug logging to be enabled"
+        );
+
+        let _e = ScopedEnv::set(DEBUG_LOGGING_VAR, "true");
+        let config = ClientConfig::default();
+        assert!(
+            debug_logging_enabled(&config),
+            "expected debug logging to be enabled"
+        );
+
+        let _e = ScopedEnv::set(DEBUG_LOGGING_VAR, "not-true");
+        let config = ClientConfig::default();
+        assert!(
+            !debug_logging_enabled(&config),
+            "expected debug logging to be disabled"
+        );
+    }
 }
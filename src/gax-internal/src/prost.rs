@@ -65,6 +65,40 @@ pub fn pair_transpose<K, V>(a: Result<K>, b: Result<V>) -> Result<(K, V)> {
     }
 }
 
+/// Encodes `message` using the binary protobuf encoding of `P`.
+///
+/// Client libraries with a gRPC transport use this function to implement
+/// [BinaryMessage][wkt::message::BinaryMessage] for their model types.
+///
+/// The generated [ToProto] conversions consume the model type, so this clones
+/// `message` before converting it. For large messages the clone is comparable
+/// to the cost of the encoding itself.
+pub fn encode_to_vec<P, T>(message: &T) -> std::result::Result<Vec<u8>, wkt::AnyError>
+where
+    T: ToProto<P, Output = P> + Clone,
+    P: prost::Message,
+{
+    let proto = message
+        .clone()
+        .to_proto()
+        .map_err(|e| wkt::AnyError::Serialization(e.into()))?;
+    Ok(proto.encode_to_vec())
+}
+
+/// Decodes a `T` from the binary protobuf encoding of `P`.
+///
+/// Client libraries with a gRPC transport use this function to implement
+/// [BinaryMessage][wkt::message::BinaryMessage] for their model types.
+pub fn decode<P, T>(buf: &[u8]) -> std::result::Result<T, wkt::AnyError>
+where
+    P: prost::Message + Default + FromProto<T>,
+{
+    let proto = P::decode(buf).map_err(|e| wkt::AnyError::Deserialization(e.into()))?;
+    proto
+        .cnv()
+        .map_err(|e| wkt::AnyError::Deserialization(e.into()))
+}
+
 macro_rules! impl_primitive {
     ($t: ty) => {
         impl ToProto<$t> for $t {
@@ -449,6 +483,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn encode_and_decode() -> anyhow::Result<()> {
+        let input = wkt::Timestamp::clamp(123, 456);
+        let bytes = encode_to_vec::<prost_types::Timestamp, _>(&input)?;
+        let proto = <prost_types::Timestamp as prost::Message>::decode(bytes.as_slice())?;
+        assert_eq!(
+            proto,
+            prost_types::Timestamp {
+                seconds: 123,
+                nanos: 456
+            }
+        );
+        let got = decode::<prost_types::Timestamp, wkt::Timestamp>(&bytes)?;
+        assert_eq!(got, input);
+        Ok(())
+    }
+
+    #[test]
+    fn decode_error() {
+        let got = decode::<prost_types::Timestamp, wkt::Timestamp>(&[0xff, 0xff]);
+        assert!(
+            matches!(got, Err(wkt::AnyError::Deserialization(_))),
+            "{got:?}"
+        );
+    }
+
     #[test]
     fn from_prost_null_value() -> anyhow::Result<()> {
         let input = prost_types::NullValue::NullValue;
@@ -0,0 +1,515 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Creates spans for each client request and propagates the trace context.
+//!
+//! The client creates a span for each *operation*, that is, each call to a
+//! client method including all its retry attempts. Each *attempt* gets a child
+//! span. The spans use the [tracing] framework, with fields following the
+//! OpenTelemetry semantic conventions. Fields with the `otel.` prefix control
+//! the span name, kind, and status when the application exports the spans
+//! using [tracing-opentelemetry].
+//!
+//! The trace context of the current attempt is sent to the service, using the
+//! W3C `traceparent` header and the `x-cloud-trace-context` header used by
+//! Cloud Trace.
+//!
+//! [tracing]: https://docs.rs/tracing
+//! [tracing-opentelemetry]: https://docs.rs/tracing-opentelemetry
+
+use crate::attributes::*;
+use gax::Result;
+use http::HeaderMap;
+#[cfg(feature = "_internal-opentelemetry")]
+use http::HeaderValue;
+#[cfg(feature = "_internal-opentelemetry")]
+use opentelemetry::trace::{SpanContext, TraceContextExt as _};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tracing::Span;
+use tracing::field::Empty;
+
+#[cfg(feature = "_internal-opentelemetry")]
+pub(crate) const TRACEPARENT: &str = "traceparent";
+#[cfg(feature = "_internal-opentelemetry")]
+pub(crate) const TRACESTATE: &str = "tracestate";
+#[cfg(feature = "_internal-opentelemetry")]
+pub(crate) const CLOUD_TRACE_CONTEXT: &str = "x-cloud-trace-context";
+
+/// Creates the spans for a client.
+///
+/// A default-constructed value creates disabled spans, this is what clients
+/// use when tracing is disabled.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Tracing {
+    client: Option<Arc<ClientAttributes>>,
+}
+
+#[derive(Debug)]
+struct ClientAttributes {
+    system: &'static str,
+    service: Option<String>,
+    server_address: Option<String>,
+}
+
+impl Tracing {
+    /// Creates the span factory for a client, if `enabled`.
+    ///
+    /// The `default_endpoint` identifies the service, even if the application
+    /// overrides the endpoint. The `endpoint` is the server receiving the
+    /// requests.
+    pub(crate) fn new(
+        enabled: bool,
+        default_endpoint: &str,
+        endpoint: &str,
+        system: &'static str,
+    ) -> Self {
+        if !enabled {
+            return Self::default();
+        }
+        let client = ClientAttributes {
+            system,
+            service: host(default_endpoint).and_then(service).map(str::to_string),
+            server_address: host(endpoint).map(str::to_string),
+        };
+        Self {
+            client: Some(Arc::new(client)),
+        }
+    }
+
+    /// Returns true if the client creates spans and propagates the trace
+    /// context.
+    pub(crate) fn enabled(&self) -> bool {
+        self.client.is_some()
+    }
+
+    /// Creates the span for a gRPC operation.
+    #[cfg(feature = "_internal-grpc-client")]
+    pub(crate) fn grpc_operation(&self, path: &str, request_params: &str) -> OperationSpan {
+        let Some(client) = &self.client else {
+            return OperationSpan::none();
+        };
+        let (service, method) = grpc_method(path);
+        let span = OperationSpan::new(client, path.trim_start_matches('/'));
+        span.span.record(RPC_SERVICE, service);
+        span.span.record(RPC_METHOD, method);
+        span.span.record(LOCATION, location(request_params));
+        span.span.record(
+            RESOURCE_NAME,
+            resource_name_from_params(request_params).as_deref(),
+        );
+        span
+    }
+
+    /// Creates the span for an HTTP operation.
+    #[cfg(feature = "_internal-http-client")]
+    pub(crate) fn http_operation(&self, builder: &reqwest::RequestBuilder) -> OperationSpan {
+        let Some(client) = &self.client else {
+            return OperationSpan::none();
+        };
+        let Some(Ok(request)) = builder.try_clone().map(|b| b.build()) else {
+            return OperationSpan::new(client, "HTTP");
+        };
+        let method = request.method().as_str();
+        let span = OperationSpan::new(client, method);
+        span.span.record(HTTP_METHOD, method);
+        span.span.record(LOCATION, location(request.url().path()));
+        span.span.record(
+            RESOURCE_NAME,
+            resource_name_from_path(request.url().path()).as_deref(),
+        );
+        span
+    }
+}
+
+/// The span for a client operation.
+#[derive(Clone, Debug)]
+pub(crate) struct OperationSpan {
+    span: Span,
+    name: String,
+    client: Option<Arc<ClientAttributes>>,
+    attempts: Arc<AtomicU32>,
+}
+
+impl OperationSpan {
+    fn none() -> Self {
+        Self {
+            span: Span::none(),
+            name: String::new(),
+            client: None,
+            attempts: Arc::default(),
+        }
+    }
+
+    fn new(client: &Arc<ClientAttributes>, name: &str) -> Self {
+        let span = tracing::info_span!(
+            "client_request",
+            "otel.name" = name,
+            "otel.kind" = "internal",
+            "otel.status_code" = Empty,
+            "otel.status_description" = Empty,
+            "rpc.system" = client.system,
+            "rpc.service" = Empty,
+            "rpc.method" = Empty,
+            "http.request.method" = Empty,
+            "server.address" = client.server_address.as_deref(),
+            "gcp.client.service" = client.service.as_deref(),
+            "gcp.resource.destination.location" = Empty,
+            "gcp.resource.name" = Empty,
+            "rpc.response.status_code" = Empty,
+            "error.type" = Empty,
+        );
+        Self {
+            span,
+            name: name.to_string(),
+            client: Some(client.clone()),
+            attempts: Arc::default(),
+        }
+    }
+
+    /// The span, use it to instrument the retry loop.
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Creates the span for a new attempt, as a child of the operation span.
+    pub(crate) fn attempt(&self) -> Span {
+        let Some(client) = &self.client else {
+            return Span::none();
+        };
+        let attempt = self.attempts.fetch_add(1, Ordering::Relaxed);
+        let span = tracing::info_span!(
+            parent: &self.span,
+            "client_request_attempt",
+            "otel.name" = self.name.as_str(),
+            "otel.kind" = "client",
+            "otel.status_code" = Empty,
+            "otel.status_description" = Empty,
+            "rpc.system" = client.system,
+            "server.address" = client.server_address.as_deref(),
+            "gcp.client.service" = client.service.as_deref(),
+            "http.request.resend_count" = Empty,
+            "rpc.response.status_code" = Empty,
+            "http.response.status_code" = Empty,
+            "error.type" = Empty,
+        );
+        if attempt > 0 {
+            span.record("http.request.resend_count", i64::from(attempt));
+        }
+        span
+    }
+
+    /// Records the outcome of the operation.
+    pub(crate) fn finish<T>(&self, result: &Result<T>) {
+        record_outcome(&self.span, result);
+    }
+}
+
+/// Records the outcome of an operation or attempt in its span.
+pub(crate) fn record_outcome<T>(span: &Span, result: &Result<T>) {
+    if span.is_disabled() {
+        return;
+    }
+    match result {
+        Ok(_) => {
+            span.record("otel.status_code", "OK");
+            span.record(STATUS_CODE, "OK");
+        }
+        Err(e) => {
+            span.record("otel.status_code", "ERROR");
+            span.record("otel.status_description", e.to_string());
+            if let Some(status) = e.status() {
+                span.record(STATUS_CODE, status.code.name());
+            }
+            if let Some(code) = e.http_status_code() {
+                span.record(HTTP_STATUS_CODE, code);
+            }
+            span.record(ERROR_TYPE, error_type(e));
+        }
+    }
+}
+
+/// Returns the headers to propagate the current trace context.
+///
+/// Call this function while the attempt span is entered. Applications using
+/// [tracing-opentelemetry] activate the OpenTelemetry context of each span
+/// when the span is entered.
+///
+/// [tracing-opentelemetry]: https://docs.rs/tracing-opentelemetry
+#[cfg(feature = "_internal-opentelemetry")]
+pub(crate) fn propagation_headers() -> HeaderMap {
+    let context = opentelemetry::Context::current();
+    to_headers(context.span().span_context())
+}
+
+/// Without the `_internal-opentelemetry` feature there is no trace context to
+/// propagate.
+#[cfg(not(feature = "_internal-opentelemetry"))]
+pub(crate) fn propagation_headers() -> HeaderMap {
+    HeaderMap::new()
+}
+
+#[cfg(feature = "_internal-opentelemetry")]
+fn to_headers(context: &SpanContext) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if !context.is_valid() {
+        return headers;
+    }
+    let sampled = context.is_sampled();
+    let traceparent = format!(
+        "00-{}-{}-{:02x}",
+        context.trace_id(),
+        context.span_id(),
+        context.trace_flags().to_u8()
+    );
+    // Cloud Trace uses the decimal representation of the span id.
+    let cloud_trace = format!(
+        "{}/{};o={}",
+        context.trace_id(),
+        u64::from_be_bytes(context.span_id().to_bytes()),
+        u8::from(sampled)
+    );
+    let values = [
+        (TRACEPARENT, traceparent),
+        (CLOUD_TRACE_CONTEXT, cloud_trace),
+        (TRACESTATE, context.trace_state().header()),
+    ];
+    for (name, value) in values {
+        if value.is_empty() {
+            continue;
+        }
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+#[cfg(all(test, feature = "_internal-opentelemetry"))]
+mod tests {
+    use super::*;
+    use gax::error::Error;
+    use gax::error::rpc::{Code, Status};
+    use opentelemetry::trace::{
+        SpanId, SpanKind, Status as OtelStatus, TraceFlags, TraceId, TraceState,
+        TracerProvider as _,
+    };
+    use opentelemetry::{KeyValue, Value};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+    use test_case::test_case;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    const TRACE_ID: u128 = 0x4bf92f3577b34da6a3ce929d0e0e4736;
+    const SPAN_ID: u64 = 0x00f067aa0ba902b7;
+
+    #[test_case(TraceFlags::SAMPLED, "01", "1")]
+    #[test_case(TraceFlags::NOT_SAMPLED, "00", "0")]
+    fn headers(flags: TraceFlags, want_flags: &str, want_option: &str) {
+        let context = SpanContext::new(
+            TraceId::from(TRACE_ID),
+            SpanId::from(SPAN_ID),
+            flags,
+            false,
+            TraceState::default(),
+        );
+        let headers = to_headers(&context);
+        assert_eq!(
+            headers.get(TRACEPARENT).and_then(|v| v.to_str().ok()),
+            Some(
+                format!("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-{want_flags}")
+                    .as_str()
+            ),
+            "{headers:?}"
+        );
+        assert_eq!(
+            headers
+                .get(CLOUD_TRACE_CONTEXT)
+                .and_then(|v| v.to_str().ok()),
+            Some(format!("4bf92f3577b34da6a3ce929d0e0e4736/{SPAN_ID};o={want_option}").as_str()),
+            "{headers:?}"
+        );
+        assert!(headers.get(TRACESTATE).is_none(), "{headers:?}");
+    }
+
+    #[test]
+    fn headers_with_trace_state() -> anyhow::Result<()> {
+        let state = TraceState::from_key_value([("vendor", "value")])?;
+        let context = SpanContext::new(
+            TraceId::from(TRACE_ID),
+            SpanId::from(SPAN_ID),
+            TraceFlags::SAMPLED,
+            false,
+            state,
+        );
+        let headers = to_headers(&context);
+        assert_eq!(
+            headers.get(TRACESTATE).and_then(|v| v.to_str().ok()),
+            Some("vendor=value"),
+            "{headers:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn headers_invalid_context() {
+        let headers = to_headers(&SpanContext::empty_context());
+        assert!(headers.is_empty(), "{headers:?}");
+    }
+
+    #[test]
+    fn disabled() {
+        let (exporter, provider) = test_provider();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        tracing::subscriber::with_default(subscriber, || {
+            let tracing = Tracing::default();
+            assert!(!tracing.enabled());
+            let span = tracing.grpc_operation(
+                "/google.test.v1.EchoService/Echo",
+                "name=projects%2Fp%2Flocations%2Fus-central1",
+            );
+            assert!(span.span().is_none(), "{span:?}");
+            let attempt = span.attempt();
+            assert!(attempt.is_none(), "{attempt:?}");
+            let _enter = attempt.enter();
+            assert!(propagation_headers().is_empty());
+        });
+        let _ = provider.force_flush();
+        let spans = exporter.get_finished_spans().unwrap();
+        assert!(spans.is_empty(), "{spans:?}");
+    }
+
+    #[test]
+    fn spans() -> anyhow::Result<()> {
+        let (exporter, provider) = test_provider();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let headers = tracing::subscriber::with_default(subscriber, || {
+            let tracing = Tracing::new(
+                true,
+                "https://test.googleapis.com",
+                "https://private.googleapis.com",
+                "grpc",
+            );
+            assert!(tracing.enabled());
+            let span = tracing.grpc_operation(
+                "/google.test.v1.EchoService/Echo",
+                "name=projects%2Fp%2Flocations%2Fus-central1%2Fsecrets%2Fs",
+            );
+            let _operation = span.span().enter();
+
+            let attempt = span.attempt();
+            record_outcome(
+                &attempt,
+                &Err::<(), _>(Error::service(
+                    Status::default()
+                        .set_code(Code::Unavailable)
+                        .set_message("try-again"),
+                )),
+            );
+            drop(attempt);
+
+            let attempt = span.attempt();
+            let headers = {
+                let _enter = attempt.enter();
+                propagation_headers()
+            };
+            record_outcome(&attempt, &Ok(()));
+            drop(attempt);
+            span.finish(&Ok(()));
+            headers
+        });
+        provider.force_flush()?;
+        let spans = exporter.get_finished_spans()?;
+        assert_eq!(spans.len(), 3, "{spans:?}");
+        let (attempts, operations): (Vec<_>, Vec<_>) =
+            spans.iter().partition(|s| s.span_kind == SpanKind::Client);
+        let operation = operations[0];
+        assert_eq!(operation.name, "google.test.v1.EchoService/Echo");
+        assert_eq!(operation.span_kind, SpanKind::Internal);
+        assert_eq!(operation.status, OtelStatus::Ok);
+        assert_eq!(
+            attribute(operation, "rpc.service"),
+            Some("google.test.v1.EchoService".into())
+        );
+        assert_eq!(attribute(operation, "rpc.method"), Some("Echo".into()));
+        assert_eq!(
+            attribute(operation, "server.address"),
+            Some("private.googleapis.com".into())
+        );
+        assert_eq!(
+            attribute(operation, "gcp.client.service"),
+            Some("test".into())
+        );
+        assert_eq!(
+            attribute(operation, "gcp.resource.destination.location"),
+            Some("us-central1".into())
+        );
+        assert_eq!(
+            attribute(operation, "gcp.resource.name"),
+            Some("projects/p/locations/us-central1/secrets/s".into())
+        );
+
+        assert_eq!(attempts.len(), 2, "{attempts:?}");
+        for attempt in &attempts {
+            assert_eq!(attempt.name, "google.test.v1.EchoService/Echo");
+            assert_eq!(attempt.parent_span_id, operation.span_context.span_id());
+        }
+        let first = attempts[0];
+        assert!(
+            matches!(&first.status, OtelStatus::Error { description } if description.contains("try-again")),
+            "{first:?}"
+        );
+        assert_eq!(
+            attribute(first, "rpc.response.status_code"),
+            Some("UNAVAILABLE".into())
+        );
+        assert_eq!(attribute(first, "error.type"), Some("UNAVAILABLE".into()));
+        assert_eq!(attribute(first, "http.request.resend_count"), None);
+        let second = attempts[1];
+        assert_eq!(second.status, OtelStatus::Ok);
+        assert_eq!(
+            attribute(second, "rpc.response.status_code"),
+            Some("OK".into())
+        );
+        assert_eq!(
+            attribute(second, "http.request.resend_count"),
+            Some(Value::I64(1))
+        );
+
+        let context = &second.span_context;
+        assert_eq!(
+            headers.get(TRACEPARENT).and_then(|v| v.to_str().ok()),
+            Some(format!("00-{}-{}-01", context.trace_id(), context.span_id()).as_str()),
+            "{headers:?}"
+        );
+        assert!(headers.contains_key(CLOUD_TRACE_CONTEXT), "{headers:?}");
+        Ok(())
+    }
+
+    fn attribute(span: &SpanData, key: &str) -> Option<Value> {
+        span.attributes
+            .iter()
+            .find(|kv: &&KeyValue| kv.key.as_str() == key)
+            .map(|kv| kv.value.clone())
+    }
+
+    fn test_provider() -> (InMemorySpanExporter, SdkTracerProvider) {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        (exporter, provider)
+    }
+}
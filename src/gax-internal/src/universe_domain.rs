@@ -0,0 +1,187 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for universe domains in the transport clients.
+//!
+//! The generated clients have a default endpoint in the `googleapis.com`
+//! universe. Applications running in other universes configure the universe
+//! domain in the client builder, and the clients use the same service name in
+//! that universe. Before sending any request, the clients verify the
+//! credentials belong to the same universe, to avoid sending tokens to the
+//! wrong universe.
+
+use auth::credentials::Credentials;
+use gax::Result;
+use gax::error::{CredentialsError, Error};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+pub(crate) const DEFAULT_UNIVERSE_DOMAIN: &str = "googleapis.com";
+
+/// Returns the endpoint for a client.
+///
+/// An explicitly configured endpoint is always used as-is. Otherwise, the
+/// host in `default_endpoint` is moved to the configured universe domain, for
+/// example, `https://secretmanager.googleapis.com/` becomes
+/// `https://secretmanager.example.com/`.
+pub(crate) fn endpoint(
+    endpoint: Option<String>,
+    default_endpoint: &str,
+    universe_domain: Option<&str>,
+) -> String {
+    if let Some(e) = endpoint {
+        return e;
+    }
+    let universe_domain = match universe_domain {
+        None => return default_endpoint.to_string(),
+        Some(u) if u == DEFAULT_UNIVERSE_DOMAIN => return default_endpoint.to_string(),
+        Some(u) => u,
+    };
+    let (scheme, rest) = match default_endpoint.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, default_endpoint),
+    };
+    let host_end = rest.find(['/', ':']).unwrap_or(rest.len());
+    let (host, tail) = rest.split_at(host_end);
+    let service = match host.strip_suffix(DEFAULT_UNIVERSE_DOMAIN) {
+        Some(s) if s.ends_with('.') => s,
+        _ => return default_endpoint.to_string(),
+    };
+    match scheme {
+        Some(scheme) => format!("{scheme}://{service}{universe_domain}{tail}"),
+        None => format!("{service}{universe_domain}{tail}"),
+    }
+}
+
+/// Verifies the credentials universe domain matches the client's.
+///
+/// The result of the first check is cached, the universe domain of a
+/// credentials object does not change.
+#[derive(Clone, Debug)]
+pub(crate) struct UniverseDomainCheck {
+    universe_domain: String,
+    result: Arc<OnceCell<std::result::Result<(), String>>>,
+}
+
+impl UniverseDomainCheck {
+    pub(crate) fn new(universe_domain: Option<String>) -> Self {
+        Self {
+            universe_domain: universe_domain.unwrap_or_else(|| DEFAULT_UNIVERSE_DOMAIN.to_string()),
+            result: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub(crate) async fn check(&self, credentials: &Credentials) -> Result<()> {
+        let result = self
+            .result
+            .get_or_init(|| async {
+                let found = credentials
+                    .universe_domain()
+                    .await
+                    .unwrap_or_else(|| DEFAULT_UNIVERSE_DOMAIN.to_string());
+                if found == self.universe_domain {
+                    return Ok(());
+                }
+                Err(format!(
+                    "the client universe domain ({}) does not match the credentials \
+                     universe domain ({found}). Configure the client with \
+                     `with_universe_domain()`, or use credentials from the same universe",
+                    self.universe_domain
+                ))
+            })
+            .await;
+        result
+            .clone()
+            .map_err(|msg| Error::authentication(CredentialsError::from_msg(false, msg)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auth::credentials::{CacheableResource, CredentialsProvider};
+    use http::{Extensions, HeaderMap};
+    use test_case::test_case;
+
+    #[test_case(None, None, "https://secretmanager.googleapis.com/")]
+    #[test_case(
+        Some("http://localhost:8080"),
+        Some("example.com"),
+        "http://localhost:8080"
+    )]
+    #[test_case(None, Some("googleapis.com"), "https://secretmanager.googleapis.com/")]
+    #[test_case(None, Some("example.com"), "https://secretmanager.example.com/")]
+    fn endpoint_templating(endpoint: Option<&str>, universe: Option<&str>, want: &str) {
+        let got = super::endpoint(
+            endpoint.map(str::to_string),
+            "https://secretmanager.googleapis.com/",
+            universe,
+        );
+        assert_eq!(got, want);
+    }
+
+    #[test_case("https://storage.googleapis.com", "https://storage.example.com")]
+    #[test_case(
+        "https://storage.googleapis.com:443",
+        "https://storage.example.com:443"
+    )]
+    #[test_case("storage.googleapis.com", "storage.example.com")]
+    #[test_case("https://private.example.net/", "https://private.example.net/")]
+    #[test_case("https://notgoogleapis.com/", "https://notgoogleapis.com/")]
+    fn endpoint_default_formats(default: &str, want: &str) {
+        let got = super::endpoint(None, default, Some("example.com"));
+        assert_eq!(got, want);
+    }
+
+    #[derive(Debug)]
+    struct FakeCredentials(Option<&'static str>);
+    impl CredentialsProvider for FakeCredentials {
+        async fn headers(
+            &self,
+            _extensions: Extensions,
+        ) -> std::result::Result<CacheableResource<HeaderMap>, CredentialsError> {
+            unimplemented!("not used in these tests")
+        }
+        async fn universe_domain(&self) -> Option<String> {
+            self.0.map(str::to_string)
+        }
+    }
+
+    #[tokio::test]
+    async fn check_matches() -> anyhow::Result<()> {
+        let credentials = Credentials::from(FakeCredentials(Some("example.com")));
+        let check = UniverseDomainCheck::new(Some("example.com".to_string()));
+        check.check(&credentials).await?;
+
+        let credentials = Credentials::from(FakeCredentials(None));
+        let check = UniverseDomainCheck::new(None);
+        check.check(&credentials).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn check_mismatch() {
+        let credentials = Credentials::from(FakeCredentials(Some("googleapis.com")));
+        let check = UniverseDomainCheck::new(Some("example.com".to_string()));
+        let err = check.check(&credentials).await.unwrap_err();
+        assert!(err.is_authentication(), "{err:?}");
+        let fmt = format!("{err}");
+        assert!(fmt.contains("example.com"), "{fmt}");
+        assert!(fmt.contains("googleapis.com"), "{fmt}");
+
+        // The result is cached.
+        let err = check.check(&credentials).await.unwrap_err();
+        assert!(err.is_authentication(), "{err:?}");
+    }
+}
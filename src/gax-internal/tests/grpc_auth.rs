@@ -45,6 +45,7 @@ mod tests {
         // 1. we can test that multiple headers are included in the request
         // 2. it gives us extra confidence that our interfaces are called
         let mut mock = MockCredentials::new();
+        mock.expect_universe_domain().returning(|| None);
         let headers = HeaderMap::from_iter([
             (
                 HeaderName::from_static("auth-key-1"),
@@ -85,6 +86,7 @@ mod tests {
 
         let retry_count = 3;
         let mut mock = MockCredentials::new();
+        mock.expect_universe_domain().returning(|| None);
         mock.expect_headers()
             .times(retry_count..)
             .returning(|_extensions| Err(CredentialsError::from_msg(true, "mock retryable error")));
@@ -118,6 +120,7 @@ mod tests {
         let (endpoint, _server) = start_echo_server().await?;
 
         let mut mock = MockCredentials::new();
+        mock.expect_universe_domain().returning(|| None);
         mock.expect_headers()
             .times(1)
             .returning(move |_extensions| headers_response.clone());
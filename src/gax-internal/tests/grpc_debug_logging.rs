@@ -0,0 +1,158 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(test, feature = "_internal-grpc-client"))]
+mod tests {
+    use auth::credentials::api_key_credentials;
+    use gax::options::*;
+    use gax::retry_throttler::CircuitBreaker;
+    use google_cloud_gax_internal::grpc;
+    use grpc_server::google::test::v1::EchoResponse;
+    use grpc_server::{builder, google, start_fixed_responses};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    #[tokio::test]
+    async fn debug_logging() -> anyhow::Result<()> {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&capture));
+
+        let (endpoint, _server) = start_fixed_responses([transient(), success()]).await?;
+        let client = builder(endpoint)
+            .with_credentials(api_key_credentials::Builder::new("test-api-key").build())
+            .with_backoff_policy(test_backoff())
+            // The default throttler may randomly throttle the retry attempt.
+            .with_retry_throttler(CircuitBreaker::default())
+            .with_debug_logging()
+            .build()
+            .await?;
+        let response = send_request(client).await?;
+        assert_eq!(response.message, "success!");
+
+        let logs = capture.contents();
+        assert_eq!(logs.matches("sending request").count(), 2, "{logs}");
+        assert!(logs.contains("received error"), "{logs}");
+        assert!(logs.contains("Unavailable"), "{logs}");
+        assert!(logs.contains("try-again"), "{logs}");
+        assert!(logs.contains("received response"), "{logs}");
+        assert!(
+            logs.contains("rpc.method=\"/google.test.v1.EchoService/Echo\""),
+            "{logs}"
+        );
+        assert!(logs.contains("test message"), "{logs}");
+        assert!(logs.contains("success!"), "{logs}");
+        assert!(logs.contains("[REDACTED]"), "{logs}");
+        assert!(!logs.contains("test-api-key"), "{logs}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disabled() -> anyhow::Result<()> {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&capture));
+
+        let (endpoint, _server) = start_fixed_responses([success()]).await?;
+        let client = builder(endpoint)
+            .with_credentials(api_key_credentials::Builder::new("test-api-key").build())
+            .build()
+            .await?;
+        let _ = send_request(client).await?;
+
+        let logs = capture.contents();
+        assert!(!logs.contains("sending request"), "{logs}");
+        assert!(!logs.contains("received response"), "{logs}");
+        Ok(())
+    }
+
+    fn test_subscriber(capture: &Capture) -> impl tracing::Subscriber + Send + Sync {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(capture.clone())
+            .finish()
+    }
+
+    // Captures the log output for inspection.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Capture {
+        fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+        }
+    }
+
+    impl std::io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Capture {
+        type Writer = Capture;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    fn success() -> tonic::Result<tonic::Response<EchoResponse>> {
+        Ok(tonic::Response::new(EchoResponse {
+            message: "success!".into(),
+            metadata: std::collections::HashMap::default(),
+        }))
+    }
+
+    fn transient() -> tonic::Result<tonic::Response<EchoResponse>> {
+        Err(tonic::Status::unavailable("try-again"))
+    }
+
+    fn test_backoff() -> impl gax::backoff_policy::BackoffPolicy {
+        gax::exponential_backoff::ExponentialBackoffBuilder::new()
+            .with_initial_delay(Duration::from_micros(1))
+            .with_maximum_delay(Duration::from_micros(1))
+            .build()
+            .expect("a valid backoff policy")
+    }
+
+    async fn send_request(client: grpc::Client) -> gax::Result<EchoResponse> {
+        let extensions = {
+            let mut e = tonic::Extensions::new();
+            e.insert(tonic::GrpcMethod::new("google.test.v1.EchoService", "Echo"));
+            e
+        };
+        let request = google::test::v1::EchoRequest {
+            message: "test message".into(),
+            ..google::test::v1::EchoRequest::default()
+        };
+        let mut options = RequestOptions::default();
+        options.set_idempotency(true);
+        client
+            .execute(
+                extensions,
+                http::uri::PathAndQuery::from_static("/google.test.v1.EchoService/Echo"),
+                request,
+                options,
+                "test-only-api-client/1.0",
+                "name=projects%2Fp%2Fsecrets%2Fs",
+            )
+            .await
+            .map(tonic::Response::into_inner)
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(test, feature = "_internal-grpc-client"))]
+mod tests {
+    use auth::credentials::testing::test_credentials;
+    use gax::options::*;
+    use gax::rate_limiter::{ConcurrencyLimiter, RateLimiter};
+    use google_cloud_gax_internal::grpc;
+    use grpc_server::google::test::v1::EchoResponse;
+    use grpc_server::{builder, google, start_echo_server};
+    use std::time::{Duration, Instant};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn shared_concurrency_limiter() -> anyhow::Result<()> {
+        let (endpoint, _server) = start_echo_server().await?;
+        let limiter = ConcurrencyLimiter::new(1)?;
+        let client1 = builder(endpoint.clone())
+            .with_credentials(test_credentials())
+            .with_concurrency_limiter(limiter.clone())
+            .build()
+            .await?;
+        let client2 = builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_concurrency_limiter(limiter)
+            .build()
+            .await?;
+
+        let start = Instant::now();
+        let delay = Duration::from_millis(200);
+        let (r1, r2) = tokio::join!(
+            send_request(&client1, delay, RequestOptions::default()),
+            send_request(&client2, delay, RequestOptions::default()),
+        );
+        r1?;
+        r2?;
+        // The limiter serializes the requests across both clients.
+        let elapsed = start.elapsed();
+        assert!(elapsed >= 2 * delay, "{elapsed:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn concurrency_limiter_timeout() -> anyhow::Result<()> {
+        let (endpoint, _server) = start_echo_server().await?;
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_concurrency_limiter(ConcurrencyLimiter::new(1)?)
+            .build()
+            .await?;
+
+        let mut options = RequestOptions::default();
+        options.set_attempt_timeout(Duration::from_millis(50));
+        let (r1, r2) = tokio::join!(
+            send_request(
+                &client,
+                Duration::from_millis(500),
+                RequestOptions::default()
+            ),
+            async {
+                // Start after the first request holds the only slot.
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                send_request(&client, Duration::ZERO, options).await
+            },
+        );
+        r1?;
+        let err = r2.unwrap_err();
+        assert!(err.is_timeout(), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn request_rate_limiter() -> anyhow::Result<()> {
+        let (endpoint, _server) = start_echo_server().await?;
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .build()
+            .await?;
+
+        let limiter = RateLimiter::new().with_requests_per_second(4.0)?;
+        let mut options = RequestOptions::default();
+        options.set_rate_limiter(limiter);
+        let start = Instant::now();
+        // The first 4 requests use the initial burst, the next 4 wait.
+        for _ in 0..8 {
+            send_request(&client, Duration::ZERO, options.clone()).await?;
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(900), "{elapsed:?}");
+        Ok(())
+    }
+
+    async fn send_request(
+        client: &grpc::Client,
+        delay: Duration,
+        options: RequestOptions,
+    ) -> gax::Result<EchoResponse> {
+        let extensions = {
+            let mut e = tonic::Extensions::new();
+            e.insert(tonic::GrpcMethod::new("google.test.v1.EchoService", "Echo"));
+            e
+        };
+        let request = google::test::v1::EchoRequest {
+            message: "test message".into(),
+            delay_ms: Some(delay.as_millis() as u64),
+        };
+        client
+            .execute(
+                extensions,
+                http::uri::PathAndQuery::from_static("/google.test.v1.EchoService/Echo"),
+                request,
+                options,
+                "test-only-api-client/1.0",
+                "",
+            )
+            .await
+            .map(tonic::Response::into_inner)
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(
+    test,
+    feature = "_internal-grpc-client",
+    feature = "_internal-opentelemetry"
+))]
+mod tests {
+    use auth::credentials::testing::test_credentials;
+    use gax::options::*;
+    use gax::retry_throttler::CircuitBreaker;
+    use google_cloud_gax_internal::grpc;
+    use grpc_server::google::test::v1::EchoResponse;
+    use grpc_server::{builder, google, start_fixed_responses};
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+    use opentelemetry_sdk::metrics::{InMemoryMetricExporter, SdkMeterProvider};
+    use std::time::Duration;
+
+    // The meter provider is global state, keep all the metrics tests in a
+    // single function.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn metrics() -> anyhow::Result<()> {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(exporter.clone())
+            .build();
+        opentelemetry::global::set_meter_provider(provider.clone());
+
+        let (endpoint, _server) = start_fixed_responses([transient(), success()]).await?;
+        let client = builder(endpoint.clone())
+            .with_credentials(test_credentials())
+            .with_backoff_policy(test_backoff())
+            // The default throttler may randomly throttle the retry attempt.
+            .with_retry_throttler(CircuitBreaker::default())
+            .with_metrics()
+            .build()
+            .await?;
+        let response = send_request(client).await?;
+        assert_eq!(response.message, "success!");
+
+        // Clients without metrics do not record anything.
+        let (endpoint, _server) = start_fixed_responses([success()]).await?;
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .build()
+            .await?;
+        let _ = send_request(client).await?;
+
+        provider.force_flush()?;
+        let exported = exporter.get_finished_metrics()?;
+        let common = [
+            ("rpc.system", "grpc"),
+            ("server.address", "127.0.0.1"),
+            ("rpc.service", "google.test.v1.EchoService"),
+            ("rpc.method", "Echo"),
+            ("gcp.resource.destination.location", "us-central1"),
+        ];
+        let ok = [common.as_slice(), &[("rpc.response.status_code", "OK")]].concat();
+        let unavailable = [
+            common.as_slice(),
+            &[
+                ("rpc.response.status_code", "UNAVAILABLE"),
+                ("error.type", "UNAVAILABLE"),
+            ],
+        ]
+        .concat();
+
+        assert_eq!(count(&exported, "gcp.client.operation.duration", &ok), 1);
+        assert_eq!(count(&exported, "gcp.client.attempt.duration", &ok), 1);
+        assert_eq!(
+            count(&exported, "gcp.client.attempt.duration", &unavailable),
+            1
+        );
+        assert_eq!(count(&exported, "gcp.client.attempt.count", &ok), 1);
+        assert_eq!(
+            count(&exported, "gcp.client.attempt.count", &unavailable),
+            1
+        );
+        assert_eq!(count(&exported, "gcp.client.retry.count", &common), 1);
+        assert_eq!(count(&exported, "gcp.client.request.size", &common), 2);
+        assert_eq!(count(&exported, "gcp.client.response.size", &common), 1);
+        assert_eq!(count(&exported, "gcp.client.throttle.count", &common), 0);
+        // Only the client with metrics enabled records measurements.
+        assert_eq!(count(&exported, "gcp.client.operation.duration", &[]), 1);
+
+        provider.shutdown()?;
+        Ok(())
+    }
+
+    // Counts the measurements for `name` with all the `attributes`.
+    fn count(exported: &[ResourceMetrics], name: &str, attributes: &[(&str, &str)]) -> u64 {
+        let matches = |input: Vec<&KeyValue>| {
+            attributes.iter().all(|(k, v)| {
+                input
+                    .iter()
+                    .any(|kv| kv.key.as_str() == *k && kv.value.as_str() == *v)
+            })
+        };
+        exported
+            .iter()
+            .flat_map(|r| r.scope_metrics())
+            .flat_map(|s| s.metrics())
+            .filter(|m| m.name() == name)
+            .map(|m| -> u64 {
+                match m.data() {
+                    AggregatedMetrics::F64(MetricData::Histogram(h)) => h
+                        .data_points()
+                        .filter(|p| matches(p.attributes().collect()))
+                        .map(|p| p.count())
+                        .sum::<u64>(),
+                    AggregatedMetrics::U64(MetricData::Histogram(h)) => h
+                        .data_points()
+                        .filter(|p| matches(p.attributes().collect()))
+                        .map(|p| p.count())
+                        .sum::<u64>(),
+                    AggregatedMetrics::U64(MetricData::Sum(s)) => s
+                        .data_points()
+                        .filter(|p| matches(p.attributes().collect()))
+                        .map(|p| p.value())
+                        .sum::<u64>(),
+                    d => panic!("unexpected metric data {d:?}"),
+                }
+            })
+            .sum()
+    }
+
+    fn success() -> tonic::Result<tonic::Response<EchoResponse>> {
+        Ok(tonic::Response::new(EchoResponse {
+            message: "success!".into(),
+            metadata: std::collections::HashMap::default(),
+        }))
+    }
+
+    fn transient() -> tonic::Result<tonic::Response<EchoResponse>> {
+        Err(tonic::Status::unavailable("try-again"))
+    }
+
+    fn test_backoff() -> impl gax::backoff_policy::BackoffPolicy {
+        gax::exponential_backoff::ExponentialBackoffBuilder::new()
+            .with_initial_delay(Duration::from_micros(1))
+            .with_maximum_delay(Duration::from_micros(1))
+            .build()
+            .expect("a valid backoff policy")
+    }
+
+    async fn send_request(client: grpc::Client) -> gax::Result<EchoResponse> {
+        let extensions = {
+            let mut e = tonic::Extensions::new();
+            e.insert(tonic::GrpcMethod::new("google.test.v1.EchoService", "Echo"));
+            e
+        };
+        let request = google::test::v1::EchoRequest {
+            message: "test message".into(),
+            ..google::test::v1::EchoRequest::default()
+        };
+        let mut options = RequestOptions::default();
+        options.set_idempotency(true);
+        client
+            .execute(
+                extensions,
+                http::uri::PathAndQuery::from_static("/google.test.v1.EchoService/Echo"),
+                request,
+                options,
+                "test-only-api-client/1.0",
+                "name=projects%2Fp%2Flocations%2Fus-central1%2Fsecrets%2Fs",
+            )
+            .await
+            .map(tonic::Response::into_inner)
+    }
+}
@@ -64,6 +64,157 @@ mod tests {
         check_simple_request(client).await
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn channel_pool() -> anyhow::Result<()> {
+        use gax::grpc_config::{ChannelSelection, GrpcConfig};
+        let (endpoint, _server) = start_echo_server().await?;
+
+        for selection in [ChannelSelection::RoundRobin, ChannelSelection::LeastLoaded] {
+            let config = GrpcConfig::default()
+                .with_channel_pool_size(3)
+                .with_channel_selection(selection)
+                .with_connect_timeout(std::time::Duration::from_secs(5))
+                .with_tcp_nodelay(true);
+            let client = builder(endpoint.clone())
+                .with_credentials(test_credentials())
+                .with_grpc_config(config)
+                .build()
+                .await?;
+            let tasks = (0..6)
+                .map(|i| {
+                    let client = client.clone();
+                    tokio::spawn(
+                        async move { send_request(client, &format!("message {i}"), "").await },
+                    )
+                })
+                .collect::<Vec<_>>();
+            for (i, task) in tasks.into_iter().enumerate() {
+                let response = task.await??;
+                assert_eq!(response.message, format!("message {i}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn proxy() -> anyhow::Result<()> {
+        use gax::transport_config::{Proxy, TransportConfig};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let (endpoint, _server) = start_echo_server().await?;
+
+        // A minimal proxy: accepts one `CONNECT` request and tunnels the
+        // connection to the echo server.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let proxy_url = format!("http://{}", listener.local_addr()?);
+        let proxy = tokio::spawn(async move {
+            let (mut client, _) = listener.accept().await?;
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(client.read_u8().await?);
+            }
+            let request = String::from_utf8(request)?;
+            let target = request
+                .strip_prefix("CONNECT ")
+                .and_then(|r| r.split_whitespace().next())
+                .ok_or_else(|| anyhow::anyhow!("unexpected request {request}"))?
+                .to_string();
+            let mut server = tokio::net::TcpStream::connect(&target).await?;
+            client
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await?;
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+            anyhow::Ok(request)
+        });
+
+        let config = TransportConfig::default()
+            .with_proxy(Proxy::new(proxy_url).with_basic_auth("user", "pass"));
+        let client = builder(endpoint.clone())
+            .with_credentials(test_credentials())
+            .with_transport_config(config)
+            .build()
+            .await?;
+        let response = send_request(client, "via proxy", "").await?;
+        assert_eq!(&response.message, "via proxy");
+
+        let authority = endpoint.trim_start_matches("http://");
+        let request = proxy.await??;
+        assert!(
+            request.starts_with(&format!("CONNECT {authority} HTTP/1.1\r\n")),
+            "{request}"
+        );
+        assert!(
+            request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"),
+            "{request}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn transport_config_errors() -> anyhow::Result<()> {
+        use gax::transport_config::{Proxy, TransportConfig};
+        for config in [
+            TransportConfig::default().with_proxy(Proxy::new("https://proxy.example.com")),
+            TransportConfig::default().with_root_certificates_pem("not a certificate"),
+        ] {
+            let err = builder("https://test.googleapis.com")
+                .with_credentials(test_credentials())
+                .with_transport_config(config.clone())
+                .build()
+                .await
+                .unwrap_err();
+            assert!(err.is_transport(), "{config:?} => {err:?}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn max_decoding_message_size() -> anyhow::Result<()> {
+        use gax::grpc_config::GrpcConfig;
+        let (endpoint, _server) = start_echo_server().await?;
+
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_grpc_config(GrpcConfig::default().with_max_decoding_message_size(8))
+            .build()
+            .await?;
+        let response = send_request(client, "a message larger than the limit", "").await;
+        assert!(response.is_err(), "{response:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn compression() -> anyhow::Result<()> {
+        use gax::compression::Compression;
+        let (endpoint, _server) = start_echo_server().await?;
+
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_request_compression(Compression::Gzip)
+            .with_accept_compression([Compression::Zstd])
+            .build()
+            .await?;
+        let response = send_request(client.clone(), "compressed message", "").await?;
+        assert_eq!(&response.message, "compressed message");
+        let get = |name: &str| response.metadata.get(name).map(String::as_str);
+        assert_eq!(get("grpc-encoding"), Some("gzip"));
+        let accept = get("grpc-accept-encoding").unwrap_or_default();
+        assert!(accept.contains("zstd"), "{accept}");
+        assert!(!accept.contains("gzip"), "{accept}");
+
+        // The request options override the client configuration.
+        let mut options = RequestOptions::default();
+        options.set_request_compression(Compression::Identity);
+        options.set_accept_compression([Compression::Gzip]);
+        let response = send_request_with_options(client, "uncompressed message", options).await?;
+        assert_eq!(&response.message, "uncompressed message");
+        let get = |name: &str| response.metadata.get(name).map(String::as_str);
+        assert_eq!(get("grpc-encoding"), None);
+        let accept = get("grpc-accept-encoding").unwrap_or_default();
+        assert!(accept.contains("gzip"), "{accept}");
+        assert!(!accept.contains("zstd"), "{accept}");
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn credentials_error() -> anyhow::Result<()> {
         let (endpoint, _server) = start_echo_server().await?;
@@ -137,6 +288,23 @@ mod tests {
         client: grpc::Client,
         msg: &str,
         request_params: &str,
+    ) -> gax::Result<google::test::v1::EchoResponse> {
+        send_request_impl(client, msg, request_params, RequestOptions::default()).await
+    }
+
+    async fn send_request_with_options(
+        client: grpc::Client,
+        msg: &str,
+        options: RequestOptions,
+    ) -> gax::Result<google::test::v1::EchoResponse> {
+        send_request_impl(client, msg, "", options).await
+    }
+
+    async fn send_request_impl(
+        client: grpc::Client,
+        msg: &str,
+        request_params: &str,
+        options: RequestOptions,
     ) -> gax::Result<google::test::v1::EchoResponse> {
         let extensions = {
             let mut e = tonic::Extensions::new();
@@ -155,7 +323,7 @@ mod tests {
                 extensions,
                 http::uri::PathAndQuery::from_static("/google.test.v1.EchoService/Echo"),
                 request,
-                RequestOptions::default(),
+                options,
                 "test-only-api-client/1.0",
                 request_params,
             )
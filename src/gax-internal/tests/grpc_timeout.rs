@@ -194,6 +194,71 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_deadline_expires() -> Result<()> {
+        let (endpoint, server) = start_echo_server().await?;
+        let client = test_client(endpoint).await?;
+        let mut server = connect_client(client.clone(), server).await?;
+
+        // Each attempt takes longer than the deadline allows, and the retry
+        // policy would retry forever.
+        let deadline = Duration::from_millis(150);
+        let delay = Duration::from_millis(200);
+        let mut interval = tokio::time::interval(Duration::from_millis(10));
+        let mut request_options = RequestOptions::default();
+        request_options.set_retry_policy(AlwaysRetry);
+        request_options.set_deadline(tokio::time::Instant::now().into_std() + deadline);
+        disable_throttling(&mut request_options);
+        let response = send_request(client, request_options, "should timeout", Some(delay));
+
+        let start = tokio::time::Instant::now();
+        tokio::pin!(response);
+        loop {
+            tokio::select! {
+                _ = &mut server => {  },
+                r = &mut response => {
+                    let err = r.expect_err("expected a deadline exceeded error");
+                    assert!(err.is_deadline_exceeded(), "{err:?}");
+                    break;
+                },
+                _ = interval.tick() => { },
+            }
+        }
+
+        let elapsed = tokio::time::Instant::now() - start;
+        assert_eq!(elapsed, deadline);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_deadline_propagates() -> Result<()> {
+        let (endpoint, _server) = start_echo_server().await?;
+        let client = test_client(endpoint).await?;
+
+        let mut request_options = RequestOptions::default();
+        request_options.set_deadline(std::time::Instant::now() + Duration::from_secs(30));
+        let response = send_request(client, request_options, "great success!", None).await?;
+        assert_eq!(response.message, "great success!");
+        let got = response.metadata.get("grpc-timeout");
+        assert!(got.is_some(), "{response:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_deadline_already_expired() -> Result<()> {
+        let (endpoint, _server) = start_echo_server().await?;
+        let client = test_client(endpoint).await?;
+
+        let mut request_options = RequestOptions::default();
+        request_options.set_deadline(std::time::Instant::now());
+        let err = send_request(client, request_options, "too late", None)
+            .await
+            .expect_err("expected a deadline exceeded error");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        Ok(())
+    }
+
     async fn test_client(endpoint: String) -> gax::client_builder::Result<grpc::Client> {
         builder(endpoint)
             .with_credentials(test_credentials())
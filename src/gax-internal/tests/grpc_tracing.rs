@@ -0,0 +1,242 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(
+    test,
+    feature = "_internal-grpc-client",
+    feature = "_internal-opentelemetry"
+))]
+mod tests {
+    use auth::credentials::testing::test_credentials;
+    use gax::options::*;
+    use gax::retry_throttler::CircuitBreaker;
+    use google_cloud_gax_internal::grpc;
+    use grpc_server::google::test::v1::EchoResponse;
+    use grpc_server::{builder, google, start_echo_server, start_fixed_responses};
+    use opentelemetry::trace::{SpanKind, Status, TracerProvider as _};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+    use std::time::Duration;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    const NAME: &str = "google.test.v1.EchoService/Echo";
+
+    #[tokio::test]
+    async fn propagation() -> anyhow::Result<()> {
+        let (exporter, provider) = test_provider();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&provider));
+
+        let (endpoint, _server) = start_echo_server().await?;
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_tracing()
+            .build()
+            .await?;
+        let response = send_request(client).await?;
+
+        let spans = wait_for_spans(&provider, &exporter, 2).await?;
+        let attempt = spans
+            .iter()
+            .find(|s| s.span_kind == SpanKind::Client)
+            .expect("an attempt span");
+        let context = &attempt.span_context;
+        assert_eq!(
+            response.metadata.get("traceparent"),
+            Some(&format!(
+                "00-{}-{}-01",
+                context.trace_id(),
+                context.span_id()
+            )),
+            "{response:?}"
+        );
+        assert_eq!(
+            response.metadata.get("x-cloud-trace-context"),
+            Some(&format!(
+                "{}/{};o=1",
+                context.trace_id(),
+                u64::from_be_bytes(context.span_id().to_bytes())
+            )),
+            "{response:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retry() -> anyhow::Result<()> {
+        let (exporter, provider) = test_provider();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&provider));
+
+        let (endpoint, _server) = start_fixed_responses([transient(), success()]).await?;
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_backoff_policy(test_backoff())
+            // The default throttler may randomly throttle the retry attempt.
+            .with_retry_throttler(CircuitBreaker::default())
+            .with_tracing()
+            .build()
+            .await?;
+        let response = send_request(client).await?;
+        assert_eq!(response.message, "success!");
+
+        let spans = wait_for_spans(&provider, &exporter, 3).await?;
+        let (mut attempts, operations): (Vec<_>, Vec<_>) =
+            spans.iter().partition(|s| s.span_kind == SpanKind::Client);
+        // The spans are exported as they close, which may be out of order.
+        attempts.sort_by_key(|s| s.start_time);
+        assert_eq!(operations.len(), 1, "{spans:?}");
+        let operation = operations[0];
+        assert_eq!(operation.span_kind, SpanKind::Internal);
+        assert_eq!(operation.status, Status::Ok);
+        assert_eq!(attribute(operation, "rpc.system"), Some("grpc".into()));
+        assert_eq!(
+            attribute(operation, "gcp.resource.name"),
+            Some("projects/p/locations/us-central1/secrets/s".into())
+        );
+
+        assert_eq!(attempts.len(), 2, "{spans:?}");
+        assert!(
+            attempts
+                .iter()
+                .all(|s| s.parent_span_id == operation.span_context.span_id()),
+            "{spans:?}"
+        );
+        assert!(
+            matches!(attempts[0].status, Status::Error { .. }),
+            "{spans:?}"
+        );
+        assert_eq!(
+            attribute(attempts[0], "rpc.response.status_code"),
+            Some("UNAVAILABLE".into())
+        );
+        assert_eq!(attempts[1].status, Status::Ok);
+        assert_eq!(
+            attribute(attempts[1], "http.request.resend_count"),
+            Some("1".into())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disabled() -> anyhow::Result<()> {
+        let (exporter, provider) = test_provider();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&provider));
+
+        let (endpoint, _server) = start_echo_server().await?;
+        let client = builder(endpoint)
+            .with_credentials(test_credentials())
+            .build()
+            .await?;
+        let response = send_request(client).await?;
+        assert!(
+            !response.metadata.contains_key("traceparent"),
+            "{response:?}"
+        );
+        assert!(
+            !response.metadata.contains_key("x-cloud-trace-context"),
+            "{response:?}"
+        );
+
+        provider.force_flush()?;
+        let spans = client_spans(&exporter)?;
+        assert!(spans.is_empty(), "{spans:?}");
+        Ok(())
+    }
+
+    fn attribute(span: &SpanData, key: &str) -> Option<String> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.to_string())
+    }
+
+    // Ignore any spans created by the server or the transport.
+    fn client_spans(exporter: &InMemorySpanExporter) -> anyhow::Result<Vec<SpanData>> {
+        let spans = exporter.get_finished_spans()?;
+        Ok(spans.into_iter().filter(|s| s.name == NAME).collect())
+    }
+
+    // The transport may hold the attempt span for a while after the request
+    // completes. The spans are exported once they are closed.
+    async fn wait_for_spans(
+        provider: &SdkTracerProvider,
+        exporter: &InMemorySpanExporter,
+        count: usize,
+    ) -> anyhow::Result<Vec<SpanData>> {
+        for _ in 0..100 {
+            provider.force_flush()?;
+            let spans = client_spans(exporter)?;
+            if spans.len() >= count {
+                return Ok(spans);
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        anyhow::bail!("timeout waiting for {count} spans")
+    }
+
+    fn test_provider() -> (InMemorySpanExporter, SdkTracerProvider) {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        (exporter, provider)
+    }
+
+    fn test_subscriber(provider: &SdkTracerProvider) -> impl tracing::Subscriber + Send + Sync {
+        tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")))
+    }
+
+    fn success() -> tonic::Result<tonic::Response<EchoResponse>> {
+        Ok(tonic::Response::new(EchoResponse {
+            message: "success!".into(),
+            metadata: std::collections::HashMap::default(),
+        }))
+    }
+
+    fn transient() -> tonic::Result<tonic::Response<EchoResponse>> {
+        Err(tonic::Status::unavailable("try-again"))
+    }
+
+    fn test_backoff() -> impl gax::backoff_policy::BackoffPolicy {
+        gax::exponential_backoff::ExponentialBackoffBuilder::new()
+            .with_initial_delay(Duration::from_micros(1))
+            .with_maximum_delay(Duration::from_micros(1))
+            .build()
+            .expect("a valid backoff policy")
+    }
+
+    async fn send_request(client: grpc::Client) -> gax::Result<EchoResponse> {
+        let extensions = {
+            let mut e = tonic::Extensions::new();
+            e.insert(tonic::GrpcMethod::new("google.test.v1.EchoService", "Echo"));
+            e
+        };
+        let request = google::test::v1::EchoRequest {
+            message: "test message".into(),
+            ..google::test::v1::EchoRequest::default()
+        };
+        let mut options = RequestOptions::default();
+        options.set_idempotency(true);
+        client
+            .execute(
+                extensions,
+                http::uri::PathAndQuery::from_static("/google.test.v1.EchoService/Echo"),
+                request,
+                options,
+                "test-only-api-client/1.0",
+                "name=projects%2Fp%2Flocations%2Fus-central1%2Fsecrets%2Fs",
+            )
+            .await
+            .map(tonic::Response::into_inner)
+    }
+}
@@ -33,6 +33,7 @@ mod tests {
         impl CredentialsProvider for Credentials {
             async fn headers(&self, extensions: Extensions) -> AuthResult<CacheableResource<HeaderMap>>;
             async fn universe_domain(&self) -> Option<String>;
+            async fn force_refresh(&self);
         }
     }
 
@@ -44,6 +45,7 @@ mod tests {
         // 1. we can test that multiple headers are included in the request
         // 2. it gives us extra confidence that our interfaces are called
         let mut mock = MockCredentials::new();
+        mock.expect_universe_domain().returning(|| None);
         let header = HeaderMap::from_iter([
             (
                 HeaderName::from_static("auth-key-1"),
@@ -88,6 +90,7 @@ mod tests {
         let (endpoint, _server) = echo_server::start().await?;
         let retry_count = 3;
         let mut mock = MockCredentials::new();
+        mock.expect_universe_domain().returning(|| None);
         mock.expect_headers()
             .times(retry_count..)
             .returning(|_extensions| Err(CredentialsError::from_msg(true, "mock retryable error")));
@@ -126,6 +129,7 @@ mod tests {
     ) -> Result<()> {
         let (endpoint, _server) = echo_server::start().await?;
         let mut mock = MockCredentials::new();
+        mock.expect_universe_domain().returning(|| None);
         mock.expect_headers()
             .times(1)
             .returning(move |_extensions| headers_response.clone());
@@ -156,6 +160,105 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn unauthorized_forces_refresh() -> Result<()> {
+        use httptest::{Expectation, Server, matchers::*, responders::*};
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/echo"))
+                .respond_with(status_code(401).body("unauthorized")),
+        );
+        let endpoint = format!("http://{}", server.addr());
+
+        let mut mock = MockCredentials::new();
+        mock.expect_universe_domain().returning(|| None);
+        mock.expect_headers().times(1).returning(|_extensions| {
+            Ok(CacheableResource::New {
+                entity_tag: EntityTag::default(),
+                data: HeaderMap::new(),
+            })
+        });
+        mock.expect_force_refresh().times(1).return_const(());
+
+        let client = echo_server::builder(endpoint)
+            .with_credentials(Credentials::from(mock))
+            .build()
+            .await?;
+
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let result = client
+            .execute::<serde_json::Value, serde_json::Value>(
+                builder,
+                Some(json!({})),
+                RequestOptions::default(),
+            )
+            .await;
+        let err = result.unwrap_err();
+        assert_eq!(err.http_status_code(), Some(401), "{err:?}");
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn universe_domain_mismatch() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+        let mut mock = MockCredentials::new();
+        mock.expect_universe_domain()
+            .returning(|| Some("my-universe.example.com".to_string()));
+        mock.expect_headers().never();
+
+        let client = echo_server::builder(endpoint)
+            .with_credentials(Credentials::from(mock))
+            .build()
+            .await?;
+
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let result = client
+            .execute::<serde_json::Value, serde_json::Value>(
+                builder,
+                Some(json!({})),
+                RequestOptions::default(),
+            )
+            .await;
+        let err = result.unwrap_err();
+        assert!(err.is_authentication(), "{err:?}");
+        assert!(err.to_string().contains("my-universe.example.com"), "{err}");
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn universe_domain_match() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+        let mut mock = MockCredentials::new();
+        mock.expect_universe_domain()
+            .times(1)
+            .returning(|| Some("my-universe.example.com".to_string()));
+        mock.expect_headers().times(2).returning(|_extensions| {
+            Ok(CacheableResource::New {
+                entity_tag: EntityTag::default(),
+                data: HeaderMap::new(),
+            })
+        });
+
+        let client = echo_server::builder(endpoint)
+            .with_universe_domain("my-universe.example.com")
+            .with_credentials(Credentials::from(mock))
+            .build()
+            .await?;
+
+        // The universe domain is only checked once.
+        for _ in 0..2 {
+            let builder = client.builder(reqwest::Method::GET, "/echo".into());
+            let _response: serde_json::Value = client
+                .execute(builder, Some(json!({})), RequestOptions::default())
+                .await?
+                .into_body();
+        }
+
+        Ok(())
+    }
+
     fn test_backoff() -> impl gax::backoff_policy::BackoffPolicy {
         use std::time::Duration;
         gax::exponential_backoff::ExponentialBackoffBuilder::new()
@@ -121,4 +121,31 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn deadline_polling_policies() -> TestResult {
+        let (endpoint, _server) = echo_server::start().await?;
+        let client = echo_server::builder(endpoint)
+            .with_credentials(auth::credentials::testing::test_credentials())
+            .with_polling_backoff_policy(TestBackoffPolicy {
+                _name: "client-polling-backoff".to_string(),
+            })
+            .build()
+            .await?;
+
+        let start = std::time::Instant::now();
+        let mut options = gax::options::RequestOptions::default();
+        options.set_deadline(start);
+        let polling = client.get_polling_error_policy(&options);
+        let err = polling
+            .on_in_progress(start, 1, "test-only-operation")
+            .expect_err("the deadline has expired");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        let backoff = client.get_polling_backoff_policy(&options);
+        let fmt = format!("{backoff:?}");
+        assert!(fmt.contains("client-polling-backoff"), "{backoff:?}");
+        assert_eq!(backoff.wait_period(start, 1), std::time::Duration::ZERO);
+
+        Ok(())
+    }
 }
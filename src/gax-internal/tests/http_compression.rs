@@ -0,0 +1,207 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(test, feature = "_internal-http-client"))]
+mod tests {
+    use gax::compression::Compression;
+    use gax::options::*;
+    use google_cloud_gax_internal::http::ReqwestClient;
+    use google_cloud_gax_internal::options::ClientConfig;
+    use httptest::matchers::{ExecutionContext, Matcher};
+    use httptest::{Expectation, Server, all_of, matchers::*, responders::*};
+    use serde_json::json;
+    use std::io::{Read as _, Write as _};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn gzip_request() -> anyhow::Result<()> {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/compressed"),
+                request::headers(contains(("content-encoding", "gzip"))),
+                request::headers(contains(("content-type", "application/json"))),
+                request::headers(not(contains(key("accept-encoding")))),
+                DecodedBody(Compression::Gzip, json!({"name": "test-only"})),
+            ])
+            .respond_with(json_encoded(json!({"greeting": "Hello"}))),
+        );
+        let config = ClientConfig {
+            request_compression: Compression::Gzip,
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &format!("http://{}", server.addr())).await?;
+        let builder = client.builder(reqwest::Method::POST, "/compressed".into());
+        let response: serde_json::Value = client
+            .execute(
+                builder,
+                Some(json!({"name": "test-only"})),
+                RequestOptions::default(),
+            )
+            .await?
+            .into_body();
+        assert_eq!(response, json!({"greeting": "Hello"}));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn zstd_request_options() -> anyhow::Result<()> {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/compressed"),
+                request::headers(contains(("content-encoding", "zstd"))),
+                DecodedBody(Compression::Zstd, json!({"name": "test-only"})),
+            ])
+            .respond_with(json_encoded(json!({"greeting": "Hello"}))),
+        );
+        // The request options override the client configuration.
+        let config = ClientConfig {
+            request_compression: Compression::Gzip,
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &format!("http://{}", server.addr())).await?;
+        let builder = client.builder(reqwest::Method::POST, "/compressed".into());
+        let mut options = RequestOptions::default();
+        options.set_request_compression(Compression::Zstd);
+        let response: serde_json::Value = client
+            .execute(builder, Some(json!({"name": "test-only"})), options)
+            .await?
+            .into_body();
+        assert_eq!(response, json!({"greeting": "Hello"}));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn uncompressed_request() -> anyhow::Result<()> {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/uncompressed"),
+                request::headers(not(contains(key("content-encoding")))),
+                request::body(json_decoded(eq(json!({"name": "test-only"})))),
+            ])
+            .respond_with(json_encoded(json!({"greeting": "Hello"}))),
+        );
+        let client =
+            ReqwestClient::new(test_config(), &format!("http://{}", server.addr())).await?;
+        let builder = client.builder(reqwest::Method::POST, "/uncompressed".into());
+        let response: serde_json::Value = client
+            .execute(
+                builder,
+                Some(json!({"name": "test-only"})),
+                RequestOptions::default(),
+            )
+            .await?
+            .into_body();
+        assert_eq!(response, json!({"greeting": "Hello"}));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn compressed_response() -> anyhow::Result<()> {
+        let server = Server::run();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(json!({"greeting": "Hello"}).to_string().as_bytes())?;
+        let payload = encoder.finish()?;
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/compressed"),
+                request::headers(contains(("accept-encoding", "zstd, gzip"))),
+            ])
+            .respond_with(
+                status_code(200)
+                    .insert_header("content-type", "application/json")
+                    .insert_header("content-encoding", "gzip")
+                    .body(payload),
+            ),
+        );
+        let config = ClientConfig {
+            accept_compression: vec![Compression::Zstd, Compression::Gzip],
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &format!("http://{}", server.addr())).await?;
+        let builder = client.builder(reqwest::Method::GET, "/compressed".into());
+        let response: serde_json::Value = client
+            .execute(
+                builder,
+                None::<serde_json::Value>,
+                RequestOptions::default(),
+            )
+            .await?
+            .into_body();
+        assert_eq!(response, json!({"greeting": "Hello"}));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn accept_compression_options() -> anyhow::Result<()> {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/hello"),
+                request::headers(not(contains(key("accept-encoding")))),
+            ])
+            .respond_with(json_encoded(json!({"greeting": "Hello"}))),
+        );
+        let config = ClientConfig {
+            accept_compression: vec![Compression::Gzip],
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &format!("http://{}", server.addr())).await?;
+        let builder = client.builder(reqwest::Method::GET, "/hello".into());
+        let mut options = RequestOptions::default();
+        options.set_accept_compression([]);
+        let response: serde_json::Value = client
+            .execute(builder, None::<serde_json::Value>, options)
+            .await?
+            .into_body();
+        assert_eq!(response, json!({"greeting": "Hello"}));
+        Ok(())
+    }
+
+    // Matches requests with a compressed JSON body.
+    #[derive(Debug)]
+    struct DecodedBody(Compression, serde_json::Value);
+
+    impl<B: AsRef<[u8]>> Matcher<http::Request<B>> for DecodedBody {
+        fn matches(&mut self, input: &http::Request<B>, _ctx: &mut ExecutionContext) -> bool {
+            let body = input.body().as_ref();
+            let decoded = match self.0 {
+                Compression::Gzip => {
+                    let mut decoded = Vec::new();
+                    flate2::read::GzDecoder::new(body)
+                        .read_to_end(&mut decoded)
+                        .map(|_| decoded)
+                        .ok()
+                }
+                Compression::Zstd => zstd::stream::decode_all(body).ok(),
+                _ => Some(body.to_vec()),
+            };
+            decoded
+                .and_then(|d| serde_json::from_slice::<serde_json::Value>(&d).ok())
+                .is_some_and(|v| v == self.1)
+        }
+
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{self:?}")
+        }
+    }
+
+    fn test_config() -> ClientConfig {
+        ClientConfig {
+            cred: auth::credentials::testing::test_credentials().into(),
+            ..Default::default()
+        }
+    }
+}
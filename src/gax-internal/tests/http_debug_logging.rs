@@ -0,0 +1,153 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(test, feature = "_internal-http-client"))]
+mod tests {
+    use auth::credentials::api_key_credentials;
+    use gax::options::*;
+    use google_cloud_gax_internal::http::ReqwestClient;
+    use google_cloud_gax_internal::options::ClientConfig;
+    use httptest::{Expectation, Server, matchers::*, responders::*};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::fmt::MakeWriter;
+
+    type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    const PATH: &str = "/v1/projects/p/secrets/s";
+
+    #[tokio::test]
+    async fn debug_logging() -> Result<()> {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&capture));
+
+        let server = start_server();
+        let config = ClientConfig {
+            debug_logging: true,
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &format!("http://{}", server.addr())).await?;
+        let response = send_request(&client).await?;
+        assert_eq!(
+            response,
+            json!({"greeting": "Hello", "accessToken": "response-secret"})
+        );
+
+        let logs = capture.contents();
+        assert!(logs.contains("sending request"), "{logs}");
+        assert!(logs.contains("received response"), "{logs}");
+        assert!(logs.contains(r#"http.request.method=POST"#), "{logs}");
+        assert!(logs.contains("http.response.status_code=200"), "{logs}");
+        assert!(logs.contains(PATH), "{logs}");
+        assert!(logs.contains("test-only"), "{logs}");
+        assert!(logs.contains("greeting"), "{logs}");
+        assert!(logs.contains("[REDACTED]"), "{logs}");
+        for secret in [
+            "test-api-key",
+            "query-api-key",
+            "hunter2",
+            "response-secret",
+        ] {
+            assert!(!logs.contains(secret), "{secret} found in {logs}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disabled() -> Result<()> {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&capture));
+
+        let server = start_server();
+        let client =
+            ReqwestClient::new(test_config(), &format!("http://{}", server.addr())).await?;
+        let _ = send_request(&client).await?;
+
+        let logs = capture.contents();
+        assert!(!logs.contains("sending request"), "{logs}");
+        assert!(!logs.contains("received response"), "{logs}");
+        Ok(())
+    }
+
+    fn start_server() -> Server {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", PATH),
+                request::headers(contains(("x-goog-api-key", "test-api-key"))),
+            ])
+            .respond_with(json_encoded(
+                json!({"greeting": "Hello", "accessToken": "response-secret"}),
+            )),
+        );
+        server
+    }
+
+    async fn send_request(client: &ReqwestClient) -> gax::Result<serde_json::Value> {
+        let builder = client
+            .builder(reqwest::Method::POST, PATH.into())
+            .query(&[("key", "query-api-key")]);
+        client
+            .execute(
+                builder,
+                Some(json!({"name": "test-only", "password": "hunter2"})),
+                RequestOptions::default(),
+            )
+            .await
+            .map(|r| r.into_body())
+    }
+
+    fn test_config() -> ClientConfig {
+        ClientConfig {
+            cred: Some(api_key_credentials::Builder::new("test-api-key").build()),
+            ..Default::default()
+        }
+    }
+
+    fn test_subscriber(capture: &Capture) -> impl tracing::Subscriber + Send + Sync {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(capture.clone())
+            .finish()
+    }
+
+    // Captures the log output for inspection.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Capture {
+        fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+        }
+    }
+
+    impl std::io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Capture {
+        type Writer = Capture;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+}
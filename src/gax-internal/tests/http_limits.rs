@@ -0,0 +1,118 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(test, feature = "_internal-http-client"))]
+mod tests {
+    use gax::options::*;
+    use gax::rate_limiter::{ConcurrencyLimiter, RateLimiter};
+    use google_cloud_gax_internal::http::ReqwestClient;
+    use google_cloud_gax_internal::options::ClientConfig;
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn shared_concurrency_limiter() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+        let limiter = ConcurrencyLimiter::new(1)?;
+        let config = ClientConfig {
+            concurrency_limiter: Some(limiter.clone()),
+            ..test_config()
+        };
+        let client1 = ReqwestClient::new(config.clone(), &endpoint).await?;
+        let client2 = ReqwestClient::new(config, &endpoint).await?;
+
+        let start = Instant::now();
+        let delay = Duration::from_millis(200);
+        let (r1, r2) = tokio::join!(
+            send_request(&client1, delay, RequestOptions::default()),
+            send_request(&client2, delay, RequestOptions::default()),
+        );
+        r1?;
+        r2?;
+        // The limiter serializes the requests across both clients.
+        let elapsed = start.elapsed();
+        assert!(elapsed >= 2 * delay, "{elapsed:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn concurrency_limiter_timeout() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+        let config = ClientConfig {
+            concurrency_limiter: Some(ConcurrencyLimiter::new(1)?),
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &endpoint).await?;
+
+        let mut options = RequestOptions::default();
+        options.set_attempt_timeout(Duration::from_millis(50));
+        let (r1, r2) = tokio::join!(
+            send_request(
+                &client,
+                Duration::from_millis(500),
+                RequestOptions::default()
+            ),
+            async {
+                // Start after the first request holds the only slot.
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                send_request(&client, Duration::ZERO, options).await
+            },
+        );
+        r1?;
+        let err = r2.unwrap_err();
+        assert!(err.is_timeout(), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn request_rate_limiter() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+        let client = ReqwestClient::new(test_config(), &endpoint).await?;
+
+        let limiter = RateLimiter::new().with_requests_per_second(4.0)?;
+        let mut options = RequestOptions::default();
+        options.set_rate_limiter(limiter);
+        let start = Instant::now();
+        // The first 4 requests use the initial burst, the next 4 wait.
+        for _ in 0..8 {
+            send_request(&client, Duration::ZERO, options.clone()).await?;
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(900), "{elapsed:?}");
+        Ok(())
+    }
+
+    async fn send_request(
+        client: &ReqwestClient,
+        delay: Duration,
+        options: RequestOptions,
+    ) -> gax::Result<serde_json::Value> {
+        let builder = client
+            .builder(reqwest::Method::GET, "/echo".into())
+            .query(&[("delay_ms", format!("{}", delay.as_millis()))]);
+        client
+            .execute::<serde_json::Value, serde_json::Value>(builder, Some(json!({})), options)
+            .await
+            .map(|r| r.into_body())
+    }
+
+    fn test_config() -> ClientConfig {
+        ClientConfig {
+            cred: auth::credentials::testing::test_credentials().into(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(
+    test,
+    feature = "_internal-http-client",
+    feature = "_internal-opentelemetry"
+))]
+mod tests {
+    use gax::exponential_backoff::ExponentialBackoffBuilder;
+    use gax::options::*;
+    use gax::retry_throttler::CircuitBreaker;
+    use google_cloud_gax_internal::http::ReqwestClient;
+    use google_cloud_gax_internal::options::ClientConfig;
+    use httptest::{Expectation, Server, matchers::*, responders::*};
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+    use opentelemetry_sdk::metrics::{InMemoryMetricExporter, SdkMeterProvider};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    const PATH: &str = "/v1/projects/p/locations/us-central1/secrets/s";
+
+    // The meter provider is global state, keep all the metrics tests in a
+    // single function.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn metrics() -> anyhow::Result<()> {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(exporter.clone())
+            .build();
+        opentelemetry::global::set_meter_provider(provider.clone());
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", PATH))
+                .times(3)
+                .respond_with(cycle![
+                    status_code(503)
+                        .insert_header("content-type", "application/json")
+                        .body(
+                            json!({"error": {
+                                "code": 503,
+                                "status": "UNAVAILABLE",
+                                "message": "try-again",
+                            }})
+                            .to_string()
+                        ),
+                    json_encoded(json!({"greeting": "Hello"})),
+                    json_encoded(json!({"greeting": "Hello"})),
+                ]),
+        );
+        let endpoint = format!("http://{}", server.addr());
+        let config = ClientConfig {
+            metrics: true,
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &endpoint).await?;
+        let response = send_request(&client).await?;
+        assert_eq!(response, json!({"greeting": "Hello"}));
+
+        // Clients without metrics do not record anything.
+        let client = ReqwestClient::new(test_config(), &endpoint).await?;
+        let _ = send_request(&client).await?;
+
+        provider.force_flush()?;
+        let exported = exporter.get_finished_metrics()?;
+        let address = server.addr().ip().to_string();
+        let common = [
+            ("rpc.system", "http"),
+            ("server.address", address.as_str()),
+            ("http.request.method", "POST"),
+            ("url.template", "/v1/projects/*/locations/*/secrets/*"),
+            ("gcp.resource.destination.location", "us-central1"),
+        ];
+        let ok = [common.as_slice(), &[("rpc.response.status_code", "OK")]].concat();
+        let unavailable = [
+            common.as_slice(),
+            &[
+                ("rpc.response.status_code", "UNAVAILABLE"),
+                ("http.response.status_code", "503"),
+                ("error.type", "UNAVAILABLE"),
+            ],
+        ]
+        .concat();
+
+        assert_eq!(count(&exported, "gcp.client.operation.duration", &ok), 1);
+        assert_eq!(count(&exported, "gcp.client.attempt.duration", &ok), 1);
+        assert_eq!(
+            count(&exported, "gcp.client.attempt.duration", &unavailable),
+            1
+        );
+        assert_eq!(count(&exported, "gcp.client.attempt.count", &ok), 1);
+        assert_eq!(
+            count(&exported, "gcp.client.attempt.count", &unavailable),
+            1
+        );
+        assert_eq!(count(&exported, "gcp.client.retry.count", &common), 1);
+        assert_eq!(count(&exported, "gcp.client.request.size", &common), 2);
+        assert_eq!(count(&exported, "gcp.client.response.size", &common), 2);
+        assert_eq!(count(&exported, "gcp.client.throttle.count", &common), 0);
+        // Only the client with metrics enabled records measurements.
+        assert_eq!(count(&exported, "gcp.client.operation.duration", &[]), 1);
+
+        provider.shutdown()?;
+        Ok(())
+    }
+
+    // Counts the measurements for `name` with all the `attributes`.
+    fn count(exported: &[ResourceMetrics], name: &str, attributes: &[(&str, &str)]) -> u64 {
+        let matches = |input: Vec<&KeyValue>| {
+            attributes.iter().all(|(k, v)| {
+                input
+                    .iter()
+                    .any(|kv| kv.key.as_str() == *k && kv.value.as_str() == *v)
+            })
+        };
+        exported
+            .iter()
+            .flat_map(|r| r.scope_metrics())
+            .flat_map(|s| s.metrics())
+            .filter(|m| m.name() == name)
+            .map(|m| -> u64 {
+                match m.data() {
+                    AggregatedMetrics::F64(MetricData::Histogram(h)) => h
+                        .data_points()
+                        .filter(|p| matches(p.attributes().collect()))
+                        .map(|p| p.count())
+                        .sum::<u64>(),
+                    AggregatedMetrics::U64(MetricData::Histogram(h)) => h
+                        .data_points()
+                        .filter(|p| matches(p.attributes().collect()))
+                        .map(|p| p.count())
+                        .sum::<u64>(),
+                    AggregatedMetrics::U64(MetricData::Sum(s)) => s
+                        .data_points()
+                        .filter(|p| matches(p.attributes().collect()))
+                        .map(|p| p.value())
+                        .sum::<u64>(),
+                    d => panic!("unexpected metric data {d:?}"),
+                }
+            })
+            .sum()
+    }
+
+    async fn send_request(client: &ReqwestClient) -> gax::Result<serde_json::Value> {
+        let builder = client.builder(reqwest::Method::POST, PATH.into());
+        let mut options = RequestOptions::default();
+        options.set_idempotency(true);
+        client
+            .execute(builder, Some(json!({"name": "test-only"})), options)
+            .await
+            .map(|r| r.into_body())
+    }
+
+    fn test_config() -> ClientConfig {
+        ClientConfig {
+            cred: auth::credentials::testing::test_credentials().into(),
+            backoff_policy: Some(Arc::new(
+                ExponentialBackoffBuilder::new()
+                    .with_initial_delay(Duration::from_millis(1))
+                    .with_maximum_delay(Duration::from_millis(1))
+                    .clamp(),
+            )),
+            // The default throttler may randomly throttle the retry attempt.
+            retry_throttler: Arc::new(Mutex::new(CircuitBreaker::default())),
+            ..Default::default()
+        }
+    }
+}
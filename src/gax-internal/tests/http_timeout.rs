@@ -229,6 +229,76 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_deadline_expires() -> Result<()> {
+        let (endpoint, server) = echo_server::start().await?;
+        let config = test_config();
+        let client = ReqwestClient::new(config, &endpoint).await?;
+
+        // Each attempt takes longer than the deadline allows, and the retry
+        // policy would retry forever.
+        let delay = Duration::from_millis(200);
+        let deadline = Duration::from_millis(150);
+        let mut interval = tokio::time::interval(Duration::from_millis(10));
+        let builder = client
+            .builder(reqwest::Method::GET, "/echo".into())
+            .query(&[("delay_ms", format!("{}", delay.as_millis()))]);
+        let mut options = RequestOptions::default();
+        options.set_idempotency(true);
+        options.set_retry_policy(AlwaysRetry);
+        options.set_deadline(tokio::time::Instant::now().into_std() + deadline);
+        disable_throttling(&mut options);
+        let response = client.execute::<serde_json::Value, serde_json::Value>(
+            builder,
+            Some(json!({})),
+            options,
+        );
+
+        let start = tokio::time::Instant::now();
+        tokio::pin!(server);
+        tokio::pin!(response);
+        loop {
+            tokio::select! {
+                _ = &mut server => {  },
+                r = &mut response => {
+                    let err = r.expect_err("expected a deadline exceeded error");
+                    assert!(err.is_deadline_exceeded(), "{err:?}");
+                    break;
+                },
+                _ = interval.tick() => { },
+            }
+        }
+
+        let elapsed = tokio::time::Instant::now() - start;
+        assert_eq!(elapsed, deadline);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_deadline_spans_requests() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+        let client = ReqwestClient::new(test_config(), &endpoint).await?;
+
+        // Paginators and pollers send each request with the same options, the
+        // deadline applies to all of them.
+        let mut options = RequestOptions::default();
+        options.set_deadline(std::time::Instant::now() + Duration::from_millis(100));
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let _ = client
+            .execute::<serde_json::Value, serde_json::Value>(builder, None, options.clone())
+            .await?;
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let err = client
+            .execute::<serde_json::Value, serde_json::Value>(builder, None, options)
+            .await
+            .expect_err("expected a deadline exceeded error");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        Ok(())
+    }
+
     fn test_options(timeout: &Duration) -> RequestOptions {
         let mut options = RequestOptions::default();
         options.set_attempt_timeout(*timeout);
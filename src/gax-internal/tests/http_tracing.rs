@@ -0,0 +1,245 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(
+    test,
+    feature = "_internal-http-client",
+    feature = "_internal-opentelemetry"
+))]
+mod tests {
+    use gax::exponential_backoff::ExponentialBackoffBuilder;
+    use gax::options::*;
+    use gax::retry_throttler::CircuitBreaker;
+    use google_cloud_gax_internal::http::ReqwestClient;
+    use google_cloud_gax_internal::options::ClientConfig;
+    use httptest::{Expectation, Server, matchers::*, responders::*};
+    use opentelemetry::trace::{SpanKind, Status, TracerProvider as _};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    const PATH: &str = "/v1/projects/p/locations/us-central1/secrets/s";
+
+    #[tokio::test]
+    async fn propagation() -> Result<()> {
+        let (exporter, provider) = test_provider();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&provider));
+
+        let (endpoint, _server) = echo_server::start().await?;
+        let client = echo_server::builder(endpoint)
+            .with_credentials(auth::credentials::testing::test_credentials())
+            .with_tracing()
+            .build()
+            .await?;
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let response: serde_json::Value = client
+            .execute(builder, Some(json!({})), RequestOptions::default())
+            .await?
+            .into_body();
+
+        provider.force_flush()?;
+        let spans = client_spans(&exporter, "GET")?;
+        let attempt = spans
+            .iter()
+            .find(|s| s.span_kind == SpanKind::Client)
+            .expect("an attempt span");
+        let context = &attempt.span_context;
+        assert_eq!(
+            get_header_value(&response, "traceparent"),
+            Some(format!(
+                "00-{}-{}-01",
+                context.trace_id(),
+                context.span_id()
+            )),
+            "{response:?}"
+        );
+        assert_eq!(
+            get_header_value(&response, "x-cloud-trace-context"),
+            Some(format!(
+                "{}/{};o=1",
+                context.trace_id(),
+                u64::from_be_bytes(context.span_id().to_bytes())
+            )),
+            "{response:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retry() -> Result<()> {
+        let (exporter, provider) = test_provider();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&provider));
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", PATH),
+                request::headers(contains(key("traceparent"))),
+                request::headers(contains(key("x-cloud-trace-context"))),
+            ])
+            .times(2)
+            .respond_with(cycle![
+                status_code(503)
+                    .insert_header("content-type", "application/json")
+                    .body(
+                        json!({"error": {
+                            "code": 503,
+                            "status": "UNAVAILABLE",
+                            "message": "try-again",
+                        }})
+                        .to_string()
+                    ),
+                json_encoded(json!({"greeting": "Hello"})),
+            ]),
+        );
+        let endpoint = format!("http://{}", server.addr());
+        let config = ClientConfig {
+            tracing: true,
+            ..test_config()
+        };
+        let client = ReqwestClient::new(config, &endpoint).await?;
+        let builder = client.builder(reqwest::Method::POST, PATH.into());
+        let mut options = RequestOptions::default();
+        options.set_idempotency(true);
+        let response: serde_json::Value = client
+            .execute(builder, Some(json!({"name": "test-only"})), options)
+            .await?
+            .into_body();
+        assert_eq!(response, json!({"greeting": "Hello"}));
+
+        provider.force_flush()?;
+        let spans = client_spans(&exporter, "POST")?;
+        let (mut attempts, operations): (Vec<_>, Vec<_>) =
+            spans.iter().partition(|s| s.span_kind == SpanKind::Client);
+        // The spans are exported as they close, which may be out of order.
+        attempts.sort_by_key(|s| s.start_time);
+        assert_eq!(operations.len(), 1, "{spans:?}");
+        let operation = operations[0];
+        assert_eq!(operation.span_kind, SpanKind::Internal);
+        assert_eq!(operation.status, Status::Ok);
+        assert_eq!(attribute(operation, "rpc.system"), Some("http".into()));
+        assert_eq!(
+            attribute(operation, "http.request.method"),
+            Some("POST".into())
+        );
+        assert_eq!(
+            attribute(operation, "gcp.resource.name"),
+            Some("projects/p/locations/us-central1/secrets/s".into())
+        );
+
+        assert_eq!(attempts.len(), 2, "{spans:?}");
+        assert!(
+            attempts
+                .iter()
+                .all(|s| s.parent_span_id == operation.span_context.span_id()),
+            "{spans:?}"
+        );
+        assert!(
+            matches!(attempts[0].status, Status::Error { .. }),
+            "{spans:?}"
+        );
+        assert_eq!(
+            attribute(attempts[0], "http.response.status_code"),
+            Some("503".into())
+        );
+        assert_eq!(
+            attribute(attempts[0], "error.type"),
+            Some("UNAVAILABLE".into())
+        );
+        assert_eq!(attempts[1].status, Status::Ok);
+        assert_eq!(
+            attribute(attempts[1], "http.request.resend_count"),
+            Some("1".into())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disabled() -> Result<()> {
+        let (exporter, provider) = test_provider();
+        let _guard = tracing::subscriber::set_default(test_subscriber(&provider));
+
+        let (endpoint, _server) = echo_server::start().await?;
+        let client = echo_server::builder(endpoint)
+            .with_credentials(auth::credentials::testing::test_credentials())
+            .build()
+            .await?;
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let response: serde_json::Value = client
+            .execute(builder, Some(json!({})), RequestOptions::default())
+            .await?
+            .into_body();
+        assert_eq!(get_header_value(&response, "traceparent"), None);
+        assert_eq!(get_header_value(&response, "x-cloud-trace-context"), None);
+
+        provider.force_flush()?;
+        let spans = client_spans(&exporter, "GET")?;
+        assert!(spans.is_empty(), "{spans:?}");
+        Ok(())
+    }
+
+    fn get_header_value(response: &serde_json::Value, name: &str) -> Option<String> {
+        response
+            .as_object()
+            .and_then(|o| o.get("headers"))
+            .and_then(|h| h.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    }
+
+    fn attribute(span: &SpanData, key: &str) -> Option<String> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.to_string())
+    }
+
+    // Ignore any spans created by the server or the transport.
+    fn client_spans(exporter: &InMemorySpanExporter, name: &str) -> Result<Vec<SpanData>> {
+        let spans = exporter.get_finished_spans()?;
+        Ok(spans.into_iter().filter(|s| s.name == name).collect())
+    }
+
+    fn test_provider() -> (InMemorySpanExporter, SdkTracerProvider) {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        (exporter, provider)
+    }
+
+    fn test_subscriber(provider: &SdkTracerProvider) -> impl tracing::Subscriber + Send + Sync {
+        tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")))
+    }
+
+    fn test_config() -> ClientConfig {
+        ClientConfig {
+            cred: auth::credentials::testing::test_credentials().into(),
+            backoff_policy: Some(Arc::new(
+                ExponentialBackoffBuilder::new()
+                    .with_initial_delay(Duration::from_millis(1))
+                    .with_maximum_delay(Duration::from_millis(1))
+                    .clamp(),
+            )),
+            // The default throttler may randomly throttle the retry attempt.
+            retry_throttler: Arc::new(Mutex::new(CircuitBreaker::default())),
+            ..Default::default()
+        }
+    }
+}
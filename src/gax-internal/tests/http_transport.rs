@@ -0,0 +1,154 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(all(test, feature = "_internal-http-client"))]
+mod tests {
+    use auth::credentials::testing::test_credentials;
+    use gax::options::*;
+    use gax::transport_config::{Proxy, TransportConfig};
+    use serde_json::json;
+    use std::time::Duration;
+
+    type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    // Any valid certificate works, the tests only verify it is loaded.
+    const CA_CERT: &str = concat!(
+        "-----BEGIN CERTIFICATE-----\n",
+        "MIIBezCCASGgAwIBAgIUL0rIbhcOEnc+d0zJPMdFUKP9gq8wCgYIKoZIzj0EAwIw\n",
+        "EjEQMA4GA1UEAwwHdGVzdC1jYTAgFw0yNjEwMTkwMzAwMTVaGA8yMTI2MDkyNTAz\n",
+        "MDAxNVowEjEQMA4GA1UEAwwHdGVzdC1jYTBZMBMGByqGSM49AgEGCCqGSM49AwEH\n",
+        "A0IABNCUm60zdABPxT7wZpPdYNa17vFjVWCbn6wsU+blxhssSArQ2cUKCJS3uAXC\n",
+        "BMYzCNV1VujyWbZTHM4YBsZQvqyjUzBRMB0GA1UdDgQWBBQF7jX2A23QJmJBhDRZ\n",
+        "eWrtF6oVzDAfBgNVHSMEGDAWgBQF7jX2A23QJmJBhDRZeWrtF6oVzDAPBgNVHRMB\n",
+        "Af8EBTADAQH/MAoGCCqGSM49BAMCA0gAMEUCIQDqK9ZFzi3YtoIRCAy0ZlslGcVd\n",
+        "pZX4UHPoUtL0KaPhrgIgE6xHJrAdVOT3kkVj3geH+mocb8s10deTQk5LoE1K1Yo=\n",
+        "-----END CERTIFICATE-----\n",
+    );
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn proxy() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+
+        // The echo server acts as the proxy. It receives the requests for the
+        // (unreachable) service endpoint.
+        let config = TransportConfig::default()
+            .with_proxy(Proxy::new(endpoint).with_basic_auth("user", "pass"));
+        let client = echo_server::builder("http://unreachable.invalid")
+            .with_credentials(test_credentials())
+            .with_transport_config(config)
+            .build()
+            .await?;
+
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let response: serde_json::Value = client
+            .execute(builder, Some(json!({})), RequestOptions::default())
+            .await?
+            .into_body();
+        let got = response
+            .get("headers")
+            .and_then(|h| h.get("proxy-authorization"))
+            .and_then(|v| v.as_str());
+        assert_eq!(got, Some("Basic dXNlcjpwYXNz"), "{response:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn pool_settings() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+
+        let config = TransportConfig::default()
+            .with_root_certificates_pem(CA_CERT)
+            .with_pool_idle_timeout(Duration::from_secs(5))
+            .with_pool_max_idle_per_host(2);
+        let client = echo_server::builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_transport_config(config)
+            .build()
+            .await?;
+
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let response = client
+            .execute::<serde_json::Value, serde_json::Value>(
+                builder,
+                Some(json!({})),
+                RequestOptions::default(),
+            )
+            .await;
+        assert!(response.is_ok(), "{response:?}");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn http2_prior_knowledge() -> Result<()> {
+        let (endpoint, _server) = echo_server::start().await?;
+
+        let config = TransportConfig::default().with_http2_prior_knowledge(true);
+        let client = echo_server::builder(endpoint)
+            .with_credentials(test_credentials())
+            .with_transport_config(config)
+            .build()
+            .await?;
+
+        let builder = client.builder(reqwest::Method::GET, "/echo".into());
+        let response = client
+            .execute::<serde_json::Value, serde_json::Value>(
+                builder,
+                Some(json!({})),
+                RequestOptions::default(),
+            )
+            .await;
+        // The echo server supports HTTP/2 over plain text.
+        assert!(response.is_ok(), "{response:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bad_root_certificates() -> Result<()> {
+        let config = TransportConfig::default().with_root_certificates_pem("not a certificate");
+        let err = echo_server::builder("http://unused")
+            .with_credentials(test_credentials())
+            .with_transport_config(config)
+            .build()
+            .await
+            .unwrap_err();
+        assert!(err.is_transport(), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bad_proxy() -> Result<()> {
+        let config = TransportConfig::default().with_proxy(Proxy::new("not a url"));
+        let err = echo_server::builder("http://unused")
+            .with_credentials(test_credentials())
+            .with_transport_config(config)
+            .build()
+            .await
+            .unwrap_err();
+        assert!(err.is_transport(), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bad_client_identity() -> Result<()> {
+        let config = TransportConfig::default().with_client_identity(CA_CERT, "not a key");
+        let err = echo_server::builder("http://unused")
+            .with_credentials(test_credentials())
+            .with_transport_config(config)
+            .build()
+            .await
+            .unwrap_err();
+        assert!(err.is_transport(), "{err:?}");
+        Ok(())
+    }
+}
@@ -48,10 +48,14 @@
 //! ```
 
 use crate::backoff_policy::{BackoffPolicy, BackoffPolicyArg};
+use crate::compression::Compression;
+use crate::grpc_config::GrpcConfig;
 use crate::polling_backoff_policy::{PollingBackoffPolicy, PollingBackoffPolicyArg};
 use crate::polling_error_policy::{PollingErrorPolicy, PollingErrorPolicyArg};
+use crate::rate_limiter::{ConcurrencyLimiter, RateLimiter};
 use crate::retry_policy::{RetryPolicy, RetryPolicyArg};
 use crate::retry_throttler::{RetryThrottlerArg, SharedRetryThrottler};
+use crate::transport_config::TransportConfig;
 use std::sync::Arc;
 
 /// The result type for this module.
@@ -199,11 +203,44 @@ impl<F, Cr> ClientBuilder<F, Cr> {
         self
     }
 
+    /// Sets the universe domain.
+    ///
+    /// Google Cloud is available in multiple universes, such as sovereign
+    /// cloud deployments. By default the clients use the `googleapis.com`
+    /// universe. If the endpoint is not set, the client uses the default
+    /// endpoint for the service in this universe, for example,
+    /// `https://secretmanager.{universe_domain}`.
+    ///
+    /// The client verifies the credentials belong to the same universe before
+    /// sending any requests, and fails the request if they do not.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax::client_builder::Result;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// let client = Client::builder()
+    ///     .with_universe_domain("my-universe.example.com")
+    ///     .build().await?;
+    /// # Result::<()>::Ok(()) });
+    /// ```
+    pub fn with_universe_domain<V: Into<String>>(mut self, v: V) -> Self {
+        self.config.universe_domain = Some(v.into());
+        self
+    }
+
     /// Enables tracing.
     ///
     /// The client libraries can be dynamically instrumented with the Tokio
     /// [tracing] framework. Setting this flag enables this instrumentation.
     ///
+    /// With tracing enabled, the client creates a span for each request, and a
+    /// child span for each attempt. If the application exports these spans
+    /// using [tracing-opentelemetry], the client sends the trace context to the
+    /// service in the W3C `traceparent` and the `x-cloud-trace-context`
+    /// headers. Sending the trace context requires the
+    /// `_internal-opentelemetry` feature of `google-cloud-gax-internal`.
+    ///
     /// ```
     /// # use google_cloud_gax::client_builder::examples;
     /// # use google_cloud_gax::client_builder::Result;
@@ -216,11 +253,172 @@ impl<F, Cr> ClientBuilder<F, Cr> {
     /// ```
     ///
     /// [tracing]: https://docs.rs/tracing/latest/tracing/
+    /// [tracing-opentelemetry]: https://docs.rs/tracing-opentelemetry
     pub fn with_tracing(mut self) -> Self {
         self.config.tracing = true;
         self
     }
 
+    /// Enables metrics.
+    ///
+    /// The client libraries can record [OpenTelemetry] metrics for each
+    /// request, including the latency of each operation and each attempt, the
+    /// number of attempts, retries, and throttled retries, and the size of the
+    /// request and response payloads. Setting this flag enables these metrics.
+    ///
+    /// The metrics are recorded using the global meter provider, see
+    /// [opentelemetry::global::set_meter_provider] for details. The metrics
+    /// are discarded if the application does not install a meter provider.
+    ///
+    /// Recording metrics requires the `_internal-opentelemetry` feature of
+    /// `google-cloud-gax-internal`, this flag has no effect without it. The
+    /// feature keeps the OpenTelemetry dependency out of applications that do
+    /// not use it.
+    ///
+    /// To measure the response size, HTTP clients with metrics enabled read
+    /// the full response body into memory before parsing it. This may increase
+    /// the memory usage for large responses.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax::client_builder::Result;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// let client = Client::builder()
+    ///     .with_metrics()
+    ///     .build().await?;
+    /// # Result::<()>::Ok(()) });
+    /// ```
+    ///
+    /// [OpenTelemetry]: https://opentelemetry.io/docs/specs/otel/metrics/
+    /// [opentelemetry::global::set_meter_provider]: https://docs.rs/opentelemetry/latest/opentelemetry/global/fn.set_meter_provider.html
+    pub fn with_metrics(mut self) -> Self {
+        self.config.metrics = true;
+        self
+    }
+
+    /// Enables debug logging of requests and responses.
+    ///
+    /// With debug logging enabled the client emits a `DEBUG` level [tracing]
+    /// event for each request attempt and its response. The events include the
+    /// method, the URL or gRPC method, the headers, and the payload. The client
+    /// redacts credentials, such as the `authorization` header and API keys,
+    /// and payload fields known to contain secrets. Large payloads are
+    /// truncated.
+    ///
+    /// Debug logging is also enabled if the `GOOGLE_SDK_RUST_LOGGING`
+    /// environment variable is set to `true`.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax::client_builder::Result;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// let client = Client::builder()
+    ///     .with_debug_logging()
+    ///     .build().await?;
+    /// # Result::<()>::Ok(()) });
+    /// ```
+    ///
+    /// [tracing]: https://docs.rs/tracing/latest/tracing/
+    pub fn with_debug_logging(mut self) -> Self {
+        self.config.debug_logging = true;
+        self
+    }
+
+    /// Configure the connections used by gRPC-based clients.
+    ///
+    /// Use this to create a pool of channels, or to change the HTTP/2
+    /// settings of each channel. See [GrpcConfig] for details. This setting
+    /// has no effect on clients using HTTP+JSON.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax::client_builder::Result;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// use google_cloud_gax::grpc_config::GrpcConfig;
+    /// let client = Client::builder()
+    ///     .with_grpc_config(GrpcConfig::default().with_channel_pool_size(4))
+    ///     .build().await?;
+    /// # Result::<()>::Ok(()) });
+    /// ```
+    pub fn with_grpc_config(mut self, v: GrpcConfig) -> Self {
+        self.config.grpc_config = v;
+        self
+    }
+
+    /// Configure the network transport.
+    ///
+    /// Use this to send requests through a proxy, to trust additional root
+    /// certificates, or to change the connection pool settings. See
+    /// [TransportConfig] for details.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax::client_builder::Result;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// use google_cloud_gax::transport_config::{Proxy, TransportConfig};
+    /// let config = TransportConfig::default()
+    ///     .with_proxy(Proxy::new("http://proxy.example.com:3128"));
+    /// let client = Client::builder()
+    ///     .with_transport_config(config)
+    ///     .build().await?;
+    /// # Result::<()>::Ok(()) });
+    /// ```
+    pub fn with_transport_config(mut self, v: TransportConfig) -> Self {
+        self.config.transport_config = v;
+        self
+    }
+
+    /// Compress the request payloads.
+    ///
+    /// By default the requests are not compressed. Compression can also be
+    /// configured for each request, see
+    /// [RequestOptionsBuilder][crate::options::RequestOptionsBuilder].
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax::client_builder::Result;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// use google_cloud_gax::compression::Compression;
+    /// let client = Client::builder()
+    ///     .with_request_compression(Compression::Gzip)
+    ///     .build().await?;
+    /// # Result::<()>::Ok(()) });
+    /// ```
+    pub fn with_request_compression(mut self, v: Compression) -> Self {
+        self.config.request_compression = v;
+        self
+    }
+
+    /// Accept compressed response payloads.
+    ///
+    /// The client asks the service to compress the responses with any of the
+    /// given algorithms, in order of preference. By default the client only
+    /// accepts uncompressed responses.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax::client_builder::Result;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// use google_cloud_gax::compression::Compression;
+    /// let client = Client::builder()
+    ///     .with_accept_compression([Compression::Zstd, Compression::Gzip])
+    ///     .build().await?;
+    /// # Result::<()>::Ok(()) });
+    /// ```
+    pub fn with_accept_compression<I>(mut self, v: I) -> Self
+    where
+        I: IntoIterator<Item = Compression>,
+    {
+        self.config.accept_compression = v.into_iter().collect();
+        self
+    }
+
     /// Configure the authentication credentials.
     ///
     /// Most Google Cloud services require authentication, though some services
@@ -325,6 +523,57 @@ impl<F, Cr> ClientBuilder<F, Cr> {
         self
     }
 
+    /// Configure a rate limiter.
+    ///
+    /// The client waits until each request attempt is within the limits on
+    /// requests per second and request payload bytes per second. Share the
+    /// same limiter across multiple clients to keep their combined traffic
+    /// within a quota. See [RateLimiter] for details.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax as gax;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// use gax::rate_limiter::RateLimiter;
+    /// let limiter = RateLimiter::new().with_requests_per_second(100.0)?;
+    /// let client = Client::builder()
+    ///     .with_rate_limiter(limiter.clone())
+    ///     .build().await?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) });
+    /// ```
+    ///
+    /// [RateLimiter]: crate::rate_limiter::RateLimiter
+    pub fn with_rate_limiter(mut self, v: RateLimiter) -> Self {
+        self.config.rate_limiter = Some(v);
+        self
+    }
+
+    /// Configure a concurrency limiter.
+    ///
+    /// The client waits until the limiter has capacity before sending each
+    /// request attempt. Share the same limiter across multiple clients to limit
+    /// their combined requests in flight. See [ConcurrencyLimiter] for
+    /// details.
+    ///
+    /// ```
+    /// # use google_cloud_gax::client_builder::examples;
+    /// # use google_cloud_gax as gax;
+    /// # tokio_test::block_on(async {
+    /// use examples::Client; // Placeholder for examples
+    /// use gax::rate_limiter::ConcurrencyLimiter;
+    /// let client = Client::builder()
+    ///     .with_concurrency_limiter(ConcurrencyLimiter::new(8)?)
+    ///     .build().await?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(()) });
+    /// ```
+    ///
+    /// [ConcurrencyLimiter]: crate::rate_limiter::ConcurrencyLimiter
+    pub fn with_concurrency_limiter(mut self, v: ConcurrencyLimiter) -> Self {
+        self.config.concurrency_limiter = Some(v);
+        self
+    }
+
     /// Configure the polling error policy.
     ///
     /// Some clients support long-running operations, the client libraries can
@@ -415,11 +664,20 @@ pub mod internal {
     #[derive(Clone, Debug)]
     pub struct ClientConfig<Cr> {
         pub endpoint: Option<String>,
+        pub universe_domain: Option<String>,
         pub cred: Option<Cr>,
         pub tracing: bool,
+        pub metrics: bool,
+        pub debug_logging: bool,
+        pub grpc_config: GrpcConfig,
+        pub transport_config: TransportConfig,
+        pub request_compression: Compression,
+        pub accept_compression: Vec<Compression>,
         pub retry_policy: Option<Arc<dyn RetryPolicy>>,
         pub backoff_policy: Option<Arc<dyn BackoffPolicy>>,
         pub retry_throttler: SharedRetryThrottler,
+        pub rate_limiter: Option<RateLimiter>,
+        pub concurrency_limiter: Option<ConcurrencyLimiter>,
         pub polling_error_policy: Option<Arc<dyn PollingErrorPolicy>>,
         pub polling_backoff_policy: Option<Arc<dyn PollingBackoffPolicy>>,
     }
@@ -430,11 +688,20 @@ pub mod internal {
             use std::sync::{Arc, Mutex};
             Self {
                 endpoint: None,
+                universe_domain: None,
                 cred: None,
                 tracing: false,
+                metrics: false,
+                debug_logging: false,
+                grpc_config: GrpcConfig::default(),
+                transport_config: TransportConfig::default(),
+                request_compression: Compression::Identity,
+                accept_compression: Vec::new(),
                 retry_policy: None,
                 backoff_policy: None,
                 retry_throttler: Arc::new(Mutex::new(AdaptiveThrottler::default())),
+                rate_limiter: None,
+                concurrency_limiter: None,
                 polling_error_policy: None,
                 polling_backoff_policy: None,
             }
@@ -520,8 +787,15 @@ pub mod examples {
             let client = Client::builder().build().await.unwrap();
             let config = client.0;
             assert_eq!(config.endpoint, None);
+            assert_eq!(config.universe_domain, None);
             assert_eq!(config.cred, None);
             assert!(!config.tracing);
+            assert!(!config.metrics);
+            assert!(!config.debug_logging);
+            assert_eq!(
+                config.grpc_config,
+                crate::grpc_config::GrpcConfig::default()
+            );
             assert!(
                 format!("{:?}", &config).contains("AdaptiveThrottler"),
                 "{config:?}"
@@ -530,6 +804,8 @@ pub mod examples {
             assert!(config.backoff_policy.is_none(), "{config:?}");
             assert!(config.polling_error_policy.is_none(), "{config:?}");
             assert!(config.polling_backoff_policy.is_none(), "{config:?}");
+            assert!(config.rate_limiter.is_none(), "{config:?}");
+            assert!(config.concurrency_limiter.is_none(), "{config:?}");
         }
 
         #[tokio::test]
@@ -543,6 +819,58 @@ pub mod examples {
             assert_eq!(config.endpoint.as_deref(), Some("http://example.com"));
         }
 
+        #[tokio::test]
+        async fn universe_domain() {
+            let client = Client::builder()
+                .with_universe_domain("example.com")
+                .build()
+                .await
+                .unwrap();
+            let config = client.0;
+            assert_eq!(config.universe_domain.as_deref(), Some("example.com"));
+        }
+
+        #[tokio::test]
+        async fn grpc_config() {
+            use crate::grpc_config::GrpcConfig;
+            let client = Client::builder()
+                .with_grpc_config(GrpcConfig::default().with_channel_pool_size(4))
+                .build()
+                .await
+                .unwrap();
+            let config = client.0;
+            assert_eq!(config.grpc_config.channel_pool_size(), 4);
+        }
+
+        #[tokio::test]
+        async fn transport_config() {
+            use crate::transport_config::TransportConfig;
+            let client = Client::builder()
+                .with_transport_config(TransportConfig::default().with_http2_prior_knowledge(true))
+                .build()
+                .await
+                .unwrap();
+            let config = client.0;
+            assert!(config.transport_config.http2_prior_knowledge());
+        }
+
+        #[tokio::test]
+        async fn compression() {
+            use crate::compression::Compression;
+            let client = Client::builder()
+                .with_request_compression(Compression::Gzip)
+                .with_accept_compression([Compression::Zstd, Compression::Gzip])
+                .build()
+                .await
+                .unwrap();
+            let config = client.0;
+            assert_eq!(config.request_compression, Compression::Gzip);
+            assert_eq!(
+                config.accept_compression,
+                vec![Compression::Zstd, Compression::Gzip]
+            );
+        }
+
         #[tokio::test]
         async fn tracing() {
             let client = Client::builder().with_tracing().build().await.unwrap();
@@ -550,6 +878,26 @@ pub mod examples {
             assert!(config.tracing);
         }
 
+        #[tokio::test]
+        async fn metrics() {
+            let client = Client::builder().with_metrics().build().await.unwrap();
+            let config = client.0;
+            assert!(config.metrics);
+            assert!(!config.tracing);
+        }
+
+        #[tokio::test]
+        async fn debug_logging() {
+            let client = Client::builder()
+                .with_debug_logging()
+                .build()
+                .await
+                .unwrap();
+            let config = client.0;
+            assert!(config.debug_logging);
+            assert!(!config.tracing);
+        }
+
         #[tokio::test]
         async fn credentials() {
             let client = Client::builder()
@@ -604,6 +952,37 @@ pub mod examples {
             );
         }
 
+        #[tokio::test]
+        async fn rate_limiter() -> anyhow::Result<()> {
+            use crate::rate_limiter::RateLimiter;
+            let limiter = RateLimiter::new().with_requests_per_second(10.0)?;
+            let client = Client::builder()
+                .with_rate_limiter(limiter.clone())
+                .build()
+                .await?;
+            let config = client.0;
+            let got = config.rate_limiter.expect("rate limiter is configured");
+            // The client shares the limits with the application.
+            limiter.set_requests_per_second(20.0)?;
+            assert_eq!(got.requests_per_second(), 20.0);
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn concurrency_limiter() -> anyhow::Result<()> {
+            use crate::rate_limiter::ConcurrencyLimiter;
+            let client = Client::builder()
+                .with_concurrency_limiter(ConcurrencyLimiter::new(8)?)
+                .build()
+                .await?;
+            let config = client.0;
+            let got = config
+                .concurrency_limiter
+                .expect("concurrency limiter is configured");
+            assert_eq!(got.max_concurrency(), 8);
+            Ok(())
+        }
+
         #[tokio::test]
         async fn polling_error_policy() {
             use crate::polling_error_policy::PollingErrorPolicyExt;
@@ -0,0 +1,89 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compress request and response payloads.
+//!
+//! By default, the clients send and receive uncompressed payloads. Applications
+//! sending large requests over constrained networks can compress the request
+//! payloads, and ask the service to compress the response payloads. This
+//! trades CPU usage for network bandwidth.
+//!
+//! Compression can be configured for all the requests in a client, using the
+//! client builder, or for a single request, using the
+//! [RequestOptionsBuilder][crate::options::RequestOptionsBuilder]. The
+//! request options override the client configuration.
+//!
+//! # Example
+//! ```
+//! # use google_cloud_gax::client_builder::examples;
+//! # use google_cloud_gax::client_builder::Result;
+//! # tokio_test::block_on(async {
+//! use examples::Client; // Placeholder for examples
+//! use google_cloud_gax::compression::Compression;
+//! let client = Client::builder()
+//!     .with_request_compression(Compression::Gzip)
+//!     .with_accept_compression([Compression::Zstd, Compression::Gzip])
+//!     .build().await?;
+//! # Result::<()>::Ok(()) });
+//! ```
+
+/// A compression algorithm for the request or response payloads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Compression {
+    /// No compression.
+    #[default]
+    Identity,
+    /// Compress using [gzip](https://www.rfc-editor.org/rfc/rfc1952).
+    Gzip,
+    /// Compress using [zstd](https://www.rfc-editor.org/rfc/rfc8878).
+    Zstd,
+}
+
+impl Compression {
+    /// The name of the algorithm in the `Content-Encoding` and
+    /// `grpc-encoding` headers.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Identity => "identity",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Compression::Identity, "identity")]
+    #[test_case(Compression::Gzip, "gzip")]
+    #[test_case(Compression::Zstd, "zstd")]
+    fn name(input: Compression, want: &str) {
+        assert_eq!(input.name(), want);
+        assert_eq!(input.to_string(), want);
+    }
+
+    #[test]
+    fn default() {
+        assert_eq!(Compression::default(), Compression::Identity);
+    }
+}
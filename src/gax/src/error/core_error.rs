@@ -119,6 +119,80 @@ impl Error {
         matches!(self.kind, ErrorKind::Timeout)
     }
 
+    /// Creates an error representing an expired operation deadline.
+    ///
+    /// # Example
+    /// ```
+    /// use std::error::Error as _;
+    /// use google_cloud_gax::error::Error;
+    /// let error = Error::deadline_exceeded("simulated deadline");
+    /// assert!(error.is_deadline_exceeded());
+    /// assert!(error.source().is_some());
+    /// ```
+    pub fn deadline_exceeded<T: Into<BoxError>>(source: T) -> Self {
+        Self {
+            kind: ErrorKind::DeadlineExceeded,
+            source: Some(source.into()),
+        }
+    }
+
+    /// The operation could not be completed before its overall deadline.
+    ///
+    /// This is always a client-side generated error. It is returned when the
+    /// deadline set via [RequestOptions::set_deadline] expires, which may
+    /// happen in the middle of an attempt, while waiting to retry, while
+    /// polling a long-running operation, or while fetching a page. The error
+    /// source, if any, is the last error received before the deadline
+    /// expired.
+    ///
+    /// Unlike [is_timeout][Error::is_timeout], which reports a single attempt
+    /// exceeding its timeout, this reports that the operation as a whole ran
+    /// out of time. As with timeouts, the operation may or may not have
+    /// completed in the service.
+    ///
+    /// # Troubleshooting
+    ///
+    /// Consider extending the deadline if the operation is expected to take
+    /// longer, for example, if it is a long-running operation or lists a
+    /// large collection.
+    ///
+    /// [RequestOptions::set_deadline]: crate::options::RequestOptions::set_deadline
+    pub fn is_deadline_exceeded(&self) -> bool {
+        matches!(self.kind, ErrorKind::DeadlineExceeded)
+    }
+
+    /// Creates an error representing a cancelled operation.
+    ///
+    /// # Example
+    /// ```
+    /// use std::error::Error as _;
+    /// use google_cloud_gax::error::Error;
+    /// let error = Error::cancelled("simulated cancellation");
+    /// assert!(error.is_cancelled());
+    /// assert!(error.source().is_some());
+    /// ```
+    pub fn cancelled<T: Into<BoxError>>(source: T) -> Self {
+        Self {
+            kind: ErrorKind::Cancelled,
+            source: Some(source.into()),
+        }
+    }
+
+    /// The application stopped waiting for the operation.
+    ///
+    /// This is always a client-side generated error. It is returned when the
+    /// application cancels a local wait, for example, while waiting for a
+    /// long-running operation to complete. The operation may or may not have
+    /// completed in the service, and it is not cancelled in the service.
+    ///
+    /// Note that operations cancelled in the service are reported as service
+    /// errors, typically with the [CANCELLED] code.
+    ///
+    /// [CANCELLED]: crate::error::rpc::Code::Cancelled
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.kind, ErrorKind::Cancelled)
+    }
+
     /// Creates an error representing an exhausted policy.
     ///
     /// # Example
@@ -652,9 +726,15 @@ impl std::fmt::Display for Error {
             (ErrorKind::Timeout, Some(e)) => {
                 write!(f, "the request exceeded the request deadline {e}")
             }
+            (ErrorKind::DeadlineExceeded, Some(e)) => {
+                write!(f, "the operation exceeded its deadline {e}")
+            }
             (ErrorKind::Exhausted, Some(e)) => {
                 write!(f, "{e}")
             }
+            (ErrorKind::Cancelled, Some(e)) => {
+                write!(f, "the operation was cancelled {e}")
+            }
             (ErrorKind::Transport(details), _) => details.display(self.source(), f),
             (ErrorKind::Service(d), _) => {
                 write!(
@@ -684,7 +764,9 @@ enum ErrorKind {
     Deserialization,
     Authentication,
     Timeout,
+    DeadlineExceeded,
     Exhausted,
+    Cancelled,
     Transport(Box<TransportDetails>),
     Service(Box<ServiceDetails>),
 }
@@ -775,6 +857,56 @@ mod tests {
         assert!(error.status().is_none(), "{error:?}");
     }
 
+    #[test]
+    fn deadline_exceeded() {
+        let source = wkt::TimestampError::OutOfRange;
+        let error = Error::deadline_exceeded(source);
+        assert!(error.is_deadline_exceeded(), "{error:?}");
+        assert!(!error.is_timeout(), "{error:?}");
+        assert!(!error.is_exhausted(), "{error:?}");
+        let got = error
+            .source()
+            .and_then(|e| e.downcast_ref::<wkt::TimestampError>());
+        assert!(
+            matches!(got, Some(wkt::TimestampError::OutOfRange)),
+            "{error:?}"
+        );
+        let source = wkt::TimestampError::OutOfRange;
+        assert!(error.to_string().contains(&source.to_string()), "{error}");
+        assert!(error.to_string().contains("deadline"), "{error}");
+        assert!(!error.is_transient_and_before_rpc(), "{error:?}");
+
+        assert!(error.http_headers().is_none(), "{error:?}");
+        assert!(error.http_status_code().is_none(), "{error:?}");
+        assert!(error.http_payload().is_none(), "{error:?}");
+        assert!(error.status().is_none(), "{error:?}");
+    }
+
+    #[test]
+    fn cancelled() {
+        let source = wkt::TimestampError::OutOfRange;
+        let error = Error::cancelled(source);
+        assert!(error.is_cancelled(), "{error:?}");
+        assert!(!error.is_timeout(), "{error:?}");
+        assert!(!error.is_deadline_exceeded(), "{error:?}");
+        let got = error
+            .source()
+            .and_then(|e| e.downcast_ref::<wkt::TimestampError>());
+        assert!(
+            matches!(got, Some(wkt::TimestampError::OutOfRange)),
+            "{error:?}"
+        );
+        let source = wkt::TimestampError::OutOfRange;
+        assert!(error.to_string().contains(&source.to_string()), "{error}");
+        assert!(error.to_string().contains("cancelled"), "{error}");
+        assert!(!error.is_transient_and_before_rpc(), "{error:?}");
+
+        assert!(error.http_headers().is_none(), "{error:?}");
+        assert!(error.http_status_code().is_none(), "{error:?}");
+        assert!(error.http_payload().is_none(), "{error:?}");
+        assert!(error.status().is_none(), "{error:?}");
+    }
+
     #[test]
     fn exhausted() {
         let source = wkt::TimestampError::OutOfRange;
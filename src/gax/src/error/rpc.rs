@@ -399,6 +399,26 @@ impl From<&rpc::model::Status> for Status {
 ///
 /// Google cloud RPCs often return a detailed error description. This details
 /// can be used to better understand the root cause of the problem.
+///
+/// Details of types not listed here are returned as [StatusDetails::Other].
+/// Use a [TypeRegistry][wkt::TypeRegistry] to extract them when the set of
+/// expected types is only known at runtime.
+///
+/// # Example
+/// ```
+/// # use google_cloud_gax::error::rpc::StatusDetails;
+/// use wkt::{TypeRegistry, UnpackedAny};
+/// fn print_details(details: &[StatusDetails], registry: &TypeRegistry) {
+///     for d in details {
+///         let StatusDetails::Other(any) = d else { continue; };
+///         match any.unpack_dynamic(registry) {
+///             Ok(UnpackedAny::Message(m)) => println!("{}: {m:?}", m.type_url()),
+///             Ok(UnpackedAny::Unknown(v)) => println!("unknown detail: {v}"),
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
@@ -486,6 +506,39 @@ impl From<&wkt::Any> for StatusDetails {
     }
 }
 
+/// Registers the `google.rpc` error detail types, and [rpc::model::Status].
+///
+/// Use this function to unpack error details received outside of a
+/// [Status], for example, in audit logs or long-running operation results.
+///
+/// # Example
+/// ```
+/// # use google_cloud_gax::error::rpc::register_types;
+/// use wkt::{Any, TypeRegistry, UnpackedAny};
+/// let mut registry = TypeRegistry::new();
+/// register_types(&mut registry);
+///
+/// let detail = rpc::model::ErrorInfo::new().set_reason("test-only");
+/// let any = Any::from_msg(&detail)?;
+/// let UnpackedAny::Message(m) = any.unpack_dynamic(&registry)? else { unreachable!() };
+/// assert_eq!(m.downcast_ref::<rpc::model::ErrorInfo>(), Some(&detail));
+/// # Ok::<(), wkt::AnyError>(())
+/// ```
+pub fn register_types(registry: &mut wkt::TypeRegistry) {
+    registry
+        .register::<rpc::model::BadRequest>()
+        .register::<rpc::model::DebugInfo>()
+        .register::<rpc::model::ErrorInfo>()
+        .register::<rpc::model::Help>()
+        .register::<rpc::model::LocalizedMessage>()
+        .register::<rpc::model::PreconditionFailure>()
+        .register::<rpc::model::QuotaFailure>()
+        .register::<rpc::model::RequestInfo>()
+        .register::<rpc::model::ResourceInfo>()
+        .register::<rpc::model::RetryInfo>()
+        .register::<rpc::model::Status>();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -737,6 +790,75 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn registered_types() -> Result<()> {
+        let mut registry = wkt::TypeRegistry::new();
+        register_types(&mut registry);
+        assert_eq!(registry.len(), 11, "{registry:?}");
+        for detail in [
+            StatusDetails::BadRequest(rpc::model::BadRequest::default()),
+            StatusDetails::DebugInfo(rpc::model::DebugInfo::default()),
+            StatusDetails::ErrorInfo(rpc::model::ErrorInfo::default()),
+            StatusDetails::Help(rpc::model::Help::default()),
+            StatusDetails::LocalizedMessage(rpc::model::LocalizedMessage::default()),
+            StatusDetails::PreconditionFailure(rpc::model::PreconditionFailure::default()),
+            StatusDetails::QuotaFailure(rpc::model::QuotaFailure::default()),
+            StatusDetails::RequestInfo(rpc::model::RequestInfo::default()),
+            StatusDetails::ResourceInfo(rpc::model::ResourceInfo::default()),
+            StatusDetails::RetryInfo(rpc::model::RetryInfo::default()),
+        ] {
+            let any = serde_json::from_value::<wkt::Any>(serde_json::to_value(&detail)?)?;
+            let got = any.unpack_dynamic(&registry)?;
+            assert!(
+                matches!(got, wkt::UnpackedAny::Message(_)),
+                "{detail:?} => {got:?}"
+            );
+        }
+        assert!(registry.contains("type.googleapis.com/google.rpc.Status"));
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_other() -> Result<()> {
+        const TIME: &str = "2025-05-27T10:00:00Z";
+        let json = json!({
+            "code": Code::Unknown as i32,
+            "message": "test",
+            "details": [
+                {"@type": "type.googleapis.com/google.protobuf.Timestamp", "value": TIME},
+                {"@type": "type.googleapis.com/google.protobuf.Duration", "value": "1s"},
+            ]
+        });
+        let got: Status = serde_json::from_value(json)?;
+        let mut registry = wkt::TypeRegistry::new();
+        registry.register::<wkt::Timestamp>();
+
+        let unpacked = got
+            .details
+            .iter()
+            .filter_map(|d| match d {
+                StatusDetails::Other(any) => Some(any.unpack_dynamic(&registry)),
+                _ => None,
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        assert_eq!(unpacked.len(), 2, "{unpacked:?}");
+        let wkt::UnpackedAny::Message(m) = &unpacked[0] else {
+            panic!("expected a message, got={unpacked:?}");
+        };
+        assert_eq!(
+            m.downcast_ref::<wkt::Timestamp>(),
+            Some(&wkt::Timestamp::try_from(TIME)?)
+        );
+        let wkt::UnpackedAny::Unknown(v) = &unpacked[1] else {
+            panic!("expected an unknown value, got={unpacked:?}");
+        };
+        assert_eq!(
+            v,
+            &json!({"@type": "type.googleapis.com/google.protobuf.Duration", "value": "1s"})
+        );
+        Ok(())
+    }
+
     #[test]
     fn deserialization_other() -> Result<()> {
         const TIME: &str = "2025-05-27T10:00:00Z";
@@ -0,0 +1,285 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Configure the connections used by gRPC-based clients.
+//!
+//! By default, gRPC-based clients use a single HTTP/2 connection (a channel)
+//! with the default transport settings. This works for most applications.
+//! Applications with high throughput may saturate the number of concurrent
+//! streams in a single connection, and need a pool of channels. Other
+//! applications may need to tune the HTTP/2 keepalive or flow control
+//! settings.
+//!
+//! The settings in this module have no effect on clients using HTTP+JSON.
+//!
+//! # Example
+//! ```
+//! # use google_cloud_gax::client_builder::examples;
+//! # use google_cloud_gax::client_builder::Result;
+//! # tokio_test::block_on(async {
+//! use examples::Client; // Placeholder for examples
+//! use google_cloud_gax::grpc_config::{ChannelSelection, GrpcConfig};
+//! use std::time::Duration;
+//! let config = GrpcConfig::default()
+//!     .with_channel_pool_size(8)
+//!     .with_channel_selection(ChannelSelection::LeastLoaded)
+//!     .with_http2_keep_alive_interval(Duration::from_secs(30));
+//! let client = Client::builder()
+//!     .with_grpc_config(config)
+//!     .build().await?;
+//! # Result::<()>::Ok(()) });
+//! ```
+
+use std::time::Duration;
+
+/// Connection settings for gRPC-based clients.
+///
+/// Settings that are not configured use the transport defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrpcConfig {
+    channel_pool_size: usize,
+    channel_selection: ChannelSelection,
+    connect_timeout: Option<Duration>,
+    http2_keep_alive_interval: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    keep_alive_while_idle: Option<bool>,
+    initial_stream_window_size: Option<u32>,
+    initial_connection_window_size: Option<u32>,
+    max_decoding_message_size: Option<usize>,
+    max_encoding_message_size: Option<usize>,
+    tcp_nodelay: Option<bool>,
+}
+
+impl std::default::Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            channel_pool_size: 1,
+            channel_selection: ChannelSelection::default(),
+            connect_timeout: None,
+            http2_keep_alive_interval: None,
+            keep_alive_timeout: None,
+            keep_alive_while_idle: None,
+            initial_stream_window_size: None,
+            initial_connection_window_size: None,
+            max_decoding_message_size: None,
+            max_encoding_message_size: None,
+            tcp_nodelay: None,
+        }
+    }
+}
+
+impl GrpcConfig {
+    /// Sets the number of channels in the pool.
+    ///
+    /// Each channel is a separate HTTP/2 connection. The service limits the
+    /// number of concurrent requests on each connection, typically to 100.
+    /// Applications sending more concurrent requests should use more
+    /// channels. The default is a single channel, a value of zero is treated
+    /// as one.
+    pub fn with_channel_pool_size(mut self, v: usize) -> Self {
+        self.channel_pool_size = v.max(1);
+        self
+    }
+
+    /// Gets the number of channels in the pool.
+    pub fn channel_pool_size(&self) -> usize {
+        self.channel_pool_size
+    }
+
+    /// Sets how requests are assigned to the channels in the pool.
+    pub fn with_channel_selection(mut self, v: ChannelSelection) -> Self {
+        self.channel_selection = v;
+        self
+    }
+
+    /// Gets how requests are assigned to the channels in the pool.
+    pub fn channel_selection(&self) -> ChannelSelection {
+        self.channel_selection
+    }
+
+    /// Sets the timeout to establish a new connection.
+    pub fn with_connect_timeout(mut self, v: Duration) -> Self {
+        self.connect_timeout = Some(v);
+        self
+    }
+
+    /// Gets the timeout to establish a new connection, if set.
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    /// Sets the interval between HTTP/2 keepalive pings.
+    ///
+    /// By default, the client does not send keepalive pings.
+    pub fn with_http2_keep_alive_interval(mut self, v: Duration) -> Self {
+        self.http2_keep_alive_interval = Some(v);
+        self
+    }
+
+    /// Gets the interval between HTTP/2 keepalive pings, if set.
+    pub fn http2_keep_alive_interval(&self) -> Option<Duration> {
+        self.http2_keep_alive_interval
+    }
+
+    /// Sets how long to wait for a keepalive ping response before closing the
+    /// connection.
+    pub fn with_keep_alive_timeout(mut self, v: Duration) -> Self {
+        self.keep_alive_timeout = Some(v);
+        self
+    }
+
+    /// Gets how long to wait for a keepalive ping response, if set.
+    pub fn keep_alive_timeout(&self) -> Option<Duration> {
+        self.keep_alive_timeout
+    }
+
+    /// Sends keepalive pings even if there are no requests in progress.
+    pub fn with_keep_alive_while_idle(mut self, v: bool) -> Self {
+        self.keep_alive_while_idle = Some(v);
+        self
+    }
+
+    /// Gets the keepalive while idle setting, if set.
+    pub fn keep_alive_while_idle(&self) -> Option<bool> {
+        self.keep_alive_while_idle
+    }
+
+    /// Sets the HTTP/2 initial window size for each stream, in bytes.
+    pub fn with_initial_stream_window_size(mut self, v: u32) -> Self {
+        self.initial_stream_window_size = Some(v);
+        self
+    }
+
+    /// Gets the HTTP/2 initial window size for each stream, if set.
+    pub fn initial_stream_window_size(&self) -> Option<u32> {
+        self.initial_stream_window_size
+    }
+
+    /// Sets the HTTP/2 initial window size for each connection, in bytes.
+    pub fn with_initial_connection_window_size(mut self, v: u32) -> Self {
+        self.initial_connection_window_size = Some(v);
+        self
+    }
+
+    /// Gets the HTTP/2 initial window size for each connection, if set.
+    pub fn initial_connection_window_size(&self) -> Option<u32> {
+        self.initial_connection_window_size
+    }
+
+    /// Sets the maximum size of a response message, in bytes.
+    ///
+    /// The transport default is 4MiB.
+    pub fn with_max_decoding_message_size(mut self, v: usize) -> Self {
+        self.max_decoding_message_size = Some(v);
+        self
+    }
+
+    /// Gets the maximum size of a response message, if set.
+    pub fn max_decoding_message_size(&self) -> Option<usize> {
+        self.max_decoding_message_size
+    }
+
+    /// Sets the maximum size of a request message, in bytes.
+    pub fn with_max_encoding_message_size(mut self, v: usize) -> Self {
+        self.max_encoding_message_size = Some(v);
+        self
+    }
+
+    /// Gets the maximum size of a request message, if set.
+    pub fn max_encoding_message_size(&self) -> Option<usize> {
+        self.max_encoding_message_size
+    }
+
+    /// Enables or disables `TCP_NODELAY` on the connections.
+    ///
+    /// The transport enables `TCP_NODELAY` by default.
+    pub fn with_tcp_nodelay(mut self, v: bool) -> Self {
+        self.tcp_nodelay = Some(v);
+        self
+    }
+
+    /// Gets the `TCP_NODELAY` setting, if set.
+    pub fn tcp_nodelay(&self) -> Option<bool> {
+        self.tcp_nodelay
+    }
+}
+
+/// Determines how requests are assigned to the channels in a pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChannelSelection {
+    /// Assign requests to each channel in turn.
+    #[default]
+    RoundRobin,
+    /// Assign each request to the channel with the fewest requests in
+    /// progress.
+    LeastLoaded,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let config = GrpcConfig::default();
+        assert_eq!(config.channel_pool_size(), 1);
+        assert_eq!(config.channel_selection(), ChannelSelection::RoundRobin);
+        assert_eq!(config.connect_timeout(), None);
+        assert_eq!(config.http2_keep_alive_interval(), None);
+        assert_eq!(config.keep_alive_timeout(), None);
+        assert_eq!(config.keep_alive_while_idle(), None);
+        assert_eq!(config.initial_stream_window_size(), None);
+        assert_eq!(config.initial_connection_window_size(), None);
+        assert_eq!(config.max_decoding_message_size(), None);
+        assert_eq!(config.max_encoding_message_size(), None);
+        assert_eq!(config.tcp_nodelay(), None);
+    }
+
+    #[test]
+    fn setters() {
+        let config = GrpcConfig::default()
+            .with_channel_pool_size(4)
+            .with_channel_selection(ChannelSelection::LeastLoaded)
+            .with_connect_timeout(Duration::from_secs(1))
+            .with_http2_keep_alive_interval(Duration::from_secs(2))
+            .with_keep_alive_timeout(Duration::from_secs(3))
+            .with_keep_alive_while_idle(true)
+            .with_initial_stream_window_size(1024)
+            .with_initial_connection_window_size(2048)
+            .with_max_decoding_message_size(4096)
+            .with_max_encoding_message_size(8192)
+            .with_tcp_nodelay(false);
+        assert_eq!(config.channel_pool_size(), 4);
+        assert_eq!(config.channel_selection(), ChannelSelection::LeastLoaded);
+        assert_eq!(config.connect_timeout(), Some(Duration::from_secs(1)));
+        assert_eq!(
+            config.http2_keep_alive_interval(),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(config.keep_alive_timeout(), Some(Duration::from_secs(3)));
+        assert_eq!(config.keep_alive_while_idle(), Some(true));
+        assert_eq!(config.initial_stream_window_size(), Some(1024));
+        assert_eq!(config.initial_connection_window_size(), Some(2048));
+        assert_eq!(config.max_decoding_message_size(), Some(4096));
+        assert_eq!(config.max_encoding_message_size(), Some(8192));
+        assert_eq!(config.tcp_nodelay(), Some(false));
+    }
+
+    #[test]
+    fn zero_pool_size() {
+        let config = GrpcConfig::default().with_channel_pool_size(0);
+        assert_eq!(config.channel_pool_size(), 1);
+    }
+}
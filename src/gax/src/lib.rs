@@ -46,14 +46,18 @@ pub mod response;
 
 pub mod backoff_policy;
 pub mod client_builder;
+pub mod compression;
 pub mod exponential_backoff;
+pub mod grpc_config;
 pub mod options;
 pub mod polling_backoff_policy;
 pub mod polling_error_policy;
+pub mod rate_limiter;
 pub mod retry_policy;
 pub mod retry_result;
 pub mod retry_throttler;
 pub mod throttle_result;
+pub mod transport_config;
 
 #[cfg_attr(not(feature = "_internal-semver"), doc(hidden))]
 pub mod retry_loop_internal;
@@ -26,8 +26,10 @@
 //! [RequestOptionsBuilder] trait where applications can override some defaults.
 
 use crate::backoff_policy::{BackoffPolicy, BackoffPolicyArg};
+use crate::compression::Compression;
 use crate::polling_backoff_policy::{PollingBackoffPolicy, PollingBackoffPolicyArg};
 use crate::polling_error_policy::{PollingErrorPolicy, PollingErrorPolicyArg};
+use crate::rate_limiter::{ConcurrencyLimiter, RateLimiter};
 use crate::retry_policy::{RetryPolicy, RetryPolicyArg};
 use crate::retry_throttler::{RetryThrottlerArg, SharedRetryThrottler};
 use std::sync::Arc;
@@ -44,11 +46,16 @@ pub struct RequestOptions {
     idempotent: Option<bool>,
     user_agent: Option<String>,
     attempt_timeout: Option<std::time::Duration>,
+    deadline: Option<std::time::Instant>,
     retry_policy: Option<Arc<dyn RetryPolicy>>,
     backoff_policy: Option<Arc<dyn BackoffPolicy>>,
     retry_throttler: Option<SharedRetryThrottler>,
     polling_error_policy: Option<Arc<dyn PollingErrorPolicy>>,
     polling_backoff_policy: Option<Arc<dyn PollingBackoffPolicy>>,
+    request_compression: Option<Compression>,
+    accept_compression: Option<Vec<Compression>>,
+    rate_limiter: Option<RateLimiter>,
+    concurrency_limiter: Option<ConcurrencyLimiter>,
 }
 
 impl RequestOptions {
@@ -104,6 +111,26 @@ impl RequestOptions {
         &self.attempt_timeout
     }
 
+    /// Sets the overall deadline for the operation.
+    ///
+    /// The deadline spans all the attempts in the retry loop, the polling
+    /// loop for long-running operations, and the page requests for list
+    /// operations. Each attempt is sent with a timeout no longer than the
+    /// time remaining until the deadline, which gRPC-based clients propagate
+    /// to the service as `grpc-timeout`.
+    ///
+    /// Once the deadline expires, the operation fails with an error where
+    /// [is_deadline_exceeded][crate::error::Error::is_deadline_exceeded]
+    /// returns `true`.
+    pub fn set_deadline(&mut self, v: std::time::Instant) {
+        self.deadline = Some(v);
+    }
+
+    /// Gets the overall deadline for the operation, if any.
+    pub fn deadline(&self) -> Option<std::time::Instant> {
+        self.deadline
+    }
+
     /// Get the current retry policy override, if any.
     pub fn retry_policy(&self) -> &Option<Arc<dyn RetryPolicy>> {
         &self.retry_policy
@@ -153,6 +180,61 @@ impl RequestOptions {
     pub fn set_polling_backoff_policy<V: Into<PollingBackoffPolicyArg>>(&mut self, v: V) {
         self.polling_backoff_policy = Some(v.into().0);
     }
+
+    /// Get the current request compression override, if any.
+    pub fn request_compression(&self) -> Option<Compression> {
+        self.request_compression
+    }
+
+    /// Sets the compression for the request payload.
+    ///
+    /// Use [Compression::Identity] to send an uncompressed request, even if
+    /// the client is configured to compress requests.
+    pub fn set_request_compression(&mut self, v: Compression) {
+        self.request_compression = Some(v);
+    }
+
+    /// Get the current accepted response compression override, if any.
+    pub fn accept_compression(&self) -> Option<&[Compression]> {
+        self.accept_compression.as_deref()
+    }
+
+    /// Sets the accepted compression algorithms for the response payload.
+    ///
+    /// The algorithms are in order of preference. An empty list only
+    /// accepts uncompressed responses.
+    pub fn set_accept_compression<I>(&mut self, v: I)
+    where
+        I: IntoIterator<Item = Compression>,
+    {
+        self.accept_compression = Some(v.into_iter().collect());
+    }
+
+    /// Get the current rate limiter override, if any.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Sets the rate limiter for this request.
+    ///
+    /// The request waits until it is within the limits before sending each
+    /// attempt. This replaces any rate limiter configured in the client.
+    pub fn set_rate_limiter(&mut self, v: RateLimiter) {
+        self.rate_limiter = Some(v);
+    }
+
+    /// Get the current concurrency limiter override, if any.
+    pub fn concurrency_limiter(&self) -> Option<&ConcurrencyLimiter> {
+        self.concurrency_limiter.as_ref()
+    }
+
+    /// Sets the concurrency limiter for this request.
+    ///
+    /// The request waits until the limiter has capacity before sending each
+    /// attempt. This replaces any concurrency limiter configured in the client.
+    pub fn set_concurrency_limiter(&mut self, v: ConcurrencyLimiter) {
+        self.concurrency_limiter = Some(v);
+    }
 }
 
 /// Implementations of this trait provide setters to configure request options.
@@ -174,6 +256,13 @@ pub trait RequestOptionsBuilder: internal::RequestBuilder {
     /// overall timeout for a request is set by the retry policy.
     fn with_attempt_timeout<V: Into<std::time::Duration>>(self, v: V) -> Self;
 
+    /// Sets the overall deadline for the operation.
+    ///
+    /// The deadline spans all the attempts in the retry loop, the polling
+    /// loop for long-running operations, and the page requests for list
+    /// operations.
+    fn with_deadline(self, v: std::time::Instant) -> Self;
+
     /// Sets the retry policy configuration.
     fn with_retry_policy<V: Into<RetryPolicyArg>>(self, v: V) -> Self;
 
@@ -188,6 +277,18 @@ pub trait RequestOptionsBuilder: internal::RequestBuilder {
 
     /// Sets the polling backoff policy configuration.
     fn with_polling_backoff_policy<V: Into<PollingBackoffPolicyArg>>(self, v: V) -> Self;
+
+    /// Sets the compression for the request payload.
+    fn with_request_compression(self, v: Compression) -> Self;
+
+    /// Sets the accepted compression algorithms for the response payload.
+    fn with_accept_compression<I: IntoIterator<Item = Compression>>(self, v: I) -> Self;
+
+    /// Sets the rate limiter for this request.
+    fn with_rate_limiter(self, v: RateLimiter) -> Self;
+
+    /// Sets the concurrency limiter for this request.
+    fn with_concurrency_limiter(self, v: ConcurrencyLimiter) -> Self;
 }
 
 #[cfg_attr(not(feature = "_internal-semver"), doc(hidden))]
@@ -233,6 +334,11 @@ where
         self
     }
 
+    fn with_deadline(mut self, v: std::time::Instant) -> Self {
+        self.request_options().set_deadline(v);
+        self
+    }
+
     fn with_retry_policy<V: Into<RetryPolicyArg>>(mut self, v: V) -> Self {
         self.request_options().set_retry_policy(v);
         self
@@ -257,6 +363,26 @@ where
         self.request_options().set_polling_backoff_policy(v);
         self
     }
+
+    fn with_request_compression(mut self, v: Compression) -> Self {
+        self.request_options().set_request_compression(v);
+        self
+    }
+
+    fn with_accept_compression<I: IntoIterator<Item = Compression>>(mut self, v: I) -> Self {
+        self.request_options().set_accept_compression(v);
+        self
+    }
+
+    fn with_rate_limiter(mut self, v: RateLimiter) -> Self {
+        self.request_options().set_rate_limiter(v);
+        self
+    }
+
+    fn with_concurrency_limiter(mut self, v: ConcurrencyLimiter) -> Self {
+        self.request_options().set_concurrency_limiter(v);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -281,7 +407,7 @@ mod tests {
     }
 
     #[test]
-    fn request_options() {
+    fn request_options() -> Result {
         let mut opts = RequestOptions::default();
 
         assert_eq!(opts.idempotent, None);
@@ -299,6 +425,11 @@ mod tests {
         assert_eq!(opts.user_agent().as_deref(), Some("test-only"));
         assert_eq!(opts.attempt_timeout(), &Some(d));
 
+        assert_eq!(opts.deadline(), None);
+        let deadline = std::time::Instant::now() + Duration::from_secs(60);
+        opts.set_deadline(deadline);
+        assert_eq!(opts.deadline(), Some(deadline));
+
         opts.set_retry_policy(LimitedAttemptCount::new(3));
         assert!(opts.retry_policy().is_some(), "{opts:?}");
 
@@ -313,6 +444,35 @@ mod tests {
 
         opts.set_polling_backoff_policy(ExponentialBackoffBuilder::new().clamp());
         assert!(opts.polling_backoff_policy().is_some(), "{opts:?}");
+
+        assert_eq!(opts.request_compression(), None);
+        opts.set_request_compression(Compression::Gzip);
+        assert_eq!(opts.request_compression(), Some(Compression::Gzip));
+
+        assert_eq!(opts.accept_compression(), None);
+        opts.set_accept_compression([Compression::Zstd]);
+        assert_eq!(
+            opts.accept_compression(),
+            Some([Compression::Zstd].as_slice())
+        );
+        opts.set_accept_compression([]);
+        assert_eq!(opts.accept_compression(), Some([].as_slice()));
+
+        assert!(opts.rate_limiter().is_none(), "{opts:?}");
+        opts.set_rate_limiter(RateLimiter::new().with_requests_per_second(10.0)?);
+        assert_eq!(
+            opts.rate_limiter().map(RateLimiter::requests_per_second),
+            Some(10.0)
+        );
+
+        assert!(opts.concurrency_limiter().is_none(), "{opts:?}");
+        opts.set_concurrency_limiter(ConcurrencyLimiter::new(4)?);
+        assert_eq!(
+            opts.concurrency_limiter()
+                .map(ConcurrencyLimiter::max_concurrency),
+            Some(4)
+        );
+        Ok(())
     }
 
     #[test]
@@ -351,6 +511,10 @@ mod tests {
         assert_eq!(builder.request_options().user_agent(), &None);
         assert_eq!(builder.request_options().attempt_timeout(), &Some(d));
 
+        let deadline = std::time::Instant::now() + Duration::from_secs(60);
+        let mut builder = TestBuilder::default().with_deadline(deadline);
+        assert_eq!(builder.request_options().deadline(), Some(deadline));
+
         let mut builder = TestBuilder::default().with_retry_policy(LimitedAttemptCount::new(3));
         assert!(
             builder.request_options().retry_policy().is_some(),
@@ -384,6 +548,38 @@ mod tests {
             "{builder:?}"
         );
 
+        let mut builder = TestBuilder::default().with_request_compression(Compression::Zstd);
+        assert_eq!(
+            builder.request_options().request_compression(),
+            Some(Compression::Zstd)
+        );
+
+        let mut builder = TestBuilder::default().with_accept_compression([Compression::Gzip]);
+        assert_eq!(
+            builder.request_options().accept_compression(),
+            Some([Compression::Gzip].as_slice())
+        );
+
+        let mut builder = TestBuilder::default()
+            .with_rate_limiter(RateLimiter::new().with_bytes_per_second(1000.0)?);
+        assert_eq!(
+            builder
+                .request_options()
+                .rate_limiter()
+                .map(RateLimiter::bytes_per_second),
+            Some(1000.0)
+        );
+
+        let mut builder =
+            TestBuilder::default().with_concurrency_limiter(ConcurrencyLimiter::new(2)?);
+        assert_eq!(
+            builder
+                .request_options()
+                .concurrency_limiter()
+                .map(ConcurrencyLimiter::max_concurrency),
+            Some(2)
+        );
+
         Ok(())
     }
 }
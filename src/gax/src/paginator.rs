@@ -77,6 +77,40 @@
 //! # Result::<()>::Ok(()) });
 //! ```
 //!
+//! # Example: checkpoint and resume a long listing
+//!
+//! Use [Paginator::next_page_token] to save the position after processing
+//! each page. To resume, set the page token in the request before calling
+//! `by_page()`.
+//! ```no_run
+//! # use google_cloud_gax::{paginator, Result, error::Error};
+//! struct Page { items: Vec<String>, token: String }
+//! # impl paginator::internal::PageableResponse for Page {
+//! #     type PageItem = String;
+//! #     fn items(self) -> Vec<String> { self.items }
+//! #     fn next_page_token(&self) -> String { self.token.clone() }
+//! # }
+//! # async fn get_page(_: String) -> Result<Page> { panic!(); }
+//! # fn save_checkpoint(_: &str) {}
+//! use paginator::Paginator;
+//! fn list(page_token: String) -> impl Paginator<Page, Error> {
+//!     // ... details omitted ...
+//!     # paginator::internal::new_paginator(page_token, get_page)
+//! }
+//! # tokio_test::block_on(async {
+//! # let checkpoint = String::new();
+//! // Fetch the next page while processing the current one.
+//! let mut pages = list(checkpoint).prefetch(1);
+//! while let Some(page) = pages.next().await {
+//!     let page = page?;
+//!     page.items.into_iter().for_each(|i| println!("  item = {i}"));
+//!     if let Some(token) = pages.next_page_token() {
+//!         save_checkpoint(&token);
+//!     }
+//! }
+//! # Result::<()>::Ok(()) });
+//! ```
+//!
 //! [AIP-4233]: https://google.aip.dev/client-libraries/4233
 
 use futures::stream::unfold;
@@ -85,6 +119,9 @@ use pin_project::pin_project;
 use std::future::Future;
 use std::pin::Pin;
 
+mod fan_out;
+pub use fan_out::{PartitionError, fan_out};
+
 #[cfg_attr(not(feature = "_internal-semver"), doc(hidden))]
 pub mod internal {
     //! This module contains implementation details. It is not part of the
@@ -151,6 +188,36 @@ where
     /// Returns the next mutation of the wrapped stream.
     fn next(&mut self) -> impl Future<Output = Option<Result<PageType, Error>>> + Send;
 
+    /// Returns the token to resume the listing after the last page returned
+    /// by [next()][Paginator::next].
+    ///
+    /// Before any page is returned this is the initial page token, which is
+    /// empty when listing from the start. After an error this is the token
+    /// for the page that failed, so the listing can be resumed from the same
+    /// position. Returns `None` once all the pages have been returned.
+    ///
+    /// Applications can save this token to checkpoint long listings. To
+    /// resume, set the page token in the request before calling `by_page()`.
+    fn next_page_token(&self) -> Option<String>;
+
+    /// Fetches up to `depth` pages ahead of the caller.
+    ///
+    /// By default, the paginator fetches each page when [next()][Paginator::next]
+    /// is called. With a prefetch depth, the paginator fetches the following
+    /// pages in a background task, while the application processes the
+    /// current page. At most `depth` pages are buffered. A `depth` of zero
+    /// disables prefetching.
+    ///
+    /// The background task stops when the paginator is dropped.
+    ///
+    /// # Panics
+    ///
+    /// If called outside the context of a Tokio runtime.
+    fn prefetch(self, depth: usize) -> impl Paginator<PageType, Error>
+    where
+        PageType: Send + 'static,
+        Error: Send + 'static;
+
     #[cfg(feature = "unstable-stream")]
     #[cfg_attr(docsrs, doc(cfg(feature = "unstable-stream")))]
     /// Convert the paginator to a [Stream].
@@ -161,6 +228,7 @@ where
 struct PaginatorImpl<T, E> {
     #[pin]
     stream: Pin<Box<dyn Stream<Item = Result<T, E>> + Send>>,
+    resume_token: Option<String>,
 }
 
 type ControlFlow = std::ops::ControlFlow<(), String>;
@@ -178,6 +246,7 @@ where
     where
         F: Future<Output = Result<T, E>> + Send + 'static,
     {
+        let resume_token = Some(seed_token.clone());
         let stream = unfold(ControlFlow::Continue(seed_token), move |state| {
             let execute = execute.clone();
             async move {
@@ -201,6 +270,7 @@ where
         });
         Self {
             stream: Box::pin(stream),
+            resume_token,
         }
     }
 }
@@ -216,7 +286,46 @@ where
 
     /// Returns the next mutation of the wrapped stream.
     async fn next(&mut self) -> Option<Result<T, E>> {
-        self.stream.next().await
+        let next = self.stream.next().await;
+        // Errors keep the token for the failed page, the application may
+        // resume from it. The stream ends after an error, or after the last
+        // page, where the token is already `None`.
+        if let Some(Ok(page)) = &next {
+            let token = page.next_page_token();
+            self.resume_token = (!token.is_empty()).then_some(token);
+        }
+        next
+    }
+
+    fn next_page_token(&self) -> Option<String> {
+        self.resume_token.clone()
+    }
+
+    fn prefetch(mut self, depth: usize) -> impl Paginator<T, E>
+    where
+        T: Send + 'static,
+        E: Send + 'static,
+    {
+        if depth == 0 {
+            return self;
+        }
+        let (tx, rx) = tokio::sync::mpsc::channel(depth);
+        let mut stream = self.stream;
+        tokio::spawn(async move {
+            // Reserve a slot before fetching, this bounds the number of pages
+            // fetched ahead of the caller, including any page in flight. The
+            // reservation fails once the paginator is dropped.
+            while let Ok(permit) = tx.reserve().await {
+                match stream.next().await {
+                    Some(page) => permit.send(page),
+                    None => break,
+                }
+            }
+        });
+        self.stream = Box::pin(unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|page| (page, rx))
+        }));
+        self
     }
 
     #[cfg(feature = "unstable-stream")]
@@ -529,6 +638,156 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    fn test_pages(count: usize) -> Vec<TestResponse> {
+        (0..count)
+            .map(|i| TestResponse {
+                items: vec![PageItem {
+                    name: format!("item{i}"),
+                }],
+                next_page_token: if i + 1 == count {
+                    String::new()
+                } else {
+                    format!("token{}", i + 1)
+                },
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn next_page_token() {
+        let state = Arc::new(Mutex::new(VecDeque::from(test_pages(2))));
+        let execute = move |_token: String| {
+            let resp = state.lock().unwrap().pop_front().unwrap();
+            async move { Ok::<_, Box<dyn std::error::Error>>(resp) }
+        };
+
+        let mut paginator = new_paginator("token0".to_string(), execute);
+        assert_eq!(paginator.next_page_token().as_deref(), Some("token0"));
+        let page = paginator.next().await.unwrap().unwrap();
+        assert_eq!(page.items[0].name, "item0");
+        assert_eq!(paginator.next_page_token().as_deref(), Some("token1"));
+        let page = paginator.next().await.unwrap().unwrap();
+        assert_eq!(page.items[0].name, "item1");
+        assert_eq!(paginator.next_page_token(), None);
+        assert!(paginator.next().await.is_none());
+        assert_eq!(paginator.next_page_token(), None);
+    }
+
+    #[tokio::test]
+    async fn next_page_token_after_error() {
+        let count = Arc::new(Mutex::new(0));
+        let execute = move |token: String| {
+            let mut count = count.lock().unwrap();
+            *count += 1;
+            let result = match *count {
+                1 => Ok(TestResponse {
+                    items: Vec::new(),
+                    next_page_token: "token1".to_string(),
+                }),
+                _ => Err::<TestResponse, Box<dyn std::error::Error + Send + Sync>>(
+                    format!("cannot fetch {token}").into(),
+                ),
+            };
+            async move { result }
+        };
+
+        let mut paginator = new_paginator(String::new(), execute);
+        assert_eq!(paginator.next_page_token().as_deref(), Some(""));
+        assert!(matches!(paginator.next().await, Some(Ok(_))));
+        assert!(matches!(paginator.next().await, Some(Err(_))));
+        // The token for the failed page is preserved.
+        assert_eq!(paginator.next_page_token().as_deref(), Some("token1"));
+        // The paginator stops after an error, and still preserves the token.
+        assert!(paginator.next().await.is_none());
+        assert_eq!(paginator.next_page_token().as_deref(), Some("token1"));
+    }
+
+    #[tokio::test]
+    async fn resume_from_token() {
+        let execute = move |token: String| {
+            let page = match token.as_str() {
+                "token1" => TestResponse {
+                    items: vec![PageItem {
+                        name: "item1".to_string(),
+                    }],
+                    next_page_token: String::new(),
+                },
+                t => panic!("unexpected token {t}"),
+            };
+            async move { Ok::<_, Box<dyn std::error::Error>>(page) }
+        };
+        let mut items = new_paginator("token1".to_string(), execute).items();
+        let item = items.next().await.unwrap().unwrap();
+        assert_eq!(item.name, "item1");
+        assert!(items.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn prefetch() {
+        let fetched = Arc::new(Mutex::new(Vec::new()));
+        let pages = Arc::new(Mutex::new(VecDeque::from(test_pages(4))));
+        let execute = {
+            let fetched = fetched.clone();
+            move |token: String| {
+                fetched.lock().unwrap().push(token);
+                let resp = pages.lock().unwrap().pop_front().unwrap();
+                async move { Ok::<_, Box<dyn std::error::Error + Send + Sync>>(resp) }
+            }
+        };
+
+        let mut paginator = new_paginator(String::new(), execute).prefetch(2);
+        let page = paginator.next().await.unwrap().unwrap();
+        assert_eq!(page.items[0].name, "item0");
+        assert_eq!(paginator.next_page_token().as_deref(), Some("token1"));
+        // Give the background task a chance to fetch ahead.
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(
+            fetched.lock().unwrap().clone(),
+            vec!["", "token1", "token2"]
+        );
+
+        let mut names = vec![page.items[0].name.clone()];
+        while let Some(page) = paginator.next().await {
+            names.push(page.unwrap().items[0].name.clone());
+        }
+        assert_eq!(names, vec!["item0", "item1", "item2", "item3"]);
+        assert_eq!(paginator.next_page_token(), None);
+    }
+
+    #[tokio::test]
+    async fn prefetch_zero_is_lazy() {
+        let fetched = Arc::new(Mutex::new(0));
+        let pages = Arc::new(Mutex::new(VecDeque::from(test_pages(3))));
+        let execute = {
+            let fetched = fetched.clone();
+            move |_token: String| {
+                *fetched.lock().unwrap() += 1;
+                let resp = pages.lock().unwrap().pop_front().unwrap();
+                async move { Ok::<_, Box<dyn std::error::Error + Send + Sync>>(resp) }
+            }
+        };
+
+        let mut paginator = new_paginator(String::new(), execute).prefetch(0);
+        assert!(matches!(paginator.next().await, Some(Ok(_))));
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(*fetched.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn prefetch_error() {
+        let execute = |_| async {
+            Err::<TestResponse, Box<dyn std::error::Error + Send + Sync>>("err".into())
+        };
+        let mut paginator = new_paginator(String::new(), execute).prefetch(3);
+        let err = paginator.next().await.unwrap().err().unwrap();
+        assert_eq!(err.to_string(), "err");
+        assert!(paginator.next().await.is_none());
+    }
+
     #[cfg(feature = "unstable-stream")]
     #[tokio::test]
     async fn test_paginator_into_stream() {
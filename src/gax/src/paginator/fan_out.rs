@@ -0,0 +1,331 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{ItemPaginator, Paginator, internal, sealed};
+use futures::stream::{iter, unfold};
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+
+/// Merges the items from independent paginators into a single
+/// [ItemPaginator].
+///
+/// Listing a large collection page by page is serial: each request needs the
+/// token returned by the previous one. Some collections can be split into
+/// independent partitions, for example, listing objects with different
+/// prefixes, or the cursors returned by a partitioned query. This function
+/// lists up to `concurrency` partitions at the same time and returns their
+/// items, in no particular order, as they are received.
+///
+/// The paginators are polled only as the application consumes items, so a
+/// slow consumer slows down all the partitions. Each partition buffers at
+/// most one page. Use [Paginator::prefetch] on each partition to fetch pages
+/// ahead of the application.
+///
+/// An error in one partition is returned as a [PartitionError], which
+/// identifies the partition by its position in `partitions`. The failed
+/// partition stops, and the remaining partitions continue. A `concurrency` of
+/// zero is treated as one.
+///
+/// # Example
+/// ```no_run
+/// # use google_cloud_gax::{paginator, Result, error::Error};
+/// struct Page { items: Vec<String>, token: String }
+/// # impl paginator::internal::PageableResponse for Page {
+/// #     type PageItem = String;
+/// #     fn items(self) -> Vec<String> { self.items }
+/// #     fn next_page_token(&self) -> String { self.token.clone() }
+/// # }
+/// # async fn get_page(_: String) -> Result<Page> { panic!(); }
+/// use paginator::{ItemPaginator, Paginator, fan_out};
+/// fn list(prefix: &str) -> impl Paginator<Page, Error> + 'static {
+///     // ... details omitted ...
+///     # paginator::internal::new_paginator(String::new(), get_page)
+/// }
+/// # tokio_test::block_on(async {
+/// let partitions = ["a", "b", "c", "d"].map(list);
+/// let mut items = fan_out(partitions, 2);
+/// while let Some(item) = items.next().await {
+///     match item {
+///         Ok(item) => println!("item = {item}"),
+///         Err(e) => println!("partition {} failed: {}", e.partition(), e.error()),
+///     }
+/// }
+/// # Result::<()>::Ok(()) });
+/// ```
+pub fn fan_out<T, E, P>(
+    partitions: impl IntoIterator<Item = P>,
+    concurrency: usize,
+) -> impl ItemPaginator<T, PartitionError<E>>
+where
+    T: internal::PageableResponse + 'static,
+    T::PageItem: 'static,
+    E: Send + 'static,
+    P: Paginator<T, E> + 'static,
+{
+    FanOut::new(partitions, concurrency)
+}
+
+/// The error returned by [fan_out] when one of the partitions fails.
+#[derive(Debug)]
+pub struct PartitionError<E> {
+    partition: usize,
+    error: E,
+}
+
+impl<E> PartitionError<E> {
+    /// The position of the failed partition, in the order given to [fan_out].
+    pub fn partition(&self) -> usize {
+        self.partition
+    }
+
+    /// The error returned by the partition.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Consumes `self` and returns the error returned by the partition.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+}
+
+impl<E> std::fmt::Display for PartitionError<E>
+where
+    E: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error in partition {}: {}", self.partition, self.error)
+    }
+}
+
+impl<E> std::error::Error for PartitionError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+type ItemStream<T, E> = Pin<Box<dyn Stream<Item = Result<T, PartitionError<E>>> + Send>>;
+
+struct FanOut<T, E>
+where
+    T: internal::PageableResponse,
+{
+    stream: ItemStream<T::PageItem, E>,
+}
+
+impl<T, E> FanOut<T, E>
+where
+    T: internal::PageableResponse + 'static,
+    T::PageItem: 'static,
+    E: Send + 'static,
+{
+    fn new<P>(partitions: impl IntoIterator<Item = P>, concurrency: usize) -> Self
+    where
+        P: Paginator<T, E> + 'static,
+    {
+        let partitions = partitions
+            .into_iter()
+            .enumerate()
+            .map(|(partition, paginator)| Self::partition(partition, paginator))
+            .collect::<Vec<_>>();
+        let stream = iter(partitions).flatten_unordered(concurrency.max(1));
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+
+    fn partition<P>(partition: usize, paginator: P) -> ItemStream<T::PageItem, E>
+    where
+        P: Paginator<T, E> + 'static,
+    {
+        let items = paginator.items();
+        Box::pin(unfold(Some(items), move |state| async move {
+            let mut items = state?;
+            match items.next().await? {
+                Ok(item) => Some((Ok(item), Some(items))),
+                Err(error) => Some((Err(PartitionError { partition, error }), None)),
+            }
+        }))
+    }
+}
+
+impl<T, E> ItemPaginator<T, PartitionError<E>> for FanOut<T, E>
+where
+    T: internal::PageableResponse,
+{
+    async fn next(&mut self) -> Option<Result<T::PageItem, PartitionError<E>>> {
+        self.stream.next().await
+    }
+
+    #[cfg(feature = "unstable-stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unstable-stream")))]
+    fn into_stream(self) -> impl Stream<Item = Result<T::PageItem, PartitionError<E>>> + Unpin {
+        self.stream
+    }
+}
+
+impl<T, E> sealed::Paginator for FanOut<T, E> where T: internal::PageableResponse {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::internal::*;
+    use super::*;
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::{Arc, Mutex};
+
+    type TestError = Box<dyn std::error::Error + Send + Sync>;
+
+    #[derive(Debug)]
+    struct TestResponse {
+        items: Vec<String>,
+        next_page_token: String,
+    }
+
+    impl PageableResponse for TestResponse {
+        type PageItem = String;
+
+        fn items(self) -> Vec<String> {
+            self.items
+        }
+
+        fn next_page_token(&self) -> String {
+            self.next_page_token.clone()
+        }
+    }
+
+    type Fetches = Arc<Mutex<HashMap<usize, usize>>>;
+
+    // Returns a paginator with `pages` pages of two items each, and records
+    // the number of pages fetched. If `fail` is set, the paginator fails when
+    // fetching the page after the last one.
+    fn partition(
+        id: usize,
+        pages: usize,
+        fail: bool,
+        fetches: Fetches,
+    ) -> impl Paginator<TestResponse, TestError> + 'static {
+        let responses = (0..pages)
+            .map(|p| TestResponse {
+                items: vec![format!("{id}-{p}-a"), format!("{id}-{p}-b")],
+                next_page_token: if p + 1 == pages && !fail {
+                    String::new()
+                } else {
+                    format!("{}", p + 1)
+                },
+            })
+            .collect::<VecDeque<_>>();
+        let responses = Arc::new(Mutex::new(responses));
+        let execute = move |_token: String| {
+            *fetches.lock().unwrap().entry(id).or_default() += 1;
+            let response = responses.lock().unwrap().pop_front();
+            async move { response.ok_or_else(|| TestError::from(format!("failed {id}"))) }
+        };
+        new_paginator(String::new(), execute)
+    }
+
+    async fn collect<P>(mut items: P) -> Vec<Result<String, PartitionError<TestError>>>
+    where
+        P: ItemPaginator<TestResponse, PartitionError<TestError>>,
+    {
+        let mut got = Vec::new();
+        while let Some(item) = items.next().await {
+            got.push(item);
+        }
+        got
+    }
+
+    #[tokio::test]
+    async fn merges_all_items() {
+        let fetches = Fetches::default();
+        let partitions = (0..4).map(|id| partition(id, id + 1, false, fetches.clone()));
+        let got = collect(fan_out(partitions, 2)).await;
+        let mut got = got.into_iter().map(|r| r.unwrap()).collect::<Vec<_>>();
+        got.sort();
+
+        let mut want = (0..4)
+            .flat_map(|id| {
+                (0..=id).flat_map(move |p| [format!("{id}-{p}-a"), format!("{id}-{p}-b")])
+            })
+            .collect::<Vec<_>>();
+        want.sort();
+        assert_eq!(got, want);
+    }
+
+    #[tokio::test]
+    async fn concurrency_one_is_sequential() {
+        let fetches = Fetches::default();
+        let partitions = (0..3).map(|id| partition(id, 2, false, fetches.clone()));
+        let got = collect(fan_out(partitions, 1))
+            .await
+            .into_iter()
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        let want = (0..3)
+            .flat_map(|id| (0..2).flat_map(move |p| [format!("{id}-{p}-a"), format!("{id}-{p}-b")]))
+            .collect::<Vec<_>>();
+        assert_eq!(got, want);
+    }
+
+    #[tokio::test]
+    async fn zero_concurrency() {
+        let fetches = Fetches::default();
+        let partitions = (0..2).map(|id| partition(id, 1, false, fetches.clone()));
+        let got = collect(fan_out(partitions, 0)).await;
+        assert_eq!(got.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn bounded_and_lazy() {
+        let fetches = Fetches::default();
+        let partitions = (0..5)
+            .map(|id| partition(id, 3, false, fetches.clone()))
+            .collect::<Vec<_>>();
+        let mut items = fan_out(partitions, 2);
+        assert!(fetches.lock().unwrap().is_empty());
+
+        let item = items.next().await;
+        assert!(matches!(item, Some(Ok(_))), "{item:?}");
+        let fetches = fetches.lock().unwrap().clone();
+        assert!(fetches.len() <= 2, "{fetches:?}");
+        assert!(fetches.values().all(|v| *v == 1), "{fetches:?}");
+    }
+
+    #[tokio::test]
+    async fn partition_error() {
+        let fetches = Fetches::default();
+        let partitions = (0..3).map(|id| {
+            if id == 1 {
+                partition(id, 1, true, fetches.clone())
+            } else {
+                partition(id, 2, false, fetches.clone())
+            }
+        });
+        let got = collect(fan_out(partitions, 3)).await;
+
+        let (errors, items): (Vec<_>, Vec<_>) = got.into_iter().partition(|r| r.is_err());
+        let errors = errors
+            .into_iter()
+            .map(|r| r.unwrap_err())
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(errors[0].partition(), 1);
+        assert_eq!(errors[0].error().to_string(), "failed 1");
+        assert_eq!(errors[0].to_string(), "error in partition 1: failed 1");
+        // The other partitions are not affected.
+        assert_eq!(items.len(), 2 + 4 + 4, "{items:?}");
+    }
+}
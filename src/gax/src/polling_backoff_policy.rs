@@ -86,6 +86,12 @@ impl std::convert::From<Arc<dyn PollingBackoffPolicy>> for PollingBackoffPolicyA
     }
 }
 
+impl From<PollingBackoffPolicyArg> for Arc<dyn PollingBackoffPolicy> {
+    fn from(value: PollingBackoffPolicyArg) -> Arc<dyn PollingBackoffPolicy> {
+        value.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,6 +105,7 @@ mod tests {
 
         let policy: Arc<dyn PollingBackoffPolicy> =
             Arc::new(ExponentialBackoffBuilder::default().clamp());
-        let _ = PollingBackoffPolicyArg::from(policy);
+        let arg = PollingBackoffPolicyArg::from(policy);
+        let _: Arc<dyn PollingBackoffPolicy> = arg.into();
     }
 }
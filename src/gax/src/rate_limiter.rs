@@ -0,0 +1,650 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines client-side rate limits and concurrency limits.
+//!
+//! Services enforce quotas on the number of requests, and sometimes on the
+//! number of bytes, that a project may send. Requests over quota fail with
+//! `RESOURCE_EXHAUSTED`. Applications running batch jobs may prefer to stay
+//! within the quota, by waiting before sending a request, instead of handling
+//! these errors.
+//!
+//! A [RateLimiter] limits the number of requests per second, and the number of
+//! request payload bytes per second. A [ConcurrencyLimiter] limits the number
+//! of requests in flight. The client waits until the request is within the
+//! limits before sending each attempt, including any retry attempts.
+//!
+//! To configure the limits for a client, use
+//! [ClientBuilder::with_rate_limiter] and
+//! [ClientBuilder::with_concurrency_limiter]. To configure the limits for a
+//! specific request, use [RequestOptionsBuilder::with_rate_limiter] and
+//! [RequestOptionsBuilder::with_concurrency_limiter].
+//!
+//! The limiters are cheap to clone, and all the clones share the same limits.
+//! Use the same limiter with multiple clients to keep the combined traffic
+//! within a quota. The limits can be changed while the limiter is in use.
+//!
+//! [ClientBuilder::with_rate_limiter]: crate::client_builder::ClientBuilder::with_rate_limiter
+//! [ClientBuilder::with_concurrency_limiter]: crate::client_builder::ClientBuilder::with_concurrency_limiter
+//! [RequestOptionsBuilder::with_rate_limiter]: crate::options::RequestOptionsBuilder::with_rate_limiter
+//! [RequestOptionsBuilder::with_concurrency_limiter]: crate::options::RequestOptionsBuilder::with_concurrency_limiter
+//!
+//! # Example
+//!
+//! Send at most 100 requests and 10 MB per second:
+//! ```
+//! # use google_cloud_gax::rate_limiter::*;
+//! let limiter = RateLimiter::new()
+//!     .with_requests_per_second(100.0)?
+//!     .with_bytes_per_second(10_000_000.0)?;
+//! # Ok::<(), Error>(())
+//! ```
+//!
+//! Keep at most 8 requests in flight:
+//! ```
+//! # use google_cloud_gax::rate_limiter::*;
+//! let limiter = ConcurrencyLimiter::new(8)?;
+//! # Ok::<(), Error>(())
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The error type for rate and concurrency limiter configuration.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("the rate ({0}) must be greater than 0.0")]
+    InvalidRate(f64),
+    #[error("the maximum concurrency must be greater than 0")]
+    InvalidConcurrency,
+}
+
+/// Limits the rate of requests and request payload bytes.
+///
+/// The limiter implements a [token bucket] for the number of requests and
+/// another for the number of bytes. Each bucket holds up to one second worth
+/// of tokens, so short bursts of requests are sent without delay as long as
+/// the average rate is within the limits. A request larger than one second
+/// worth of bytes is sent once the bucket is full, and the following requests
+/// wait until the bucket refills.
+///
+/// A new limiter has no limits. Use [with_requests_per_second] and
+/// [with_bytes_per_second] to configure the limits, or
+/// [set_requests_per_second] and [set_bytes_per_second] to change them while
+/// the limiter is in use.
+///
+/// # Example
+/// ```
+/// # use google_cloud_gax::rate_limiter::*;
+/// let limiter = RateLimiter::new().with_requests_per_second(100.0)?;
+/// // Later, for example after a quota increase:
+/// limiter.set_requests_per_second(200.0)?;
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// [token bucket]: https://en.wikipedia.org/wiki/Token_bucket
+/// [with_requests_per_second]: Self::with_requests_per_second
+/// [with_bytes_per_second]: Self::with_bytes_per_second
+/// [set_requests_per_second]: Self::set_requests_per_second
+/// [set_bytes_per_second]: Self::set_bytes_per_second
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    inner: Arc<RateLimiterShared>,
+}
+
+#[derive(Debug, Default)]
+struct RateLimiterShared {
+    state: Mutex<RateLimiterState>,
+    // Wakes up the requests waiting for tokens when the limits change.
+    changed: tokio::sync::Notify,
+}
+
+#[derive(Debug, Default)]
+struct RateLimiterState {
+    requests: TokenBucket,
+    bytes: TokenBucket,
+}
+
+impl RateLimiter {
+    /// Creates a new limiter, without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of requests per second.
+    ///
+    /// Use [f64::INFINITY] to remove the limit.
+    pub fn with_requests_per_second(self, v: f64) -> Result<Self, Error> {
+        self.set_requests_per_second(v)?;
+        Ok(self)
+    }
+
+    /// Limits the number of request payload bytes per second.
+    ///
+    /// Use [f64::INFINITY] to remove the limit.
+    pub fn with_bytes_per_second(self, v: f64) -> Result<Self, Error> {
+        self.set_bytes_per_second(v)?;
+        Ok(self)
+    }
+
+    /// Changes the limit on the number of requests per second.
+    ///
+    /// The new limit applies to all the clones of this limiter, including
+    /// any requests already waiting.
+    pub fn set_requests_per_second(&self, v: f64) -> Result<(), Error> {
+        let rate = validate_rate(v)?;
+        self.lock().requests.set_rate(rate, Instant::now());
+        self.inner.changed.notify_waiters();
+        Ok(())
+    }
+
+    /// Changes the limit on the number of request payload bytes per second.
+    ///
+    /// The new limit applies to all the clones of this limiter, including
+    /// any requests already waiting.
+    pub fn set_bytes_per_second(&self, v: f64) -> Result<(), Error> {
+        let rate = validate_rate(v)?;
+        self.lock().bytes.set_rate(rate, Instant::now());
+        self.inner.changed.notify_waiters();
+        Ok(())
+    }
+
+    /// Returns the current limit on the number of requests per second.
+    pub fn requests_per_second(&self) -> f64 {
+        self.lock().requests.rate
+    }
+
+    /// Returns the current limit on the number of request payload bytes per
+    /// second.
+    pub fn bytes_per_second(&self) -> f64 {
+        self.lock().bytes.rate
+    }
+
+    /// Waits until a request with `bytes` of payload is within the limits.
+    ///
+    /// Not part of the public API, subject to change without notice.
+    #[cfg_attr(not(feature = "_internal-semver"), doc(hidden))]
+    pub async fn acquire(&self, bytes: usize) {
+        loop {
+            // Register for notifications before checking the limits, so a
+            // change between the check and the wait is not missed.
+            let changed = self.inner.changed.notified();
+            let delay = self.reserve(bytes);
+            if delay.is_zero() {
+                return;
+            }
+            // Recompute the delay when the limits change or the wait ends.
+            let _ = tokio::time::timeout(delay, changed).await;
+        }
+    }
+
+    // Consumes the tokens for a request, if available. Otherwise returns how
+    // long to wait before trying again, at most `MAX_WAIT`.
+    fn reserve(&self, bytes: usize) -> Duration {
+        let now = Instant::now();
+        let mut state = self.lock();
+        let delay = std::cmp::max(
+            state.requests.delay(1.0, now),
+            state.bytes.delay(bytes as f64, now),
+        );
+        if delay.is_zero() {
+            state.requests.consume(1.0);
+            state.bytes.consume(bytes as f64);
+        }
+        delay.min(MAX_WAIT)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RateLimiterState> {
+        self.inner
+            .state
+            .lock()
+            .expect("rate limiter lock is poisoned")
+    }
+}
+
+// Waiting requests recompute their delay at least this often. Changes to the
+// limits wake them up earlier, this only bounds the wait for very small rates.
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+fn validate_rate(v: f64) -> Result<f64, Error> {
+    // This also rejects NaN.
+    if v > 0.0 {
+        return Ok(v);
+    }
+    Err(Error::InvalidRate(v))
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Default for TokenBucket {
+    fn default() -> Self {
+        Self {
+            rate: f64::INFINITY,
+            tokens: 0.0,
+            updated: Instant::now(),
+        }
+    }
+}
+
+impl TokenBucket {
+    // The bucket holds one second worth of tokens, and at least enough for
+    // one request.
+    fn capacity(&self) -> f64 {
+        self.rate.max(1.0)
+    }
+
+    fn set_rate(&mut self, rate: f64, now: Instant) {
+        let unlimited = self.rate.is_infinite();
+        self.refill(now);
+        self.rate = rate;
+        self.tokens = if unlimited {
+            self.capacity()
+        } else {
+            self.tokens.min(self.capacity())
+        };
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated);
+        self.updated = now;
+        if self.rate.is_infinite() {
+            return;
+        }
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity());
+    }
+
+    // Returns how long to wait until `cost` tokens are available. Requests
+    // larger than the bucket only wait until the bucket is full.
+    fn delay(&mut self, cost: f64, now: Instant) -> Duration {
+        self.refill(now);
+        if self.rate.is_infinite() {
+            return Duration::ZERO;
+        }
+        let needed = cost.min(self.capacity()) - self.tokens;
+        if needed <= 0.0 {
+            return Duration::ZERO;
+        }
+        // Very small rates may produce delays that do not fit in a `Duration`.
+        Duration::try_from_secs_f64(needed / self.rate)
+            .unwrap_or(Duration::MAX)
+            .max(Duration::from_micros(1))
+    }
+
+    fn consume(&mut self, cost: f64) {
+        if self.rate.is_infinite() {
+            return;
+        }
+        self.tokens -= cost;
+    }
+}
+
+/// Limits the number of requests in flight.
+///
+/// Each request attempt holds one of the available slots until it completes,
+/// further requests wait until a slot is available.
+///
+/// # Example
+/// ```
+/// # use google_cloud_gax::rate_limiter::*;
+/// let limiter = ConcurrencyLimiter::new(8)?;
+/// // Later, for example to speed up a batch job:
+/// limiter.set_max_concurrency(16)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimiter {
+    inner: Arc<ConcurrencyState>,
+}
+
+#[derive(Debug)]
+struct ConcurrencyState {
+    semaphore: tokio::sync::Semaphore,
+    limits: Mutex<ConcurrencyLimits>,
+}
+
+#[derive(Debug)]
+struct ConcurrencyLimits {
+    max: usize,
+    // Permits in use that must be discarded, instead of returned to the
+    // semaphore, after the maximum concurrency is reduced.
+    excess: usize,
+}
+
+impl ConcurrencyLimiter {
+    /// Creates a limiter allowing at most `max` requests in flight.
+    pub fn new(max: usize) -> Result<Self, Error> {
+        if max == 0 {
+            return Err(Error::InvalidConcurrency);
+        }
+        let state = ConcurrencyState {
+            semaphore: tokio::sync::Semaphore::new(max),
+            limits: Mutex::new(ConcurrencyLimits { max, excess: 0 }),
+        };
+        Ok(Self {
+            inner: Arc::new(state),
+        })
+    }
+
+    /// Changes the maximum number of requests in flight.
+    ///
+    /// The new limit applies to all the clones of this limiter. If the limit
+    /// is reduced, requests already in flight complete normally, and new
+    /// requests wait until the number of requests in flight is below the new
+    /// limit.
+    pub fn set_max_concurrency(&self, max: usize) -> Result<(), Error> {
+        if max == 0 {
+            return Err(Error::InvalidConcurrency);
+        }
+        let mut limits = self.inner.lock();
+        if max > limits.max {
+            let increase = max - limits.max;
+            let repaid = increase.min(limits.excess);
+            limits.excess -= repaid;
+            self.inner.semaphore.add_permits(increase - repaid);
+        } else {
+            let decrease = limits.max - max;
+            let forgotten = self.inner.semaphore.forget_permits(decrease);
+            limits.excess += decrease - forgotten;
+        }
+        limits.max = max;
+        Ok(())
+    }
+
+    /// Returns the maximum number of requests in flight.
+    pub fn max_concurrency(&self) -> usize {
+        self.inner.lock().max
+    }
+
+    /// Waits until a slot is available and returns a permit holding it.
+    ///
+    /// The slot is released when the permit is dropped.
+    ///
+    /// Not part of the public API, subject to change without notice.
+    #[cfg_attr(not(feature = "_internal-semver"), doc(hidden))]
+    pub async fn acquire(&self) -> ConcurrencyPermit {
+        self.inner
+            .semaphore
+            .acquire()
+            .await
+            .expect("the concurrency limiter semaphore is never closed")
+            .forget();
+        ConcurrencyPermit {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl ConcurrencyState {
+    fn lock(&self) -> std::sync::MutexGuard<'_, ConcurrencyLimits> {
+        self.limits
+            .lock()
+            .expect("concurrency limiter lock is poisoned")
+    }
+}
+
+/// Holds a slot in a [ConcurrencyLimiter] until dropped.
+///
+/// Not part of the public API, subject to change without notice.
+#[cfg_attr(not(feature = "_internal-semver"), doc(hidden))]
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    inner: Arc<ConcurrencyState>,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        let mut limits = self.inner.lock();
+        if limits.excess > 0 {
+            limits.excess -= 1;
+            return;
+        }
+        self.inner.semaphore.add_permits(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0.0)]
+    #[test_case(-1.0)]
+    #[test_case(f64::NAN)]
+    fn invalid_rate(v: f64) {
+        let limiter = RateLimiter::new();
+        let err = limiter.clone().with_requests_per_second(v).unwrap_err();
+        assert!(matches!(err, Error::InvalidRate(_)), "{err:?}");
+        let err = limiter.clone().with_bytes_per_second(v).unwrap_err();
+        assert!(matches!(err, Error::InvalidRate(_)), "{err:?}");
+        assert!(limiter.set_requests_per_second(v).is_err());
+        assert!(limiter.set_bytes_per_second(v).is_err());
+        assert!(limiter.requests_per_second().is_infinite());
+        assert!(limiter.bytes_per_second().is_infinite());
+    }
+
+    #[test_case(1e-20)]
+    #[test_case(f64::MIN_POSITIVE)]
+    fn tiny_rate(v: f64) -> anyhow::Result<()> {
+        let limiter = RateLimiter::new().with_requests_per_second(v)?;
+        // The initial burst allows one request.
+        assert_eq!(limiter.reserve(0), Duration::ZERO);
+        assert_eq!(limiter.reserve(0), MAX_WAIT);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unlimited() {
+        let limiter = RateLimiter::new();
+        let start = Instant::now();
+        for _ in 0..1000 {
+            limiter.acquire(1_000_000).await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn requests_per_second() -> anyhow::Result<()> {
+        let limiter = RateLimiter::new().with_requests_per_second(10.0)?;
+        assert_eq!(limiter.requests_per_second(), 10.0);
+        let start = Instant::now();
+        // The first 10 requests use the initial burst.
+        for _ in 0..10 {
+            limiter.acquire(0).await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        for _ in 0..10 {
+            limiter.acquire(0).await;
+        }
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(990) && elapsed <= Duration::from_millis(1010),
+            "{elapsed:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bytes_per_second() -> anyhow::Result<()> {
+        let limiter = RateLimiter::new().with_bytes_per_second(1000.0)?;
+        assert_eq!(limiter.bytes_per_second(), 1000.0);
+        let start = Instant::now();
+        limiter.acquire(1000).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        limiter.acquire(500).await;
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(495) && elapsed <= Duration::from_millis(505),
+            "{elapsed:?}"
+        );
+
+        // Requests larger than the bucket wait until the bucket is full, and
+        // the next request waits until the bucket refills.
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let start = Instant::now();
+        limiter.acquire(3000).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        limiter.acquire(1000).await;
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(2995) && elapsed <= Duration::from_millis(3005),
+            "{elapsed:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn change_rate() -> anyhow::Result<()> {
+        let limiter = RateLimiter::new().with_requests_per_second(1.0)?;
+        let shared = limiter.clone();
+        let start = Instant::now();
+        limiter.acquire(0).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        shared.set_requests_per_second(100.0)?;
+        assert_eq!(limiter.requests_per_second(), 100.0);
+        limiter.acquire(0).await;
+        let elapsed = start.elapsed();
+        assert!(elapsed <= Duration::from_millis(20), "{elapsed:?}");
+
+        // Removing the limit stops any waits.
+        shared.set_requests_per_second(f64::INFINITY)?;
+        for _ in 0..1000 {
+            limiter.acquire(0).await;
+        }
+        assert_eq!(start.elapsed(), elapsed);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn change_rate_wakes_waiters() -> anyhow::Result<()> {
+        let limiter = RateLimiter::new().with_requests_per_second(0.001)?;
+        limiter.acquire(0).await;
+
+        let start = Instant::now();
+        let waiting = limiter.clone();
+        let task = tokio::spawn(async move { waiting.acquire(0).await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+
+        limiter.set_requests_per_second(100.0)?;
+        task.await?;
+        let elapsed = start.elapsed();
+        assert!(elapsed <= Duration::from_millis(120), "{elapsed:?}");
+
+        // Removing the limit also wakes up the waiters.
+        limiter.set_requests_per_second(0.001)?;
+        limiter.acquire(0).await;
+        let start = Instant::now();
+        let waiting = limiter.clone();
+        let task = tokio::spawn(async move { waiting.acquire(0).await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+        limiter.set_requests_per_second(f64::INFINITY)?;
+        task.await?;
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_concurrency() -> anyhow::Result<()> {
+        let err = ConcurrencyLimiter::new(0).unwrap_err();
+        assert!(matches!(err, Error::InvalidConcurrency), "{err:?}");
+        let limiter = ConcurrencyLimiter::new(1)?;
+        let err = limiter.set_max_concurrency(0).unwrap_err();
+        assert!(matches!(err, Error::InvalidConcurrency), "{err:?}");
+        assert_eq!(limiter.max_concurrency(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn concurrency() -> anyhow::Result<()> {
+        let limiter = ConcurrencyLimiter::new(2)?;
+        let p1 = limiter.acquire().await;
+        let _p2 = limiter.acquire().await;
+        assert!(try_acquire(&limiter).await.is_none());
+        drop(p1);
+        assert!(try_acquire(&limiter).await.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn increase_concurrency() -> anyhow::Result<()> {
+        let limiter = ConcurrencyLimiter::new(1)?;
+        let _p1 = limiter.acquire().await;
+        assert!(try_acquire(&limiter).await.is_none());
+        limiter.clone().set_max_concurrency(2)?;
+        assert_eq!(limiter.max_concurrency(), 2);
+        let _p2 = try_acquire(&limiter).await.expect("a permit");
+        assert!(try_acquire(&limiter).await.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn decrease_concurrency() -> anyhow::Result<()> {
+        let limiter = ConcurrencyLimiter::new(3)?;
+        let p1 = limiter.acquire().await;
+        let p2 = limiter.acquire().await;
+        limiter.set_max_concurrency(1)?;
+        assert_eq!(limiter.max_concurrency(), 1);
+        // The available permit is discarded immediately.
+        assert!(try_acquire(&limiter).await.is_none());
+        // One of the permits in flight is discarded when released.
+        drop(p1);
+        assert!(try_acquire(&limiter).await.is_none());
+        drop(p2);
+        let p3 = try_acquire(&limiter).await.expect("a permit");
+        assert!(try_acquire(&limiter).await.is_none());
+        drop(p3);
+
+        // Increasing the limit again restores the permits.
+        let p1 = limiter.acquire().await;
+        limiter.set_max_concurrency(3)?;
+        let _p2 = try_acquire(&limiter).await.expect("a permit");
+        let _p3 = try_acquire(&limiter).await.expect("a permit");
+        assert!(try_acquire(&limiter).await.is_none());
+        drop(p1);
+        assert!(try_acquire(&limiter).await.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn decrease_with_excess_then_increase() -> anyhow::Result<()> {
+        let limiter = ConcurrencyLimiter::new(2)?;
+        let p1 = limiter.acquire().await;
+        let p2 = limiter.acquire().await;
+        limiter.set_max_concurrency(1)?;
+        // Both permits are in flight, one becomes excess. Increasing the limit
+        // before it is released cancels the excess.
+        limiter.set_max_concurrency(2)?;
+        drop(p1);
+        drop(p2);
+        let _p1 = try_acquire(&limiter).await.expect("a permit");
+        let _p2 = try_acquire(&limiter).await.expect("a permit");
+        assert!(try_acquire(&limiter).await.is_none());
+        Ok(())
+    }
+
+    async fn try_acquire(limiter: &ConcurrencyLimiter) -> Option<ConcurrencyPermit> {
+        tokio::time::timeout(Duration::from_millis(10), limiter.acquire())
+            .await
+            .ok()
+    }
+}
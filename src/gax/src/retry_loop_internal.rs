@@ -17,11 +17,13 @@ use crate::throttle_result::ThrottleResult;
 use super::Result;
 use super::backoff_policy::BackoffPolicy;
 use super::error::Error;
+use super::polling_backoff_policy::PollingBackoffPolicy;
+use super::polling_error_policy::PollingErrorPolicy;
 use super::retry_policy::RetryPolicy;
 use super::retry_result::RetryResult;
 use super::retry_throttler::RetryThrottler;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 enum RetryLoopAttempt {
     // The first attempt
@@ -48,24 +50,63 @@ impl RetryLoopAttempt {
 /// In between calls the function waits the amount of time prescribed by the
 /// backoff policy, using `sleep` to implement any sleep.
 pub async fn retry_loop<F, S, Response>(
+    inner: F,
+    sleep: S,
+    idempotent: bool,
+    retry_throttler: Arc<Mutex<dyn RetryThrottler>>,
+    retry_policy: Arc<dyn RetryPolicy>,
+    backoff_policy: Arc<dyn BackoffPolicy>,
+) -> Result<Response>
+where
+    F: AsyncFnMut(Option<Duration>) -> Result<Response> + Send,
+    S: AsyncFn(Duration) -> () + Send,
+{
+    retry_loop_with_deadline(
+        inner,
+        sleep,
+        idempotent,
+        retry_throttler,
+        retry_policy,
+        backoff_policy,
+        None,
+    )
+    .await
+}
+
+/// Runs the retry loop for a given function, stopping at `deadline`.
+///
+/// Like [retry_loop], but the loop also stops, with an error where
+/// [Error::is_deadline_exceeded] is `true`, if the deadline expires before
+/// the request succeeds. The time remaining until the deadline caps the
+/// timeout passed to the inner function.
+pub async fn retry_loop_with_deadline<F, S, Response>(
     mut inner: F,
     sleep: S,
     idempotent: bool,
     retry_throttler: Arc<Mutex<dyn RetryThrottler>>,
     retry_policy: Arc<dyn RetryPolicy>,
     backoff_policy: Arc<dyn BackoffPolicy>,
+    deadline: Option<Instant>,
 ) -> Result<Response>
 where
     F: AsyncFnMut(Option<Duration>) -> Result<Response> + Send,
     S: AsyncFn(Duration) -> () + Send,
 {
     let loop_start = tokio::time::Instant::now().into_std();
+    if deadline.is_some_and(|d| d <= loop_start) {
+        return Err(Error::deadline_exceeded(
+            "the deadline expired before the first attempt",
+        ));
+    }
     let mut attempt = RetryLoopAttempt::Initial;
     loop {
         let mut attempt_count = attempt.count();
         let remaining_time = retry_policy.remaining_time(loop_start, attempt_count);
 
         if let RetryLoopAttempt::Retry(attempt_count, delay, prev_error) = attempt {
+            if deadline.is_some_and(|d| time_until(d) <= delay) {
+                return Err(Error::deadline_exceeded(prev_error));
+            }
             if remaining_time.is_some_and(|remaining| remaining < delay) {
                 return Err(Error::exhausted(prev_error));
             }
@@ -89,6 +130,10 @@ where
             }
         }
         attempt_count += 1;
+        let remaining_time = match (remaining_time, deadline.map(time_until)) {
+            (Some(r), Some(d)) => Some(r.min(d)),
+            (r, d) => r.or(d),
+        };
         match inner(remaining_time).await {
             Ok(r) => {
                 retry_throttler
@@ -105,7 +150,9 @@ where
                     .expect("retry throttler lock is poisoned")
                     .on_retry_failure(&flow);
                 match flow {
-                    RetryResult::Permanent(e) | RetryResult::Exhausted(e) => return Err(e),
+                    RetryResult::Permanent(e) | RetryResult::Exhausted(e) => {
+                        return Err(deadline_error(deadline, e));
+                    }
                     RetryResult::Continue(e) => {
                         attempt = RetryLoopAttempt::Retry(attempt_count, delay, e);
                         continue;
@@ -116,6 +163,102 @@ where
     }
 }
 
+/// Returns the time until `deadline`, or zero if it has expired.
+///
+/// Uses the tokio clock, so the loops can be tested with paused time.
+fn time_until(deadline: Instant) -> Duration {
+    deadline.saturating_duration_since(tokio::time::Instant::now().into_std())
+}
+
+/// Classifies an error caused by the deadline expiring during an attempt.
+fn deadline_error(deadline: Option<Instant>, error: Error) -> Error {
+    let expired = deadline.is_some_and(|d| time_until(d).is_zero());
+    let timed_out = error.is_timeout()
+        || error
+            .status()
+            .is_some_and(|s| s.code == crate::error::rpc::Code::DeadlineExceeded);
+    if expired && timed_out {
+        return Error::deadline_exceeded(error);
+    }
+    error
+}
+
+/// Limits a polling error policy to the request deadline.
+///
+/// Once the deadline expires the polling loop stops with an error where
+/// [Error::is_deadline_exceeded] is `true`. Returns `policy` unchanged if
+/// there is no deadline.
+pub fn polling_error_policy_with_deadline(
+    policy: Arc<dyn PollingErrorPolicy>,
+    deadline: Option<Instant>,
+) -> Arc<dyn PollingErrorPolicy> {
+    match deadline {
+        None => policy,
+        Some(deadline) => Arc::new(PollingDeadline {
+            inner: policy,
+            deadline,
+        }),
+    }
+}
+
+/// Limits a polling backoff policy to the request deadline.
+///
+/// The polling loop never waits past the deadline. Returns `policy` unchanged
+/// if there is no deadline.
+pub fn polling_backoff_policy_with_deadline(
+    policy: Arc<dyn PollingBackoffPolicy>,
+    deadline: Option<Instant>,
+) -> Arc<dyn PollingBackoffPolicy> {
+    match deadline {
+        None => policy,
+        Some(deadline) => Arc::new(PollingDeadline {
+            inner: policy,
+            deadline,
+        }),
+    }
+}
+
+#[derive(Debug)]
+struct PollingDeadline<P: ?Sized> {
+    inner: Arc<P>,
+    deadline: Instant,
+}
+
+impl PollingErrorPolicy for PollingDeadline<dyn PollingErrorPolicy> {
+    fn on_error(&self, loop_start: Instant, attempt_count: u32, error: Error) -> RetryResult {
+        match self.inner.on_error(loop_start, attempt_count, error) {
+            RetryResult::Continue(e) if time_until(self.deadline).is_zero() => {
+                RetryResult::Exhausted(Error::deadline_exceeded(e))
+            }
+            r => r,
+        }
+    }
+
+    fn on_in_progress(
+        &self,
+        loop_start: Instant,
+        attempt_count: u32,
+        operation_name: &str,
+    ) -> Result<()> {
+        self.inner
+            .on_in_progress(loop_start, attempt_count, operation_name)?;
+        if time_until(self.deadline).is_zero() {
+            return Err(Error::deadline_exceeded(format!(
+                "polling loop for {operation_name} stopped, the operation is still in progress"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl PollingBackoffPolicy for PollingDeadline<dyn PollingBackoffPolicy> {
+    fn wait_period(&self, loop_start: Instant, attempt_count: u32) -> Duration {
+        self.inner
+            .wait_period(loop_start, attempt_count)
+            .min(time_until(self.deadline))
+    }
+}
+
 /// A helper to compute the time remaining in a retry loop, given the attempt
 /// timeout and the overall timeout.
 pub fn effective_timeout(
@@ -860,6 +1003,249 @@ mod tests {
         Ok(())
     }
 
+    fn tokio_now() -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    #[tokio::test]
+    async fn deadline_expired_before_first_attempt() -> anyhow::Result<()> {
+        let call = MockCall::new();
+        let inner = async move |d| call.call(d);
+        let sleep = MockSleep::new();
+        let backoff = async move |d| sleep.sleep(d).await;
+        let response = retry_loop_with_deadline(
+            inner,
+            backoff,
+            true,
+            to_retry_throttler(MockRetryThrottler::new()),
+            to_retry_policy(MockRetryPolicy::new()),
+            to_backoff_policy(MockBackoffPolicy::new()),
+            Some(tokio_now()),
+        )
+        .await;
+        let err = response.expect_err("retry loop should terminate");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deadline_caps_attempt_timeout() -> anyhow::Result<()> {
+        let mut call = MockCall::new();
+        call.expect_call()
+            .once()
+            .withf(|d| *d == Some(Duration::from_secs(10)))
+            .returning(|_| success());
+        let inner = async move |d| call.call(d);
+
+        let mut throttler = MockRetryThrottler::new();
+        throttler.expect_on_success().once().return_const(());
+        let mut retry_policy = MockRetryPolicy::new();
+        retry_policy
+            .expect_remaining_time()
+            .once()
+            .return_const(Duration::from_secs(30));
+        let sleep = MockSleep::new();
+        let backoff = async move |d| sleep.sleep(d).await;
+
+        let response = retry_loop_with_deadline(
+            inner,
+            backoff,
+            true,
+            to_retry_throttler(throttler),
+            to_retry_policy(retry_policy),
+            to_backoff_policy(MockBackoffPolicy::new()),
+            Some(tokio_now() + Duration::from_secs(10)),
+        )
+        .await?;
+        assert_eq!(response, "success");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deadline_stops_retries() -> anyhow::Result<()> {
+        let mut call = MockCall::new();
+        call.expect_call().once().returning(|_| transient());
+        let inner = async move |d| call.call(d);
+
+        let mut throttler = MockRetryThrottler::new();
+        throttler.expect_on_retry_failure().once().return_const(());
+        let mut retry_policy = MockRetryPolicy::new();
+        retry_policy.expect_remaining_time().return_const(None);
+        retry_policy
+            .expect_on_error()
+            .once()
+            .returning(|_, _, _, e| RetryResult::Continue(e));
+        let mut backoff_policy = MockBackoffPolicy::new();
+        backoff_policy
+            .expect_on_failure()
+            .once()
+            .return_const(Duration::from_secs(2));
+        // There is not enough time left to sleep before the next attempt.
+        let sleep = MockSleep::new();
+        let backoff = async move |d| sleep.sleep(d).await;
+
+        let response = retry_loop_with_deadline(
+            inner,
+            backoff,
+            true,
+            to_retry_throttler(throttler),
+            to_retry_policy(retry_policy),
+            to_backoff_policy(backoff_policy),
+            Some(tokio_now() + Duration::from_secs(1)),
+        )
+        .await;
+        let err = response.expect_err("retry loop should terminate");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        let got = err
+            .source()
+            .and_then(|e| e.downcast_ref::<Error>())
+            .and_then(|e| e.status());
+        assert_eq!(got, Some(&transient_status()), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deadline_expires_during_attempt() -> anyhow::Result<()> {
+        // Simulate an attempt that times out because of the deadline.
+        let inner = async move |d: Option<Duration>| -> Result<String> {
+            tokio::time::sleep(d.expect("deadline sets the attempt timeout")).await;
+            Err(Error::timeout("simulated timeout"))
+        };
+
+        let mut throttler = MockRetryThrottler::new();
+        throttler.expect_on_retry_failure().once().return_const(());
+        let mut retry_policy = MockRetryPolicy::new();
+        retry_policy.expect_remaining_time().return_const(None);
+        retry_policy
+            .expect_on_error()
+            .once()
+            .returning(|_, _, _, e| RetryResult::Permanent(e));
+        let mut backoff_policy = MockBackoffPolicy::new();
+        backoff_policy
+            .expect_on_failure()
+            .once()
+            .return_const(Duration::from_secs(1));
+        let sleep = MockSleep::new();
+        let backoff = async move |d| sleep.sleep(d).await;
+
+        let response = retry_loop_with_deadline(
+            inner,
+            backoff,
+            true,
+            to_retry_throttler(throttler),
+            to_retry_policy(retry_policy),
+            to_backoff_policy(backoff_policy),
+            Some(tokio_now() + Duration::from_secs(5)),
+        )
+        .await;
+        let err = response.expect_err("retry loop should terminate");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        let source = err.source().and_then(|e| e.downcast_ref::<Error>());
+        assert!(source.is_some_and(Error::is_timeout), "{err:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deadline_keeps_permanent_errors() -> anyhow::Result<()> {
+        let mut call = MockCall::new();
+        call.expect_call().once().returning(|_| permanent());
+        let inner = async move |d| call.call(d);
+
+        let mut throttler = MockRetryThrottler::new();
+        throttler.expect_on_retry_failure().once().return_const(());
+        let mut retry_policy = MockRetryPolicy::new();
+        retry_policy.expect_remaining_time().return_const(None);
+        retry_policy
+            .expect_on_error()
+            .once()
+            .returning(|_, _, _, e| RetryResult::Permanent(e));
+        let mut backoff_policy = MockBackoffPolicy::new();
+        backoff_policy
+            .expect_on_failure()
+            .once()
+            .return_const(Duration::from_secs(1));
+        let sleep = MockSleep::new();
+        let backoff = async move |d| sleep.sleep(d).await;
+
+        let response = retry_loop_with_deadline(
+            inner,
+            backoff,
+            true,
+            to_retry_throttler(throttler),
+            to_retry_policy(retry_policy),
+            to_backoff_policy(backoff_policy),
+            Some(tokio_now() + Duration::from_secs(60)),
+        )
+        .await;
+        let err = response.expect_err("retry loop should terminate");
+        assert!(!err.is_deadline_exceeded(), "{err:?}");
+        assert_eq!(
+            err.status().map(|s| s.code),
+            Some(Code::PermissionDenied),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn polling_policies_without_deadline() {
+        use crate::exponential_backoff::ExponentialBackoff;
+        use crate::polling_error_policy::Aip194Strict;
+        let error: Arc<dyn PollingErrorPolicy> = Arc::new(Aip194Strict);
+        let got = polling_error_policy_with_deadline(error.clone(), None);
+        assert!(Arc::ptr_eq(&got, &error));
+
+        let backoff: Arc<dyn PollingBackoffPolicy> = Arc::new(ExponentialBackoff::default());
+        let got = polling_backoff_policy_with_deadline(backoff.clone(), None);
+        assert!(Arc::ptr_eq(&got, &backoff));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_error_policy_deadline() {
+        use crate::polling_error_policy::AlwaysContinue;
+        let start = tokio_now();
+        let policy = polling_error_policy_with_deadline(
+            Arc::new(AlwaysContinue),
+            Some(start + Duration::from_secs(10)),
+        );
+        let result = policy.on_error(start, 1, Error::service(transient_status()));
+        assert!(result.is_continue(), "{result:?}");
+        assert!(policy.on_in_progress(start, 1, "test-op").is_ok());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        let result = policy.on_error(start, 2, Error::service(transient_status()));
+        let err = match result {
+            RetryResult::Exhausted(e) => e,
+            r => panic!("expected an exhausted result, got {r:?}"),
+        };
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        let err = policy
+            .on_in_progress(start, 2, "test-op")
+            .expect_err("polling should stop after the deadline");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        assert!(err.to_string().contains("test-op"), "{err}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_backoff_policy_deadline() {
+        use crate::exponential_backoff::ExponentialBackoffBuilder;
+        let start = tokio_now();
+        let inner = ExponentialBackoffBuilder::new()
+            .with_initial_delay(Duration::from_secs(4))
+            .with_maximum_delay(Duration::from_secs(4))
+            .build()
+            .expect("valid backoff configuration");
+        let policy = polling_backoff_policy_with_deadline(
+            Arc::new(inner),
+            Some(start + Duration::from_secs(6)),
+        );
+        assert_eq!(policy.wait_period(start, 1), Duration::from_secs(4));
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert_eq!(policy.wait_period(start, 2), Duration::from_secs(2));
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert_eq!(policy.wait_period(start, 3), Duration::ZERO);
+    }
+
     fn success() -> Result<String> {
         Ok("success".into())
     }
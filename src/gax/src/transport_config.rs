@@ -0,0 +1,323 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Configure the network transport used by the clients.
+//!
+//! Some networks require clients to send all traffic through a proxy, or use
+//! TLS interception with a private certificate authority. The types in this
+//! module configure the proxy, the trusted root certificates, and other
+//! settings of the underlying transport. Both HTTP+JSON and gRPC-based clients
+//! use this configuration, unless noted otherwise.
+//!
+//! # Example
+//! ```
+//! # use google_cloud_gax::client_builder::examples;
+//! # use google_cloud_gax::client_builder::Result;
+//! # tokio_test::block_on(async {
+//! use examples::Client; // Placeholder for examples
+//! use google_cloud_gax::transport_config::{Proxy, TransportConfig};
+//! # let ca_bundle = Vec::new();
+//! let config = TransportConfig::default()
+//!     .with_proxy(Proxy::new("http://proxy.example.com:3128").with_basic_auth("user", "password"))
+//!     .with_root_certificates_pem(ca_bundle);
+//! let client = Client::builder()
+//!     .with_transport_config(config)
+//!     .build().await?;
+//! # Result::<()>::Ok(()) });
+//! ```
+
+use std::time::Duration;
+
+/// Network transport settings for the clients.
+///
+/// Settings that are not configured use the transport defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransportConfig {
+    proxy: Option<Proxy>,
+    root_certificates_pem: Vec<Vec<u8>>,
+    builtin_root_certificates: bool,
+    client_identity: Option<ClientIdentity>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    http2_prior_knowledge: bool,
+}
+
+impl std::default::Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            root_certificates_pem: Vec::new(),
+            builtin_root_certificates: true,
+            client_identity: None,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            http2_prior_knowledge: false,
+        }
+    }
+}
+
+impl TransportConfig {
+    /// Sends all requests through a proxy.
+    pub fn with_proxy(mut self, v: Proxy) -> Self {
+        self.proxy = Some(v);
+        self
+    }
+
+    /// Gets the proxy configuration, if any.
+    pub fn proxy(&self) -> Option<&Proxy> {
+        self.proxy.as_ref()
+    }
+
+    /// Adds trusted root certificates.
+    ///
+    /// The value is a PEM bundle with one or more certificates. This can be
+    /// called multiple times to add more bundles. The certificates are trusted
+    /// in addition to the built-in root certificates, unless those are
+    /// disabled with [with_builtin_root_certificates][Self::with_builtin_root_certificates].
+    ///
+    /// The client returns an error if the bundle cannot be parsed.
+    pub fn with_root_certificates_pem<V: Into<Vec<u8>>>(mut self, v: V) -> Self {
+        self.root_certificates_pem.push(v.into());
+        self
+    }
+
+    /// Gets the additional root certificates, in PEM format.
+    pub fn root_certificates_pem(&self) -> &[Vec<u8>] {
+        &self.root_certificates_pem
+    }
+
+    /// Enables or disables the built-in root certificates.
+    ///
+    /// The built-in root certificates are enabled by default. Applications
+    /// that only trust a private certificate authority can disable them.
+    pub fn with_builtin_root_certificates(mut self, v: bool) -> Self {
+        self.builtin_root_certificates = v;
+        self
+    }
+
+    /// Returns true if the built-in root certificates are trusted.
+    pub fn builtin_root_certificates(&self) -> bool {
+        self.builtin_root_certificates
+    }
+
+    /// Sets the client certificate and private key for mutual TLS.
+    ///
+    /// Both values are in PEM format.
+    pub fn with_client_identity<C, K>(mut self, certificate_pem: C, private_key_pem: K) -> Self
+    where
+        C: Into<Vec<u8>>,
+        K: Into<Vec<u8>>,
+    {
+        self.client_identity = Some(ClientIdentity {
+            certificate_pem: certificate_pem.into(),
+            private_key_pem: private_key_pem.into(),
+        });
+        self
+    }
+
+    /// Gets the client identity for mutual TLS, if any.
+    pub fn client_identity(&self) -> Option<&ClientIdentity> {
+        self.client_identity.as_ref()
+    }
+
+    /// Sets how long idle connections are kept in the pool.
+    ///
+    /// Only used by HTTP+JSON clients. gRPC-based clients use long-lived
+    /// channels, see [GrpcConfig][crate::grpc_config::GrpcConfig].
+    pub fn with_pool_idle_timeout(mut self, v: Duration) -> Self {
+        self.pool_idle_timeout = Some(v);
+        self
+    }
+
+    /// Gets how long idle connections are kept in the pool, if set.
+    pub fn pool_idle_timeout(&self) -> Option<Duration> {
+        self.pool_idle_timeout
+    }
+
+    /// Sets the maximum number of idle connections kept for each host.
+    ///
+    /// Only used by HTTP+JSON clients.
+    pub fn with_pool_max_idle_per_host(mut self, v: usize) -> Self {
+        self.pool_max_idle_per_host = Some(v);
+        self
+    }
+
+    /// Gets the maximum number of idle connections for each host, if set.
+    pub fn pool_max_idle_per_host(&self) -> Option<usize> {
+        self.pool_max_idle_per_host
+    }
+
+    /// Uses HTTP/2 without negotiating the protocol.
+    ///
+    /// Only used by HTTP+JSON clients, gRPC always uses HTTP/2. This is
+    /// useful with services or proxies that support HTTP/2 over plain text.
+    pub fn with_http2_prior_knowledge(mut self, v: bool) -> Self {
+        self.http2_prior_knowledge = v;
+        self
+    }
+
+    /// Returns true if the client uses HTTP/2 without negotiation.
+    pub fn http2_prior_knowledge(&self) -> bool {
+        self.http2_prior_knowledge
+    }
+}
+
+/// A proxy for all the client requests.
+///
+/// HTTP+JSON clients support `http://` and `https://` proxies.
+/// gRPC-based clients support `http://` proxies, and tunnel each connection
+/// using `CONNECT` requests.
+#[derive(Clone, PartialEq)]
+pub struct Proxy {
+    url: String,
+    basic_auth: Option<(String, String)>,
+}
+
+impl Proxy {
+    /// Creates a proxy configuration for the given URL.
+    pub fn new<V: Into<String>>(url: V) -> Self {
+        Self {
+            url: url.into(),
+            basic_auth: None,
+        }
+    }
+
+    /// Authenticates with the proxy using HTTP basic authentication.
+    pub fn with_basic_auth<U, P>(mut self, username: U, password: P) -> Self
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        self.basic_auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Gets the proxy URL.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Gets the username and password for the proxy, if any.
+    pub fn basic_auth(&self) -> Option<(&str, &str)> {
+        self.basic_auth
+            .as_ref()
+            .map(|(u, p)| (u.as_str(), p.as_str()))
+    }
+}
+
+impl std::fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Proxy")
+            .field("url", &self.url)
+            .field(
+                "basic_auth",
+                &self.basic_auth.as_ref().map(|(u, _)| (u, "[censored]")),
+            )
+            .finish()
+    }
+}
+
+/// A client certificate and private key, used for mutual TLS.
+#[derive(Clone, PartialEq)]
+pub struct ClientIdentity {
+    certificate_pem: Vec<u8>,
+    private_key_pem: Vec<u8>,
+}
+
+impl ClientIdentity {
+    /// Gets the client certificate chain, in PEM format.
+    pub fn certificate_pem(&self) -> &[u8] {
+        &self.certificate_pem
+    }
+
+    /// Gets the private key, in PEM format.
+    pub fn private_key_pem(&self) -> &[u8] {
+        &self.private_key_pem
+    }
+}
+
+impl std::fmt::Debug for ClientIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientIdentity")
+            .field(
+                "certificate_pem",
+                &String::from_utf8_lossy(&self.certificate_pem),
+            )
+            .field("private_key_pem", &"[censored]")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let config = TransportConfig::default();
+        assert_eq!(config.proxy(), None);
+        assert!(config.root_certificates_pem().is_empty());
+        assert!(config.builtin_root_certificates());
+        assert_eq!(config.client_identity(), None);
+        assert_eq!(config.pool_idle_timeout(), None);
+        assert_eq!(config.pool_max_idle_per_host(), None);
+        assert!(!config.http2_prior_knowledge());
+    }
+
+    #[test]
+    fn setters() {
+        let config = TransportConfig::default()
+            .with_proxy(Proxy::new("http://proxy:3128"))
+            .with_root_certificates_pem("bundle-1")
+            .with_root_certificates_pem(b"bundle-2".to_vec())
+            .with_builtin_root_certificates(false)
+            .with_client_identity("cert", "key")
+            .with_pool_idle_timeout(Duration::from_secs(30))
+            .with_pool_max_idle_per_host(4)
+            .with_http2_prior_knowledge(true);
+        assert_eq!(config.proxy().map(Proxy::url), Some("http://proxy:3128"));
+        assert_eq!(
+            config.root_certificates_pem(),
+            &[b"bundle-1".to_vec(), b"bundle-2".to_vec()]
+        );
+        assert!(!config.builtin_root_certificates());
+        let identity = config.client_identity().unwrap();
+        assert_eq!(identity.certificate_pem(), b"cert");
+        assert_eq!(identity.private_key_pem(), b"key");
+        assert_eq!(config.pool_idle_timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(config.pool_max_idle_per_host(), Some(4));
+        assert!(config.http2_prior_knowledge());
+    }
+
+    #[test]
+    fn proxy() {
+        let proxy = Proxy::new("http://proxy:3128");
+        assert_eq!(proxy.url(), "http://proxy:3128");
+        assert_eq!(proxy.basic_auth(), None);
+
+        let proxy = proxy.with_basic_auth("user", "secret-password");
+        assert_eq!(proxy.basic_auth(), Some(("user", "secret-password")));
+        let fmt = format!("{proxy:?}");
+        assert!(fmt.contains("user"), "{fmt}");
+        assert!(!fmt.contains("secret-password"), "{fmt}");
+    }
+
+    #[test]
+    fn client_identity_debug() {
+        let config = TransportConfig::default().with_client_identity("cert", "secret-key");
+        let fmt = format!("{config:?}");
+        assert!(fmt.contains("cert"), "{fmt}");
+        assert!(!fmt.contains("secret-key"), "{fmt}");
+    }
+}
@@ -18,12 +18,29 @@
 //! module may be changed or removed without warnings. Applications should not
 //! use any types contained within.
 
-use super::{Poller, PollingBackoffPolicy, PollingErrorPolicy, PollingResult, Result};
+use super::{Error, Poller, PollingBackoffPolicy, PollingErrorPolicy, PollingResult, Result};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Instant;
 
 pub type Operation<R, M> = super::details::Operation<R, M>;
 
+/// The callback used to report the progress of an operation.
+type ProgressFn<M> = Box<dyn FnMut(&M) + Send>;
+
+/// The type-erased closure used to cancel operations in the service.
+type CancelFn =
+    Arc<dyn Fn(String) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+fn cancel_fn<C, CF>(cancel: C) -> CancelFn
+where
+    C: Fn(String) -> CF + Send + Sync + 'static,
+    CF: Future<Output = Result<()>> + Send + 'static,
+{
+    Arc::new(move |name| Box::pin(cancel(name)))
+}
+
 /// Creates a new `impl Poller<R, M>` from the closures created by the generator.
 ///
 /// This is intended as an implementation detail of the generated clients.
@@ -51,6 +68,41 @@ where
     PollerImpl::new(polling_error_policy, polling_backoff_policy, start, query)
 }
 
+/// Creates a new `impl Poller<R, M>` that can cancel the operation.
+///
+/// Like [new_poller], the `cancel` closure receives the name of the operation
+/// and cancels it in the service. Pollers created without this closure return
+/// an error from [Poller::cancel].
+///
+/// This is intended as an implementation detail of the generated clients.
+/// Applications should have no need to use this function directly.
+pub fn new_poller_with_cancel<ResponseType, MetadataType, S, SF, Q, QF, C, CF>(
+    polling_error_policy: Arc<dyn PollingErrorPolicy>,
+    polling_backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    start: S,
+    query: Q,
+    cancel: C,
+) -> impl Poller<ResponseType, MetadataType>
+where
+    ResponseType:
+        wkt::message::Message + serde::ser::Serialize + serde::de::DeserializeOwned + Send,
+    MetadataType:
+        wkt::message::Message + serde::ser::Serialize + serde::de::DeserializeOwned + Send,
+    S: FnOnce() -> SF + Send + Sync,
+    SF: std::future::Future<Output = Result<Operation<ResponseType, MetadataType>>>
+        + Send
+        + 'static,
+    Q: Fn(String) -> QF + Send + Sync + Clone,
+    QF: std::future::Future<Output = Result<Operation<ResponseType, MetadataType>>>
+        + Send
+        + 'static,
+    C: Fn(String) -> CF + Send + Sync + 'static,
+    CF: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    PollerImpl::new(polling_error_policy, polling_backoff_policy, start, query)
+        .with_cancel(cancel_fn(cancel))
+}
+
 /// Creates a new `impl Poller<(), M>` from the closures created by the generator.
 ///
 /// This is intended as an implementation detail of the generated clients.
@@ -73,6 +125,39 @@ where
     UnitResponsePoller::new(poller)
 }
 
+/// Creates a new `impl Poller<(), MetadataType>` that can cancel the operation.
+///
+/// See [new_poller_with_cancel] for details.
+///
+/// This is intended as an implementation detail of the generated clients.
+/// Applications should have no need to use this function directly.
+pub fn new_unit_response_poller_with_cancel<MetadataType, S, SF, Q, QF, C, CF>(
+    polling_error_policy: Arc<dyn PollingErrorPolicy>,
+    polling_backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    start: S,
+    query: Q,
+    cancel: C,
+) -> impl Poller<(), MetadataType>
+where
+    MetadataType:
+        wkt::message::Message + serde::ser::Serialize + serde::de::DeserializeOwned + Send,
+    S: FnOnce() -> SF + Send + Sync,
+    SF: std::future::Future<Output = Result<Operation<wkt::Empty, MetadataType>>> + Send + 'static,
+    Q: Fn(String) -> QF + Send + Sync + Clone,
+    QF: std::future::Future<Output = Result<Operation<wkt::Empty, MetadataType>>> + Send + 'static,
+    C: Fn(String) -> CF + Send + Sync + 'static,
+    CF: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    let poller = new_poller_with_cancel(
+        polling_error_policy,
+        polling_backoff_policy,
+        start,
+        query,
+        cancel,
+    );
+    UnitResponsePoller::new(poller)
+}
+
 /// Creates a new `impl Poller<(), M>` from the closures created by the generator.
 ///
 /// This is intended as an implementation detail of the generated clients.
@@ -95,6 +180,39 @@ where
     UnitMetadataPoller::new(poller)
 }
 
+/// Creates a new `impl Poller<ResponseType, ()>` that can cancel the operation.
+///
+/// See [new_poller_with_cancel] for details.
+///
+/// This is intended as an implementation detail of the generated clients.
+/// Applications should have no need to use this function directly.
+pub fn new_unit_metadata_poller_with_cancel<ResponseType, S, SF, Q, QF, C, CF>(
+    polling_error_policy: Arc<dyn PollingErrorPolicy>,
+    polling_backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    start: S,
+    query: Q,
+    cancel: C,
+) -> impl Poller<ResponseType, ()>
+where
+    ResponseType:
+        wkt::message::Message + serde::ser::Serialize + serde::de::DeserializeOwned + Send,
+    S: FnOnce() -> SF + Send + Sync,
+    SF: std::future::Future<Output = Result<Operation<ResponseType, wkt::Empty>>> + Send + 'static,
+    Q: Fn(String) -> QF + Send + Sync + Clone,
+    QF: std::future::Future<Output = Result<Operation<ResponseType, wkt::Empty>>> + Send + 'static,
+    C: Fn(String) -> CF + Send + Sync + 'static,
+    CF: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    let poller = new_poller_with_cancel(
+        polling_error_policy,
+        polling_backoff_policy,
+        start,
+        query,
+        cancel,
+    );
+    UnitMetadataPoller::new(poller)
+}
+
 /// Creates a new `impl Poller<(), ()>` from the closures created by the generator.
 ///
 /// This is intended as an implementation detail of the generated clients.
@@ -115,6 +233,37 @@ where
     UnitResponsePoller::new(UnitMetadataPoller::new(poller))
 }
 
+/// Creates a new `impl Poller<(), ()>` that can cancel the operation.
+///
+/// See [new_poller_with_cancel] for details.
+///
+/// This is intended as an implementation detail of the generated clients.
+/// Applications should have no need to use this function directly.
+pub fn new_unit_poller_with_cancel<S, SF, Q, QF, C, CF>(
+    polling_error_policy: Arc<dyn PollingErrorPolicy>,
+    polling_backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    start: S,
+    query: Q,
+    cancel: C,
+) -> impl Poller<(), ()>
+where
+    S: FnOnce() -> SF + Send + Sync,
+    SF: std::future::Future<Output = Result<Operation<wkt::Empty, wkt::Empty>>> + Send + 'static,
+    Q: Fn(String) -> QF + Send + Sync + Clone,
+    QF: std::future::Future<Output = Result<Operation<wkt::Empty, wkt::Empty>>> + Send + 'static,
+    C: Fn(String) -> CF + Send + Sync + 'static,
+    CF: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    let poller = new_poller_with_cancel(
+        polling_error_policy,
+        polling_backoff_policy,
+        start,
+        query,
+        cancel,
+    );
+    UnitResponsePoller::new(UnitMetadataPoller::new(poller))
+}
+
 struct UnitResponsePoller<P> {
     poller: P,
 }
@@ -137,6 +286,24 @@ where
     async fn until_done(self) -> Result<()> {
         self.poller.until_done().await.map(|_| ())
     }
+    fn name(&self) -> Option<String> {
+        self.poller.name()
+    }
+    fn cancel(&self) -> impl Future<Output = Result<()>> + Send {
+        self.poller.cancel()
+    }
+    fn resume<T>(self, name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new(self.poller.resume(name))
+    }
+    fn with_progress<F>(self, callback: F) -> Self
+    where
+        F: FnMut(&M) + Send + 'static,
+    {
+        Self::new(self.poller.with_progress(callback))
+    }
     #[cfg(feature = "unstable-stream")]
     fn into_stream(self) -> impl futures::Stream<Item = PollingResult<(), M>> + Unpin {
         use futures::StreamExt;
@@ -166,6 +333,24 @@ where
     async fn until_done(self) -> Result<R> {
         self.poller.until_done().await
     }
+    fn name(&self) -> Option<String> {
+        self.poller.name()
+    }
+    fn cancel(&self) -> impl Future<Output = Result<()>> + Send {
+        self.poller.cancel()
+    }
+    fn resume<T>(self, name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::new(self.poller.resume(name))
+    }
+    fn with_progress<F>(self, mut callback: F) -> Self
+    where
+        F: FnMut(&()) + Send + 'static,
+    {
+        Self::new(self.poller.with_progress(move |_| callback(&())))
+    }
     #[cfg(feature = "unstable-stream")]
     fn into_stream(self) -> impl futures::Stream<Item = PollingResult<R, ()>> + Unpin {
         use futures::StreamExt;
@@ -228,6 +413,9 @@ where
     operation: Option<String>,
     loop_start: Instant,
     attempt_count: u32,
+    name: Option<String>,
+    progress: Option<ProgressFn<MetadataType>>,
+    cancel: Option<CancelFn>,
 }
 
 impl<ResponseType, MetadataType, S, SF, Q, QF> PollerImpl<ResponseType, MetadataType, S, SF, Q, QF>
@@ -255,7 +443,27 @@ where
             operation: None,
             loop_start: Instant::now(),
             attempt_count: 0,
+            name: None,
+            progress: None,
+            cancel: None,
+        }
+    }
+
+    fn with_cancel(mut self, cancel: CancelFn) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn report_progress(
+        &mut self,
+        poll: PollingResult<ResponseType, MetadataType>,
+    ) -> PollingResult<ResponseType, MetadataType> {
+        if let (Some(callback), PollingResult::InProgress(Some(m))) =
+            (self.progress.as_mut(), &poll)
+        {
+            callback(m);
         }
+        poll
     }
 }
 
@@ -278,9 +486,12 @@ where
     async fn poll(&mut self) -> Option<PollingResult<ResponseType, MetadataType>> {
         if let Some(start) = self.start.take() {
             let result = start().await;
+            if let Ok(op) = &result {
+                self.name = Some(op.name()).filter(|n| !n.is_empty());
+            }
             let (op, poll) = super::details::handle_start(result);
             self.operation = op;
-            return Some(poll);
+            return Some(self.report_progress(poll));
         }
         if let Some(name) = self.operation.take() {
             self.attempt_count += 1;
@@ -293,7 +504,7 @@ where
                 result,
             );
             self.operation = op;
-            return Some(poll);
+            return Some(self.report_progress(poll));
         }
         None
     }
@@ -322,6 +533,49 @@ where
         unreachable!("loop should exit via the `Completed` branch vs. this line");
     }
 
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn cancel(&self) -> impl Future<Output = Result<()>> + Send {
+        let name = self.name.clone();
+        let cancel = self.cancel.clone();
+        async move {
+            // These conditions are detected before making any request, they
+            // are not service errors.
+            let Some(name) = name else {
+                return Err(Error::binding(
+                    "cannot cancel a long-running operation that has not started",
+                ));
+            };
+            let Some(cancel) = cancel else {
+                return Err(Error::binding(
+                    "this poller cannot cancel long-running operations, use the client's `cancel_operation()` method",
+                ));
+            };
+            cancel(name).await
+        }
+    }
+
+    fn resume<T>(mut self, name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        let name = name.into();
+        self.start = None;
+        self.operation = Some(name.clone());
+        self.name = Some(name);
+        self
+    }
+
+    fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&MetadataType) + Send + 'static,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
     #[cfg(feature = "unstable-stream")]
     fn into_stream(
         self,
@@ -528,6 +782,129 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resume_poll_basic_flow() {
+        let query = |name: String| async move {
+            assert_eq!(name, "test-only-name");
+            let any = wkt::Any::from_msg(&wkt::Duration::clamp(234, 0))
+                .expect("test message deserializes via Any::from_msg");
+            let result = longrunning::model::operation::Result::Response(any.into());
+            let op = longrunning::model::Operation::default()
+                .set_name(name)
+                .set_done(true)
+                .set_result(result);
+            let op = TestOperation::new(op);
+
+            Ok::<TestOperation, Error>(op)
+        };
+
+        let mut poller = new_poller(
+            Arc::new(AlwaysContinue),
+            Arc::new(ExponentialBackoff::default()),
+            no_start,
+            query,
+        )
+        .resume("test-only-name");
+        let p0 = poller.poll().await;
+        match p0.unwrap() {
+            PollingResult::Completed(r) => {
+                let response = r.unwrap();
+                assert_eq!(response, wkt::Duration::clamp(234, 0));
+            }
+            r => {
+                panic!("{r:?}");
+            }
+        }
+
+        let p1 = poller.poll().await;
+        assert!(p1.is_none(), "{p1:?}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resume_poll_with_polling_error() {
+        let query = |_: String| async move { Err::<TestOperation, Error>(polling_error()) };
+
+        let mut poller = new_poller(
+            Arc::new(AlwaysContinue),
+            Arc::new(ExponentialBackoff::default()),
+            no_start,
+            query,
+        )
+        .resume("test-only-name");
+        // The error policy decides if the errors are recoverable, as in any
+        // other polling attempt.
+        let p0 = poller.poll().await;
+        assert!(matches!(p0, Some(PollingResult::PollingError(_))), "{p0:?}");
+        let p1 = poller.poll().await;
+        assert!(matches!(p1, Some(PollingResult::PollingError(_))), "{p1:?}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resume_until_done_basic_flow() -> Result<()> {
+        let count = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let query = move |name: String| {
+            let count = count.clone();
+            async move {
+                let op = longrunning::model::Operation::default().set_name(name);
+                let op = match count.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => {
+                        let any = wkt::Any::from_msg(&wkt::Timestamp::clamp(123, 0))
+                            .expect("test message deserializes via Any::from_msg");
+                        op.set_metadata(any)
+                    }
+                    _ => {
+                        let any = wkt::Any::from_msg(&wkt::Duration::clamp(234, 0))
+                            .expect("test message deserializes via Any::from_msg");
+                        let result = longrunning::model::operation::Result::Response(any.into());
+                        op.set_done(true).set_result(result)
+                    }
+                };
+                Ok::<TestOperation, Error>(TestOperation::new(op))
+            }
+        };
+
+        let poller = new_poller(
+            Arc::new(AlwaysContinue),
+            Arc::new(
+                ExponentialBackoffBuilder::new()
+                    .with_initial_delay(Duration::from_millis(1))
+                    .clamp(),
+            ),
+            no_start,
+            query,
+        )
+        .resume("test-only-name");
+        let response = poller.until_done().await?;
+        assert_eq!(response, wkt::Duration::clamp(234, 0));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resume_unit_until_done_basic_flow() -> Result<()> {
+        let query = |name: String| async move {
+            let any = wkt::Any::from_msg(&wkt::Empty::default())
+                .expect("test message deserializes via Any::from_msg");
+            let result = longrunning::model::operation::Result::Response(any.into());
+            let op = longrunning::model::Operation::default()
+                .set_name(name)
+                .set_done(true)
+                .set_result(result);
+            Ok::<_, Error>(Operation::<wkt::Empty, wkt::Empty>::new(op))
+        };
+
+        let poller = new_unit_poller(
+            Arc::new(AlwaysContinue),
+            Arc::new(ExponentialBackoff::default()),
+            no_start,
+            query,
+        )
+        .resume("test-only-name");
+        poller.until_done().await?;
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn unit_poll_basic_flow() {
         let start = || async move {
@@ -995,6 +1372,288 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn until_done_with_deadline() -> Result<()> {
+        use gax::retry_loop_internal::{
+            polling_backoff_policy_with_deadline, polling_error_policy_with_deadline,
+        };
+        let in_progress = || {
+            let any = wkt::Any::from_msg(&wkt::Timestamp::clamp(123, 0))
+                .expect("test message deserializes via Any::from_msg");
+            let op = longrunning::model::Operation::default()
+                .set_name("test-only-name")
+                .set_metadata(any);
+            Ok::<TestOperation, Error>(TestOperation::new(op))
+        };
+        let start = move || async move { in_progress() };
+        let query = move |_: String| async move { in_progress() };
+
+        let loop_start = tokio::time::Instant::now();
+        let deadline = Some(loop_start.into_std() + Duration::from_secs(10));
+        let backoff = ExponentialBackoffBuilder::new()
+            .with_initial_delay(Duration::from_secs(4))
+            .with_maximum_delay(Duration::from_secs(4))
+            .clamp();
+        let poller = PollerImpl::new(
+            polling_error_policy_with_deadline(Arc::new(AlwaysContinue), deadline),
+            polling_backoff_policy_with_deadline(Arc::new(backoff), deadline),
+            start,
+            query,
+        );
+        let err = poller
+            .until_done()
+            .await
+            .expect_err("the operation never completes");
+        assert!(err.is_deadline_exceeded(), "{err:?}");
+        assert!(err.to_string().contains("test-only-name"), "{err}");
+        // The poller does not wait past the deadline.
+        assert_eq!(loop_start.elapsed(), Duration::from_secs(10));
+
+        Ok(())
+    }
+
+    fn in_progress(percent: i64) -> Result<TestOperation> {
+        let any = wkt::Any::from_msg(&wkt::Timestamp::clamp(percent, 0))
+            .expect("test message deserializes via Any::from_msg");
+        let op = longrunning::model::Operation::default()
+            .set_name("test-only-name")
+            .set_metadata(any);
+        Ok(TestOperation::new(op))
+    }
+
+    fn completed() -> Result<TestOperation> {
+        let any = wkt::Any::from_msg(&wkt::Duration::clamp(234, 0))
+            .expect("test message deserializes via Any::from_msg");
+        let result = longrunning::model::operation::Result::Response(any.into());
+        let op = longrunning::model::Operation::default()
+            .set_name("test-only-name")
+            .set_done(true)
+            .set_result(result);
+        Ok(TestOperation::new(op))
+    }
+
+    fn test_backoff() -> Arc<dyn PollingBackoffPolicy> {
+        Arc::new(
+            ExponentialBackoffBuilder::new()
+                .with_initial_delay(Duration::from_millis(1))
+                .clamp(),
+        )
+    }
+
+    // Resumed pollers never call the `start` closure.
+    fn no_start<R, M>() -> std::future::Ready<Result<Operation<R, M>>> {
+        unreachable!("resumed pollers do not start new operations")
+    }
+
+    #[tokio::test]
+    async fn name() {
+        let start = || async move { in_progress(25) };
+        let query = |_: String| async move { completed() };
+        let mut poller = new_poller(Arc::new(AlwaysContinue), test_backoff(), start, query);
+        assert_eq!(poller.name(), None);
+        let p = poller.poll().await;
+        assert!(matches!(p, Some(PollingResult::InProgress(_))), "{p:?}");
+        assert_eq!(poller.name().as_deref(), Some("test-only-name"));
+        let p = poller.poll().await;
+        assert!(matches!(p, Some(PollingResult::Completed(_))), "{p:?}");
+        // The name remains available after the operation completes.
+        assert_eq!(poller.name().as_deref(), Some("test-only-name"));
+
+        let poller = new_unit_metadata_poller(
+            Arc::new(AlwaysContinue),
+            test_backoff(),
+            no_start,
+            |_: String| async move { Err::<EmptyMetadataOperation, Error>(polling_error()) },
+        )
+        .resume("resumed-name");
+        assert_eq!(poller.name().as_deref(), Some("resumed-name"));
+    }
+
+    #[tokio::test]
+    async fn name_start_error() {
+        let start = || async move { Err::<TestOperation, Error>(service_error()) };
+        let query = |_: String| async move { completed() };
+        let mut poller = new_poller(Arc::new(AlwaysContinue), test_backoff(), start, query);
+        let p = poller.poll().await;
+        assert!(matches!(p, Some(PollingResult::Completed(Err(_)))), "{p:?}");
+        assert_eq!(poller.name(), None);
+    }
+
+    #[tokio::test]
+    async fn cancel() -> Result<()> {
+        let start = || async move { in_progress(25) };
+        let query = |_: String| async move { completed() };
+        let cancelled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cancel = {
+            let cancelled = cancelled.clone();
+            move |name: String| {
+                cancelled.lock().unwrap().push(name);
+                async { Ok(()) }
+            }
+        };
+        let mut poller = new_poller_with_cancel(
+            Arc::new(AlwaysContinue),
+            test_backoff(),
+            start,
+            query,
+            cancel,
+        );
+
+        let err = poller.cancel().await.unwrap_err();
+        assert!(err.is_binding(), "{err:?}");
+        assert!(err.status().is_none(), "{err:?}");
+        assert!(err.to_string().contains("has not started"), "{err}");
+        assert!(cancelled.lock().unwrap().is_empty());
+
+        let _ = poller.poll().await;
+        poller.cancel().await?;
+        assert_eq!(*cancelled.lock().unwrap(), vec!["test-only-name"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancel_unit_poller() -> Result<()> {
+        let cancelled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cancel = {
+            let cancelled = cancelled.clone();
+            move |name: String| {
+                cancelled.lock().unwrap().push(name);
+                async { Err(service_error()) }
+            }
+        };
+        let poller =
+            new_unit_poller_with_cancel(
+                Arc::new(AlwaysContinue),
+                test_backoff(),
+                no_start,
+                |_: String| async move {
+                    Err::<Operation<wkt::Empty, wkt::Empty>, Error>(polling_error())
+                },
+                cancel,
+            )
+            .resume("test-only-name");
+        let err = poller.cancel().await.unwrap_err();
+        assert_eq!(
+            err.status().map(|s| s.code),
+            Some(Code::ResourceExhausted),
+            "{err:?}"
+        );
+        assert_eq!(*cancelled.lock().unwrap(), vec!["test-only-name"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancel_unsupported() {
+        let poller = new_poller(
+            Arc::new(AlwaysContinue),
+            test_backoff(),
+            no_start,
+            |_: String| async move { completed() },
+        )
+        .resume("test-only-name");
+        let err = poller.cancel().await.unwrap_err();
+        assert!(err.is_binding(), "{err:?}");
+        assert!(err.status().is_none(), "{err:?}");
+        assert!(err.to_string().contains("cancel_operation()"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn with_progress() -> Result<()> {
+        let start = || async move { in_progress(25) };
+        let count = Arc::new(std::sync::atomic::AtomicI64::new(50));
+        let query = move |_: String| {
+            let count = count.clone();
+            async move {
+                let percent = count.fetch_add(25, std::sync::atomic::Ordering::SeqCst);
+                if percent < 100 {
+                    return in_progress(percent);
+                }
+                completed()
+            }
+        };
+        let progress = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let callback = {
+            let progress = progress.clone();
+            move |m: &MetadataType| progress.lock().unwrap().push(m.seconds())
+        };
+        let response = new_poller(Arc::new(AlwaysContinue), test_backoff(), start, query)
+            .with_progress(callback)
+            .until_done()
+            .await?;
+        assert_eq!(response, wkt::Duration::clamp(234, 0));
+        assert_eq!(*progress.lock().unwrap(), vec![25, 50, 75]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_progress_unit_metadata() -> Result<()> {
+        let start = || async move {
+            let op = longrunning::model::Operation::default().set_name("test-only-name");
+            Ok::<_, Error>(EmptyMetadataOperation::new(op))
+        };
+        let query = |_: String| async move {
+            let any = wkt::Any::from_msg(&wkt::Duration::clamp(234, 0))
+                .expect("test message deserializes via Any::from_msg");
+            let result = longrunning::model::operation::Result::Response(any.into());
+            let op = longrunning::model::Operation::default()
+                .set_done(true)
+                .set_result(result);
+            Ok::<_, Error>(EmptyMetadataOperation::new(op))
+        };
+        let count = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let callback = {
+            let count = count.clone();
+            move |_: &()| {
+                count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        };
+        let response =
+            new_unit_metadata_poller(Arc::new(AlwaysContinue), test_backoff(), start, query)
+                .with_progress(callback)
+                .until_done()
+                .await?;
+        assert_eq!(response, wkt::Duration::clamp(234, 0));
+        // Operations without metadata do not report progress.
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 0);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn until_done_with_cancellation() -> Result<()> {
+        let start = || async move { in_progress(25) };
+        let query = |_: String| async move { in_progress(50) };
+        let poller = new_poller(
+            Arc::new(AlwaysContinue),
+            Arc::new(ExponentialBackoff::default()),
+            start,
+            query,
+        );
+        let token = tokio_util::sync::CancellationToken::new();
+        let child = token.child_token();
+        let cancel = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            token.cancel();
+        });
+        let err = poller
+            .until_done_with_cancellation(child)
+            .await
+            .expect_err("the operation never completes");
+        assert!(err.is_cancelled(), "{err:?}");
+        cancel.await.expect("task completes");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn until_done_with_cancellation_completes() -> Result<()> {
+        let start = || async move { in_progress(25) };
+        let query = |_: String| async move { completed() };
+        let poller = new_poller(Arc::new(AlwaysContinue), test_backoff(), start, query);
+        let token = tokio_util::sync::CancellationToken::new();
+        let response = poller.until_done_with_cancellation(token).await?;
+        assert_eq!(response, wkt::Duration::clamp(234, 0));
+        Ok(())
+    }
+
     fn service_error() -> gax::error::Error {
         gax::error::Error::service(
             Status::default()
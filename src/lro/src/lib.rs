@@ -146,6 +146,135 @@ pub trait Poller<ResponseType, MetadataType>: Send + sealed::Poller {
     /// Poll the long-running operation until it completes.
     fn until_done(self) -> impl Future<Output = Result<ResponseType>> + Send;
 
+    /// Poll the long-running operation until it completes, or until `token`
+    /// is cancelled.
+    ///
+    /// Cancelling `token` only stops waiting for the operation, it does not
+    /// cancel the operation in the service. In this case the function returns
+    /// an error where [is_cancelled()][Error::is_cancelled] is `true`. Save
+    /// the [name][Poller::name] of the operation before calling this function
+    /// if you need to [resume][Poller::resume] polling later, or call
+    /// [cancel][Poller::cancel] to cancel the operation in the service.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use google_cloud_lro::Poller;
+    /// # async fn sample(poller: impl Poller<wkt::Timestamp, wkt::Duration>) -> gax::Result<()> {
+    /// use tokio_util::sync::CancellationToken;
+    /// let token = CancellationToken::new();
+    /// let child = token.child_token();
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    ///     token.cancel();
+    /// });
+    /// match poller.until_done_with_cancellation(child).await {
+    ///     Ok(r) => println!("LRO completed, response={r:?}"),
+    ///     Err(e) if e.is_cancelled() => println!("stopped waiting for the LRO"),
+    ///     Err(e) => println!("LRO failed: {e}"),
+    /// }
+    /// # Ok(()) }
+    /// ```
+    fn until_done_with_cancellation(
+        self,
+        token: tokio_util::sync::CancellationToken,
+    ) -> impl Future<Output = Result<ResponseType>> + Send
+    where
+        Self: Sized,
+    {
+        async move {
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(Error::cancelled(
+                    "stopped waiting for the long-running operation, the operation may still be running in the service",
+                )),
+                r = self.until_done() => r,
+            }
+        }
+    }
+
+    /// The name of the long-running operation.
+    ///
+    /// Returns `None` until the operation starts, that is, until the first
+    /// call to [poll][Poller::poll] returns. [Resumed][Poller::resume] pollers
+    /// always return the name used to resume them.
+    ///
+    /// Applications may save this name and use it to resume polling the
+    /// operation, for example, after the application restarts.
+    fn name(&self) -> Option<String>;
+
+    /// Requests the cancellation of the long-running operation in the service.
+    ///
+    /// Cancellation is best effort: the service may not support cancelling
+    /// the operation, or the operation may complete before the cancellation
+    /// takes effect. Continue polling to learn the final status of the
+    /// operation. Operations successfully cancelled typically complete with an
+    /// error using the [CANCELLED] code.
+    ///
+    /// Returns an error if the operation has not started, or if the poller
+    /// cannot cancel operations. In the latter case use the
+    /// `cancel_operation()` method in the client, if the service supports it,
+    /// with the [name][Poller::name] of the operation. These errors are
+    /// detected before making any request, they do not include a
+    /// [status][gax::error::Error::status].
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use google_cloud_lro::Poller;
+    /// # async fn sample(mut poller: impl Poller<wkt::Timestamp, wkt::Duration>) -> gax::Result<()> {
+    /// let _ = poller.poll().await;
+    /// poller.cancel().await?;
+    /// let result = poller.until_done().await;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [CANCELLED]: rpc::model::Code::Cancelled
+    fn cancel(&self) -> impl Future<Output = Result<()>> + Send;
+
+    /// Resumes polling an existing long-running operation.
+    ///
+    /// Use this function to continue polling an operation started elsewhere,
+    /// for example, by a previous run of the application. The poller does not
+    /// start a new operation, the first call to [poll][Poller::poll] queries
+    /// the status of the operation called `name`. Any fields set in the request
+    /// builder are ignored.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use google_cloud_lro::Poller;
+    /// # async fn sample(poller: impl Poller<wkt::Timestamp, wkt::Duration>) -> gax::Result<()> {
+    /// // The `poller` is typically created via `client.create_foo().poller()`.
+    /// let response = poller
+    ///     .resume("projects/my-project/locations/us-central1/operations/my-operation")
+    ///     .until_done()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    fn resume<T>(self, name: T) -> Self
+    where
+        Self: Sized,
+        T: Into<String>;
+
+    /// Calls `callback` with the metadata of each in-progress result.
+    ///
+    /// Use this function to report the progress of a long-running operation
+    /// while using [until_done][Poller::until_done]. The callback receives the
+    /// metadata returned each time the operation is polled, if any.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use google_cloud_lro::Poller;
+    /// # async fn sample(poller: impl Poller<wkt::Timestamp, wkt::Duration>) -> gax::Result<()> {
+    /// let response = poller
+    ///     .with_progress(|m| println!("LRO in progress, metadata={m:?}"))
+    ///     .until_done()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    fn with_progress<F>(self, callback: F) -> Self
+    where
+        Self: Sized,
+        F: FnMut(&MetadataType) + Send + 'static;
+
     /// Convert a poller to a [Stream][futures::Stream].
     #[cfg(feature = "unstable-stream")]
     #[cfg_attr(docsrs, doc(cfg(feature = "unstable-stream")))]
@@ -154,4 +283,42 @@ pub trait Poller<ResponseType, MetadataType>: Send + sealed::Poller {
     ) -> impl futures::Stream<Item = PollingResult<ResponseType, MetadataType>> + Unpin;
 }
 
+/// Registers the `google.longrunning` message types.
+///
+/// Long-running operations are sometimes embedded in other messages as
+/// [Any][wkt::Any] values, for example, in audit logs. Use this function to
+/// add their types to a [TypeRegistry][wkt::TypeRegistry].
+///
+/// # Example
+/// ```
+/// # use google_cloud_lro::register_types;
+/// use wkt::{Any, TypeRegistry, UnpackedAny};
+/// let mut registry = TypeRegistry::new();
+/// register_types(&mut registry);
+///
+/// let operation = longrunning::model::Operation::new().set_name("test-only");
+/// let any = Any::from_msg(&operation)?;
+/// let UnpackedAny::Message(m) = any.unpack_dynamic(&registry)? else { unreachable!() };
+/// assert_eq!(m.downcast_ref::<longrunning::model::Operation>(), Some(&operation));
+/// # Ok::<(), wkt::AnyError>(())
+/// ```
+pub fn register_types(registry: &mut wkt::TypeRegistry) {
+    use longrunning::model::*;
+    registry
+        .register::<Operation>()
+        .register::<GetOperationRequest>()
+        .register::<ListOperationsRequest>()
+        .register::<ListOperationsResponse>()
+        .register::<CancelOperationRequest>()
+        .register::<DeleteOperationRequest>()
+        .register::<WaitOperationRequest>()
+        .register::<OperationInfo>();
+}
+
 mod details;
+
+#[cfg(feature = "unstable-stream")]
+mod poller_set;
+#[cfg(feature = "unstable-stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unstable-stream")))]
+pub use poller_set::PollerSet;
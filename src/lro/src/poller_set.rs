@@ -0,0 +1,546 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Wait for many long-running operations with a shared polling schedule.
+
+use super::{Poller, PollingBackoffPolicy, PollingResult, Result};
+use futures::stream::{FuturesUnordered, StreamExt};
+use gax::polling_backoff_policy::PollingBackoffPolicyArg;
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// The type-erased closure used to list operations.
+type ListFn = Arc<
+    dyn Fn() -> Pin<Box<dyn Future<Output = Result<Vec<longrunning::model::Operation>>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// The default maximum number of concurrent polling requests.
+const DEFAULT_MAX_CONCURRENT_POLLS: usize = 16;
+
+/// Waits for many long-running operations with a shared polling schedule.
+///
+/// Each [Poller] calling [until_done][Poller::until_done] polls its operation
+/// on its own schedule. When an application starts many operations this
+/// multiplies the number of `GetOperation` requests. A `PollerSet` polls all
+/// the operations in rounds, waiting between rounds as determined by a single
+/// [PollingBackoffPolicy], and limits the number of concurrent polling
+/// requests.
+///
+/// Optionally, the set can use a single `ListOperations` request to find
+/// which operations are completed in each round. Only those operations, and
+/// any operations not included in the list results, are polled individually.
+///
+/// The set returns a stream with the result of each operation in completion
+/// order. Each item includes the index of the poller, as returned by
+/// [push][PollerSet::push].
+///
+/// # Example
+/// ```no_run
+/// # use google_cloud_lro::{Poller, PollerSet};
+/// # async fn sample(pollers: Vec<impl Poller<wkt::Timestamp, wkt::Duration> + 'static>) -> gax::Result<()> {
+/// use futures::StreamExt;
+/// let mut set = PollerSet::new().with_max_concurrent_polls(8);
+/// for p in pollers {
+///     set.push(p);
+/// }
+/// let mut stream = set.into_stream();
+/// while let Some((index, result)) = stream.next().await {
+///     println!("operation {index} completed with {result:?}");
+/// }
+/// # Ok(()) }
+/// ```
+///
+/// # Parameters
+/// * `P` - the type of pollers in the set.
+/// * `ResponseType` - the response type of the operations.
+/// * `MetadataType` - the metadata type of the operations.
+pub struct PollerSet<P, ResponseType, MetadataType> {
+    pollers: Vec<P>,
+    backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    max_concurrent_polls: usize,
+    list: Option<ListFn>,
+    _phantom: PhantomData<fn() -> (ResponseType, MetadataType)>,
+}
+
+impl<P, ResponseType, MetadataType> PollerSet<P, ResponseType, MetadataType>
+where
+    P: Poller<ResponseType, MetadataType> + 'static,
+    ResponseType: Send + 'static,
+    MetadataType: Send + 'static,
+{
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self {
+            pollers: Vec::new(),
+            backoff_policy: Arc::new(gax::exponential_backoff::ExponentialBackoff::default()),
+            max_concurrent_polls: DEFAULT_MAX_CONCURRENT_POLLS,
+            list: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the policy to wait between polling rounds.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_lro::PollerSet;
+    /// use gax::exponential_backoff::ExponentialBackoffBuilder;
+    /// use std::time::Duration;
+    /// # fn sample<P>() -> anyhow::Result<()> where P: google_cloud_lro::Poller<wkt::Timestamp, wkt::Duration> + 'static {
+    /// let policy = ExponentialBackoffBuilder::new()
+    ///     .with_initial_delay(Duration::from_secs(5))
+    ///     .with_maximum_delay(Duration::from_secs(60))
+    ///     .build()?;
+    /// let set = PollerSet::<P, _, _>::new().with_backoff_policy(policy);
+    /// # Ok(()) }
+    /// ```
+    pub fn with_backoff_policy<V: Into<PollingBackoffPolicyArg>>(mut self, v: V) -> Self {
+        self.backoff_policy = v.into().into();
+        self
+    }
+
+    /// Sets the maximum number of concurrent polling requests.
+    ///
+    /// The default is 16. Values smaller than 1 are treated as 1.
+    pub fn with_max_concurrent_polls(mut self, v: usize) -> Self {
+        self.max_concurrent_polls = v.max(1);
+        self
+    }
+
+    /// Uses `list` to find completed operations in each polling round.
+    ///
+    /// The closure should return the operations in the set, for example by
+    /// calling `list_operations` with a filter that matches them. Operations
+    /// listed as not done are not polled in that round. Operations listed as
+    /// done, or not included in the results, are polled individually. If the
+    /// closure returns an error all the operations are polled individually in
+    /// that round.
+    ///
+    /// Operations that are not polled in a round do not report their
+    /// progress, see [Poller::with_progress].
+    pub fn with_list_operations<F, FF>(mut self, list: F) -> Self
+    where
+        F: Fn() -> FF + Send + Sync + 'static,
+        FF: Future<Output = Result<Vec<longrunning::model::Operation>>> + Send + 'static,
+    {
+        self.list = Some(Arc::new(move || Box::pin(list())));
+        self
+    }
+
+    /// Adds a poller to the set, returns its index.
+    pub fn push(&mut self, poller: P) -> usize {
+        self.pollers.push(poller);
+        self.pollers.len() - 1
+    }
+
+    /// The number of pollers in the set.
+    pub fn len(&self) -> usize {
+        self.pollers.len()
+    }
+
+    /// Returns `true` if the set has no pollers.
+    pub fn is_empty(&self) -> bool {
+        self.pollers.is_empty()
+    }
+
+    /// Converts the set into a stream of results in completion order.
+    ///
+    /// Each item contains the index of the poller and the result of its
+    /// operation.
+    pub fn into_stream(self) -> impl futures::Stream<Item = (usize, Result<ResponseType>)> + Unpin {
+        let state = State {
+            to_poll: self.pollers.into_iter().enumerate().collect(),
+            waiting: Vec::new(),
+            in_flight: FuturesUnordered::new(),
+            backoff_policy: self.backoff_policy,
+            max_concurrent_polls: self.max_concurrent_polls,
+            list: self.list,
+            loop_start: Instant::now(),
+            round: 0,
+        };
+        Box::pin(futures::stream::unfold(state, State::next))
+    }
+}
+
+impl<P, ResponseType, MetadataType> Default for PollerSet<P, ResponseType, MetadataType>
+where
+    P: Poller<ResponseType, MetadataType> + 'static,
+    ResponseType: Send + 'static,
+    MetadataType: Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, ResponseType, MetadataType> Extend<P> for PollerSet<P, ResponseType, MetadataType> {
+    fn extend<T: IntoIterator<Item = P>>(&mut self, iter: T) {
+        self.pollers.extend(iter);
+    }
+}
+
+impl<P, ResponseType, MetadataType> FromIterator<P> for PollerSet<P, ResponseType, MetadataType>
+where
+    P: Poller<ResponseType, MetadataType> + 'static,
+    ResponseType: Send + 'static,
+    MetadataType: Send + 'static,
+{
+    fn from_iter<T: IntoIterator<Item = P>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+type PollFuture<P, R, M> =
+    Pin<Box<dyn Future<Output = (usize, P, Option<PollingResult<R, M>>)> + Send>>;
+
+struct State<P, R, M> {
+    // The pollers to poll in this round.
+    to_poll: VecDeque<(usize, P)>,
+    // The pollers to poll in the next round.
+    waiting: Vec<(usize, P)>,
+    in_flight: FuturesUnordered<PollFuture<P, R, M>>,
+    backoff_policy: Arc<dyn PollingBackoffPolicy>,
+    max_concurrent_polls: usize,
+    list: Option<ListFn>,
+    loop_start: Instant,
+    round: u32,
+}
+
+impl<P, R, M> State<P, R, M>
+where
+    P: Poller<R, M> + 'static,
+    R: Send + 'static,
+    M: Send + 'static,
+{
+    async fn next(mut self) -> Option<((usize, Result<R>), Self)> {
+        loop {
+            while self.in_flight.len() < self.max_concurrent_polls {
+                let Some((index, mut poller)) = self.to_poll.pop_front() else {
+                    break;
+                };
+                self.in_flight.push(Box::pin(async move {
+                    let result = poller.poll().await;
+                    (index, poller, result)
+                }));
+            }
+            if let Some((index, poller, result)) = self.in_flight.next().await {
+                match result {
+                    Some(PollingResult::Completed(r)) => return Some(((index, r), self)),
+                    Some(PollingResult::InProgress(_)) | Some(PollingResult::PollingError(_)) => {
+                        self.waiting.push((index, poller))
+                    }
+                    // The poller already returned its result.
+                    None => {}
+                }
+                continue;
+            }
+            if self.waiting.is_empty() {
+                return None;
+            }
+            self.round += 1;
+            let wait = self.backoff_policy.wait_period(self.loop_start, self.round);
+            tokio::time::sleep(wait).await;
+            self.start_round().await;
+        }
+    }
+
+    async fn start_round(&mut self) {
+        let running = match &self.list {
+            None => HashSet::new(),
+            Some(list) => match list().await {
+                Err(_) => HashSet::new(),
+                Ok(operations) => operations
+                    .into_iter()
+                    .filter(|o| !o.done)
+                    .map(|o| o.name)
+                    .collect(),
+            },
+        };
+        for (index, poller) in std::mem::take(&mut self.waiting) {
+            match poller.name() {
+                Some(name) if running.contains(&name) => self.waiting.push((index, poller)),
+                _ => self.to_poll.push_back((index, poller)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::*;
+    use gax::exponential_backoff::{ExponentialBackoff, ExponentialBackoffBuilder};
+    use gax::polling_error_policy::AlwaysContinue;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    type ResponseType = wkt::Duration;
+    type MetadataType = wkt::Timestamp;
+    type TestOperation = Operation<ResponseType, MetadataType>;
+
+    fn in_progress(name: &str) -> longrunning::model::Operation {
+        longrunning::model::Operation::default().set_name(name)
+    }
+
+    fn completed(name: &str, seconds: i64) -> longrunning::model::Operation {
+        let any = wkt::Any::from_msg(&wkt::Duration::clamp(seconds, 0))
+            .expect("test message deserializes via Any::from_msg");
+        let result = longrunning::model::operation::Result::Response(any.into());
+        longrunning::model::Operation::default()
+            .set_name(name)
+            .set_done(true)
+            .set_result(result)
+    }
+
+    // Resumed pollers never call the `start` closure.
+    fn no_start<R, M>() -> std::future::Ready<Result<Operation<R, M>>> {
+        unreachable!("resumed pollers do not start new operations")
+    }
+
+    fn test_backoff() -> ExponentialBackoff {
+        ExponentialBackoffBuilder::new()
+            .with_initial_delay(Duration::from_secs(1))
+            .with_maximum_delay(Duration::from_secs(1))
+            .clamp()
+    }
+
+    // Creates a poller completing after `polls` queries and counts the
+    // queries in `counter`.
+    fn test_poller(
+        name: &'static str,
+        polls: u32,
+        counter: Arc<AtomicU32>,
+    ) -> impl Poller<ResponseType, MetadataType> + 'static {
+        let calls = Arc::new(AtomicU32::new(0));
+        let query = move |name: String| {
+            let calls = calls.clone();
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                let op = if n >= polls {
+                    completed(&name, polls as i64)
+                } else {
+                    in_progress(&name)
+                };
+                Ok::<_, gax::error::Error>(TestOperation::new(op))
+            }
+        };
+        new_poller(
+            Arc::new(AlwaysContinue),
+            Arc::new(test_backoff()),
+            no_start,
+            query,
+        )
+        .resume(name)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn empty() {
+        let counter = Arc::new(AtomicU32::new(0));
+        let set: PollerSet<_, _, _> = std::iter::once(test_poller("op-0", 1, counter.clone()))
+            .take(0)
+            .collect();
+        assert!(set.is_empty());
+        let mut stream = set.into_stream();
+        assert!(stream.next().await.is_none());
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn from_iter() -> anyhow::Result<()> {
+        let counter = Arc::new(AtomicU32::new(0));
+        let set: PollerSet<_, _, _> = ["op-0", "op-1"]
+            .into_iter()
+            .map(|name| test_poller(name, 1, counter.clone()))
+            .collect();
+        assert_eq!(set.len(), 2);
+        let mut got = set
+            .into_stream()
+            .map(|(index, result)| result.map(|r| (index, r)))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+        got.sort_by_key(|(index, _)| *index);
+        let want = vec![
+            (0, wkt::Duration::clamp(1, 0)),
+            (1, wkt::Duration::clamp(1, 0)),
+        ];
+        assert_eq!(got, want);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn completion_order() -> anyhow::Result<()> {
+        let counter = Arc::new(AtomicU32::new(0));
+        let mut set = PollerSet::new().with_backoff_policy(test_backoff());
+        assert!(set.is_empty());
+        assert_eq!(set.push(test_poller("op-0", 3, counter.clone())), 0);
+        assert_eq!(set.push(test_poller("op-1", 1, counter.clone())), 1);
+        assert_eq!(set.push(test_poller("op-2", 2, counter.clone())), 2);
+        assert_eq!(set.len(), 3);
+
+        let start = tokio::time::Instant::now();
+        let mut got = Vec::new();
+        let mut stream = set.into_stream();
+        while let Some((index, result)) = stream.next().await {
+            got.push((index, result?, start.elapsed()));
+        }
+        let want = vec![
+            (1, wkt::Duration::clamp(1, 0), Duration::ZERO),
+            (2, wkt::Duration::clamp(2, 0), Duration::from_secs(1)),
+            (0, wkt::Duration::clamp(3, 0), Duration::from_secs(2)),
+        ];
+        assert_eq!(got, want);
+        assert_eq!(counter.load(Ordering::SeqCst), 6);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn max_concurrent_polls() -> anyhow::Result<()> {
+        let in_flight = Arc::new(AtomicU32::new(0));
+        let max_in_flight = Arc::new(AtomicU32::new(0));
+        let make = |name: &'static str| {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            let query = move |name: String| {
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                async move {
+                    let n = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(n, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, gax::error::Error>(TestOperation::new(completed(&name, 1)))
+                }
+            };
+            new_poller(
+                Arc::new(AlwaysContinue),
+                Arc::new(test_backoff()),
+                no_start,
+                query,
+            )
+            .resume(name)
+        };
+        let mut set = PollerSet::new().with_max_concurrent_polls(2);
+        for name in ["op-0", "op-1", "op-2", "op-3", "op-4"] {
+            set.push(make(name));
+        }
+        let results = set.into_stream().collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 5);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn list_operations() -> anyhow::Result<()> {
+        let counter = Arc::new(AtomicU32::new(0));
+        let listed = Arc::new(Mutex::new(vec![
+            vec![in_progress("op-0"), in_progress("op-1")],
+            vec![in_progress("op-0"), completed("op-1", 2)],
+            vec![completed("op-0", 3)],
+        ]));
+        let list = {
+            let listed = listed.clone();
+            move || {
+                let listed = listed.clone();
+                async move {
+                    let mut listed = listed.lock().unwrap();
+                    if listed.is_empty() {
+                        return Err(gax::error::Error::io("exhausted"));
+                    }
+                    Ok(listed.remove(0))
+                }
+            }
+        };
+        let mut set = PollerSet::new()
+            .with_backoff_policy(test_backoff())
+            .with_list_operations(list);
+        // The first round always polls each operation, after that the
+        // operations are only polled once `list` reports them as done.
+        set.push(test_poller("op-0", 2, counter.clone()));
+        set.push(test_poller("op-1", 2, counter.clone()));
+
+        let mut got = Vec::new();
+        let mut stream = set.into_stream();
+        while let Some((index, result)) = stream.next().await {
+            got.push((index, result?));
+        }
+        let want = vec![
+            (1, wkt::Duration::clamp(2, 0)),
+            (0, wkt::Duration::clamp(2, 0)),
+        ];
+        assert_eq!(got, want);
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+        assert!(listed.lock().unwrap().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn list_operations_error() -> anyhow::Result<()> {
+        let counter = Arc::new(AtomicU32::new(0));
+        let list = || async { Err(gax::error::Error::io("simulated")) };
+        let mut set = PollerSet::new()
+            .with_backoff_policy(test_backoff())
+            .with_list_operations(list);
+        set.push(test_poller("op-0", 3, counter.clone()));
+
+        let results = set.into_stream().collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 1);
+        // Without list results each operation is polled in each round.
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn errors() -> anyhow::Result<()> {
+        let query = |_: String| async move {
+            Err::<TestOperation, _>(gax::error::Error::service(
+                gax::error::rpc::Status::default()
+                    .set_code(gax::error::rpc::Code::NotFound)
+                    .set_message("not found"),
+            ))
+        };
+        let poller = new_poller(
+            Arc::new(gax::polling_error_policy::Aip194Strict),
+            Arc::new(test_backoff()),
+            no_start,
+            query,
+        )
+        .resume("op-0");
+        let mut set = PollerSet::new();
+        set.push(poller);
+        let results = set.into_stream().collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 1);
+        let (index, result) = &results[0];
+        assert_eq!(*index, 0);
+        let err = result.as_ref().unwrap_err();
+        assert_eq!(
+            err.status().map(|s| s.code),
+            Some(gax::error::rpc::Code::NotFound),
+            "{err:?}"
+        );
+        Ok(())
+    }
+}
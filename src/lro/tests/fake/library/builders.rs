@@ -86,15 +86,27 @@ impl CreateResource {
             }
         };
 
+        let stub = self.stub.clone();
+        let cancel = move |name| {
+            let stub = stub.clone();
+            async {
+                super::builders::CancelOperation::new(stub)
+                    .set_name(name)
+                    .send()
+                    .await
+            }
+        };
+
         let start = move || async {
             let op = self.send().await?;
             Ok(Operation::new(op))
         };
-        google_cloud_lro::internal::new_poller(
+        google_cloud_lro::internal::new_poller_with_cancel(
             polling_error_policy,
             polling_backoff_policy,
             start,
             query,
+            cancel,
         )
     }
 }
@@ -140,3 +152,38 @@ impl GetOperation {
         Ok(r.into_body())
     }
 }
+
+pub struct CancelOperation {
+    inner: ReqwestClient,
+    request: longrunning::model::CancelOperationRequest,
+}
+
+impl CancelOperation {
+    pub fn new(inner: ReqwestClient) -> Self {
+        Self {
+            inner,
+            request: longrunning::model::CancelOperationRequest::default(),
+        }
+    }
+
+    pub fn set_name(mut self, v: impl Into<String>) -> Self {
+        self.request.name = v.into();
+        self
+    }
+
+    pub async fn send(self) -> gax::Result<()> {
+        let builder = self
+            .inner
+            .builder(reqwest::Method::POST, "/cancel".to_string())
+            .query(&[("alt", "json"), ("name", self.request.name.as_str())]);
+        let _: gax::response::Response<wkt::Empty> = self
+            .inner
+            .execute(
+                builder,
+                None::<gaxi::http::NoBody>,
+                gax::options::RequestOptions::default(),
+            )
+            .await?;
+        Ok(())
+    }
+}
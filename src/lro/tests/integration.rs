@@ -178,6 +178,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn until_done_with_progress() -> Result<()> {
+        let create = vec![responses::pending("op/001", 25)?];
+        let poll = vec![
+            responses::pending("op/001", 75)?,
+            responses::success("op/001", "p/test-p/r/r-001")?,
+        ];
+        let (endpoint, _server) = start(ServerState {
+            create: create.into(),
+            poll: poll.into(),
+        })?;
+
+        let client = new_client(endpoint).await?;
+        let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let callback = {
+            let progress = progress.clone();
+            move |m: &model::CreateResourceMetadata| progress.lock().unwrap().push(m.percent)
+        };
+        let mut poller = client
+            .create_resource("test-p", "r-001")
+            .poller()
+            .with_progress(callback);
+        assert_eq!(poller.name(), None);
+        let p = poller.poll().await;
+        assert!(
+            matches!(p, Some(lro::PollingResult::InProgress(_))),
+            "{p:?}"
+        );
+        assert_eq!(poller.name().as_deref(), Some("op/001"));
+        let response = poller.until_done().await?;
+        assert_eq!(
+            response,
+            model::Resource {
+                name: "p/test-p/r/r-001".to_string()
+            }
+        );
+        assert_eq!(*progress.lock().unwrap(), vec![25, 75]);
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn until_done_error() -> Result<()> {
         let create = vec![responses::pending("op/001", 25)?];
@@ -202,6 +243,117 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn resume_until_done_success() -> Result<()> {
+        // The server rejects any attempt to start a new operation.
+        let create = vec![];
+        let poll = vec![
+            responses::pending("op/001", 75)?,
+            responses::success("op/001", "p/test-p/r/r-001")?,
+        ];
+        let (endpoint, _server) = start(ServerState {
+            create: create.into(),
+            poll: poll.into(),
+        })?;
+
+        let client = new_client(endpoint).await?;
+        let response = client
+            .create_resource("test-p", "r-001")
+            .poller()
+            .resume("op/001")
+            .until_done()
+            .await?;
+        assert_eq!(
+            response,
+            model::Resource {
+                name: "p/test-p/r/r-001".to_string()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn resume_poller_success() -> Result<()> {
+        let create = vec![];
+        let poll = vec![
+            responses::pending("op/001", 50)?,
+            responses::success("op/001", "p/test-p/r/r-001")?,
+        ];
+        let (endpoint, _server) = start(ServerState {
+            create: create.into(),
+            poll: poll.into(),
+        })?;
+
+        let client = new_client(endpoint).await?;
+        let mut poller = client
+            .create_resource("test-p", "r-001")
+            .poller()
+            .resume("op/001");
+        let p = poller.poll().await;
+        match p {
+            Some(lro::PollingResult::InProgress(m)) => {
+                assert_eq!(m, Some(model::CreateResourceMetadata { percent: 50 }));
+            }
+            _ => panic!("unexpected polling result {p:?}"),
+        }
+        let p = poller.poll().await;
+        match p {
+            Some(lro::PollingResult::Completed(r)) => {
+                let response = r?;
+                assert_eq!(
+                    response,
+                    model::Resource {
+                        name: "p/test-p/r/r-001".to_string()
+                    }
+                );
+            }
+            _ => panic!("unexpected polling result {p:?}"),
+        }
+        let p = poller.poll().await;
+        assert!(p.is_none(), "{p:?}");
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn poller_cancel() -> Result<()> {
+        use httptest::{Expectation, matchers::*, responders::*};
+
+        let create = vec![responses::pending("op/001", 25)?];
+        let poll = vec![responses::operation_error("op/001")?];
+        let (endpoint, server) = start(ServerState {
+            create: create.into(),
+            poll: poll.into(),
+        })?;
+        server.expect(
+            Expectation::matching(all_of![
+                request::method("POST"),
+                request::path("/cancel"),
+                request::query(url_decoded(contains(("name", "op/001")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({}))),
+        );
+
+        let client = new_client(endpoint).await?;
+        let mut poller = client.create_resource("test-p", "r-001").poller();
+        let err = poller.cancel().await.unwrap_err();
+        assert!(err.is_binding(), "{err:?}");
+
+        let p = poller.poll().await;
+        assert!(
+            matches!(p, Some(lro::PollingResult::InProgress(_))),
+            "{p:?}"
+        );
+        poller.cancel().await?;
+        let p = poller.poll().await;
+        assert!(
+            matches!(p, Some(lro::PollingResult::Completed(Err(_)))),
+            "{p:?}"
+        );
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn poller_immediate_success() -> Result<()> {
         let create = vec![responses::success("op/001", "p/test-p/r/r-001")?];
@@ -24,6 +24,7 @@ mod tests {
     use super::*;
     use generated::test_protos::TestAllTypesProto3;
     use serde_json::json;
+    use test_case::test_case;
 
     #[test]
     fn field13() -> anyhow::Result<()> {
@@ -41,4 +42,12 @@ mod tests {
         assert!(err.is_data());
         Ok(())
     }
+
+    #[test_case(json!({"optionalTimestamp": "0001-01-01t00:00:00Z"}); "lowercase t")]
+    #[test_case(json!({"optionalTimestamp": "0001-01-01T00:00:00z"}); "lowercase z")]
+    #[test_case(json!({"optionalTimestamp": "0001-01-01 00:00:00Z"}); "missing t")]
+    fn timestamp_strict(input: serde_json::Value) {
+        let err = serde_json::from_value::<TestAllTypesProto3>(input).unwrap_err();
+        assert!(err.is_data(), "{err:?}");
+    }
 }
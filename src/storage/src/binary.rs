@@ -0,0 +1,233 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implements the binary protobuf encoding for the Cloud Storage model types.
+//!
+//! The encoding is only available for the messages in `google.storage.v2` and
+//! `google.storage.control.v2`. It reuses the conversions between the model
+//! types and the `prost` types used by the gRPC transport.
+
+/// Implements [BinaryMessage][wkt::message::BinaryMessage] for model types.
+///
+/// Each model type is encoded via the `prost` type with the same relative path
+/// in the `$proto` module.
+macro_rules! binary_message {
+    ($proto:ident, $model:ident; $($($name:ident)::+),* $(,)?) => {
+        $(
+            impl wkt::message::BinaryMessage for $model::$($name)::+ {
+                fn encode_to_vec(&self) -> std::result::Result<std::vec::Vec<u8>, wkt::AnyError> {
+                    gaxi::prost::encode_to_vec::<$proto::$($name)::+, _>(self)
+                }
+
+                fn decode(buf: &[u8]) -> std::result::Result<Self, wkt::AnyError> {
+                    gaxi::prost::decode::<$proto::$($name)::+, _>(buf)
+                }
+            }
+        )*
+
+        /// The relative paths of the model types listed above.
+        #[cfg(test)]
+        pub(super) const NAMES: &[&str] = &[$(stringify!($($name)::+)),*];
+    };
+}
+
+mod storage {
+    use crate::generated::gapic::model;
+    use crate::google::storage::v2 as proto;
+
+    binary_message!(
+        proto, model;
+        DeleteBucketRequest,
+        GetBucketRequest,
+        CreateBucketRequest,
+        ListBucketsRequest,
+        ListBucketsResponse,
+        LockBucketRetentionPolicyRequest,
+        UpdateBucketRequest,
+        compose_object_request::source_object::ObjectPreconditions,
+        compose_object_request::SourceObject,
+        ComposeObjectRequest,
+        DeleteObjectRequest,
+        RestoreObjectRequest,
+        GetObjectRequest,
+        ListObjectsRequest,
+        RewriteObjectRequest,
+        RewriteResponse,
+        MoveObjectRequest,
+        UpdateObjectRequest,
+        CommonObjectRequestParams,
+        bucket::Billing,
+        bucket::Cors,
+        bucket::encryption::GoogleManagedEncryptionEnforcementConfig,
+        bucket::encryption::CustomerManagedEncryptionEnforcementConfig,
+        bucket::encryption::CustomerSuppliedEncryptionEnforcementConfig,
+        bucket::Encryption,
+        bucket::iam_config::UniformBucketLevelAccess,
+        bucket::IamConfig,
+        bucket::lifecycle::rule::Action,
+        bucket::lifecycle::rule::Condition,
+        bucket::lifecycle::Rule,
+        bucket::Lifecycle,
+        bucket::Logging,
+        bucket::ObjectRetention,
+        bucket::RetentionPolicy,
+        bucket::SoftDeletePolicy,
+        bucket::Versioning,
+        bucket::Website,
+        bucket::CustomPlacementConfig,
+        bucket::Autoclass,
+        bucket::ip_filter::PublicNetworkSource,
+        bucket::ip_filter::VpcNetworkSource,
+        bucket::IpFilter,
+        bucket::HierarchicalNamespace,
+        Bucket,
+        BucketAccessControl,
+        ObjectChecksums,
+        ObjectCustomContextPayload,
+        ObjectContexts,
+        CustomerEncryption,
+        object::Retention,
+        Object,
+        ObjectAccessControl,
+        ListObjectsResponse,
+        ProjectTeam,
+        Owner,
+    );
+}
+
+mod control {
+    use crate::generated::gapic_control::model;
+    use crate::google::storage::control::v2 as proto;
+
+    binary_message!(
+        proto, model;
+        PendingRenameInfo,
+        Folder,
+        GetFolderRequest,
+        CreateFolderRequest,
+        DeleteFolderRequest,
+        ListFoldersRequest,
+        ListFoldersResponse,
+        RenameFolderRequest,
+        CommonLongRunningOperationMetadata,
+        RenameFolderMetadata,
+        storage_layout::CustomPlacementConfig,
+        storage_layout::HierarchicalNamespace,
+        StorageLayout,
+        GetStorageLayoutRequest,
+        ManagedFolder,
+        GetManagedFolderRequest,
+        CreateManagedFolderRequest,
+        DeleteManagedFolderRequest,
+        ListManagedFoldersRequest,
+        ListManagedFoldersResponse,
+        CreateAnywhereCacheMetadata,
+        UpdateAnywhereCacheMetadata,
+        AnywhereCache,
+        CreateAnywhereCacheRequest,
+        UpdateAnywhereCacheRequest,
+        DisableAnywhereCacheRequest,
+        PauseAnywhereCacheRequest,
+        ResumeAnywhereCacheRequest,
+        GetAnywhereCacheRequest,
+        ListAnywhereCachesRequest,
+        ListAnywhereCachesResponse,
+        intelligence_config::filter::CloudStorageLocations,
+        intelligence_config::filter::CloudStorageBuckets,
+        intelligence_config::Filter,
+        intelligence_config::EffectiveIntelligenceConfig,
+        intelligence_config::TrialConfig,
+        IntelligenceConfig,
+        UpdateOrganizationIntelligenceConfigRequest,
+        UpdateFolderIntelligenceConfigRequest,
+        UpdateProjectIntelligenceConfigRequest,
+        GetOrganizationIntelligenceConfigRequest,
+        GetFolderIntelligenceConfigRequest,
+        GetProjectIntelligenceConfigRequest,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generated::{gapic::model, gapic_control::model as control};
+    use std::collections::BTreeSet;
+    use test_case::test_case;
+    use wkt::message::BinaryMessage;
+
+    // Detects drift between the lists above and the generated conversions.
+    #[test_case(crate::binary::storage::NAMES, include_str!("generated/convert/storage/convert.rs"); "storage")]
+    #[test_case(crate::binary::control::NAMES, include_str!("generated/convert/control/convert.rs"); "control")]
+    fn lists_match_generated_conversions(names: &[&str], source: &str) {
+        let want = converted_messages(source);
+        assert!(!want.is_empty());
+        assert_eq!(listed(names), want);
+    }
+
+    // Returns the model types with conversions to and from a `prost` message
+    // in a generated `convert.rs` file. Messages are built field by field,
+    // enums (converted to `i32`) and oneofs (converted via `match`) are
+    // skipped.
+    fn converted_messages(source: &str) -> BTreeSet<String> {
+        let lines = source.lines().collect::<Vec<_>>();
+        lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let rest = line.strip_prefix("impl gaxi::prost::ToProto<")?;
+                let (_, model) = rest.split_once("::model::")?;
+                let body = lines.get(i + 3)?.trim();
+                body.starts_with("Ok(Self::Output {")
+                    .then(|| model.trim_end_matches(" {").to_string())
+            })
+            .collect()
+    }
+
+    fn listed(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|n| n.replace(' ', "")).collect()
+    }
+
+    #[test]
+    fn storage_roundtrip() -> anyhow::Result<()> {
+        let input = model::Bucket::new()
+            .set_name("projects/_/buckets/my-bucket")
+            .set_location("US")
+            .set_metageneration(42)
+            .set_versioning(model::bucket::Versioning::new().set_enabled(true));
+        let bytes = input.encode_to_vec()?;
+        let got = model::Bucket::decode(&bytes)?;
+        assert_eq!(got, input);
+        Ok(())
+    }
+
+    #[test]
+    fn control_roundtrip() -> anyhow::Result<()> {
+        let input = control::Folder::new()
+            .set_name("projects/_/buckets/my-bucket/folders/my-folder/")
+            .set_metageneration(42)
+            .set_create_time(wkt::Timestamp::clamp(123, 456));
+        let bytes = input.encode_to_vec()?;
+        let got = control::Folder::decode(&bytes)?;
+        assert_eq!(got, input);
+        Ok(())
+    }
+
+    #[test]
+    fn decode_error() {
+        let got = model::Bucket::decode(&[0xff, 0xff]);
+        assert!(
+            matches!(got, Err(wkt::AnyError::Deserialization(_))),
+            "{got:?}"
+        );
+    }
+}
@@ -43,6 +43,7 @@ pub mod retry_policy;
 pub use crate::storage::checksum;
 pub use crate::storage::streaming_source;
 
+mod binary;
 mod control;
 mod storage;
 
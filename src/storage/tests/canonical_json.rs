@@ -0,0 +1,52 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+    use google_cloud_storage::model::Bucket;
+    use std::collections::HashMap;
+    use wkt::{JsonSerializeOptions, Timestamp};
+
+    #[test]
+    fn equal_buckets_are_byte_identical() -> anyhow::Result<()> {
+        let labels = (0..32)
+            .map(|i| (format!("label-{i:02}"), format!("value-{i}")))
+            .collect::<Vec<_>>();
+        let forward = labels.iter().cloned().collect::<HashMap<_, _>>();
+        let reverse = labels.iter().rev().cloned().collect::<HashMap<_, _>>();
+        let create_time = Timestamp::clamp(1729341296, 500_000_000);
+        let b1 = Bucket::new()
+            .set_name("projects/_/buckets/my-bucket")
+            .set_labels(forward)
+            .set_create_time(create_time);
+        let b2 = Bucket::new()
+            .set_name("projects/_/buckets/my-bucket")
+            .set_labels(reverse)
+            .set_create_time(create_time);
+        assert_eq!(b1, b2);
+
+        let options = JsonSerializeOptions::new().with_canonical(true);
+        let got = options.to_string(&b1)?;
+        assert_eq!(got, options.to_string(&b2)?);
+        assert!(
+            got.contains(r#""createTime":"2024-10-19T12:34:56.500Z""#),
+            "{got}"
+        );
+        assert!(
+            got.contains(r#""labels":{"label-00":"value-0","label-01":"value-1","#),
+            "{got}"
+        );
+        Ok(())
+    }
+}
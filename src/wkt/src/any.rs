@@ -202,6 +202,72 @@ impl Any {
         serializer.deserialize_from_map(map)
     }
 
+    /// Extracts the message using a [TypeRegistry][crate::TypeRegistry].
+    ///
+    /// Use this function when the type of the message is not known at compile
+    /// time. If the type is not in the registry this function returns the JSON
+    /// representation of the [Any].
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Any, AnyError, Duration, TypeRegistry, UnpackedAny};
+    /// let mut registry = TypeRegistry::new();
+    /// registry.register::<Duration>();
+    /// let any = Any::from_msg(&Duration::clamp(123, 456))?;
+    /// let unpacked = any.unpack_dynamic(&registry)?;
+    /// assert!(matches!(unpacked, UnpackedAny::Message(m) if m.is::<Duration>()));
+    /// # Ok::<(), AnyError>(())
+    /// ```
+    pub fn unpack_dynamic(
+        &self,
+        registry: &crate::TypeRegistry,
+    ) -> Result<crate::UnpackedAny, Error> {
+        registry.unpack(self)
+    }
+
+    /// Creates a new [Any] from the binary protobuf encoding of a `T` value.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "prost")]
+    /// # {
+    /// # use google_cloud_wkt::{Any, AnyError, Duration};
+    /// use google_cloud_wkt::message::BinaryMessage;
+    /// let bytes = Duration::clamp(123, 456).encode_to_vec()?;
+    /// let any = Any::from_encoded::<Duration>(&bytes)?;
+    /// assert_eq!(any.to_msg::<Duration>()?, Duration::clamp(123, 456));
+    /// # }
+    /// # Ok::<(), google_cloud_wkt::AnyError>(())
+    /// ```
+    pub fn from_encoded<T>(buf: &[u8]) -> Result<Self, Error>
+    where
+        T: crate::message::BinaryMessage,
+    {
+        Self::from_msg(&T::decode(buf)?)
+    }
+
+    /// Extracts (if possible) a `T` value from the [Any] and returns its
+    /// binary protobuf encoding.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "prost")]
+    /// # {
+    /// # use google_cloud_wkt::{Any, AnyError, Duration};
+    /// use google_cloud_wkt::message::BinaryMessage;
+    /// let any = Any::from_msg(&Duration::clamp(123, 456))?;
+    /// let bytes = any.to_encoded::<Duration>()?;
+    /// assert_eq!(Duration::decode(&bytes)?, Duration::clamp(123, 456));
+    /// # }
+    /// # Ok::<(), google_cloud_wkt::AnyError>(())
+    /// ```
+    pub fn to_encoded<T>(&self) -> Result<Vec<u8>, Error>
+    where
+        T: crate::message::BinaryMessage,
+    {
+        self.to_msg::<T>()?.encode_to_vec()
+    }
+
     fn check_typename(has: &str, want: &str) -> Result<(), Error> {
         if has == want {
             return Ok(());
@@ -0,0 +1,313 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implements [BinaryMessage] for the well-known types using `prost`.
+
+use crate::AnyError as Error;
+use crate::message::BinaryMessage;
+use prost::Message as _;
+
+impl BinaryMessage for crate::Duration {
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        let proto = prost_types::Duration {
+            seconds: self.seconds(),
+            nanos: self.nanos(),
+        };
+        Ok(proto.encode_to_vec())
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, Error> {
+        let proto = prost_types::Duration::decode(buf).map_err(Error::deser)?;
+        crate::Duration::new(proto.seconds, proto.nanos).map_err(Error::deser)
+    }
+}
+
+impl BinaryMessage for crate::Timestamp {
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        let proto = prost_types::Timestamp {
+            seconds: self.seconds(),
+            nanos: self.nanos(),
+        };
+        Ok(proto.encode_to_vec())
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, Error> {
+        let proto = prost_types::Timestamp::decode(buf).map_err(Error::deser)?;
+        crate::Timestamp::new(proto.seconds, proto.nanos).map_err(Error::deser)
+    }
+}
+
+impl BinaryMessage for crate::FieldMask {
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        let proto = prost_types::FieldMask {
+            paths: self.paths.clone(),
+        };
+        Ok(proto.encode_to_vec())
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, Error> {
+        let proto = prost_types::FieldMask::decode(buf).map_err(Error::deser)?;
+        Ok(crate::FieldMask::default().set_paths(proto.paths))
+    }
+}
+
+impl BinaryMessage for crate::Empty {
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        Ok(().encode_to_vec())
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, Error> {
+        <()>::decode(buf).map_err(Error::deser)?;
+        Ok(crate::Empty::default())
+    }
+}
+
+impl BinaryMessage for crate::Struct {
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        Ok(to_struct(self).encode_to_vec())
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, Error> {
+        let proto = prost_types::Struct::decode(buf).map_err(Error::deser)?;
+        from_struct(proto)
+    }
+}
+
+impl BinaryMessage for crate::Value {
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        Ok(to_value(self).encode_to_vec())
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, Error> {
+        let proto = prost_types::Value::decode(buf).map_err(Error::deser)?;
+        from_value(proto)
+    }
+}
+
+impl BinaryMessage for crate::ListValue {
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        Ok(to_list(self).encode_to_vec())
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self, Error> {
+        let proto = prost_types::ListValue::decode(buf).map_err(Error::deser)?;
+        from_list(proto)
+    }
+}
+
+fn to_struct(value: &crate::Struct) -> prost_types::Struct {
+    prost_types::Struct {
+        fields: value
+            .iter()
+            .map(|(k, v)| (k.clone(), to_value(v)))
+            .collect(),
+    }
+}
+
+fn to_list(value: &crate::ListValue) -> prost_types::ListValue {
+    prost_types::ListValue {
+        values: value.iter().map(to_value).collect(),
+    }
+}
+
+fn to_value(value: &crate::Value) -> prost_types::Value {
+    use prost_types::value::Kind;
+    let kind = match value {
+        serde_json::Value::Null => Kind::NullValue(prost_types::NullValue::NullValue as i32),
+        serde_json::Value::Bool(v) => Kind::BoolValue(*v),
+        serde_json::Value::Number(v) => Kind::NumberValue(v.as_f64().unwrap_or_default()),
+        serde_json::Value::String(v) => Kind::StringValue(v.clone()),
+        serde_json::Value::Array(v) => Kind::ListValue(to_list(v)),
+        serde_json::Value::Object(v) => Kind::StructValue(to_struct(v)),
+    };
+    prost_types::Value { kind: Some(kind) }
+}
+
+fn from_struct(proto: prost_types::Struct) -> Result<crate::Struct, Error> {
+    proto
+        .fields
+        .into_iter()
+        .map(|(k, v)| from_value(v).map(|v| (k, v)))
+        .collect()
+}
+
+fn from_list(proto: prost_types::ListValue) -> Result<crate::ListValue, Error> {
+    proto.values.into_iter().map(from_value).collect()
+}
+
+fn from_value(proto: prost_types::Value) -> Result<crate::Value, Error> {
+    use prost_types::value::Kind;
+    let value = match proto.kind {
+        None | Some(Kind::NullValue(_)) => serde_json::Value::Null,
+        Some(Kind::BoolValue(v)) => serde_json::Value::Bool(v),
+        Some(Kind::NumberValue(v)) => serde_json::Number::from_f64(v)
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| Error::deser(format!("cannot represent {v} as a JSON number")))?,
+        Some(Kind::StringValue(v)) => serde_json::Value::String(v),
+        Some(Kind::ListValue(v)) => serde_json::Value::Array(from_list(v)?),
+        Some(Kind::StructValue(v)) => serde_json::Value::Object(from_struct(v)?),
+    };
+    Ok(value)
+}
+
+macro_rules! impl_binary_message {
+    ($t: ty) => {
+        impl BinaryMessage for $t {
+            fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+                Ok(prost::Message::encode_to_vec(self))
+            }
+
+            fn decode(buf: &[u8]) -> Result<Self, Error> {
+                <$t as prost::Message>::decode(buf).map_err(Error::deser)
+            }
+        }
+    };
+}
+
+impl_binary_message!(crate::DoubleValue);
+impl_binary_message!(crate::FloatValue);
+impl_binary_message!(crate::Int64Value);
+impl_binary_message!(crate::UInt64Value);
+impl_binary_message!(crate::Int32Value);
+impl_binary_message!(crate::UInt32Value);
+impl_binary_message!(crate::BoolValue);
+impl_binary_message!(crate::StringValue);
+impl_binary_message!(crate::BytesValue);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Any, Duration, Empty, FieldMask, Timestamp};
+    use serde_json::json;
+    use test_case::test_case;
+    type Result = anyhow::Result<()>;
+
+    fn roundtrip<T>(input: T) -> Result
+    where
+        T: BinaryMessage + std::fmt::Debug + PartialEq,
+    {
+        let bytes = input.encode_to_vec()?;
+        let got = T::decode(&bytes)?;
+        assert_eq!(got, input);
+        Ok(())
+    }
+
+    #[test]
+    fn duration() -> Result {
+        roundtrip(Duration::clamp(123, 456))?;
+        let bytes = Duration::clamp(123, 456).encode_to_vec()?;
+        let proto = prost_types::Duration::decode(bytes.as_slice())?;
+        assert_eq!(
+            proto,
+            prost_types::Duration {
+                seconds: 123,
+                nanos: 456
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duration_out_of_range() -> Result {
+        let proto = prost_types::Duration {
+            seconds: 1,
+            nanos: -1,
+        };
+        let got = Duration::decode(&proto.encode_to_vec());
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn timestamp() -> Result {
+        roundtrip(Timestamp::clamp(123, 456))?;
+        let proto = prost_types::Timestamp {
+            seconds: 123,
+            nanos: 456,
+        };
+        let got = Timestamp::decode(&proto.encode_to_vec())?;
+        assert_eq!(got, Timestamp::clamp(123, 456));
+        Ok(())
+    }
+
+    #[test]
+    fn field_mask() -> Result {
+        roundtrip(FieldMask::default().set_paths(["a", "b.c"]))
+    }
+
+    #[test]
+    fn empty() -> Result {
+        let bytes = Empty::default().encode_to_vec()?;
+        assert!(bytes.is_empty(), "{bytes:?}");
+        roundtrip(Empty::default())
+    }
+
+    #[test_case(json!(null))]
+    #[test_case(json!(true))]
+    #[test_case(json!(1.5))]
+    #[test_case(json!("abc"))]
+    #[test_case(json!([1.0, "a", null]))]
+    #[test_case(json!({"a": {"b": [true, 2.0]}}))]
+    fn value(input: crate::Value) -> Result {
+        roundtrip(input)
+    }
+
+    #[test]
+    fn value_nan() -> Result {
+        let proto = prost_types::Value {
+            kind: Some(prost_types::value::Kind::NumberValue(f64::NAN)),
+        };
+        let got = crate::Value::decode(&proto.encode_to_vec());
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn struct_and_list() -> Result {
+        let value = json!({"a": 1.0, "b": ["x", {"c": false}]});
+        roundtrip(value.as_object().cloned().unwrap())?;
+        roundtrip(vec![json!("x"), json!(2.0)])
+    }
+
+    #[test]
+    fn wrappers() -> Result {
+        roundtrip(1.5_f64)?;
+        roundtrip(2.5_f32)?;
+        roundtrip(-3_i64)?;
+        roundtrip(4_u64)?;
+        roundtrip(-5_i32)?;
+        roundtrip(6_u32)?;
+        roundtrip(true)?;
+        roundtrip("abc".to_string())?;
+        roundtrip(bytes::Bytes::from_static(b"abc"))
+    }
+
+    #[test]
+    fn decode_error() {
+        let got = Duration::decode(&[0xff, 0xff]);
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+    }
+
+    #[test]
+    fn any() -> Result {
+        let bytes = Duration::clamp(123, 456).encode_to_vec()?;
+        let any = Any::from_encoded::<Duration>(&bytes)?;
+        assert_eq!(any.to_msg::<Duration>()?, Duration::clamp(123, 456));
+        assert_eq!(any.to_encoded::<Duration>()?, bytes);
+
+        let got = any.to_encoded::<Timestamp>();
+        assert!(matches!(got, Err(Error::TypeMismatch { .. })), "{got:?}");
+        Ok(())
+    }
+}
@@ -42,7 +42,7 @@
 /// encoded in JSON format as "3s", while 3 seconds and 1 nanosecond should
 /// be expressed in JSON format as "3.000000001s", and 3 seconds and 1
 /// microsecond should be expressed in JSON format as "3.000001s".
-#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
 pub struct Duration {
     /// Signed seconds of the span of time.
@@ -233,6 +233,181 @@ impl Duration {
     pub fn nanos(&self) -> i32 {
         self.nanos
     }
+
+    /// Returns `self + rhs`, or `None` if the result is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::Duration;
+    /// let d = Duration::clamp(1, 600_000_000).checked_add(Duration::clamp(0, 500_000_000));
+    /// assert_eq!(d, Some(Duration::clamp(2, 100_000_000)));
+    /// let d = Duration::clamp(Duration::MAX_SECONDS, 0).checked_add(Duration::clamp(1, 0));
+    /// assert_eq!(d, None);
+    /// ```
+    pub fn checked_add(self, rhs: Duration) -> Option<Duration> {
+        Self::from_nanos(self.as_nanos() + rhs.as_nanos())
+    }
+
+    /// Returns `self - rhs`, or `None` if the result is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::Duration;
+    /// let d = Duration::clamp(1, 0).checked_sub(Duration::clamp(0, 500_000_000));
+    /// assert_eq!(d, Some(Duration::clamp(0, 500_000_000)));
+    /// let d = Duration::clamp(Duration::MIN_SECONDS, 0).checked_sub(Duration::clamp(1, 0));
+    /// assert_eq!(d, None);
+    /// ```
+    pub fn checked_sub(self, rhs: Duration) -> Option<Duration> {
+        Self::from_nanos(self.as_nanos() - rhs.as_nanos())
+    }
+
+    /// Returns `self + rhs`, saturating at the minimum or maximum valid
+    /// [Duration].
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::Duration;
+    /// let d = Duration::clamp(Duration::MAX_SECONDS, 0).saturating_add(Duration::clamp(1, 0));
+    /// assert_eq!(d, Duration::clamp(Duration::MAX_SECONDS, Duration::MAX_NANOS));
+    /// ```
+    pub fn saturating_add(self, rhs: Duration) -> Duration {
+        Self::saturating_from_nanos(self.as_nanos() + rhs.as_nanos())
+    }
+
+    /// Returns `self - rhs`, saturating at the minimum or maximum valid
+    /// [Duration].
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::Duration;
+    /// let d = Duration::clamp(Duration::MIN_SECONDS, 0).saturating_sub(Duration::clamp(1, 0));
+    /// assert_eq!(d, Duration::clamp(Duration::MIN_SECONDS, Duration::MIN_NANOS));
+    /// ```
+    pub fn saturating_sub(self, rhs: Duration) -> Duration {
+        Self::saturating_from_nanos(self.as_nanos() - rhs.as_nanos())
+    }
+
+    /// Returns true if the duration is less than zero.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::Duration;
+    /// assert!(Duration::clamp(-1, 0).is_negative());
+    /// assert!(!Duration::clamp(0, 0).is_negative());
+    /// ```
+    pub fn is_negative(&self) -> bool {
+        self.seconds < 0 || self.nanos < 0
+    }
+
+    /// Returns the absolute value of the duration.
+    ///
+    /// This never overflows, the range of valid durations is symmetric.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::Duration;
+    /// assert_eq!(Duration::clamp(-1, -5).abs(), Duration::clamp(1, 5));
+    /// ```
+    pub fn abs(self) -> Duration {
+        if self.is_negative() { -self } else { self }
+    }
+
+    /// The total number of nanoseconds in the duration.
+    pub(crate) fn as_nanos(&self) -> i128 {
+        self.seconds as i128 * NS + self.nanos as i128
+    }
+
+    /// Creates a duration from the total number of nanoseconds, returns `None`
+    /// if the value is out of range.
+    pub(crate) fn from_nanos(nanos: i128) -> Option<Self> {
+        let seconds = i64::try_from(nanos / NS).ok()?;
+        Self::new(seconds, (nanos % NS) as i32).ok()
+    }
+
+    fn saturating_from_nanos(nanos: i128) -> Self {
+        let min = Self::MIN_SECONDS as i128 * NS + Self::MIN_NANOS as i128;
+        let max = Self::MAX_SECONDS as i128 * NS + Self::MAX_NANOS as i128;
+        Self::from_nanos(nanos.clamp(min, max)).expect("clamped value is in range")
+    }
+}
+
+const NS: i128 = 1_000_000_000;
+const EXPECT_DURATION_ADD: &str = "overflow when adding durations";
+const EXPECT_DURATION_SUB: &str = "overflow when subtracting durations";
+
+/// Adds two durations.
+///
+/// # Panics
+///
+/// Panics if the result is out of range. Use
+/// [checked_add()][Duration::checked_add] or
+/// [saturating_add()][Duration::saturating_add] to handle overflows.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::Duration;
+/// let d = Duration::clamp(1, 0) + Duration::clamp(2, 500);
+/// assert_eq!(d, Duration::clamp(3, 500));
+/// ```
+impl std::ops::Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Duration) -> Duration {
+        self.checked_add(rhs).expect(EXPECT_DURATION_ADD)
+    }
+}
+
+impl std::ops::AddAssign for Duration {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+/// Subtracts two durations.
+///
+/// # Panics
+///
+/// Panics if the result is out of range. Use
+/// [checked_sub()][Duration::checked_sub] or
+/// [saturating_sub()][Duration::saturating_sub] to handle overflows.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::Duration;
+/// let d = Duration::clamp(1, 0) - Duration::clamp(2, 500);
+/// assert_eq!(d, Duration::clamp(-1, -500));
+/// ```
+impl std::ops::Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, rhs: Duration) -> Duration {
+        self.checked_sub(rhs).expect(EXPECT_DURATION_SUB)
+    }
+}
+
+impl std::ops::SubAssign for Duration {
+    fn sub_assign(&mut self, rhs: Duration) {
+        *self = *self - rhs;
+    }
+}
+
+/// Negates a duration.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::Duration;
+/// assert_eq!(-Duration::clamp(1, 500), Duration::clamp(-1, -500));
+/// ```
+impl std::ops::Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Duration {
+        Self {
+            seconds: -self.seconds,
+            nanos: -self.nanos,
+        }
+    }
 }
 
 impl crate::message::Message for Duration {
@@ -468,6 +643,48 @@ impl From<Duration> for chrono::Duration {
     }
 }
 
+/// Converts from [jiff::SignedDuration] to [Duration].
+///
+/// The conversion may fail if the input value is out of range.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Duration, DurationError};
+/// let d = Duration::try_from(jiff::SignedDuration::new(12, 340_000_000))?;
+/// assert_eq!(d.seconds(), 12);
+/// assert_eq!(d.nanos(), 340_000_000);
+/// # Ok::<(), DurationError>(())
+/// ```
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl TryFrom<jiff::SignedDuration> for Duration {
+    type Error = DurationError;
+
+    fn try_from(value: jiff::SignedDuration) -> Result<Self, Self::Error> {
+        Self::new(value.as_secs(), value.subsec_nanos())
+    }
+}
+
+/// Converts from [Duration] to [jiff::SignedDuration].
+///
+/// This conversion is always safe because the range for [Duration] is
+/// guaranteed to fit into the destination type.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::Duration;
+/// let d = jiff::SignedDuration::from(Duration::clamp(12, 340_000_000));
+/// assert_eq!(d.as_secs(), 12);
+/// assert_eq!(d.subsec_nanos(), 340_000_000);
+/// ```
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl From<Duration> for jiff::SignedDuration {
+    fn from(value: Duration) -> Self {
+        Self::new(value.seconds, value.nanos)
+    }
+}
+
 /// Implement [`serde`](::serde) serialization for [Duration].
 #[cfg_attr(not(feature = "_internal-semver"), doc(hidden))]
 impl serde::ser::Serialize for Duration {
@@ -763,4 +980,107 @@ mod tests {
         let got = Duration::try_from(value);
         assert!(matches!(got, Err(DurationError::OutOfRange)), "{got:?}");
     }
+
+    #[test_case(Duration::clamp(1, 600_000_000), Duration::clamp(0, 500_000_000), Duration::clamp(2, 100_000_000) ; "carry")]
+    #[test_case(Duration::clamp(1, 0), Duration::clamp(-2, -500), Duration::clamp(-1, -500) ; "change sign")]
+    #[test_case(Duration::clamp(-1, -600_000_000), Duration::clamp(0, -500_000_000), Duration::clamp(-2, -100_000_000) ; "negative carry")]
+    fn add(a: Duration, b: Duration, want: Duration) {
+        assert_eq!(a.checked_add(b), Some(want));
+        assert_eq!(a.saturating_add(b), want);
+        assert_eq!(a + b, want);
+        let mut got = a;
+        got += b;
+        assert_eq!(got, want);
+
+        assert_eq!(want.checked_sub(b), Some(a));
+        assert_eq!(want.saturating_sub(b), a);
+        assert_eq!(want - b, a);
+        let mut got = want;
+        got -= b;
+        assert_eq!(got, a);
+    }
+
+    #[test]
+    fn add_overflow() {
+        let max = Duration::clamp(Duration::MAX_SECONDS, Duration::MAX_NANOS);
+        let min = Duration::clamp(Duration::MIN_SECONDS, Duration::MIN_NANOS);
+        let one = Duration::clamp(0, 1);
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(max.saturating_add(one), max);
+        assert_eq!(min.checked_sub(one), None);
+        assert_eq!(min.saturating_sub(one), min);
+        assert_eq!(max.checked_sub(min), None);
+        assert_eq!(max.saturating_sub(min), max);
+        assert_eq!(min.saturating_sub(max), min);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when adding durations")]
+    fn add_panics() {
+        let _ = Duration::clamp(Duration::MAX_SECONDS, 0) + Duration::clamp(1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when subtracting durations")]
+    fn sub_panics() {
+        let _ = Duration::clamp(Duration::MIN_SECONDS, 0) - Duration::clamp(1, 0);
+    }
+
+    #[test]
+    fn neg_and_abs() {
+        let d = Duration::clamp(1, 500);
+        assert_eq!(-d, Duration::clamp(-1, -500));
+        assert_eq!(-(-d), d);
+        assert!((-d).is_negative());
+        assert!(!d.is_negative());
+        assert!(!Duration::default().is_negative());
+        assert!(Duration::clamp(0, -1).is_negative());
+        assert_eq!((-d).abs(), d);
+        assert_eq!(d.abs(), d);
+        let min = Duration::clamp(Duration::MIN_SECONDS, Duration::MIN_NANOS);
+        assert_eq!(
+            min.abs(),
+            Duration::clamp(Duration::MAX_SECONDS, Duration::MAX_NANOS)
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        let mut values = vec![
+            Duration::clamp(1, 0),
+            Duration::clamp(-1, -500),
+            Duration::clamp(0, 500),
+            Duration::clamp(-1, 0),
+            Duration::clamp(0, -500),
+        ];
+        values.sort();
+        let want = vec![
+            Duration::clamp(-1, -500),
+            Duration::clamp(-1, 0),
+            Duration::clamp(0, -500),
+            Duration::clamp(0, 500),
+            Duration::clamp(1, 0),
+        ];
+        assert_eq!(values, want);
+        let set = std::collections::HashSet::from([Duration::clamp(1, 0), Duration::clamp(1, 0)]);
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test_case(Duration::new(1, 400_000_000).unwrap(), jiff::SignedDuration::new(1, 400_000_000) ; "1.4s")]
+    #[test_case(Duration::new(-1, -400_000_000).unwrap(), jiff::SignedDuration::new(-1, -400_000_000) ; "minus 1.4s")]
+    #[test_case(Duration::new(10_000 * SECONDS_IN_YEAR, 0).unwrap(), jiff::SignedDuration::new(10_000 * SECONDS_IN_YEAR, 0) ; "exactly 10,000 years")]
+    fn jiff_in_range(value: Duration, want: jiff::SignedDuration) -> Result {
+        let got = jiff::SignedDuration::from(value);
+        assert_eq!(got, want);
+        let got = Duration::try_from(want)?;
+        assert_eq!(got, value);
+        Ok(())
+    }
+
+    #[test_case(jiff::SignedDuration::new(10_001 * SECONDS_IN_YEAR, 0) ; "above the range")]
+    #[test_case(jiff::SignedDuration::new(-10_001 * SECONDS_IN_YEAR, 0) ; "below the range")]
+    fn from_jiff_out_of_range(value: jiff::SignedDuration) {
+        let got = Duration::try_from(value);
+        assert!(matches!(got, Err(DurationError::OutOfRange)), "{got:?}");
+    }
 }
@@ -274,6 +274,364 @@ impl FieldMask {
         self.paths = paths.into_iter().map(|v| v.into()).collect();
         self
     }
+
+    /// Creates a mask with the top-level fields that differ between two
+    /// messages.
+    ///
+    /// Use this function to compute the `update_mask` for update RPCs. The
+    /// paths are sorted and use the field names in the protobuf definition
+    /// (`snake_case`). Nested messages are not compared field by field: if any
+    /// field in a nested message changes, the mask contains the top-level
+    /// field.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{FieldMask, FieldMaskError, Struct};
+    /// let old = serde_json::json!({"displayName": "a", "labels": {"k": "v"}});
+    /// let new = serde_json::json!({"displayName": "b", "labels": {"k": "v"}});
+    /// let (old, new) = (old.as_object().unwrap(), new.as_object().unwrap());
+    /// let mask = FieldMask::from_diff::<Struct>(old, new)?;
+    /// assert_eq!(mask.paths, vec!["display_name".to_string()]);
+    /// # Ok::<(), FieldMaskError>(())
+    /// ```
+    pub fn from_diff<T>(old: &T, new: &T) -> Result<Self, FieldMaskError>
+    where
+        T: crate::message::Message,
+    {
+        let old = to_object(old)?;
+        let new = to_object(new)?;
+        let paths = old
+            .keys()
+            .chain(new.keys())
+            .filter(|k| old.get(*k) != new.get(*k))
+            .map(|k| to_snake_case(k))
+            .collect::<Vec<_>>();
+        Ok(Self::default().set_paths(paths).normalize())
+    }
+
+    /// Returns an equivalent mask with sorted paths, and without duplicate or
+    /// redundant paths.
+    ///
+    /// A path is redundant if the mask also contains one of its prefixes. For
+    /// example, `a.b` is redundant if the mask contains `a`. Empty paths are
+    /// removed.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::FieldMask;
+    /// let mask = FieldMask::default().set_paths(["b", "a.c", "a", "b"]);
+    /// assert_eq!(mask.normalize().paths, vec!["a".to_string(), "b".to_string()]);
+    /// ```
+    pub fn normalize(mut self) -> Self {
+        self.paths.retain(|p| !p.is_empty());
+        self.paths.sort();
+        self.paths.dedup();
+        // After sorting, any prefix of a path appears before the path, but not
+        // necessarily immediately before it, e.g. `a`, `a-b`, `a.c`.
+        let mut paths: Vec<String> = Vec::with_capacity(self.paths.len());
+        for path in self.paths {
+            if paths.iter().any(|p| covers(p, &path)) {
+                continue;
+            }
+            paths.push(path);
+        }
+        self.paths = paths;
+        self
+    }
+
+    /// Returns the union of two masks, in normalized form.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::FieldMask;
+    /// let a = FieldMask::default().set_paths(["a.b", "c"]);
+    /// let b = FieldMask::default().set_paths(["a", "d"]);
+    /// assert_eq!(a.union(&b).paths, vec!["a", "c", "d"]);
+    /// ```
+    pub fn union(&self, other: &FieldMask) -> Self {
+        let paths = self.paths.iter().chain(other.paths.iter()).cloned();
+        Self::default().set_paths(paths).normalize()
+    }
+
+    /// Returns the intersection of two masks, in normalized form.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::FieldMask;
+    /// let a = FieldMask::default().set_paths(["a.b", "c"]);
+    /// let b = FieldMask::default().set_paths(["a", "d"]);
+    /// assert_eq!(a.intersect(&b).paths, vec!["a.b"]);
+    /// ```
+    pub fn intersect(&self, other: &FieldMask) -> Self {
+        let mut paths = Vec::new();
+        for p in &self.paths {
+            for q in &other.paths {
+                if covers(p, q) {
+                    paths.push(q.clone());
+                } else if covers(q, p) {
+                    paths.push(p.clone());
+                }
+            }
+        }
+        Self::default().set_paths(paths).normalize()
+    }
+
+    /// Returns true if the mask covers `path`, that is, if the mask contains
+    /// `path` or one of its prefixes.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::FieldMask;
+    /// let mask = FieldMask::default().set_paths(["a.b"]);
+    /// assert!(mask.contains("a.b.c"));
+    /// assert!(!mask.contains("a"));
+    /// ```
+    pub fn contains(&self, path: &str) -> bool {
+        self.paths.iter().any(|p| covers(p, path))
+    }
+
+    /// Verifies that all the paths in the mask are fields of `T`.
+    ///
+    /// Each segment in a path must name a field in the corresponding message,
+    /// using the names in the protobuf definition (`snake_case`). The field
+    /// names are discovered using the JSON serialization of `T`. Paths that
+    /// traverse a map, a repeated field, or a `google.protobuf.Struct` field
+    /// are rejected.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{FieldMask, FieldMaskError};
+    /// #[derive(Default, serde::Deserialize, serde::Serialize)]
+    /// #[serde(rename_all = "camelCase")]
+    /// struct Profile {
+    ///     #[serde(skip_serializing_if = "Option::is_none", default)]
+    ///     display_name: Option<String>,
+    ///     // Generated messages capture unknown fields.
+    ///     #[serde(flatten)]
+    ///     _unknown_fields: serde_json::Map<String, serde_json::Value>,
+    /// }
+    /// impl google_cloud_wkt::message::Message for Profile {
+    ///     fn typename() -> &'static str { "type.googleapis.com/test.Profile" }
+    /// }
+    ///
+    /// let mask = FieldMask::default().set_paths(["display_name"]);
+    /// assert!(mask.validate::<Profile>().is_ok());
+    /// let mask = FieldMask::default().set_paths(["address"]);
+    /// assert!(matches!(mask.validate::<Profile>(), Err(FieldMaskError::InvalidPath { .. })));
+    /// ```
+    pub fn validate<T>(&self) -> Result<(), FieldMaskError>
+    where
+        T: crate::message::Message,
+    {
+        self.paths
+            .iter()
+            .try_for_each(|path| validate_path::<T>(path))
+    }
+
+    /// Copies the fields in the mask from `source` into `target`.
+    ///
+    /// Fields in the mask that are not set in `source` are cleared in
+    /// `target`. Fields not in the mask are unchanged. Unlike the update
+    /// semantics of some services, repeated fields and nested messages named
+    /// in the mask are replaced, not merged.
+    ///
+    /// `target` is unchanged if this function returns an error.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{FieldMask, FieldMaskError, Struct};
+    /// let source = serde_json::json!({"a": "x", "b": {"c": 1.0, "d": 2.0}});
+    /// let source = source.as_object().cloned().unwrap();
+    /// let mut target = Struct::new();
+    /// let mask = FieldMask::default().set_paths(["b.c"]);
+    /// mask.apply(&source, &mut target)?;
+    /// assert_eq!(serde_json::Value::Object(target), serde_json::json!({"b": {"c": 1.0}}));
+    /// # Ok::<(), FieldMaskError>(())
+    /// ```
+    pub fn apply<T>(&self, source: &T, target: &mut T) -> Result<(), FieldMaskError>
+    where
+        T: crate::message::Message,
+    {
+        let source = to_object(source)?;
+        let mut object = to_object(target)?;
+        for path in self.clone().normalize().paths {
+            let segments = json_segments(&path);
+            let value = lookup(&source, &segments).map_err(|_| invalid_path::<T>(&path))?;
+            update(&mut object, &segments, value.cloned()).map_err(|_| invalid_path::<T>(&path))?;
+        }
+        *target = serde_json::from_value(serde_json::Value::Object(object))
+            .map_err(FieldMaskError::deser)?;
+        Ok(())
+    }
+}
+
+/// Represents the errors in [FieldMask] operations.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{FieldMask, FieldMaskError, Duration};
+/// let mask = FieldMask::default().set_paths(["seconds"]);
+/// let mut target = Duration::default();
+/// let err = mask.apply(&Duration::clamp(1, 0), &mut target).unwrap_err();
+/// assert!(matches!(err, FieldMaskError::Serialization(_)));
+/// ```
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum FieldMaskError {
+    /// A path in the mask does not name a field in the message.
+    #[error("the field mask path `{path}` is not valid for {typename}")]
+    InvalidPath {
+        /// The path in the mask.
+        path: String,
+        /// The typename of the message.
+        typename: &'static str,
+    },
+
+    /// Problem serializing the message. The message must serialize to a JSON
+    /// object.
+    #[error("cannot serialize message, source={0}")]
+    Serialization(#[source] BoxedError),
+
+    /// Problem deserializing the message.
+    #[error("cannot deserialize message, source={0}")]
+    Deserialization(#[source] BoxedError),
+}
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+type Map = serde_json::Map<String, serde_json::Value>;
+
+impl FieldMaskError {
+    fn ser<T: Into<BoxedError>>(v: T) -> Self {
+        Self::Serialization(v.into())
+    }
+
+    fn deser<T: Into<BoxedError>>(v: T) -> Self {
+        Self::Deserialization(v.into())
+    }
+}
+
+fn invalid_path<T: crate::message::Message>(path: &str) -> FieldMaskError {
+    FieldMaskError::InvalidPath {
+        path: path.to_string(),
+        typename: T::typename(),
+    }
+}
+
+/// Returns true if `prefix` is `path` or a prefix of `path`.
+fn covers(prefix: &str, path: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+fn to_object<T: crate::message::Message>(message: &T) -> Result<Map, FieldMaskError> {
+    match serde_json::to_value(message).map_err(FieldMaskError::ser)? {
+        serde_json::Value::Object(map) => Ok(map),
+        _ => Err(FieldMaskError::ser(format!(
+            "{} does not serialize to a JSON object",
+            T::typename()
+        ))),
+    }
+}
+
+/// Converts a field mask path into the field names used in JSON.
+fn json_segments(path: &str) -> Vec<String> {
+    path.split('.').map(to_camel_case).collect()
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '_' => upper = true,
+            c if upper => {
+                result.push(c.to_ascii_uppercase());
+                upper = false;
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            result.push('_');
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn validate_path<T: crate::message::Message>(path: &str) -> Result<(), FieldMaskError> {
+    let segments = json_segments(path);
+    if segments.iter().any(String::is_empty) {
+        return Err(invalid_path::<T>(path));
+    }
+    // Deserialize a message where the field in the path is `null`. If any
+    // segment in the path is not a known field, the generated code preserves
+    // it as an unknown field, with the `null` value.
+    let json = segments
+        .iter()
+        .rev()
+        .fold(serde_json::Value::Null, |value, segment| {
+            serde_json::Value::Object(Map::from_iter([(segment.clone(), value)]))
+        });
+    let message = serde_json::from_value::<T>(json).map_err(|_| invalid_path::<T>(path))?;
+    let mut object = &to_object(&message)?;
+    for segment in &segments {
+        match object.get(segment) {
+            Some(serde_json::Value::Null) => return Err(invalid_path::<T>(path)),
+            Some(serde_json::Value::Object(o)) => object = o,
+            _ => return Ok(()),
+        }
+    }
+    Ok(())
+}
+
+/// Finds the value at `segments`, returns `None` if the field is not set.
+fn lookup<'a>(object: &'a Map, segments: &[String]) -> Result<Option<&'a serde_json::Value>, ()> {
+    let (last, parents) = segments.split_last().ok_or(())?;
+    let mut object = object;
+    for segment in parents {
+        match object.get(segment) {
+            None => return Ok(None),
+            Some(serde_json::Value::Object(o)) => object = o,
+            Some(_) => return Err(()),
+        }
+    }
+    Ok(object.get(last))
+}
+
+/// Sets (or clears if `value` is `None`) the value at `segments`.
+fn update(
+    object: &mut Map,
+    segments: &[String],
+    value: Option<serde_json::Value>,
+) -> Result<(), ()> {
+    let (last, parents) = segments.split_last().ok_or(())?;
+    let mut object = object;
+    for segment in parents {
+        if value.is_none() && !object.contains_key(segment) {
+            return Ok(());
+        }
+        let entry = object
+            .entry(segment.clone())
+            .or_insert_with(|| serde_json::Value::Object(Map::new()));
+        match entry {
+            serde_json::Value::Object(o) => object = o,
+            _ => return Err(()),
+        }
+    }
+    match value {
+        None => object.remove(last),
+        Some(v) => object.insert(last.clone(), v),
+    };
+    Ok(())
 }
 
 impl crate::message::Message for FieldMask {
@@ -360,6 +718,217 @@ mod tests {
         Ok(())
     }
 
+    // Generated messages treat `null` as the default value.
+    #[serde_with::serde_as]
+    #[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Profile {
+        #[serde(skip_serializing_if = "String::is_empty", default)]
+        #[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+        display_name: String,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        user: Option<User>,
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        #[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+        tags: Vec<String>,
+        #[serde(skip_serializing_if = "std::collections::HashMap::is_empty", default)]
+        #[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+        labels: std::collections::HashMap<String, String>,
+        #[serde(flatten)]
+        _unknown_fields: serde_json::Map<String, serde_json::Value>,
+    }
+
+    #[serde_with::serde_as]
+    #[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct User {
+        #[serde(skip_serializing_if = "String::is_empty", default)]
+        #[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+        given_name: String,
+        #[serde(skip_serializing_if = "String::is_empty", default)]
+        #[serde_as(deserialize_as = "serde_with::DefaultOnNull")]
+        family_name: String,
+        #[serde(flatten)]
+        _unknown_fields: serde_json::Map<String, serde_json::Value>,
+    }
+
+    impl crate::message::Message for Profile {
+        fn typename() -> &'static str {
+            "type.googleapis.com/test.Profile"
+        }
+    }
+
+    fn user(given_name: &str, family_name: &str) -> User {
+        User {
+            given_name: given_name.into(),
+            family_name: family_name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_diff() -> Result {
+        let old = Profile {
+            display_name: "a".into(),
+            user: Some(user("g", "f")),
+            tags: vec!["t".into()],
+            ..Default::default()
+        };
+        let new = Profile {
+            display_name: "b".into(),
+            user: Some(user("g", "f2")),
+            labels: [("k".to_string(), "v".to_string())].into(),
+            ..Default::default()
+        };
+        let mask = FieldMask::from_diff(&old, &new)?;
+        assert_eq!(mask.paths, vec!["display_name", "labels", "tags", "user"]);
+
+        let mask = FieldMask::from_diff(&old, &old)?;
+        assert!(mask.paths.is_empty(), "{mask:?}");
+        Ok(())
+    }
+
+    #[test_case(vec![], vec![])]
+    #[test_case(vec!["a", "", "a"], vec!["a"])]
+    #[test_case(vec!["b", "a.c", "a"], vec!["a", "b"])]
+    #[test_case(vec!["a.b.c", "a.b", "a-b", "ab"], vec!["a-b", "a.b", "ab"])]
+    #[test_case(vec!["a.c", "a.b", "b.a"], vec!["a.b", "a.c", "b.a"])]
+    fn normalize(input: Vec<&str>, want: Vec<&str>) {
+        let got = FieldMask::default().set_paths(input).normalize();
+        assert_eq!(got.paths, want);
+    }
+
+    #[test_case(vec!["a.b", "c"], vec!["a", "d"], vec!["a", "c", "d"])]
+    #[test_case(vec![], vec!["a"], vec!["a"])]
+    #[test_case(vec!["a.b"], vec!["a.c"], vec!["a.b", "a.c"])]
+    fn union(a: Vec<&str>, b: Vec<&str>, want: Vec<&str>) {
+        let a = FieldMask::default().set_paths(a);
+        let b = FieldMask::default().set_paths(b);
+        assert_eq!(a.union(&b).paths, want);
+        assert_eq!(b.union(&a).paths, want);
+    }
+
+    #[test_case(vec!["a.b", "c"], vec!["a", "d"], vec!["a.b"])]
+    #[test_case(vec![], vec!["a"], vec![])]
+    #[test_case(vec!["a.b"], vec!["a.c"], vec![])]
+    #[test_case(vec!["a", "b.c", "b.d"], vec!["a.x", "b"], vec!["a.x", "b.c", "b.d"])]
+    #[test_case(vec!["ab"], vec!["a"], vec![])]
+    fn intersect(a: Vec<&str>, b: Vec<&str>, want: Vec<&str>) {
+        let a = FieldMask::default().set_paths(a);
+        let b = FieldMask::default().set_paths(b);
+        assert_eq!(a.intersect(&b).paths, want);
+        assert_eq!(b.intersect(&a).paths, want);
+    }
+
+    #[test]
+    fn contains() {
+        let mask = FieldMask::default().set_paths(["a.b", "c"]);
+        assert!(mask.contains("a.b"), "{mask:?}");
+        assert!(mask.contains("a.b.c"), "{mask:?}");
+        assert!(mask.contains("c"), "{mask:?}");
+        assert!(!mask.contains("a"), "{mask:?}");
+        assert!(!mask.contains("a.bc"), "{mask:?}");
+        assert!(!mask.contains("d"), "{mask:?}");
+    }
+
+    #[test_case("display_name")]
+    #[test_case("user")]
+    #[test_case("user.given_name")]
+    #[test_case("tags")]
+    #[test_case("labels")]
+    fn validate_success(path: &str) -> Result {
+        FieldMask::default()
+            .set_paths([path])
+            .validate::<Profile>()?;
+        Ok(())
+    }
+
+    #[test_case("")]
+    #[test_case("address")]
+    #[test_case("user.address")]
+    #[test_case("user..given_name")]
+    #[test_case("display_name.x")]
+    #[test_case("tags.x")]
+    #[test_case("labels.k")]
+    fn validate_error(path: &str) {
+        let mask = FieldMask::default().set_paths(["display_name", path]);
+        let err = mask.validate::<Profile>().unwrap_err();
+        assert!(
+            matches!(&err, FieldMaskError::InvalidPath { path: p, typename } if p == path && *typename == "type.googleapis.com/test.Profile"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn apply() -> Result {
+        let source = Profile {
+            display_name: "new".into(),
+            user: Some(user("g2", "f2")),
+            tags: vec!["t2".into()],
+            ..Default::default()
+        };
+        let mut target = Profile {
+            display_name: "old".into(),
+            user: Some(user("g1", "f1")),
+            tags: vec!["t1".into()],
+            labels: [("k".to_string(), "v".to_string())].into(),
+            ..Default::default()
+        };
+        let mask = FieldMask::default().set_paths(["display_name", "user.family_name", "labels"]);
+        mask.apply(&source, &mut target)?;
+        let want = Profile {
+            display_name: "new".into(),
+            user: Some(user("g1", "f2")),
+            tags: vec!["t1".into()],
+            ..Default::default()
+        };
+        assert_eq!(target, want);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_creates_parents() -> Result {
+        let source = Profile {
+            user: Some(user("g", "f")),
+            ..Default::default()
+        };
+        let mut target = Profile::default();
+        let mask = FieldMask::default().set_paths(["user.given_name"]);
+        mask.apply(&source, &mut target)?;
+        assert_eq!(target.user, Some(user("g", "")));
+
+        let mut target = Profile::default();
+        let mask = FieldMask::default().set_paths(["display_name.x", "user.family_name"]);
+        let source = Profile::default();
+        mask.apply(&source, &mut target)?;
+        assert_eq!(target, Profile::default());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_error() {
+        let source = Profile {
+            display_name: "new".into(),
+            ..Default::default()
+        };
+        let mut target = Profile {
+            display_name: "old".into(),
+            ..Default::default()
+        };
+        let mask = FieldMask::default().set_paths(["display_name.x"]);
+        let err = mask.apply(&source, &mut target).unwrap_err();
+        assert!(matches!(err, FieldMaskError::InvalidPath { .. }), "{err:?}");
+        assert_eq!(target.display_name, "old");
+    }
+
+    #[test_case("a", "a")]
+    #[test_case("display_name", "displayName")]
+    #[test_case("a_b_c", "aBC")]
+    fn case_conversion(snake: &str, camel: &str) {
+        assert_eq!(to_camel_case(snake), camel);
+        assert_eq!(to_snake_case(camel), snake);
+    }
+
     #[test]
     fn deserialize_unexpected_input_type() -> Result {
         let err = serde_json::from_value::<FieldMask>(json!({"paths": {"a": "b"}})).unwrap_err();
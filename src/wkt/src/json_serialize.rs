@@ -0,0 +1,421 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::ser::Serialize;
+use serde_json::{Map, Value};
+
+/// Options to serialize messages to their [ProtoJSON] representation.
+///
+/// The default options produce the same output as [serde_json]. The order of
+/// the keys in map fields follows the iteration order of the map, and may
+/// change between two serializations of the same message.
+///
+/// Applications that hash or compare serialized messages, for example to
+/// detect configuration drift, should enable the canonical mode. In this mode
+/// equal messages produce byte-identical output.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Duration, JsonSerializeOptions};
+/// let options = JsonSerializeOptions::new().with_canonical(true);
+/// let got = options.to_string(&Duration::clamp(1, 500_000_000))?;
+/// assert_eq!(got, r#""1.500s""#);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+///
+/// [ProtoJSON]: https://protobuf.dev/programming-guides/json/
+#[derive(Clone, Debug, Default)]
+pub struct JsonSerializeOptions {
+    canonical: bool,
+}
+
+impl JsonSerializeOptions {
+    /// Creates the default options, which produce the same output as
+    /// [serde_json].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Produces a canonical representation of the messages.
+    ///
+    /// The canonical mode serializes the value with [serde_json], and then
+    /// rewrites the resulting JSON:
+    ///
+    /// - The output does not contain any whitespace.
+    /// - The keys of all objects are sorted, including map fields, unknown
+    ///   fields, and [Struct][crate::Struct] values.
+    /// - Strings in the [Duration][crate::Duration] or
+    ///   [Timestamp][crate::Timestamp] format use 0, 3, 6, or 9 fractional
+    ///   digits.
+    ///
+    /// The rewrite only sees the JSON, not the message types. Any string with
+    /// the format of a duration (e.g. `"1.5s"`) or a timestamp (e.g.
+    /// `"2024-10-19T12:34:56.5Z"`) is padded, even if the field is a plain
+    /// string. [FieldMask][crate::FieldMask] values are not reordered, use
+    /// [FieldMask::normalize][crate::FieldMask::normalize] before
+    /// serializing the message if the order of the paths is not significant.
+    ///
+    /// Fields with default values are omitted, unless the field tracks
+    /// presence. This is the same behavior as the default mode, so messages
+    /// that differ only in such fields are equal and produce the same output.
+    pub fn with_canonical(mut self, v: bool) -> Self {
+        self.canonical = v;
+        self
+    }
+
+    /// Serializes `value` to a string.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Api, JsonSerializeOptions};
+    /// let api = Api::new().set_name("my-api").set_version("v1");
+    /// let options = JsonSerializeOptions::new().with_canonical(true);
+    /// assert_eq!(options.to_string(&api)?, r#"{"name":"my-api","version":"v1"}"#);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn to_string<T>(&self, value: &T) -> Result<String, serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.canonical {
+            serde_json::to_string(&canonical_value(value)?)
+        } else {
+            serde_json::to_string(value)
+        }
+    }
+
+    /// Serializes `value` to a byte vector.
+    pub fn to_vec<T>(&self, value: &T) -> Result<Vec<u8>, serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.canonical {
+            serde_json::to_vec(&canonical_value(value)?)
+        } else {
+            serde_json::to_vec(value)
+        }
+    }
+
+    /// Serializes `value` to a [serde_json::Value].
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Duration, JsonSerializeOptions};
+    /// let options = JsonSerializeOptions::new().with_canonical(true);
+    /// let got = options.to_value(&Duration::clamp(0, 10_000_000))?;
+    /// assert_eq!(got, serde_json::json!("0.010s"));
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn to_value<T>(&self, value: &T) -> Result<Value, serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.canonical {
+            canonical_value(value)
+        } else {
+            serde_json::to_value(value)
+        }
+    }
+}
+
+fn canonical_value<T>(value: &T) -> Result<Value, serde_json::Error>
+where
+    T: Serialize + ?Sized,
+{
+    serde_json::to_value(value).map(canonicalize)
+}
+
+/// Sorts the keys of all objects and pads the fractional seconds in strings
+/// with the format of a duration or timestamp.
+///
+/// The [serde_json::Map] type may preserve the insertion order, depending on
+/// the features enabled by other crates. This function always inserts the
+/// keys in sorted order.
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonicalize(v)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        Value::String(s) if is_duration(&s) || is_timestamp(&s) => Value::String(pad_fraction(s)),
+        v => v,
+    }
+}
+
+// Matches the ProtoJSON format for durations, e.g. `-1.5s`.
+fn is_duration(s: &str) -> bool {
+    let Some(s) = s.strip_suffix('s') else {
+        return false;
+    };
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let (seconds, nanos) = s.split_once('.').unwrap_or((s, "0"));
+    is_digits(seconds) && is_digits(nanos) && nanos.len() <= 9
+}
+
+// Matches the ProtoJSON format for timestamps, e.g. `2024-10-19T12:34:56.5Z`.
+fn is_timestamp(s: &str) -> bool {
+    let Some(s) = s.strip_suffix('Z') else {
+        return false;
+    };
+    let (datetime, nanos) = s.split_once('.').unwrap_or((s, "0"));
+    let pattern = b"dddd-dd-ddTdd:dd:dd";
+    datetime.len() == pattern.len()
+        && datetime.bytes().zip(pattern).all(|(c, p)| match p {
+            b'd' => c.is_ascii_digit(),
+            p => c == *p,
+        })
+        && is_digits(nanos)
+        && nanos.len() <= 9
+}
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit())
+}
+
+/// Pads the fractional seconds in `s` to 3, 6, or 9 digits.
+///
+/// Both [Duration][crate::Duration] and [Timestamp][crate::Timestamp] format
+/// the fractional seconds with as few digits as possible, and the fraction is
+/// the only part of their representation that contains a `.`.
+fn pad_fraction(s: String) -> String {
+    let Some(dot) = s.find('.') else {
+        return s;
+    };
+    let start = dot + 1;
+    let end = s[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(s.len(), |n| start + n);
+    let padding = (3 - (end - start) % 3) % 3;
+    if padding == 0 {
+        return s;
+    }
+    format!("{}{}{}", &s[..end], "0".repeat(padding), &s[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Any, Duration, FieldMask, Struct, Timestamp};
+    use serde_json::json;
+    use std::collections::{BTreeMap, HashMap};
+    use test_case::test_case;
+
+    type Result = anyhow::Result<()>;
+
+    fn canonical() -> JsonSerializeOptions {
+        JsonSerializeOptions::new().with_canonical(true)
+    }
+
+    #[test_case(0, 0, "0s"; "zero")]
+    #[test_case(1, 500_000_000, "1.500s"; "millis")]
+    #[test_case(-1, -500_000_000, "-1.500s"; "negative")]
+    #[test_case(12, 340_000_000, "12.340s"; "trailing zeros")]
+    #[test_case(0, 123_000, "0.000123s"; "micros")]
+    #[test_case(0, 123_400, "0.000123400s"; "nanos")]
+    #[test_case(0, 1, "0.000000001s"; "one nano")]
+    #[test_case(0, 123_000_000, "0.123s"; "exact millis")]
+    fn duration(seconds: i64, nanos: i32, want: &str) -> Result {
+        let duration = Duration::clamp(seconds, nanos);
+        let got = canonical().to_value(&duration)?;
+        assert_eq!(got, json!(want));
+        // The canonical form is a valid representation of the same value.
+        assert_eq!(serde_json::from_value::<Duration>(got)?, duration);
+        Ok(())
+    }
+
+    #[test_case(0, "2024-10-19T12:34:56Z")]
+    #[test_case(500_000_000, "2024-10-19T12:34:56.500Z")]
+    #[test_case(123_450_000, "2024-10-19T12:34:56.123450Z")]
+    #[test_case(1, "2024-10-19T12:34:56.000000001Z")]
+    fn timestamp(nanos: i32, want: &str) -> Result {
+        let timestamp = Timestamp::clamp(1729341296, nanos);
+        let got = canonical().to_value(&timestamp)?;
+        assert_eq!(got, json!(want));
+        assert_eq!(serde_json::from_value::<Timestamp>(got)?, timestamp);
+        Ok(())
+    }
+
+    #[test_case("1.5s", "1.500s")]
+    #[test_case("-0.0001s", "-0.000100s")]
+    #[test_case("2024-10-19T12:34:56.1234Z", "2024-10-19T12:34:56.123400Z")]
+    #[test_case("1.5", "1.5"; "not a duration")]
+    #[test_case("1.5 s", "1.5 s"; "duration with space")]
+    #[test_case("1.s", "1.s"; "duration without fraction digits")]
+    #[test_case(".5s", ".5s"; "duration without seconds")]
+    #[test_case("0.1234567890s", "0.1234567890s"; "duration with too many digits")]
+    #[test_case("2024-10-19 12:34:56.5Z", "2024-10-19 12:34:56.5Z"; "timestamp with space")]
+    #[test_case("2024-10-19T12:34:56.5+01:00", "2024-10-19T12:34:56.5+01:00"; "timestamp with offset")]
+    #[test_case("v1.5s.5Z", "v1.5s.5Z"; "other")]
+    fn strings(input: &str, want: &str) -> Result {
+        // The canonical mode only sees the JSON, strings with the format of a
+        // duration or timestamp are padded like the well-known types.
+        let got = canonical().to_value(&json!({"f": input}))?;
+        assert_eq!(got, json!({"f": want}));
+        Ok(())
+    }
+
+    #[test]
+    fn field_mask() -> Result {
+        let mask = FieldMask::default().set_paths(["b", "a.c", "a"]);
+        let got = canonical().to_value(&mask)?;
+        assert_eq!(got, json!("b,a.c,a"));
+        let got = canonical().to_value(&mask.normalize())?;
+        assert_eq!(got, json!("a,b"));
+        Ok(())
+    }
+
+    #[test]
+    fn default_mode() -> Result {
+        let options = JsonSerializeOptions::new();
+        let duration = Duration::clamp(1, 500_000_000);
+        assert_eq!(options.to_string(&duration)?, r#""1.5s""#);
+        assert_eq!(options.to_value(&duration)?, json!("1.5s"));
+        assert_eq!(options.to_vec(&duration)?, br#""1.5s""#.to_vec());
+
+        let mask = FieldMask::default().set_paths(["b", "a"]);
+        assert_eq!(options.to_value(&mask)?, json!("b,a"));
+        Ok(())
+    }
+
+    #[test]
+    fn map_keys_are_sorted() -> Result {
+        let input = (0..64)
+            .map(|i| (format!("key-{i:02}"), i))
+            .collect::<HashMap<_, _>>();
+        let want = serde_json::to_string(&input.iter().collect::<BTreeMap<_, _>>())?;
+        assert_eq!(canonical().to_string(&input)?, want);
+        assert_eq!(canonical().to_vec(&input)?, want.into_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn equal_messages() -> Result {
+        let mut labels = HashMap::new();
+        let mut want = String::new();
+        for i in 0..32 {
+            labels.insert(format!("label-{i}"), format!("value-{i}"));
+            let mut entries = labels.clone().into_iter().collect::<Vec<_>>();
+            entries.sort();
+            let copy = entries.into_iter().rev().collect::<HashMap<_, _>>();
+            assert_eq!(labels, copy);
+            let got = canonical().to_string(&labels)?;
+            assert_eq!(got, canonical().to_string(&copy)?);
+            want = got;
+        }
+        assert!(want.starts_with(r#"{"label-0":"value-0","label-1":"value-1","label-10":"#));
+        Ok(())
+    }
+
+    #[test]
+    fn nested() -> Result {
+        let input = serde_json::from_value::<Struct>(json!({
+            "z": {"b": 1, "a": [{"d": true, "c": null}]},
+            "y": "y",
+        }))?;
+        let got = canonical().to_string(&input)?;
+        assert_eq!(got, r#"{"y":"y","z":{"a":[{"c":null,"d":true}],"b":1}}"#);
+        Ok(())
+    }
+
+    #[test]
+    fn message() -> Result {
+        let input = json!({
+            "version": "v1",
+            "name": "my-api",
+            "zUnknown": {"b": 1, "a": 2},
+            "aUnknown": 3,
+        });
+        let api = serde_json::from_value::<crate::Api>(input)?;
+        let got = canonical().to_string(&api)?;
+        assert_eq!(
+            got,
+            r#"{"aUnknown":3,"name":"my-api","version":"v1","zUnknown":{"a":2,"b":1}}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn any() -> Result {
+        let any = Any::from_msg(&Duration::clamp(1, 500_000_000))?;
+        let got = canonical().to_string(&any)?;
+        assert_eq!(
+            got,
+            r#"{"@type":"type.googleapis.com/google.protobuf.Duration","value":"1.500s"}"#
+        );
+        Ok(())
+    }
+
+    #[derive(serde::Serialize)]
+    enum Enum {
+        Unit,
+        Newtype(Duration),
+        Tuple(i32, Duration),
+        Struct { z: i32, a: Duration },
+    }
+
+    #[test_case(Enum::Unit, json!("Unit"))]
+    #[test_case(Enum::Newtype(Duration::clamp(1, 0)), json!({"Newtype": "1s"}))]
+    #[test_case(Enum::Tuple(1, Duration::clamp(0, 1_000_000)), json!({"Tuple": [1, "0.001s"]}))]
+    #[test_case(Enum::Struct { z: 1, a: Duration::clamp(0, 10_000_000) }, json!({"Struct": {"a": "0.010s", "z": 1}}))]
+    fn enums(input: Enum, want: Value) -> Result {
+        assert_eq!(canonical().to_value(&input)?, want);
+        Ok(())
+    }
+
+    #[test]
+    fn scalars() -> Result {
+        #[derive(serde::Serialize)]
+        struct Unit;
+        #[derive(serde::Serialize)]
+        struct Tuple(i32, &'static str);
+        let input = (
+            Some(1_u8),
+            None::<i32>,
+            (),
+            Unit,
+            Tuple(2, "x"),
+            'c',
+            -3_i64,
+            1.5_f64,
+        );
+        let got = canonical().to_value(&input)?;
+        assert_eq!(got, json!([1, null, null, null, [2, "x"], "c", -3, 1.5]));
+        assert_eq!(got, serde_json::to_value(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn map_keys() -> Result {
+        let input = BTreeMap::from([(10, "a"), (9, "b")]);
+        assert_eq!(canonical().to_string(&input)?, r#"{"10":"a","9":"b"}"#);
+
+        let input = BTreeMap::from([(true, "a"), (false, "b")]);
+        assert_eq!(
+            canonical().to_string(&input)?,
+            r#"{"false":"b","true":"a"}"#
+        );
+
+        let input = BTreeMap::from([(vec![1], "a")]);
+        let got = canonical().to_string(&input);
+        assert!(got.is_err(), "{got:?}");
+        Ok(())
+    }
+}
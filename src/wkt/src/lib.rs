@@ -27,12 +27,16 @@
 
 mod any;
 pub use crate::any::*;
+#[cfg(feature = "prost")]
+mod binary;
 mod duration;
 pub use crate::duration::*;
 mod empty;
 pub use crate::empty::*;
 mod field_mask;
 pub use crate::field_mask::*;
+mod json_serialize;
+pub use crate::json_serialize::*;
 // The generated code contains (and uses) deprecated code.
 #[allow(deprecated)]
 mod generated;
@@ -43,6 +47,10 @@ mod timestamp;
 pub use crate::timestamp::*;
 mod rstruct;
 pub use crate::rstruct::*;
+mod registry;
+pub use crate::registry::*;
+mod struct_serde;
+pub use crate::struct_serde::*;
 mod wrappers;
 pub use crate::wrappers::*;
 pub mod message;
@@ -33,6 +33,43 @@ pub trait Message: serde::ser::Serialize + serde::de::DeserializeOwned {
     }
 }
 
+/// A trait implemented by messages that support the binary protobuf encoding.
+///
+/// Use this trait to store messages compactly, to decode payloads delivered in
+/// the binary protobuf format, or to interoperate with code based on `prost`
+/// without a round trip through JSON.
+///
+/// The well-known types implement this trait when the `prost` feature is
+/// enabled. Only the messages in `google-cloud-storage` and
+/// `google-cloud-firestore` implement this trait, as these are the only client
+/// libraries with a gRPC transport. The model types in other client libraries,
+/// including the Pub/Sub and Eventarc payload types, only support the JSON
+/// encoding. To decode binary payloads for those types use a `prost`-based
+/// crate, or request the JSON encoding when possible.
+///
+/// Encoding a message converts a copy of the message to its `prost`
+/// representation. Expect the cost of a clone, in addition to the encoding.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "prost")]
+/// # {
+/// # use google_cloud_wkt::{AnyError, Duration};
+/// use google_cloud_wkt::message::BinaryMessage;
+/// let bytes = Duration::clamp(123, 456).encode_to_vec()?;
+/// let duration = Duration::decode(&bytes)?;
+/// assert_eq!(duration, Duration::clamp(123, 456));
+/// # }
+/// # Ok::<(), google_cloud_wkt::AnyError>(())
+/// ```
+pub trait BinaryMessage: Message + Sized {
+    /// Encodes the message using the binary protobuf encoding.
+    fn encode_to_vec(&self) -> Result<Vec<u8>, Error>;
+
+    /// Decodes a message from its binary protobuf encoding.
+    fn decode(buf: &[u8]) -> Result<Self, Error>;
+}
+
 pub(crate) mod sealed {
     pub trait MessageSerializer {}
 }
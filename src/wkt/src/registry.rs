@@ -0,0 +1,305 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Any;
+use crate::AnyError as Error;
+use crate::message::Message;
+use std::collections::HashMap;
+
+type UnpackFn = fn(&Any) -> Result<Box<dyn DynMessage>, Error>;
+
+/// A registry of message types, used to unpack [Any] values whose type is not
+/// known at compile time.
+///
+/// Error details, long-running operation metadata, and audit log payloads
+/// arrive as [Any] values, with many possible types. Register the types your
+/// application expects, and then use [Any::unpack_dynamic] to extract them.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Any, AnyError, Duration, Timestamp, TypeRegistry, UnpackedAny};
+/// let mut registry = TypeRegistry::new();
+/// registry.register::<Duration>().register::<Timestamp>();
+///
+/// let any = Any::from_msg(&Duration::clamp(123, 0))?;
+/// match any.unpack_dynamic(&registry)? {
+///     UnpackedAny::Message(m) => {
+///         assert_eq!(m.downcast_ref::<Duration>(), Some(&Duration::clamp(123, 0)));
+///     }
+///     UnpackedAny::Unknown(v) => println!("unknown type {v}"),
+///     _ => {}
+/// }
+/// # Ok::<(), AnyError>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct TypeRegistry {
+    types: HashMap<&'static str, UnpackFn>,
+}
+
+impl TypeRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `T`, using its [typename][Message::typename].
+    ///
+    /// Registering the same type more than once has no effect.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Duration, TypeRegistry};
+    /// let mut registry = TypeRegistry::new();
+    /// registry.register::<Duration>();
+    /// assert!(registry.contains("type.googleapis.com/google.protobuf.Duration"));
+    /// ```
+    pub fn register<T>(&mut self) -> &mut Self
+    where
+        T: Message + std::fmt::Debug + Send + Sync + 'static,
+    {
+        self.types.insert(T::typename(), unpack::<T>);
+        self
+    }
+
+    /// Returns true if the registry contains a type with the given type URL.
+    pub fn contains(&self, typename: &str) -> bool {
+        self.types.contains_key(typename)
+    }
+
+    /// Returns the number of registered types.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Returns true if the registry does not contain any types.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub(crate) fn unpack(&self, any: &Any) -> Result<UnpackedAny, Error> {
+        let typename = any
+            .type_url()
+            .ok_or_else(|| Error::deser("@type field is missing or is not a string"))?;
+        match self.types.get(typename) {
+            Some(unpack) => unpack(any).map(UnpackedAny::Message),
+            None => serde_json::to_value(any)
+                .map(UnpackedAny::Unknown)
+                .map_err(Error::deser),
+        }
+    }
+}
+
+impl std::fmt::Debug for TypeRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut types = self.types.keys().collect::<Vec<_>>();
+        types.sort();
+        f.debug_struct("TypeRegistry")
+            .field("types", &types)
+            .finish()
+    }
+}
+
+fn unpack<T>(any: &Any) -> Result<Box<dyn DynMessage>, Error>
+where
+    T: Message + std::fmt::Debug + Send + Sync + 'static,
+{
+    any.to_msg::<T>()
+        .map(|m| Box::new(m) as Box<dyn DynMessage>)
+}
+
+/// The result of [Any::unpack_dynamic].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum UnpackedAny {
+    /// The type is in the registry, use [downcast_ref()][DynMessage] to access
+    /// the concrete type.
+    Message(Box<dyn DynMessage>),
+
+    /// The type is not in the registry. Contains the JSON representation of
+    /// the [Any], including the `@type` field.
+    Unknown(serde_json::Value),
+}
+
+/// A message unpacked from an [Any], whose concrete type is only known at
+/// runtime.
+///
+/// This trait is implemented for all types that can be added to a
+/// [TypeRegistry].
+pub trait DynMessage: std::fmt::Debug + Send + Sync + sealed::DynMessage {
+    /// The type URL of the message, see [Message::typename].
+    fn type_url(&self) -> &'static str;
+
+    /// Packs the message back into an [Any].
+    fn to_any(&self) -> Result<Any, Error>;
+
+    #[doc(hidden)]
+    fn as_std_any(&self) -> &dyn std::any::Any;
+
+    #[doc(hidden)]
+    fn into_std_any(self: Box<Self>) -> Box<dyn std::any::Any>;
+}
+
+mod sealed {
+    pub trait DynMessage {}
+}
+
+impl<T> sealed::DynMessage for T where T: Message + std::fmt::Debug + Send + Sync + 'static {}
+
+impl<T> DynMessage for T
+where
+    T: Message + std::fmt::Debug + Send + Sync + 'static,
+{
+    fn type_url(&self) -> &'static str {
+        T::typename()
+    }
+
+    fn to_any(&self) -> Result<Any, Error> {
+        Any::from_msg(self)
+    }
+
+    fn as_std_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn into_std_any(self: Box<Self>) -> Box<dyn std::any::Any> {
+        self
+    }
+}
+
+impl dyn DynMessage {
+    /// Returns true if the message is a `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.as_std_any().is::<T>()
+    }
+
+    /// Returns a reference to the message if it is a `T`.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Any, AnyError, Duration, Timestamp, TypeRegistry, UnpackedAny};
+    /// let mut registry = TypeRegistry::new();
+    /// registry.register::<Duration>();
+    /// let any = Any::from_msg(&Duration::clamp(123, 0))?;
+    /// let UnpackedAny::Message(m) = any.unpack_dynamic(&registry)? else { unreachable!() };
+    /// assert_eq!(m.downcast_ref::<Duration>(), Some(&Duration::clamp(123, 0)));
+    /// assert_eq!(m.downcast_ref::<Timestamp>(), None);
+    /// # Ok::<(), AnyError>(())
+    /// ```
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.as_std_any().downcast_ref::<T>()
+    }
+
+    /// Returns the message as a `T`, or the original value if it is not a `T`.
+    pub fn downcast<T: 'static>(self: Box<Self>) -> Result<Box<T>, Box<dyn DynMessage>> {
+        if self.is::<T>() {
+            Ok(self
+                .into_std_any()
+                .downcast::<T>()
+                .expect("the type was checked before the downcast"))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Duration, Empty, FieldMask, Timestamp};
+    use serde_json::json;
+    type Result = anyhow::Result<()>;
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::new();
+        registry
+            .register::<Duration>()
+            .register::<Timestamp>()
+            .register::<Empty>();
+        registry
+    }
+
+    #[test]
+    fn register() {
+        let mut registry = registry();
+        assert_eq!(registry.len(), 3);
+        assert!(!registry.is_empty());
+        assert!(registry.contains(Duration::typename()));
+        assert!(!registry.contains(FieldMask::typename()));
+        registry.register::<Duration>();
+        assert_eq!(registry.len(), 3);
+        assert!(TypeRegistry::new().is_empty());
+
+        let fmt = format!("{registry:?}");
+        assert!(fmt.contains(Timestamp::typename()), "{fmt}");
+    }
+
+    #[test]
+    fn unpack_registered() -> Result {
+        let registry = registry();
+        let any = Any::from_msg(&Duration::clamp(123, 456))?;
+        let got = any.unpack_dynamic(&registry)?;
+        let UnpackedAny::Message(m) = got else {
+            panic!("expected a message, got={got:?}");
+        };
+        assert_eq!(m.type_url(), Duration::typename());
+        assert!(m.is::<Duration>(), "{m:?}");
+        assert!(!m.is::<Timestamp>(), "{m:?}");
+        assert_eq!(m.to_any()?, any);
+        assert_eq!(m.downcast_ref::<Timestamp>(), None);
+        assert_eq!(
+            m.downcast_ref::<Duration>(),
+            Some(&Duration::clamp(123, 456))
+        );
+
+        let m = m.downcast::<Timestamp>().unwrap_err();
+        let m = m
+            .downcast::<Duration>()
+            .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        assert_eq!(*m, Duration::clamp(123, 456));
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_unknown() -> Result {
+        let registry = registry();
+        let any = Any::from_msg(&FieldMask::default().set_paths(["a", "b"]))?;
+        let got = any.unpack_dynamic(&registry)?;
+        let UnpackedAny::Unknown(value) = got else {
+            panic!("expected an unknown value, got={got:?}");
+        };
+        assert_eq!(
+            value,
+            json!({"@type": FieldMask::typename(), "value": "a,b"})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_error() -> Result {
+        let registry = registry();
+        let any = serde_json::from_value::<Any>(json!({
+            "@type": Duration::typename(),
+            "value": "not a duration",
+        }))?;
+        let got = any.unpack_dynamic(&registry);
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_missing_type() {
+        let got = Any::default().unpack_dynamic(&registry());
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+    }
+}
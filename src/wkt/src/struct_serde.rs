@@ -0,0 +1,1328 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Convert application types to and from [Struct] and [Value].
+//!
+//! `google.protobuf.Value` can only represent numbers as 64-bit floating
+//! point values, it cannot represent NaN or infinities, and it has no type for
+//! binary data. [serde_json] silently converts non-finite numbers to `null`,
+//! and serializes integers that may lose precision when the service decodes
+//! them. The functions in this module apply the ProtoJSON rules instead.
+
+use crate::{Struct, Value};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::de::{self, IntoDeserializer};
+use serde::ser::{self, Serialize};
+
+/// Represent failures converting application types to and from [Struct] and
+/// [Value].
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{StructError, to_struct, to_value};
+/// let err = to_value(&f64::NAN).unwrap_err();
+/// assert!(matches!(err, StructError::NonFiniteNumber(_)));
+///
+/// let err = to_struct(&vec![1, 2, 3]).unwrap_err();
+/// assert!(matches!(err, StructError::NotAnObject(_)));
+/// ```
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum StructError {
+    /// Only types that serialize to a JSON object can be converted to a
+    /// [Struct].
+    #[error("expected a value that serializes to a JSON object, got {0}")]
+    NotAnObject(&'static str),
+
+    /// `google.protobuf.Value` cannot represent NaN or infinities.
+    #[error("cannot represent {0} in a google.protobuf.Value, only finite numbers are supported")]
+    NonFiniteNumber(f64),
+
+    /// `google.protobuf.Value` represents numbers as doubles, integers outside
+    /// the range where doubles are exact cannot be converted.
+    #[error(
+        "cannot represent {0} exactly in a google.protobuf.Value, consider serializing the value as a string"
+    )]
+    IntegerOutOfRange(String),
+
+    /// Map keys must serialize to strings, integers, or characters.
+    #[error("map keys must be strings, integers, or characters")]
+    InvalidMapKey,
+
+    /// There was a problem serializing the value.
+    #[error("cannot serialize value, source={0}")]
+    Serialization(#[source] BoxedError),
+
+    /// There was a problem deserializing the value.
+    #[error("cannot deserialize value, source={0}")]
+    Deserialization(#[source] BoxedError),
+}
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+type Error = StructError;
+
+impl ser::Error for StructError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Serialization(msg.to_string().into())
+    }
+}
+
+/// Converts `value` to a [Struct].
+///
+/// The conversion follows the ProtoJSON rules for `google.protobuf.Struct`:
+///
+/// - `value` must serialize to a JSON object.
+/// - NaN and infinities are rejected with [StructError::NonFiniteNumber].
+/// - Integers are always converted to numbers. Integers that cannot be
+///   represented exactly as a double are rejected with
+///   [StructError::IntegerOutOfRange]. Use a string representation for such
+///   fields, for example with [serde_with::DisplayFromStr].
+/// - Binary data is encoded using base64.
+/// - `None`, `()`, and [NullValue][crate::NullValue] are converted to `null`.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{StructError, to_struct};
+/// #[derive(serde::Serialize)]
+/// struct Instance {
+///     prompt: String,
+///     temperature: f64,
+///     seed: u64,
+/// }
+/// let instance = Instance { prompt: "hello".into(), temperature: 0.5, seed: 42 };
+/// let got = to_struct(&instance)?;
+/// assert_eq!(serde_json::Value::Object(got), serde_json::json!({
+///     "prompt": "hello",
+///     "temperature": 0.5,
+///     "seed": 42,
+/// }));
+///
+/// let instance = Instance { seed: u64::MAX, ..instance };
+/// let err = to_struct(&instance).unwrap_err();
+/// assert!(matches!(err, StructError::IntegerOutOfRange(_)));
+/// # Ok::<(), StructError>(())
+/// ```
+pub fn to_struct<T>(value: &T) -> Result<Struct, Error>
+where
+    T: Serialize + ?Sized,
+{
+    match to_value(value)? {
+        Value::Object(map) => Ok(map),
+        v => Err(Error::NotAnObject(kind(&v))),
+    }
+}
+
+/// Converts `value` to a [Value].
+///
+/// The conversion follows the same rules as [to_struct], but `value` may
+/// serialize to any JSON type.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{StructError, to_value};
+/// let got = to_value(&vec![Some(1.5), None])?;
+/// assert_eq!(got, serde_json::json!([1.5, null]));
+/// # Ok::<(), StructError>(())
+/// ```
+pub fn to_value<T>(value: &T) -> Result<Value, Error>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer)
+}
+
+/// Converts a [Struct] to `T`.
+///
+/// The conversion accepts the values produced by [to_struct], and the values
+/// produced by services:
+///
+/// - Integers may be represented as JSON numbers, including numbers with a
+///   zero fractional part such as `3.0`, or as strings.
+/// - Floating point numbers may be represented as JSON numbers or as strings.
+/// - Binary data must be encoded using base64.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{StructError, from_struct};
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// struct Prediction {
+///     label: String,
+///     count: i64,
+/// }
+/// let value = serde_json::json!({"label": "cat", "count": 3.0});
+/// let got = from_struct::<Prediction>(value.as_object().unwrap().clone())?;
+/// assert_eq!(got, Prediction { label: "cat".into(), count: 3 });
+/// # Ok::<(), StructError>(())
+/// ```
+pub fn from_struct<T>(value: Struct) -> Result<T, Error>
+where
+    T: de::DeserializeOwned,
+{
+    from_value(Value::Object(value))
+}
+
+/// Converts a [Value] to `T`.
+///
+/// The conversion follows the same rules as [from_struct].
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{StructError, from_value};
+/// let got = from_value::<Vec<u64>>(serde_json::json!([1.0, "18446744073709551615"]))?;
+/// assert_eq!(got, vec![1, u64::MAX]);
+/// # Ok::<(), StructError>(())
+/// ```
+pub fn from_value<T>(value: Value) -> Result<T, Error>
+where
+    T: de::DeserializeOwned,
+{
+    T::deserialize(Deserializer(value)).map_err(|e| Error::Deserialization(e.into()))
+}
+
+/// The largest integer such that all smaller integers can be represented
+/// exactly as a double.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+// The JSON type of a value must not depend on its magnitude, consumers with
+// a schema would reject the output. Integers are always numbers, and values
+// that cannot be represented exactly are errors.
+fn from_i128(v: i128) -> Result<Value, Error> {
+    if v.unsigned_abs() <= MAX_SAFE_INTEGER as u128 {
+        Ok(Value::from(v as i64))
+    } else {
+        Err(Error::IntegerOutOfRange(v.to_string()))
+    }
+}
+
+fn from_u128(v: u128) -> Result<Value, Error> {
+    if v <= MAX_SAFE_INTEGER as u128 {
+        Ok(Value::from(v as u64))
+    } else {
+        Err(Error::IntegerOutOfRange(v.to_string()))
+    }
+}
+
+fn from_f64(v: f64) -> Result<Value, Error> {
+    serde_json::Number::from_f64(v)
+        .map(Value::Number)
+        .ok_or(Error::NonFiniteNumber(v))
+}
+
+struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeVariant<SerializeVec>;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeVariant<SerializeMap>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        from_i128(v as i128)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        from_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        from_u128(v as u128)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        from_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        from_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        from_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::String(STANDARD.encode(v)))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Value, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut map = Struct::new();
+        map.insert(variant.to_string(), value.serialize(self)?);
+        Ok(Value::Object(map))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, Error> {
+        Ok(SerializeVec(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeVec>, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            map: Struct::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeMap>, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+struct SerializeVec(Vec<Value>);
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.0.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeMap {
+    map: Struct,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self.key.take().ok_or_else(|| {
+            <Error as ser::Error>::custom("serialize_value called before serialize_key")
+        })?;
+        self.map.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.map))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.map
+            .insert(key.to_string(), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl<S> SerializeVariant<S> {
+    fn wrap(variant: &'static str, value: Value) -> Value {
+        let mut map = Struct::new();
+        map.insert(variant.to_string(), value);
+        Value::Object(map)
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeVec> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let value = ser::SerializeSeq::end(self.inner)?;
+        Ok(Self::wrap(self.variant, value))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeMap> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        let value = ser::SerializeMap::end(self.inner)?;
+        Ok(Self::wrap(self.variant, value))
+    }
+}
+
+/// Serializes map keys, JSON only supports string keys.
+struct MapKeySerializer;
+
+macro_rules! key_to_string {
+    ($($f: ident: $t: ty),*) => {
+        $(
+            fn $f(self, v: $t) -> Result<String, Error> {
+                Ok(v.to_string())
+            }
+        )*
+    };
+}
+
+macro_rules! invalid_key {
+    ($($f: ident $(: $t: ty)?),*) => {
+        $(
+            fn $f(self $(, _v: $t)?) -> Result<String, Error> {
+                Err(Error::InvalidMapKey)
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = ser::Impossible<String, Error>;
+    type SerializeTuple = ser::Impossible<String, Error>;
+    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = ser::Impossible<String, Error>;
+    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    key_to_string!(
+        serialize_i8: i8, serialize_i16: i16, serialize_i32: i32, serialize_i64: i64,
+        serialize_i128: i128, serialize_u8: u8, serialize_u16: u16, serialize_u32: u32,
+        serialize_u64: u64, serialize_u128: u128, serialize_char: char, serialize_str: &str
+    );
+    invalid_key!(
+        serialize_bool: bool, serialize_f32: f32, serialize_f64: f64,
+        serialize_bytes: &[u8], serialize_none, serialize_unit
+    );
+
+    fn serialize_some<T>(self, _value: &T) -> Result<String, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::InvalidMapKey)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::InvalidMapKey)
+    }
+}
+
+/// Deserializes from a [Value], accepting the ProtoJSON representations of
+/// numbers and bytes.
+struct Deserializer(Value);
+
+type DeError = serde_json::Error;
+
+impl Deserializer {
+    fn unexpected(&self) -> de::Unexpected<'_> {
+        match &self.0 {
+            Value::Null => de::Unexpected::Unit,
+            Value::Bool(b) => de::Unexpected::Bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                (Some(u), _, _) => de::Unexpected::Unsigned(u),
+                (_, Some(i), _) => de::Unexpected::Signed(i),
+                (_, _, Some(f)) => de::Unexpected::Float(f),
+                _ => de::Unexpected::Other("number"),
+            },
+            Value::String(s) => de::Unexpected::Str(s),
+            Value::Array(_) => de::Unexpected::Seq,
+            Value::Object(_) => de::Unexpected::Map,
+        }
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match &self.0 {
+            Value::Number(n) => n
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from))
+                .or_else(|| n.as_f64().and_then(whole_f64)),
+            Value::String(s) => s.parse::<i128>().ok(),
+            _ => None,
+        }
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        match &self.0 {
+            Value::String(s) => s.parse::<u128>().ok(),
+            _ => self.as_i128().and_then(|v| u128::try_from(v).ok()),
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match &self.0 {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse::<f64>().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Converts floating point numbers with a zero fractional part to integers.
+fn whole_f64(v: f64) -> Option<i128> {
+    // The range check excludes NaN, and values that would saturate.
+    if v.fract() == 0.0 && (-1e38..=1e38).contains(&v) {
+        Some(v as i128)
+    } else {
+        None
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($($f: ident => $visit: ident: $t: ty),*) => {
+        $(
+            fn $f<V>(self, visitor: V) -> Result<V::Value, DeError>
+            where
+                V: de::Visitor<'de>,
+            {
+                match self.as_i128().and_then(|v| <$t>::try_from(v).ok()) {
+                    Some(v) => visitor.$visit(v),
+                    None => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Number(n) => {
+                if let Some(u) = n.as_u64() {
+                    visitor.visit_u64(u)
+                } else if let Some(i) = n.as_i64() {
+                    visitor.visit_i64(i)
+                } else {
+                    visitor.visit_f64(n.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            Value::String(s) => visitor.visit_string(s),
+            Value::Array(a) => visitor.visit_seq(SeqDeserializer(a.into_iter())),
+            Value::Object(m) => visitor.visit_map(MapDeserializer {
+                iter: m.into_iter(),
+                value: None,
+            }),
+        }
+    }
+
+    deserialize_integer!(
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64
+    );
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.as_u128() {
+            Some(v) => visitor.visit_u128(v),
+            None => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.as_f64() {
+            Some(v) => visitor.visit_f32(v as f32),
+            None => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.as_f64() {
+            Some(v) => visitor.visit_f64(v),
+            None => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match &self.0 {
+            Value::String(s) => {
+                let bytes = STANDARD.decode(s).map_err(de::Error::custom)?;
+                visitor.visit_byte_buf(bytes)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Value::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: None,
+            }),
+            Value::Object(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().expect("map has one element");
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value: Some(value),
+                })
+            }
+            _ => Err(de::Error::invalid_type(
+                self.unexpected(),
+                &"a string or an object with a single key",
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool char str string unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct SeqDeserializer(std::vec::IntoIter<Value>);
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer {
+    type Error = DeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, DeError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.0
+            .next()
+            .map(|v| seed.deserialize(Deserializer(v)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct MapDeserializer {
+    iter: serde_json::map::IntoIter,
+    value: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer {
+    type Error = DeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, DeError>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            None => Ok(None),
+            Some((k, v)) => {
+                self.value = Some(v);
+                // Keys are strings, but may represent integers, use the same
+                // rules as for values.
+                seed.deserialize(Deserializer(Value::String(k))).map(Some)
+            }
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, DeError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| <DeError as de::Error>::custom("value requested before key"))?;
+        seed.deserialize(Deserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = DeError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer), DeError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantDeserializer(self.value)))
+    }
+}
+
+struct VariantDeserializer(Option<Value>);
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = DeError;
+
+    fn unit_variant(self) -> Result<(), DeError> {
+        match self.0 {
+            None | Some(Value::Null) => Ok(()),
+            Some(v) => Err(de::Error::invalid_type(
+                Deserializer(v).unexpected(),
+                &"a unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, DeError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.0 {
+            Some(v) => seed.deserialize(Deserializer(v)),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"a newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Some(v @ Value::Array(_)) => {
+                de::Deserializer::deserialize_any(Deserializer(v), visitor)
+            }
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"a tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Some(v @ Value::Object(_)) => {
+                de::Deserializer::deserialize_any(Deserializer(v), visitor)
+            }
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"a struct variant",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NullValue;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use test_case::test_case;
+    type Result = anyhow::Result<()>;
+
+    #[serde_with::serde_as]
+    #[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Instance {
+        prompt: String,
+        temperature: f64,
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        seed: u64,
+        offset: i64,
+        #[serde_as(as = "serde_with::Bytes")]
+        payload: Vec<u8>,
+        tags: Vec<String>,
+        labels: BTreeMap<i32, String>,
+        missing: Option<i32>,
+        null: NullValue,
+        kind: Kind,
+    }
+
+    #[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    enum Kind {
+        #[default]
+        Unit,
+        Newtype(u64),
+        Tuple(i32, String),
+        Struct {
+            a: i64,
+        },
+    }
+
+    fn instance() -> Instance {
+        Instance {
+            prompt: "hello".into(),
+            temperature: 0.5,
+            seed: u64::MAX,
+            offset: -42,
+            payload: b"abc".to_vec(),
+            tags: vec!["a".into(), "b".into()],
+            labels: BTreeMap::from([(1, "one".into()), (-2, "minus two".into())]),
+            missing: None,
+            null: NullValue,
+            kind: Kind::Newtype(7),
+        }
+    }
+
+    #[test]
+    fn struct_roundtrip() -> Result {
+        let got = to_struct(&instance())?;
+        let want = json!({
+            "prompt": "hello",
+            "temperature": 0.5,
+            "seed": "18446744073709551615",
+            "offset": -42,
+            "payload": "YWJj",
+            "tags": ["a", "b"],
+            "labels": {"-2": "minus two", "1": "one"},
+            "missing": null,
+            "null": null,
+            "kind": {"Newtype": 7},
+        });
+        assert_eq!(Value::Object(got.clone()), want);
+
+        let roundtrip = from_struct::<Instance>(got)?;
+        assert_eq!(roundtrip, instance());
+        Ok(())
+    }
+
+    #[test]
+    fn from_struct_service_values() -> Result {
+        let input = json!({
+            "prompt": "hello",
+            "temperature": "0.5",
+            "seed": "18446744073709551615",
+            "offset": -42.0,
+            "payload": "YWJj",
+            "tags": ["a", "b"],
+            "labels": {"-2": "minus two", "1": "one"},
+            "null": null,
+            "kind": {"Newtype": "7"},
+        });
+        let got = from_value::<Instance>(input)?;
+        assert_eq!(got, instance());
+        Ok(())
+    }
+
+    #[test_case(0_i64, json!(0))]
+    #[test_case(MAX_SAFE_INTEGER as i64, json!(9007199254740991_i64))]
+    #[test_case(-(MAX_SAFE_INTEGER as i64), json!(-9007199254740991_i64))]
+    fn int64(input: i64, want: Value) -> Result {
+        assert_eq!(to_value(&input)?, want);
+        assert_eq!(from_value::<i64>(want)?, input);
+        Ok(())
+    }
+
+    #[test_case(MAX_SAFE_INTEGER as i64 + 1)]
+    #[test_case(-(MAX_SAFE_INTEGER as i64) - 1)]
+    #[test_case(i64::MIN)]
+    fn int64_out_of_range(input: i64) -> Result {
+        let got = to_value(&input);
+        assert!(
+            matches!(got, Err(Error::IntegerOutOfRange(ref v)) if *v == input.to_string()),
+            "{got:?}"
+        );
+        // Services may still send the values as strings.
+        assert_eq!(from_value::<i64>(json!(input.to_string()))?, input);
+        Ok(())
+    }
+
+    #[test_case(MAX_SAFE_INTEGER, json!(9007199254740991_u64))]
+    #[test_case(0, json!(0))]
+    fn uint64(input: u64, want: Value) -> Result {
+        assert_eq!(to_value(&input)?, want);
+        assert_eq!(from_value::<u64>(want)?, input);
+        Ok(())
+    }
+
+    #[test]
+    fn uint64_out_of_range() -> Result {
+        let got = to_value(&u64::MAX);
+        assert!(matches!(got, Err(Error::IntegerOutOfRange(_))), "{got:?}");
+        assert_eq!(from_value::<u64>(json!("18446744073709551615"))?, u64::MAX);
+        Ok(())
+    }
+
+    #[test]
+    fn int128() -> Result {
+        assert_eq!(to_value(&1_i128)?, json!(1));
+        assert_eq!(to_value(&1_u128)?, json!(1));
+        let got = to_value(&i128::MAX);
+        assert!(matches!(got, Err(Error::IntegerOutOfRange(_))), "{got:?}");
+        let got = to_value(&u128::MAX);
+        assert!(matches!(got, Err(Error::IntegerOutOfRange(_))), "{got:?}");
+        assert_eq!(from_value::<u128>(json!(u128::MAX.to_string()))?, u128::MAX);
+        Ok(())
+    }
+
+    #[test]
+    fn json_type_does_not_depend_on_magnitude() -> Result {
+        // Small and large values of the same type produce the same JSON type,
+        // or an error.
+        for v in [0_u64, 1, 1 << 32, MAX_SAFE_INTEGER] {
+            assert!(to_value(&v)?.is_number(), "{v}");
+        }
+        for v in [0_i64, -1, -(1 << 32), -(MAX_SAFE_INTEGER as i64)] {
+            assert!(to_value(&v)?.is_number(), "{v}");
+        }
+        Ok(())
+    }
+
+    #[test_case(f64::NAN)]
+    #[test_case(f64::INFINITY)]
+    #[test_case(f64::NEG_INFINITY)]
+    fn non_finite(input: f64) {
+        let got = to_value(&input);
+        assert!(matches!(got, Err(Error::NonFiniteNumber(_))), "{got:?}");
+        let got = to_value(&(input as f32));
+        assert!(matches!(got, Err(Error::NonFiniteNumber(_))), "{got:?}");
+        let got = to_struct(&json!({"nested": [input]}));
+        // `serde_json` already converted the value to `null`.
+        assert_eq!(got.ok(), json!({"nested": [null]}).as_object().cloned());
+        let got = to_struct(&BTreeMap::from([("nested", vec![input])]));
+        assert!(matches!(got, Err(Error::NonFiniteNumber(_))), "{got:?}");
+    }
+
+    #[test_case(json!(1.5), 1.5)]
+    #[test_case(json!(2), 2.0)]
+    #[test_case(json!("-3.25"), -3.25)]
+    fn floats(input: Value, want: f64) -> Result {
+        assert_eq!(from_value::<f64>(input.clone())?, want);
+        assert_eq!(from_value::<f32>(input)?, want as f32);
+        Ok(())
+    }
+
+    #[test_case(json!(1.5))]
+    #[test_case(json!("abc"))]
+    #[test_case(json!(256))]
+    #[test_case(json!(-1))]
+    #[test_case(json!(null))]
+    #[test_case(json!(true))]
+    fn integer_errors(input: Value) {
+        let got = from_value::<u8>(input);
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+    }
+
+    #[test]
+    fn bytes() -> Result {
+        #[serde_with::serde_as]
+        #[derive(Debug, serde::Deserialize)]
+        struct Payload(#[serde_as(as = "serde_with::Bytes")] Vec<u8>);
+        let got = from_value::<Payload>(json!("YWJj"))?;
+        assert_eq!(got.0, b"abc");
+        let got = from_value::<Payload>(json!("not base64!"));
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+        Ok(())
+    }
+
+    #[test_case(json!(1))]
+    #[test_case(json!("abc"))]
+    #[test_case(json!(null))]
+    #[test_case(json!([1, 2]))]
+    fn not_an_object(input: Value) {
+        let got = to_struct(&input);
+        assert!(matches!(got, Err(Error::NotAnObject(_))), "{got:?}");
+    }
+
+    #[test]
+    fn invalid_map_key() {
+        let got = to_struct(&BTreeMap::from([(true, 1)]));
+        assert!(matches!(got, Err(Error::InvalidMapKey)), "{got:?}");
+        let got = to_struct(&BTreeMap::from([((1, 2), 1)]));
+        assert!(matches!(got, Err(Error::InvalidMapKey)), "{got:?}");
+    }
+
+    #[test]
+    fn custom_serialization_error() {
+        struct Fails;
+        impl Serialize for Fails {
+            fn serialize<S: ser::Serializer>(&self, _: S) -> std::result::Result<S::Ok, S::Error> {
+                Err(ser::Error::custom("always fails"))
+            }
+        }
+        let got = to_value(&vec![Fails]);
+        assert!(matches!(got, Err(Error::Serialization(_))), "{got:?}");
+    }
+
+    #[test_case(Kind::Unit, json!("Unit"))]
+    #[test_case(Kind::Newtype(MAX_SAFE_INTEGER), json!({"Newtype": 9007199254740991_u64}))]
+    #[test_case(Kind::Tuple(1, "a".into()), json!({"Tuple": [1, "a"]}))]
+    #[test_case(Kind::Struct { a: -1 }, json!({"Struct": {"a": -1}}))]
+    fn enums(input: Kind, want: Value) -> Result {
+        assert_eq!(to_value(&input)?, want);
+        assert_eq!(from_value::<Kind>(want)?, input);
+        Ok(())
+    }
+
+    #[test_case(json!(1))]
+    #[test_case(json!("Unknown"))]
+    #[test_case(json!({"Unit": 1}))]
+    #[test_case(json!({"Newtype": 1, "Tuple": [1, "a"]}))]
+    #[test_case(json!({"Tuple": 1}))]
+    #[test_case(json!({"Struct": [1]}))]
+    #[test_case(json!("Newtype"))]
+    fn enum_errors(input: Value) {
+        let got = from_value::<Kind>(input);
+        assert!(matches!(got, Err(Error::Deserialization(_))), "{got:?}");
+    }
+
+    #[test]
+    fn values_roundtrip() -> Result {
+        let input = json!({"a": [1, "b", null, true, {"c": 2.5}]});
+        assert_eq!(to_value(&input)?, input);
+        assert_eq!(from_value::<Value>(input.clone())?, input);
+        let input = input.as_object().cloned().unwrap();
+        assert_eq!(from_struct::<Struct>(input.clone())?, input);
+        Ok(())
+    }
+}
@@ -54,7 +54,7 @@
 /// For example, "2017-01-15T01:30:15.01Z" encodes 15.01 seconds past
 /// 01:30 UTC on January 15, 2017.
 ///
-#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
 pub struct Timestamp {
     /// Represents seconds of UTC time since Unix epoch
@@ -227,6 +227,247 @@ impl Timestamp {
     pub fn nanos(&self) -> i32 {
         self.nanos
     }
+
+    /// Returns the current time, as reported by [SystemTime::now][std::time::SystemTime::now].
+    ///
+    /// The result is clamped to the valid range for [Timestamp].
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::Timestamp;
+    /// let now = Timestamp::now();
+    /// assert!(now > Timestamp::try_from("2025-01-01T00:00:00Z")?);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn now() -> Self {
+        Self::saturating_from_nanos(Self::system_time_nanos(std::time::SystemTime::now()))
+    }
+
+    /// Returns `self + rhs`, or `None` if the result is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Duration, Timestamp};
+    /// let ts = Timestamp::clamp(100, 0).checked_add(Duration::clamp(20, 0));
+    /// assert_eq!(ts, Some(Timestamp::clamp(120, 0)));
+    /// let ts = Timestamp::clamp(Timestamp::MAX_SECONDS, 0).checked_add(Duration::clamp(1, 0));
+    /// assert_eq!(ts, None);
+    /// ```
+    pub fn checked_add(self, rhs: Duration) -> Option<Timestamp> {
+        Self::from_nanos(self.as_nanos() + rhs.as_nanos())
+    }
+
+    /// Returns `self - rhs`, or `None` if the result is out of range.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Duration, Timestamp};
+    /// let ts = Timestamp::clamp(100, 0).checked_sub(Duration::clamp(20, 0));
+    /// assert_eq!(ts, Some(Timestamp::clamp(80, 0)));
+    /// let ts = Timestamp::clamp(Timestamp::MIN_SECONDS, 0).checked_sub(Duration::clamp(1, 0));
+    /// assert_eq!(ts, None);
+    /// ```
+    pub fn checked_sub(self, rhs: Duration) -> Option<Timestamp> {
+        Self::from_nanos(self.as_nanos() - rhs.as_nanos())
+    }
+
+    /// Returns `self + rhs`, saturating at the minimum or maximum valid
+    /// [Timestamp].
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Duration, Timestamp};
+    /// let ts = Timestamp::clamp(Timestamp::MAX_SECONDS, 0).saturating_add(Duration::clamp(1, 0));
+    /// assert_eq!(String::from(ts), "9999-12-31T23:59:59.999999999Z");
+    /// ```
+    pub fn saturating_add(self, rhs: Duration) -> Timestamp {
+        Self::saturating_from_nanos(self.as_nanos() + rhs.as_nanos())
+    }
+
+    /// Returns `self - rhs`, saturating at the minimum or maximum valid
+    /// [Timestamp].
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Duration, Timestamp};
+    /// let ts = Timestamp::clamp(Timestamp::MIN_SECONDS, 0).saturating_sub(Duration::clamp(1, 0));
+    /// assert_eq!(String::from(ts), "0001-01-01T00:00:00Z");
+    /// ```
+    pub fn saturating_sub(self, rhs: Duration) -> Timestamp {
+        Self::saturating_from_nanos(self.as_nanos() - rhs.as_nanos())
+    }
+
+    /// Returns the [Duration] elapsed from `earlier` to `self`.
+    ///
+    /// The result is negative if `earlier` is after `self`. This never
+    /// overflows: the range of valid durations is larger than the range of
+    /// valid timestamps.
+    ///
+    /// # Example
+    /// ```
+    /// # use google_cloud_wkt::{Duration, Timestamp};
+    /// let d = Timestamp::clamp(120, 0).duration_since(Timestamp::clamp(100, 500));
+    /// assert_eq!(d, Duration::clamp(19, 999_999_500));
+    /// ```
+    pub fn duration_since(self, earlier: Timestamp) -> Duration {
+        Duration::from_nanos(self.as_nanos() - earlier.as_nanos())
+            .expect("the difference between two timestamps is always a valid duration")
+    }
+
+    fn as_nanos(&self) -> i128 {
+        self.seconds as i128 * NS + self.nanos as i128
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        let seconds = i64::try_from(nanos.div_euclid(NS)).ok()?;
+        Self::new(seconds, nanos.rem_euclid(NS) as i32).ok()
+    }
+
+    fn saturating_from_nanos(nanos: i128) -> Self {
+        let min = Self::MIN_SECONDS as i128 * NS + Self::MIN_NANOS as i128;
+        let max = Self::MAX_SECONDS as i128 * NS + Self::MAX_NANOS as i128;
+        Self::from_nanos(nanos.clamp(min, max)).expect("clamped value is in range")
+    }
+
+    /// The nanoseconds since the Unix epoch. A `std::time::Duration` always
+    /// fits in an `i128` when expressed in nanoseconds.
+    fn system_time_nanos(value: std::time::SystemTime) -> i128 {
+        match value.duration_since(std::time::UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        }
+    }
+}
+
+use crate::Duration;
+
+const EXPECT_TIMESTAMP_ADD: &str = "overflow when adding duration to timestamp";
+const EXPECT_TIMESTAMP_SUB: &str = "overflow when subtracting duration from timestamp";
+
+/// Adds a [Duration] to a [Timestamp].
+///
+/// # Panics
+///
+/// Panics if the result is out of range. Use
+/// [checked_add()][Timestamp::checked_add] or
+/// [saturating_add()][Timestamp::saturating_add] to handle overflows.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Duration, Timestamp};
+/// let ts = Timestamp::clamp(100, 0) + Duration::clamp(20, 0);
+/// assert_eq!(ts, Timestamp::clamp(120, 0));
+/// ```
+impl std::ops::Add<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, rhs: Duration) -> Timestamp {
+        self.checked_add(rhs).expect(EXPECT_TIMESTAMP_ADD)
+    }
+}
+
+impl std::ops::AddAssign<Duration> for Timestamp {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+/// Subtracts a [Duration] from a [Timestamp].
+///
+/// # Panics
+///
+/// Panics if the result is out of range. Use
+/// [checked_sub()][Timestamp::checked_sub] or
+/// [saturating_sub()][Timestamp::saturating_sub] to handle overflows.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Duration, Timestamp};
+/// let ts = Timestamp::clamp(100, 0) - Duration::clamp(20, 0);
+/// assert_eq!(ts, Timestamp::clamp(80, 0));
+/// ```
+impl std::ops::Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn sub(self, rhs: Duration) -> Timestamp {
+        self.checked_sub(rhs).expect(EXPECT_TIMESTAMP_SUB)
+    }
+}
+
+impl std::ops::SubAssign<Duration> for Timestamp {
+    fn sub_assign(&mut self, rhs: Duration) {
+        *self = *self - rhs;
+    }
+}
+
+/// Computes the [Duration] between two timestamps.
+///
+/// See [duration_since()][Timestamp::duration_since].
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Duration, Timestamp};
+/// let d = Timestamp::clamp(100, 0) - Timestamp::clamp(120, 0);
+/// assert_eq!(d, Duration::clamp(-20, 0));
+/// ```
+impl std::ops::Sub for Timestamp {
+    type Output = Duration;
+
+    fn sub(self, rhs: Timestamp) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+/// Converts from [SystemTime][std::time::SystemTime] to [Timestamp].
+///
+/// This conversion may fail if the `SystemTime` value is out of range.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Timestamp, TimestampError};
+/// use std::time::{Duration, UNIX_EPOCH};
+/// let ts = Timestamp::try_from(UNIX_EPOCH + Duration::from_secs(1747388772))?;
+/// assert_eq!(String::from(ts), "2025-05-16T09:46:12Z");
+/// # Ok::<(), TimestampError>(())
+/// ```
+impl TryFrom<std::time::SystemTime> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(value: std::time::SystemTime) -> Result<Self, Self::Error> {
+        Self::from_nanos(Self::system_time_nanos(value)).ok_or(TimestampError::OutOfRange)
+    }
+}
+
+/// Converts from [Timestamp] to [SystemTime][std::time::SystemTime].
+///
+/// This conversion may fail if the platform cannot represent the [Timestamp]
+/// value.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Timestamp, TimestampError};
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+/// let ts = Timestamp::try_from("2025-05-16T09:46:12Z")?;
+/// let time = SystemTime::try_from(ts)?;
+/// assert_eq!(time, UNIX_EPOCH + Duration::from_secs(1747388772));
+/// # Ok::<(), TimestampError>(())
+/// ```
+impl TryFrom<Timestamp> for std::time::SystemTime {
+    type Error = TimestampError;
+
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        let nanos = value.as_nanos();
+        let offset = std::time::Duration::new(
+            (nanos.unsigned_abs() / NS as u128) as u64,
+            (nanos.unsigned_abs() % NS as u128) as u32,
+        );
+        let time = if nanos < 0 {
+            std::time::UNIX_EPOCH.checked_sub(offset)
+        } else {
+            std::time::UNIX_EPOCH.checked_add(offset)
+        };
+        time.ok_or(TimestampError::OutOfRange)
+    }
 }
 
 impl crate::message::Message for Timestamp {
@@ -376,6 +617,13 @@ impl From<Timestamp> for String {
 impl TryFrom<&str> for Timestamp {
     type Error = TimestampError;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // RFC 3339 allows a lowercase `t`, or a space, as the date and time
+        // separator, and a lowercase `z` for UTC. ProtoJSON does not.
+        if value.as_bytes().get(10) != Some(&b'T') || value.ends_with('z') {
+            return Err(TimestampError::Deserialize(
+                format!("{value:?} is not a valid ProtoJSON timestamp, expected an uppercase `T` separator and an uppercase `Z` for UTC").into(),
+            ));
+        }
         let odt = time::OffsetDateTime::parse(value, &Rfc3339)
             .map_err(|e| TimestampError::Deserialize(e.into()))?;
         let nanos_since_epoch = odt.unix_timestamp_nanos();
@@ -450,6 +698,50 @@ impl TryFrom<Timestamp> for chrono::DateTime<chrono::Utc> {
     }
 }
 
+/// Converts from [jiff::Timestamp] to [Timestamp].
+///
+/// This conversion may fail if the [jiff::Timestamp] value is out of range.
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::{Timestamp, TimestampError};
+/// let ts = Timestamp::try_from(jiff::Timestamp::new(1747388772, 0)?)?;
+/// assert_eq!(String::from(ts), "2025-05-16T09:46:12Z");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl TryFrom<jiff::Timestamp> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(value: jiff::Timestamp) -> Result<Self, Self::Error> {
+        Self::from_nanos(value.as_nanosecond()).ok_or(TimestampError::OutOfRange)
+    }
+}
+
+/// Converts from [Timestamp] to [jiff::Timestamp].
+///
+/// This conversion may fail if the [Timestamp] value is out of range. The
+/// maximum [jiff::Timestamp] is slightly smaller than the maximum [Timestamp].
+///
+/// # Example
+/// ```
+/// # use google_cloud_wkt::Timestamp;
+/// let ts = Timestamp::try_from("2025-05-16T09:46:12Z")?;
+/// let ts = jiff::Timestamp::try_from(ts)?;
+/// assert_eq!(ts.as_second(), 1747388772);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl TryFrom<Timestamp> for jiff::Timestamp {
+    type Error = jiff::Error;
+
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        jiff::Timestamp::new(value.seconds, value.nanos)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -598,6 +890,20 @@ mod tests {
         Ok(())
     }
 
+    #[test_case("1970-01-01t00:00:00Z"; "lowercase t")]
+    #[test_case("1970-01-01T00:00:00z"; "lowercase z")]
+    #[test_case("1970-01-01 00:00:00Z"; "missing t")]
+    #[test_case("1970-01-01"; "missing time")]
+    #[test_case(""; "empty")]
+    fn deserialize_strict(input: &str) -> Result {
+        let value = serde_json::to_value(input)?;
+        let got = serde_json::from_value::<Timestamp>(value);
+        assert!(got.is_err(), "{got:?}");
+        let got = Timestamp::try_from(input);
+        assert!(matches!(got, Err(Error::Deserialize(_))), "{got:?}");
+        Ok(())
+    }
+
     #[test_case("0000-01-01T00:00:00Z"; "below range")]
     #[test_case("10000-01-01T00:00:00Z"; "above range")]
     fn deserialize_out_of_range(input: &str) -> Result {
@@ -726,4 +1032,145 @@ mod tests {
         assert_eq!(got, want);
         Ok(())
     }
+
+    #[test_case(Timestamp::clamp(100, 0), Duration::clamp(20, 0), Timestamp::clamp(120, 0) ; "simple")]
+    #[test_case(Timestamp::clamp(100, 600_000_000), Duration::clamp(0, 500_000_000), Timestamp::clamp(101, 100_000_000) ; "carry")]
+    #[test_case(Timestamp::clamp(100, 0), Duration::clamp(-1, -500), Timestamp::clamp(98, 999_999_500) ; "negative duration")]
+    #[test_case(Timestamp::clamp(0, 0), Duration::clamp(0, -500), Timestamp::clamp(-1, 999_999_500) ; "before epoch")]
+    fn add(ts: Timestamp, d: Duration, want: Timestamp) {
+        assert_eq!(ts.checked_add(d), Some(want));
+        assert_eq!(ts.saturating_add(d), want);
+        assert_eq!(ts + d, want);
+        let mut got = ts;
+        got += d;
+        assert_eq!(got, want);
+
+        assert_eq!(want.checked_sub(d), Some(ts));
+        assert_eq!(want.saturating_sub(d), ts);
+        assert_eq!(want - d, ts);
+        let mut got = want;
+        got -= d;
+        assert_eq!(got, ts);
+
+        assert_eq!(want - ts, d);
+        assert_eq!(want.duration_since(ts), d);
+        assert_eq!(ts - want, -d);
+    }
+
+    #[test]
+    fn add_overflow() {
+        let max = Timestamp::clamp(Timestamp::MAX_SECONDS, Timestamp::MAX_NANOS);
+        let min = Timestamp::clamp(Timestamp::MIN_SECONDS, Timestamp::MIN_NANOS);
+        let one = Duration::clamp(0, 1);
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(max.saturating_add(one), max);
+        assert_eq!(min.checked_sub(one), None);
+        assert_eq!(min.saturating_sub(one), min);
+        let large = Duration::clamp(Duration::MAX_SECONDS, 0);
+        assert_eq!(min.saturating_add(large), max);
+        assert_eq!(max.saturating_sub(large), min);
+    }
+
+    #[test]
+    fn duration_since_extremes() {
+        let max = Timestamp::clamp(Timestamp::MAX_SECONDS, Timestamp::MAX_NANOS);
+        let min = Timestamp::clamp(Timestamp::MIN_SECONDS, Timestamp::MIN_NANOS);
+        let d = max - min;
+        assert_eq!(min + d, max);
+        assert_eq!(max - d, min);
+        assert_eq!(min - max, -d);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when adding duration to timestamp")]
+    fn add_panics() {
+        let _ = Timestamp::clamp(Timestamp::MAX_SECONDS, 0) + Duration::clamp(1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when subtracting duration from timestamp")]
+    fn sub_panics() {
+        let _ = Timestamp::clamp(Timestamp::MIN_SECONDS, 0) - Duration::clamp(1, 0);
+    }
+
+    #[test]
+    fn ordering() {
+        let mut values = vec![
+            Timestamp::clamp(1, 0),
+            Timestamp::clamp(-1, 500),
+            Timestamp::clamp(0, 500),
+            Timestamp::clamp(-1, 0),
+        ];
+        values.sort();
+        let want = vec![
+            Timestamp::clamp(-1, 0),
+            Timestamp::clamp(-1, 500),
+            Timestamp::clamp(0, 500),
+            Timestamp::clamp(1, 0),
+        ];
+        assert_eq!(values, want);
+        let set = std::collections::HashSet::from([Timestamp::clamp(1, 0), Timestamp::clamp(1, 0)]);
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn now() -> Result {
+        let before = std::time::SystemTime::now();
+        let now = Timestamp::now();
+        let after = std::time::SystemTime::now();
+        assert!(Timestamp::try_from(before)? <= now, "{before:?} {now:?}");
+        assert!(now <= Timestamp::try_from(after)?, "{now:?} {after:?}");
+        Ok(())
+    }
+
+    #[test_case(Timestamp::clamp(1747388772, 123_456_789) ; "after epoch")]
+    #[test_case(Timestamp::clamp(-1, 500) ; "before epoch")]
+    #[test_case(Timestamp::default() ; "epoch")]
+    fn system_time_roundtrip(ts: Timestamp) -> Result {
+        let time = std::time::SystemTime::try_from(ts)?;
+        let got = Timestamp::try_from(time)?;
+        assert_eq!(got, ts);
+        Ok(())
+    }
+
+    #[test]
+    fn system_time_values() -> Result {
+        use std::time::{Duration, UNIX_EPOCH};
+        let got = Timestamp::try_from(UNIX_EPOCH + Duration::new(123, 456))?;
+        assert_eq!(got, Timestamp::new(123, 456)?);
+        let got = Timestamp::try_from(UNIX_EPOCH - Duration::new(0, 500))?;
+        assert_eq!(got, Timestamp::new(-1, 999_999_500)?);
+        Ok(())
+    }
+
+    #[test]
+    fn system_time_out_of_range() {
+        let time = std::time::UNIX_EPOCH
+            + std::time::Duration::from_secs(Timestamp::MAX_SECONDS as u64 + 1);
+        let got = Timestamp::try_from(time);
+        assert!(matches!(got, Err(TimestampError::OutOfRange)), "{got:?}");
+    }
+
+    #[test_case(Timestamp::clamp(123, 456789012) ; "after epoch")]
+    #[test_case(Timestamp::clamp(-1, 500) ; "before epoch")]
+    #[test_case(Timestamp::clamp(Timestamp::MIN_SECONDS, 0) ; "min")]
+    fn jiff_roundtrip(ts: Timestamp) -> Result {
+        let got = jiff::Timestamp::try_from(ts)?;
+        assert_eq!(got, jiff::Timestamp::new(ts.seconds(), ts.nanos())?);
+        let got = Timestamp::try_from(got)?;
+        assert_eq!(got, ts);
+        Ok(())
+    }
+
+    #[test]
+    fn jiff_out_of_range() -> Result {
+        let ts = Timestamp::clamp(Timestamp::MAX_SECONDS, 0);
+        let got = jiff::Timestamp::try_from(ts);
+        assert!(got.is_err(), "{got:?}");
+        let got = Timestamp::try_from(jiff::Timestamp::MIN);
+        assert!(matches!(got, Err(TimestampError::OutOfRange)), "{got:?}");
+        let got = Timestamp::try_from(jiff::Timestamp::MAX)?;
+        assert_eq!(got, Timestamp::new(253402207200, 999_999_999)?);
+        Ok(())
+    }
 }
@@ -0,0 +1,80 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+    use common::MessageWithString;
+    use google_cloud_wkt::{FieldMask, FieldMaskError};
+    use test_case::test_case;
+    type Result = anyhow::Result<()>;
+
+    #[test]
+    fn from_diff() -> Result {
+        let old = MessageWithString::new()
+            .set_singular("a")
+            .set_map_key_value([("k", "v")]);
+        let new = MessageWithString::new()
+            .set_singular("a")
+            .set_optional("b")
+            .set_map_key_value([("k", "v2")]);
+        let mask = FieldMask::from_diff(&old, &new)?;
+        assert_eq!(mask.paths, vec!["map_key_value", "optional"]);
+        mask.validate::<MessageWithString>()?;
+        Ok(())
+    }
+
+    #[test_case("singular")]
+    #[test_case("optional")]
+    #[test_case("repeated")]
+    #[test_case("map_value")]
+    #[test_case("map_key_value")]
+    fn validate_success(path: &str) -> Result {
+        FieldMask::default()
+            .set_paths([path])
+            .validate::<MessageWithString>()?;
+        Ok(())
+    }
+
+    #[test_case("unknown")]
+    #[test_case("singular.x")]
+    #[test_case("map_key_value.k")]
+    fn validate_error(path: &str) {
+        let got = FieldMask::default()
+            .set_paths([path])
+            .validate::<MessageWithString>();
+        assert!(
+            matches!(got, Err(FieldMaskError::InvalidPath { .. })),
+            "{got:?}"
+        );
+    }
+
+    #[test]
+    fn apply() -> Result {
+        let source = MessageWithString::new()
+            .set_singular("new")
+            .set_repeated(["a", "b"]);
+        let mut target = MessageWithString::new()
+            .set_singular("old")
+            .set_optional("keep")
+            .set_map_key_value([("k", "v")]);
+        let mask = FieldMask::default().set_paths(["singular", "repeated", "map_key_value"]);
+        mask.apply(&source, &mut target)?;
+        let want = MessageWithString::new()
+            .set_singular("new")
+            .set_optional("keep")
+            .set_repeated(["a", "b"]);
+        assert_eq!(target, want);
+        Ok(())
+    }
+}